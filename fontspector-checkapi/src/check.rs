@@ -1,10 +1,14 @@
-use std::time::Duration;
+use std::{
+    any::Any,
+    panic::{self, AssertUnwindSafe},
+    time::Duration,
+};
 
 use crate::{
+    condition,
     context::Context,
     error::FontspectorError,
-    fix::HotfixFunction,
-    prelude::FixResult,
+    fix::{HotfixFunction, SourceFixFunction},
     status::CheckFnResult,
     testable::{TestableCollection, TestableType},
     CheckResult, Registry, Status, Testable,
@@ -64,9 +68,13 @@ pub struct Check<'a> {
     /// Function pointer implementing a hotfix to the binary file
     pub hotfix: Option<&'a HotfixFunction>,
     /// Function pointer implementing a hotfix to the font source file
-    pub fix_source: Option<&'a dyn Fn(&Testable) -> FixResult>,
+    pub fix_source: Option<&'a SourceFixFunction>,
     /// A registered file type that this check applies to
     pub applies_to: &'a str,
+    /// Named conditions which must hold for the check to be run
+    ///
+    /// If any of them does not hold, the check is skipped.
+    pub conditions: &'a [&'a str],
     /// Additional flags for the check
     pub flags: CheckFlags,
     /// Metadata for the check in JSON format
@@ -108,13 +116,54 @@ impl<'a> Check<'a> {
             .unwrap_or_default()
     }
 
+    /// The first of the check's conditions which does not hold for a testable
+    fn unfulfilled_condition(
+        &self,
+        testable: &TestableType,
+        context: &Context,
+    ) -> Result<Option<&'a str>, FontspectorError> {
+        for &condition in self.conditions {
+            if !condition::evaluate(condition, testable, context)? {
+                return Ok(Some(condition));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Run a check function, if the check's conditions hold for the testable
+    ///
+    /// A panic in the check, or in its conditions, is caught and returned as
+    /// an error, as is a check running out of time.
+    fn run_guarded(
+        &self,
+        testable: &TestableType,
+        context: &Context,
+        check_fn: impl FnOnce() -> CheckFnResult,
+    ) -> Result<Vec<Status>, FontspectorError> {
+        let run = || -> Result<Vec<Status>, FontspectorError> {
+            match self.unfulfilled_condition(testable, context)? {
+                Some(condition) => Ok(vec![Status::skip(
+                    "unfulfilled-conditions",
+                    &format!("Unfulfilled condition: {condition}"),
+                )]),
+                // Check functions may return lazy iterators, so they are only done once collected
+                None => check_fn().map(|statuses| statuses.collect::<Vec<_>>()),
+            }
+        };
+        let statuses = panic::catch_unwind(AssertUnwindSafe(run))
+            .unwrap_or_else(|payload| Err(FontspectorError::Panic(panic_message(payload))))?;
+        // A check which does not look at the clock is still held to its time limit
+        context.check_deadline()?;
+        Ok(statuses)
+    }
+
     /// Clarify the result of a check function
     ///
     /// Wraps the bare result with additional metadata identifying the check,
     /// the file, etc. so that it can be reported back to the user.
     fn clarify_result(
         &'a self,
-        fn_result: CheckFnResult,
+        fn_result: Result<Vec<Status>, FontspectorError>,
         filename: Option<&str>,
         source_filename: Option<&str>,
         section: Option<&str>,
@@ -122,8 +171,14 @@ impl<'a> Check<'a> {
         duration: Duration,
     ) -> CheckResult {
         let subresults = match fn_result {
-            Ok(results) => results.collect::<Vec<_>>(),
+            Ok(results) => results,
             Err(FontspectorError::Skip { code, message }) => vec![Status::skip(code, message)],
+            Err(e @ FontspectorError::Panic(_)) => {
+                vec![Status::error(Some("panic"), &e.to_string())]
+            }
+            Err(e @ FontspectorError::Timeout(_)) => {
+                vec![Status::error(Some("timeout"), &e.to_string())]
+            }
             Err(e) => vec![Status::error(None, &format!("Error: {e}"))],
         };
         let mut res = if subresults.is_empty() {
@@ -147,13 +202,15 @@ impl<'a> Check<'a> {
         section: Option<&str>,
     ) -> Option<CheckResult> {
         log::info!("Running check {} on {:?}", self.id, testable);
+        let context = context.start_clock();
+        let context: &Context = &context;
         match (&self.implementation, testable) {
             (CheckImplementation::CheckAll(_), TestableType::Single(_)) => None,
             (CheckImplementation::CheckOne(_), TestableType::Collection(_)) => None,
             (CheckImplementation::CheckOne(check_one), TestableType::Single(f)) => {
                 #[cfg(not(target_family = "wasm"))]
                 let start = std::time::Instant::now();
-                let result = check_one(f, context);
+                let result = self.run_guarded(testable, context, || check_one(f, context));
 
                 #[cfg(not(target_family = "wasm"))]
                 let duration = start.elapsed();
@@ -172,25 +229,38 @@ impl<'a> Check<'a> {
             (CheckImplementation::CheckAll(check_all), TestableType::Collection(f)) => {
                 #[cfg(not(target_family = "wasm"))]
                 let start = std::time::Instant::now();
-                let result = check_all(f, context);
+                let result = self.run_guarded(testable, context, || check_all(f, context));
                 #[cfg(not(target_family = "wasm"))]
                 let duration = start.elapsed();
                 #[cfg(target_family = "wasm")]
                 let duration = Duration::from_secs(0);
 
-                Some(self.clarify_result(
+                let mut result = self.clarify_result(
                     result,
                     Some(&f.directory),
                     None,
                     section,
                     context,
                     duration,
-                ))
+                );
+                result.label = f.label.clone();
+                Some(result)
             }
         }
     }
 }
 
+/// The message a panic was raised with
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "(no message)".to_string()
+    }
+}
+
 /// Utility function for returning a check result
 ///
 /// Interprets the case of an empty list of problems to mean a PASS status.
@@ -201,3 +271,81 @@ pub fn return_result(problems: Vec<Status>) -> CheckFnResult {
         Ok(Box::new(problems.into_iter()))
     }
 }
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        codetesting::{assert_messages_contain, assert_results_contain, test_able},
+        StatusCode,
+    };
+
+    fn test_check(implementation: CheckImplementation<'static>) -> Check<'static> {
+        Check {
+            id: "test/guarded",
+            title: "A test check",
+            rationale: "",
+            proposal: &[],
+            implementation,
+            hotfix: None,
+            fix_source: None,
+            applies_to: "TTF",
+            conditions: &[],
+            flags: CheckFlags::default(),
+            _metadata: None,
+        }
+    }
+
+    fn panics(_t: &Testable, _context: &Context) -> CheckFnResult {
+        panic!("something unexpected")
+    }
+
+    fn runs_forever(_t: &Testable, context: &Context) -> CheckFnResult {
+        loop {
+            context.check_deadline()?;
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    fn ignores_the_clock(_t: &Testable, _context: &Context) -> CheckFnResult {
+        std::thread::sleep(Duration::from_millis(20));
+        Ok(Status::just_one_pass())
+    }
+
+    #[test]
+    fn test_panic_is_caught() {
+        let testable = test_able("nunito/Nunito-Regular.ttf");
+        let result = test_check(CheckImplementation::CheckOne(&panics)).run(
+            &TestableType::Single(&testable),
+            &Context::default(),
+            None,
+        );
+        assert_results_contain(&result, StatusCode::Error, Some("panic".to_string()));
+        assert_messages_contain(&result, "something unexpected");
+    }
+
+    #[test]
+    fn test_timeout() {
+        let testable = test_able("nunito/Nunito-Regular.ttf");
+        let context = Context {
+            check_timeout: Some(Duration::from_millis(5)),
+            ..Default::default()
+        };
+        for implementation in [
+            CheckImplementation::CheckOne(&runs_forever),
+            CheckImplementation::CheckOne(&ignores_the_clock),
+        ] {
+            let result =
+                test_check(implementation).run(&TestableType::Single(&testable), &context, None);
+            assert_results_contain(&result, StatusCode::Error, Some("timeout".to_string()));
+        }
+        // Without a time limit, the check runs to completion
+        let result = test_check(CheckImplementation::CheckOne(&ignores_the_clock)).run(
+            &TestableType::Single(&testable),
+            &Context::default(),
+            None,
+        );
+        assert_eq!(result.unwrap().worst_status(), StatusCode::Pass);
+    }
+}
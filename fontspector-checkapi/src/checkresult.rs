@@ -2,7 +2,7 @@ use std::time::Duration;
 
 use serde::{ser::SerializeStruct, Deserialize, Serialize};
 
-use crate::{Check, CheckId, FixResult, Status, StatusCode};
+use crate::{Check, CheckId, FixResult, FontChange, Status, StatusCode};
 
 #[derive(Debug, Clone, Deserialize)]
 /// The result of a check on one or more font files.
@@ -21,6 +21,9 @@ pub struct CheckResult {
     pub check_rationale: String,
     /// The file which was checked; if None, the check was run on all files
     pub filename: Option<String>,
+    /// For a check run on a collection, the collection's label, if it has one
+    #[serde(default)]
+    pub label: Option<String>,
     /// The source where this file came from, if any
     #[serde(default)]
     pub source_filename: Option<String>,
@@ -30,6 +33,9 @@ pub struct CheckResult {
     pub subresults: Vec<Status>,
     /// If hotfixing was attempted, the result of the hotfix
     pub hotfix_result: Option<FixResult>,
+    /// If a hotfix was applied, the changes it made to the font
+    #[serde(default)]
+    pub hotfix_changes: Vec<FontChange>,
     /// If source fixing was attempted, the result of the source fix
     pub sourcefix_result: Option<FixResult>,
     /// Time taken — not serialized over the wire, defaults to zero
@@ -43,13 +49,19 @@ pub struct CheckResult {
 
 impl Serialize for CheckResult {
     fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
-        let fields =
-            7 + self.hotfix_result.is_some() as usize + self.sourcefix_result.is_some() as usize;
+        let fields = 7
+            + self.label.is_some() as usize
+            + self.hotfix_result.is_some() as usize
+            + !self.hotfix_changes.is_empty() as usize
+            + self.sourcefix_result.is_some() as usize;
         let mut s = serializer.serialize_struct("CheckResult", fields)?;
         s.serialize_field("check_id", &self.check_id)?;
         s.serialize_field("check_name", &self.check_name)?;
         s.serialize_field("check_rationale", &self.check_rationale)?;
         s.serialize_field("filename", &self.filename)?;
+        if let Some(label) = &self.label {
+            s.serialize_field("label", label)?;
+        }
         s.serialize_field("section", &self.section)?;
         s.serialize_field("subresults", &self.subresults)?;
         s.serialize_field("worst_status", &self.worst_status())?;
@@ -59,6 +71,9 @@ impl Serialize for CheckResult {
         if let Some(hotfix_result) = &self.hotfix_result {
             s.serialize_field("hotfix_result", hotfix_result)?;
         }
+        if !self.hotfix_changes.is_empty() {
+            s.serialize_field("hotfix_changes", &self.hotfix_changes)?;
+        }
         if let Some(sourcefix_result) = &self.sourcefix_result {
             s.serialize_field("sourcefix_result", sourcefix_result)?;
         }
@@ -81,10 +96,12 @@ impl CheckResult {
             check_name: check.title.to_string(),
             check_rationale: check.rationale.to_string(),
             filename: filename.map(|x| x.to_string()),
+            label: None,
             source_filename: source_filename.map(|x| x.to_string()),
             section: section.map(|x| x.to_string()),
             subresults,
             hotfix_result: None,
+            hotfix_changes: vec![],
             sourcefix_result: None,
             time: duration,
             hotfix_available: check.hotfix.is_some(),
@@ -1,26 +1,42 @@
 #![allow(clippy::unwrap_used, clippy::expect_used)]
 
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     path::{Path, PathBuf},
-    sync::LazyLock,
+    sync::{Arc, LazyLock},
+    time::Duration,
 };
 
 // No bad thing if we panic in tests
 use crate::{prelude::*, testfont, Check, CheckResult, Context, FileTypeConvert, StatusCode};
 use fontations::{
+    read::{ReadError, TopLevelTable},
     skrifa::{
-        raw::{types::NameId, TableProvider},
-        GlyphNames, MetadataProvider,
+        raw::{
+            types::{GlyphId16, NameId},
+            TableProvider,
+        },
+        FontRef, GlyphId, GlyphNames, MetadataProvider,
     },
     write::{
-        from_obj::ToOwnedTable,
+        from_obj::{FromTableRef, ToOwnedTable},
         tables::{
-            cmap::Cmap,
+            cmap::{Cmap, CmapSubtable},
+            fvar::Fvar,
+            gdef::Gdef,
+            glyf::{Bbox, GlyfLocaBuilder, Glyph, SimpleGlyph},
+            gpos::{Gpos, PositionLookup},
+            gsub::{Gsub, SubstitutionLookup},
+            head::Head,
+            hhea::Hhea,
+            layout::ClassDef,
             name::{Name, NameRecord},
             os2::Os2,
+            post::Post,
+            stat::Stat,
         },
-        FontBuilder,
+        validate::Validate,
+        FontBuilder, FontWrite,
     },
 };
 
@@ -76,6 +92,18 @@ pub fn run_check_with_config_and_network(
     things: TestableType<'_>,
     config: HashMap<String, serde_json::Value>,
     skip_network: bool,
+) -> Option<CheckResult> {
+    run_check_with_timeout(check, things, config, skip_network, None)
+}
+
+/// Run a check on a font or collection with a given configuration, network
+/// toggle and time limit
+pub fn run_check_with_timeout(
+    check: Check<'_>,
+    things: TestableType<'_>,
+    config: HashMap<String, serde_json::Value>,
+    skip_network: bool,
+    check_timeout: Option<Duration>,
 ) -> Option<CheckResult> {
     let ctx: Context = Context {
         skip_network,
@@ -84,12 +112,34 @@ pub fn run_check_with_config_and_network(
         check_metadata: check.metadata(),
         full_lists: true,
         cache: Default::default(),
+        memo: Default::default(),
         overrides: vec![],
         check_id: None,
+        conditions: Default::default(),
+        family: None,
+        check_timeout,
+        deadline: None,
     };
     check.run(&things, &ctx, None)
 }
 
+/// Run a check on one file of a family, which the check can see through its context
+pub fn run_check_in_family(
+    check: Check<'_>,
+    family: &Arc<TestableCollection>,
+    basename: &str,
+) -> Option<CheckResult> {
+    let font = family.get_file(basename).expect("File not in family");
+    let ctx: Context = Context {
+        network_timeout: Some(10),
+        check_metadata: check.metadata(),
+        full_lists: true,
+        family: Some(family.clone()),
+        ..Default::default()
+    };
+    check.run(&TestableType::Single(font), &ctx, None)
+}
+
 /// Assert that a check passes
 ///
 /// Takes a `CheckResult` and asserts that the worst status is `Pass`
@@ -174,6 +224,276 @@ fn assert_messages_contain_impl(
     }
 }
 
+/// The severity and code of each status in a check result, in order
+pub fn result_codes(check_result: &Option<CheckResult>) -> Vec<(StatusCode, Option<String>)> {
+    check_result
+        .as_ref()
+        .expect("Check result was None")
+        .subresults
+        .iter()
+        .map(|subresult| (subresult.severity, subresult.code.clone()))
+        .collect()
+}
+
+/// Assert that a check result is made up of exactly the expected statuses
+///
+/// Statuses are given in the order the check returns them, as their severity
+/// and code; a status without a code, such as a plain PASS, is given as `None`.
+pub fn assert_result_codes(
+    check_result: &Option<CheckResult>,
+    expected: &[(StatusCode, Option<&str>)],
+) {
+    let found = result_codes(check_result);
+    let found = found
+        .iter()
+        .map(|(severity, code)| (*severity, code.as_deref()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        found,
+        expected,
+        "Unexpected check results:\n- {}",
+        check_result
+            .as_ref()
+            .unwrap()
+            .subresults
+            .iter()
+            .map(|subresult| subresult.to_string())
+            .collect::<Vec<_>>()
+            .join("\n- ")
+    );
+}
+
+/// Start editing the tables of a font (for testing purposes only)
+///
+/// See [FontEditor].
+pub fn edit_font(font: &mut Testable) -> FontEditor<'_> {
+    FontEditor { font }
+}
+
+/// A fluent editor for the tables of a font (for testing purposes only)
+///
+/// Each method takes a closure which is given the table as a write-fonts
+/// object to modify as it likes. Edits are applied to the font straight
+/// away, so they can be chained and each one sees the result of the last:
+///
+/// ```ignore
+/// edit_font(&mut testable)
+///     .os2(|os2| os2.us_weight_class = 333)
+///     .post(|post| post.is_fixed_pitch = 1)
+///     .cmap(|cmap| {
+///         cmap.remove(&0x41);
+///     });
+/// ```
+///
+/// A table which is missing or can't be rebuilt is a bug in the test, so
+/// the editor panics rather than returning an error.
+pub struct FontEditor<'a> {
+    font: &'a mut Testable,
+}
+
+impl FontEditor<'_> {
+    /// Rebuild the font with the tables added by `add_tables`, keeping all others
+    fn rebuild(
+        self,
+        add_tables: impl FnOnce(&FontRef, &mut FontBuilder) -> Result<(), FontspectorError>,
+    ) -> Self {
+        let font = FontRef::new(&self.font.contents).expect("Failed to parse font");
+        let mut builder = FontBuilder::new();
+        add_tables(&font, &mut builder).expect("Failed to edit font");
+        let new_bytes = builder.copy_missing_tables(font).build();
+        self.font.set(new_bytes);
+        self
+    }
+
+    /// Read a table, edit it and put it back
+    fn edit_table<T: FontWrite + Validate + TopLevelTable>(
+        self,
+        read: impl FnOnce(&FontRef) -> Result<T, ReadError>,
+        edit: impl FnOnce(&mut T),
+    ) -> Self {
+        self.rebuild(|font, builder| {
+            let mut table = read(font)?;
+            edit(&mut table);
+            builder.add_table(&table)?;
+            Ok(())
+        })
+    }
+
+    /// Edit the OS/2 table
+    pub fn os2(self, edit: impl FnOnce(&mut Os2)) -> Self {
+        self.edit_table(|font| Ok(font.os2()?.to_owned_table()), edit)
+    }
+
+    /// Edit the head table
+    pub fn head(self, edit: impl FnOnce(&mut Head)) -> Self {
+        self.edit_table(|font| Ok(font.head()?.to_owned_table()), edit)
+    }
+
+    /// Edit the hhea table
+    pub fn hhea(self, edit: impl FnOnce(&mut Hhea)) -> Self {
+        self.edit_table(|font| Ok(font.hhea()?.to_owned_table()), edit)
+    }
+
+    /// Edit the post table
+    pub fn post(self, edit: impl FnOnce(&mut Post)) -> Self {
+        self.edit_table(|font| Ok(font.post()?.to_owned_table()), edit)
+    }
+
+    /// Edit the fvar table
+    pub fn fvar(self, edit: impl FnOnce(&mut Fvar)) -> Self {
+        self.edit_table(|font| Ok(font.fvar()?.to_owned_table()), edit)
+    }
+
+    /// Edit the STAT table
+    pub fn stat(self, edit: impl FnOnce(&mut Stat)) -> Self {
+        self.edit_table(|font| Ok(font.stat()?.to_owned_table()), edit)
+    }
+
+    /// Edit the GDEF table
+    pub fn gdef(self, edit: impl FnOnce(&mut Gdef)) -> Self {
+        self.edit_table(|font| Ok(font.gdef()?.to_owned_table()), edit)
+    }
+
+    /// Edit the GSUB table
+    pub fn gsub(self, edit: impl FnOnce(&mut Gsub)) -> Self {
+        self.edit_table(|font| Ok(font.gsub()?.to_owned_table()), edit)
+    }
+
+    /// Edit the GPOS table
+    pub fn gpos(self, edit: impl FnOnce(&mut Gpos)) -> Self {
+        self.edit_table(|font| Ok(font.gpos()?.to_owned_table()), edit)
+    }
+
+    /// Edit one lookup in the GSUB table, by its index in the lookup list
+    pub fn gsub_lookup(self, index: usize, edit: impl FnOnce(&mut SubstitutionLookup)) -> Self {
+        self.gsub(|gsub| {
+            edit(
+                gsub.lookup_list
+                    .lookups
+                    .get_mut(index)
+                    .expect("No such GSUB lookup"),
+            )
+        })
+    }
+
+    /// Edit one lookup in the GPOS table, by its index in the lookup list
+    pub fn gpos_lookup(self, index: usize, edit: impl FnOnce(&mut PositionLookup)) -> Self {
+        self.gpos(|gpos| {
+            edit(
+                gpos.lookup_list
+                    .lookups
+                    .get_mut(index)
+                    .expect("No such GPOS lookup"),
+            )
+        })
+    }
+
+    /// Edit the glyph classes in the GDEF table, as a map from glyph ID to class
+    ///
+    /// A GDEF table is added to the font if it doesn't have one.
+    pub fn glyph_classes(self, edit: impl FnOnce(&mut BTreeMap<GlyphId16, u16>)) -> Self {
+        self.rebuild(|font, builder| {
+            let mut classes = BTreeMap::new();
+            if let Ok(gdef) = font.gdef() {
+                if let Some(class_def) = gdef.glyph_class_def().transpose()? {
+                    classes.extend(class_def.iter());
+                }
+            }
+            edit(&mut classes);
+            let mut gdef: Gdef = font
+                .gdef()
+                .map(|gdef| gdef.to_owned_table())
+                .unwrap_or_default();
+            gdef.glyph_class_def = Some(classes.into_iter().collect::<ClassDef>()).into();
+            builder.add_table(&gdef)?;
+            Ok(())
+        })
+    }
+
+    /// Edit the character map, as a map from codepoint to glyph ID
+    ///
+    /// Any Unicode variation sequences (format 14 subtables) are kept as
+    /// they are. Mapping a value which is not a Unicode scalar value, such
+    /// as a surrogate, panics.
+    pub fn cmap(self, edit: impl FnOnce(&mut BTreeMap<u32, GlyphId>)) -> Self {
+        self.rebuild(|font, builder| {
+            let mut mappings = font.charmap().mappings().collect::<BTreeMap<_, _>>();
+            edit(&mut mappings);
+            let mut cmap = Cmap::from_mappings(mappings.into_iter().map(|(cp, gid)| {
+                let c = char::from_u32(cp)
+                    .unwrap_or_else(|| panic!("U+{cp:04X} is not a valid codepoint"));
+                (c, gid)
+            }))
+            .map_err(|e| FontspectorError::General(format!("Failed to create new cmap: {e}")))?;
+            if let Ok(original) = font.cmap() {
+                let original: Cmap = original.to_owned_table();
+                cmap.encoding_records.extend(
+                    original
+                        .encoding_records
+                        .into_iter()
+                        .filter(|record| matches!(*record.subtable, CmapSubtable::Format14(_))),
+                );
+                cmap.encoding_records
+                    .sort_by_key(|record| (record.platform_id, record.encoding_id));
+            }
+            builder.add_table(&cmap)?;
+            Ok(())
+        })
+    }
+
+    /// Edit the outline of a glyph in the glyf table
+    ///
+    /// The bounding box of a simple glyph is recomputed after editing. Only
+    /// the default outline is changed; any variations in gvar are left as
+    /// they are.
+    pub fn glyph(self, gid: GlyphId, edit: impl FnOnce(&mut Glyph)) -> Self {
+        self.rebuild(|font, builder| {
+            let glyf = font.glyf()?;
+            let loca = font.loca(None)?;
+            let glyph_count: u32 = font.maxp()?.num_glyphs().into();
+            let mut glyphs = (0..glyph_count)
+                .map(GlyphId::from)
+                .map(|id| {
+                    Ok(loca
+                        .get_glyf(id, &glyf)?
+                        .map(|glyph| Glyph::from_table_ref(&glyph))
+                        .unwrap_or(Glyph::Empty))
+                })
+                .collect::<Result<Vec<_>, ReadError>>()?;
+            let glyph = glyphs.get_mut(gid.to_u32() as usize).ok_or_else(|| {
+                FontspectorError::General(format!("No glyph {} in font", gid.to_u32()))
+            })?;
+            edit(glyph);
+            if let Glyph::Simple(simple) = glyph {
+                simple.bbox = simple_glyph_bbox(simple);
+            }
+            let mut glyf_builder = GlyfLocaBuilder::new();
+            for glyph in glyphs.iter() {
+                glyf_builder.add_glyph(glyph)?;
+            }
+            let (glyf, loca, loca_format) = glyf_builder.build();
+            let mut head: Head = font.head()?.to_owned_table();
+            head.index_to_loc_format = loca_format as i16;
+            builder
+                .add_table(&glyf)?
+                .add_table(&loca)?
+                .add_table(&head)?;
+            Ok(())
+        })
+    }
+}
+
+/// The bounding box of the points of a simple glyph
+fn simple_glyph_bbox(glyph: &SimpleGlyph) -> Bbox {
+    let points = || glyph.contours.iter().flat_map(|contour| contour.iter());
+    Bbox {
+        x_min: points().map(|point| point.x).min().unwrap_or_default(),
+        y_min: points().map(|point| point.y).min().unwrap_or_default(),
+        x_max: points().map(|point| point.x).max().unwrap_or_default(),
+        y_max: points().map(|point| point.y).max().unwrap_or_default(),
+    }
+}
+
 /// Manipulate a font by changing a name table entry (for testing purposes only)
 pub fn set_name_entry(
     font: &mut Testable,
@@ -373,3 +693,89 @@ pub fn add_table(font: &mut Testable, table_tag: &[u8; 4]) {
 
     font.contents = builder.build();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fontations::write::tables::glyf::{Contour, CurvePoint};
+
+    #[test]
+    fn test_edit_tables() {
+        let mut testable = test_able("nunito/Nunito-Regular.ttf");
+        edit_font(&mut testable)
+            .os2(|os2| os2.us_weight_class = 333)
+            .post(|post| post.is_fixed_pitch = 1)
+            .cmap(|cmap| {
+                cmap.remove(&0x41);
+            })
+            .glyph_classes(|classes| {
+                classes.insert(GlyphId16::new(1), 3);
+            });
+        let f = TTF.from_testable(&testable).unwrap();
+        assert_eq!(f.font().os2().unwrap().us_weight_class(), 333);
+        assert_eq!(f.font().post().unwrap().is_fixed_pitch(), 1);
+        assert_eq!(f.font().charmap().map('A'), None);
+        assert!(f.font().charmap().map('B').is_some());
+        let class_def = f.font().gdef().unwrap().glyph_class_def().unwrap().unwrap();
+        assert_eq!(class_def.get(GlyphId16::new(1)), 3);
+    }
+
+    #[test]
+    fn test_edit_cmap_keeps_variation_sequences() {
+        let mut testable = test_able("stixtwomath/STIXTwoMath-Regular.ttf");
+        let variants = |testable: &Testable| {
+            TTF.from_testable(testable)
+                .unwrap()
+                .font()
+                .charmap()
+                .variant_mappings()
+                .count()
+        };
+        let before = variants(&testable);
+        assert!(before > 0);
+        edit_font(&mut testable).cmap(|cmap| {
+            cmap.remove(&0x41);
+        });
+        assert_eq!(variants(&testable), before);
+        let f = TTF.from_testable(&testable).unwrap();
+        assert_eq!(f.font().charmap().map('A'), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "U+D800 is not a valid codepoint")]
+    fn test_edit_cmap_rejects_surrogates() {
+        let mut testable = test_able("nunito/Nunito-Regular.ttf");
+        edit_font(&mut testable).cmap(|cmap| {
+            cmap.insert(0xD800, GlyphId::new(1));
+        });
+    }
+
+    #[test]
+    fn test_edit_glyph() {
+        let mut testable = test_able("nunito/Nunito-Regular.ttf");
+        let gid = TTF
+            .from_testable(&testable)
+            .unwrap()
+            .font()
+            .charmap()
+            .map('A')
+            .unwrap();
+        edit_font(&mut testable).glyph(gid, |glyph| {
+            let square = [(10, 20), (10, 500), (300, 500), (300, 20)]
+                .into_iter()
+                .map(|(x, y)| CurvePoint::new(x, y, true))
+                .collect::<Vec<_>>();
+            if let Glyph::Simple(simple) = glyph {
+                simple.contours = vec![Contour::from(square)];
+            }
+        });
+        let f = TTF.from_testable(&testable).unwrap();
+        let glyf = f.font().glyf().unwrap();
+        let glyph = f.font().loca(None).unwrap().get_glyf(gid, &glyf).unwrap();
+        let glyph = glyph.unwrap();
+        assert_eq!(
+            (glyph.x_min(), glyph.y_min(), glyph.x_max(), glyph.y_max()),
+            (10, 20, 300, 500)
+        );
+    }
+}
@@ -0,0 +1,206 @@
+use std::collections::HashMap;
+
+use crate::{filetype::FileTypeConvert, Context, FontspectorError, TestFont, TestableType, TTF};
+
+/// A function deciding whether a condition holds for a testable
+///
+/// A condition is named in a check definition as `name` or `name:argument`;
+/// the argument, if any, is passed to the function. For example, the
+/// condition `has_table:STAT` calls the `has_table` function with
+/// `Some("STAT")`.
+pub type ConditionFunction =
+    fn(&TestableType, Option<&str>, &Context) -> Result<bool, FontspectorError>;
+
+/// The conditions which are always available to checks
+pub(crate) const BUILTIN_CONDITIONS: &[(&str, ConditionFunction)] = &[
+    ("is_variable_font", is_variable_font),
+    ("has_table", has_table),
+    ("has_feature", has_feature),
+    ("is_cjk", is_cjk),
+    ("is_italic", is_italic),
+    ("is_truetype", is_truetype),
+    ("is_cff", is_cff),
+];
+
+/// Split a condition, as written in a check definition, into whether it is
+/// negated, the name of its function and its argument
+fn parse(condition: &str) -> (bool, &str, Option<&str>) {
+    let (negated, condition) = match condition.strip_prefix('!') {
+        Some(condition) => (true, condition),
+        None => (false, condition),
+    };
+    match condition.split_once(':') {
+        Some((name, argument)) => (negated, name, Some(argument)),
+        None => (negated, condition, None),
+    }
+}
+
+/// Find the function for a condition, among those registered or built in
+fn function_for(
+    name: &str,
+    registered: &HashMap<String, ConditionFunction>,
+) -> Option<ConditionFunction> {
+    registered.get(name).copied().or_else(|| {
+        BUILTIN_CONDITIONS
+            .iter()
+            .find(|(builtin, _)| *builtin == name)
+            .map(|(_, function)| *function)
+    })
+}
+
+/// Is there a function for a condition, as written in a check definition?
+pub(crate) fn is_known(condition: &str, registered: &HashMap<String, ConditionFunction>) -> bool {
+    let (_, name, _) = parse(condition);
+    function_for(name, registered).is_some()
+}
+
+/// Evaluate a condition, as written in a check definition, on a testable
+///
+/// A leading `!` negates the condition. Conditions registered by plugins are
+/// found through the context; the built-in conditions are always available.
+/// Each condition is only evaluated once per testable.
+pub(crate) fn evaluate(
+    condition: &str,
+    testable: &TestableType,
+    context: &Context,
+) -> Result<bool, FontspectorError> {
+    let (negated, name, argument) = parse(condition);
+    let function = function_for(name, &context.conditions)
+        .ok_or_else(|| FontspectorError::General(format!("Unknown condition {name}")))?;
+    let unnegated = condition.strip_prefix('!').unwrap_or(condition);
+    let holds = context.memoize(&format!("condition:{unnegated}"), || {
+        function(testable, argument, context)
+    })?;
+    Ok(*holds != negated)
+}
+
+/// Whether the font, or every font in a collection, passes a test
+///
+/// Testables which are not fonts never satisfy a font condition.
+fn all_fonts(testable: &TestableType, test: impl Fn(&TestFont) -> bool) -> bool {
+    match testable {
+        TestableType::Single(t) => TTF.from_testable(t).is_some_and(|f| test(&f)),
+        TestableType::Collection(collection) => {
+            let fonts = collection
+                .iter()
+                .filter_map(|t| TTF.from_testable(t))
+                .collect::<Vec<_>>();
+            !fonts.is_empty() && fonts.iter().all(test)
+        }
+    }
+}
+
+/// The argument to a condition which needs one
+fn required_argument<'a>(
+    name: &str,
+    argument: Option<&'a str>,
+) -> Result<&'a str, FontspectorError> {
+    argument.ok_or_else(|| {
+        FontspectorError::General(format!(
+            "The {name} condition needs an argument, e.g. {name}:XXXX"
+        ))
+    })
+}
+
+/// The font has an `fvar` table
+fn is_variable_font(
+    testable: &TestableType,
+    _argument: Option<&str>,
+    _context: &Context,
+) -> Result<bool, FontspectorError> {
+    Ok(all_fonts(testable, |f| f.is_variable_font()))
+}
+
+/// The font has the table given as the argument, e.g. `has_table:STAT`
+fn has_table(
+    testable: &TestableType,
+    argument: Option<&str>,
+    _context: &Context,
+) -> Result<bool, FontspectorError> {
+    let tag = required_argument("has_table", argument)?;
+    let tag: [u8; 4] = format!("{tag:4}")
+        .as_bytes()
+        .try_into()
+        .map_err(|_| FontspectorError::General(format!("Bad table tag in has_table:{tag}")))?;
+    Ok(all_fonts(testable, |f| f.has_table(&tag)))
+}
+
+/// The font has the GSUB or GPOS feature given as the argument, e.g. `has_feature:smcp`
+fn has_feature(
+    testable: &TestableType,
+    argument: Option<&str>,
+    _context: &Context,
+) -> Result<bool, FontspectorError> {
+    let tag = required_argument("has_feature", argument)?;
+    Ok(all_fonts(testable, |f| f.has_feature(false, tag)))
+}
+
+/// The font has enough CJK codepoints to be considered a CJK font
+fn is_cjk(
+    testable: &TestableType,
+    _argument: Option<&str>,
+    context: &Context,
+) -> Result<bool, FontspectorError> {
+    Ok(all_fonts(testable, |f| f.is_cjk_font(Some(context))))
+}
+
+/// The font is italic
+fn is_italic(
+    testable: &TestableType,
+    _argument: Option<&str>,
+    _context: &Context,
+) -> Result<bool, FontspectorError> {
+    Ok(all_fonts(testable, |f| f.is_italic().unwrap_or(false)))
+}
+
+/// The font has TrueType outlines
+fn is_truetype(
+    testable: &TestableType,
+    _argument: Option<&str>,
+    _context: &Context,
+) -> Result<bool, FontspectorError> {
+    Ok(all_fonts(testable, |f| f.has_table(b"glyf")))
+}
+
+/// The font has CFF or CFF2 outlines
+fn is_cff(
+    testable: &TestableType,
+    _argument: Option<&str>,
+    _context: &Context,
+) -> Result<bool, FontspectorError> {
+    Ok(all_fonts(testable, |f| {
+        f.has_table(b"CFF ") || f.has_table(b"CFF2")
+    }))
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codetesting::test_able;
+
+    #[test]
+    fn test_builtin_conditions() {
+        let context = Context::default();
+        let testable = test_able("nunito/Nunito-Regular.ttf");
+        let font = TestableType::Single(&testable);
+        assert!(evaluate("has_table:GSUB", &font, &context).unwrap());
+        assert!(evaluate("is_truetype", &font, &context).unwrap());
+        assert!(!evaluate("is_variable_font", &font, &context).unwrap());
+        assert!(evaluate("!is_variable_font", &font, &context).unwrap());
+        assert!(evaluate("has_feature:liga", &font, &context).unwrap());
+        assert!(evaluate("has_table", &font, &context).is_err());
+        assert!(evaluate("no_such_condition", &font, &context).is_err());
+    }
+
+    #[test]
+    fn test_known_conditions() {
+        let mut registered: HashMap<String, ConditionFunction> = HashMap::new();
+        assert!(is_known("is_variable_font", &registered));
+        assert!(is_known("!has_table:STAT", &registered));
+        assert!(!is_known("is_varible_font", &registered));
+        assert!(!is_known("!is_monospaced", &registered));
+        registered.insert("is_monospaced".to_string(), is_italic);
+        assert!(is_known("!is_monospaced", &registered));
+    }
+}
@@ -7,6 +7,8 @@ pub enum OutlineType {
     TrueType,
     /// CFF outlines, with a CFF table and cubic curves
     CFF,
+    /// CFF2 outlines, with a CFF2 table and (possibly variable) cubic curves
+    CFF2,
 }
 
 /// The names of the RIBBI styles
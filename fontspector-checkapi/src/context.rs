@@ -1,11 +1,18 @@
 use std::{
+    any::Any,
+    borrow::Cow,
     collections::HashMap,
+    fmt::{Debug, Formatter},
     sync::{Arc, RwLock},
+    time::{Duration, Instant},
 };
 
 use serde_json::{Map, Value};
 
-use crate::{Check, CheckId, FontspectorError, Override, Profile};
+use crate::{
+    Check, CheckId, ConditionFunction, FontspectorError, Override, Profile, Testable,
+    TestableCollection,
+};
 
 #[derive(Debug, Clone, Default)]
 /// The context of a check
@@ -31,10 +38,34 @@ pub struct Context {
     pub full_lists: bool,
     /// A cache, specific to this testable
     pub cache: Arc<RwLock<Map<String, Value>>>,
+    /// Derived data, specific to this testable
+    ///
+    /// Unlike the [cache](Context::cache), values are stored as they are, so
+    /// they can be shared between checks without a round-trip through JSON.
+    pub memo: Arc<Memo>,
     /// Any overrides for this check, from the profile or the user's configuration file.
     pub overrides: Vec<Override>,
     /// The ID of the check currently being run, used for external/subprocess checks
     pub check_id: Option<String>,
+    /// Conditions registered with the [Registry](crate::Registry), by name
+    ///
+    /// The built-in conditions are available even if this is empty.
+    pub conditions: Arc<HashMap<String, ConditionFunction>>,
+    /// The collection which the testable being checked belongs to, if any
+    ///
+    /// This allows checks on a single font to look at the other fonts in its
+    /// family, while still reporting their results against the font itself.
+    /// See [Context::siblings].
+    pub family: Option<Arc<TestableCollection>>,
+    /// The longest a check may run for
+    ///
+    /// Checks which may take a long time should call [Context::check_deadline]
+    /// regularly, so that they can be stopped when they run out of time.
+    pub check_timeout: Option<Duration>,
+    /// When the currently running check runs out of time
+    ///
+    /// This is set by [Check::run] from the [check_timeout](Context::check_timeout).
+    pub deadline: Option<Instant>,
 }
 
 impl Context {
@@ -47,9 +78,49 @@ impl Context {
             check_metadata: self.check_metadata.clone(),
             full_lists: self.full_lists,
             cache: Arc::new(RwLock::new(Map::new())),
+            memo: Arc::new(Memo::default()),
             overrides: self.overrides.clone(),
             check_id: self.check_id.clone(),
+            conditions: self.conditions.clone(),
+            family: self.family.clone(),
+            check_timeout: self.check_timeout,
+            deadline: None,
+        }
+    }
+
+    /// The other files in the family of a testable
+    ///
+    /// This is empty if the testable is not being checked as part of a collection.
+    pub fn siblings<'a>(&'a self, testable: &'a Testable) -> impl Iterator<Item = &'a Testable> {
+        self.family
+            .iter()
+            .flat_map(|family| family.iter())
+            .filter(move |sibling| sibling.filename != testable.filename)
+    }
+
+    /// Return an error if the current check has run out of time
+    ///
+    /// Checks which loop over many glyphs or other items should call this
+    /// as they go, and pass on the error with `?`.
+    pub fn check_deadline(&self) -> Result<(), FontspectorError> {
+        match (self.deadline, self.check_timeout) {
+            (Some(deadline), Some(timeout)) if Instant::now() > deadline => {
+                Err(FontspectorError::Timeout(timeout))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Copy the context with a deadline for a check starting now, if it has a time limit
+    pub(crate) fn start_clock(&self) -> Cow<'_, Context> {
+        #[cfg(not(target_family = "wasm"))]
+        if let Some(timeout) = self.check_timeout {
+            return Cow::Owned(Context {
+                deadline: Some(Instant::now() + timeout),
+                ..self.clone()
+            });
         }
+        Cow::Borrowed(self)
     }
 
     /// Get the configuration for a particular check
@@ -95,8 +166,13 @@ impl Context {
             check_metadata: check.metadata(),
             full_lists: self.full_lists,
             cache: self.cache.clone(),
+            memo: self.memo.clone(),
             overrides: our_overrides,
             check_id: Some(check.id.to_string()),
+            conditions: self.conditions.clone(),
+            family: self.family.clone(),
+            check_timeout: self.check_timeout,
+            deadline: None,
         }
     }
 
@@ -124,4 +200,131 @@ impl Context {
         }
         Ok(answer)
     }
+
+    /// Compute some derived data, or fetch it from the memo if it has been computed already
+    ///
+    /// See [Memo::get_or_try_insert_with].
+    pub fn memoize<T>(
+        &self,
+        key: &str,
+        func: impl FnOnce() -> Result<T, FontspectorError>,
+    ) -> Result<Arc<T>, FontspectorError>
+    where
+        T: Any + Send + Sync,
+    {
+        self.memo.get_or_try_insert_with(key, func)
+    }
+}
+
+/// A typed store of data derived from a testable
+///
+/// Checks on the same testable may run on different threads, so values
+/// are shared behind an [Arc].
+#[derive(Default)]
+pub struct Memo(RwLock<HashMap<String, Arc<dyn Any + Send + Sync>>>);
+
+impl Debug for Memo {
+    fn fmt(&self, f: &mut Formatter) -> core::fmt::Result {
+        let entries = self.0.read().map(|memo| memo.len()).unwrap_or_default();
+        write!(f, "<Memo: {entries} entries>")
+    }
+}
+
+impl Memo {
+    /// Fetch the value stored under `key`, or compute and store it
+    ///
+    /// Errors are not stored, so a failed computation will be tried again
+    /// next time. It is an error to ask for a key with a different type to
+    /// the one it was stored with.
+    pub fn get_or_try_insert_with<T>(
+        &self,
+        key: &str,
+        func: impl FnOnce() -> Result<T, FontspectorError>,
+    ) -> Result<Arc<T>, FontspectorError>
+    where
+        T: Any + Send + Sync,
+    {
+        let wrong_type = || {
+            FontspectorError::CacheSerialization(format!(
+                "{key} is stored as a different type to the one requested"
+            ))
+        };
+        if let Some(value) = self.0.read()?.get(key) {
+            return value.clone().downcast::<T>().map_err(|_| wrong_type());
+        }
+        let value = Arc::new(func()?);
+        // Another thread may have got there first; if so, we use its answer
+        self.0
+            .write()?
+            .entry(key.to_string())
+            .or_insert_with(|| value.clone() as Arc<dyn Any + Send + Sync>)
+            .clone()
+            .downcast::<T>()
+            .map_err(|_| wrong_type())
+    }
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codetesting::test_able;
+
+    #[test]
+    fn test_memoize() {
+        let context = Context::default();
+        let shared = context.with_new_cache();
+        let first = shared.memoize("answer", || Ok(vec![42u32])).unwrap();
+        // A context for another check on the same testable sees the same value
+        let other_check = Context {
+            memo: shared.memo.clone(),
+            ..Default::default()
+        };
+        let second = other_check
+            .memoize("answer", || -> Result<Vec<u32>, FontspectorError> {
+                panic!("should not be recomputed")
+            })
+            .unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+        // Asking for the wrong type is an error, not a panic
+        assert!(other_check.memoize("answer", || Ok(42u32)).is_err());
+        // Errors are not remembered
+        assert!(context
+            .memoize("failure", || -> Result<u32, FontspectorError> {
+                Err(FontspectorError::General("nope".to_string()))
+            })
+            .is_err());
+        assert_eq!(*context.memoize("failure", || Ok(1u32)).unwrap(), 1);
+        // A new cache starts afresh
+        assert_eq!(
+            *shared
+                .with_new_cache()
+                .memoize("answer", || Ok(vec![1u32]))
+                .unwrap(),
+            vec![1]
+        );
+    }
+
+    #[test]
+    fn test_siblings() {
+        let regular = test_able("merriweather/Merriweather-Regular.ttf");
+        assert_eq!(Context::default().siblings(&regular).count(), 0);
+        let family = TestableCollection::from_testables(
+            vec![
+                test_able("merriweather/Merriweather-Regular.ttf"),
+                test_able("merriweather/Merriweather-Italic.ttf"),
+            ],
+            None,
+        );
+        let context = Context {
+            family: Some(Arc::new(family)),
+            ..Default::default()
+        }
+        .with_new_cache();
+        let siblings = context
+            .siblings(&regular)
+            .map(|sibling| sibling.basename().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(siblings, vec!["Merriweather-Italic.ttf"]);
+    }
 }
@@ -0,0 +1,623 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use fontations::skrifa::{
+    font::FontRef,
+    raw::{ReadError, TableProvider},
+    GlyphId, GlyphNames, Tag,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::FontspectorError;
+
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A single difference between two versions of a font
+///
+/// These are produced by [diff_fonts] so that the user can review exactly
+/// what a hotfix did to their font binary.
+pub enum FontChange {
+    /// A table was added to the font
+    TableAdded(String),
+    /// A table was removed from the font
+    TableRemoved(String),
+    /// A table's binary contents changed, but we don't describe how in detail
+    TableChanged(String),
+    /// A field of a table changed its value
+    FieldChanged {
+        /// The table tag
+        table: String,
+        /// The name of the field
+        field: String,
+        /// The value before the change
+        before: String,
+        /// The value after the change
+        after: String,
+    },
+    /// A name table record was added, removed or changed
+    NameChanged {
+        /// The platform ID, encoding ID and language ID of the record
+        platform: (u16, u16, u16),
+        /// The name ID of the record
+        name_id: u16,
+        /// The string before the change, if the record existed
+        before: Option<String>,
+        /// The string after the change, if the record exists
+        after: Option<String>,
+    },
+    /// The outline or metrics of a glyph changed
+    GlyphChanged {
+        /// The ID of the glyph
+        glyph_id: u32,
+        /// The name of the glyph
+        glyph_name: String,
+        /// What changed about the glyph
+        description: String,
+    },
+}
+
+impl std::fmt::Display for FontChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FontChange::TableAdded(tag) => write!(f, "Added table '{tag}'"),
+            FontChange::TableRemoved(tag) => write!(f, "Removed table '{tag}'"),
+            FontChange::TableChanged(tag) => write!(f, "Modified table '{tag}'"),
+            FontChange::FieldChanged {
+                table,
+                field,
+                before,
+                after,
+            } => write!(f, "{table}.{field}: {before} → {after}"),
+            FontChange::NameChanged {
+                platform: (platform_id, encoding_id, language_id),
+                name_id,
+                before,
+                after,
+            } => {
+                let record =
+                    format!("name ID {name_id} ({platform_id}, {encoding_id}, {language_id:#x})");
+                match (before, after) {
+                    (None, Some(after)) => write!(f, "Added {record}: {after:?}"),
+                    (Some(before), None) => write!(f, "Removed {record}: {before:?}"),
+                    (Some(before), Some(after)) => {
+                        write!(f, "Changed {record}: {before:?} → {after:?}")
+                    }
+                    (None, None) => write!(f, "Changed {record}"),
+                }
+            }
+            FontChange::GlyphChanged {
+                glyph_id,
+                glyph_name,
+                description,
+            } => write!(f, "Glyph {glyph_name} (gid {glyph_id}): {description}"),
+        }
+    }
+}
+
+/// Compare the `Debug` representation of a list of table fields, recording any differences
+macro_rules! compare_fields {
+    ($changes:ident, $table:literal, $before:expr, $after:expr, [$($field:ident),* $(,)?]) => {
+        $(
+            let before = format!("{:?}", $before.$field());
+            let after = format!("{:?}", $after.$field());
+            if before != after {
+                $changes.push(FontChange::FieldChanged {
+                    table: $table.to_string(),
+                    field: stringify!($field).to_string(),
+                    before,
+                    after,
+                });
+            }
+        )*
+    };
+}
+
+/// Compute the differences between two versions of a font binary
+///
+/// Tables which were added or removed are reported as such. For the `head`,
+/// `hhea`, `maxp`, `OS/2` and `post` tables, individual changed fields are
+/// reported; for the `name` table, changed name records; and for the `glyf`
+/// and `hmtx` tables, changed glyphs. Any other tables whose contents changed
+/// are reported as changed without further detail; in particular, changes to
+/// the outlines in a `CFF ` or `CFF2` table are only reported as a change to
+/// the table, not glyph by glyph.
+pub fn diff_fonts(before: &[u8], after: &[u8]) -> Result<Vec<FontChange>, FontspectorError> {
+    let before = FontRef::new(before)?;
+    let after = FontRef::new(after)?;
+    let mut changes = vec![];
+
+    let before_tags: BTreeSet<Tag> = before
+        .table_directory
+        .table_records()
+        .iter()
+        .map(|r| r.tag())
+        .collect();
+    let after_tags: BTreeSet<Tag> = after
+        .table_directory
+        .table_records()
+        .iter()
+        .map(|r| r.tag())
+        .collect();
+
+    for tag in before_tags.difference(&after_tags) {
+        changes.push(FontChange::TableRemoved(tag.to_string()));
+    }
+    for tag in after_tags.difference(&before_tags) {
+        changes.push(FontChange::TableAdded(tag.to_string()));
+    }
+
+    for tag in before_tags.intersection(&after_tags) {
+        let before_data = before.table_data(*tag).map(|d| d.as_bytes());
+        let after_data = after.table_data(*tag).map(|d| d.as_bytes());
+        if before_data == after_data {
+            continue;
+        }
+        let detailed = match &tag.to_be_bytes() {
+            b"head" => diff_head(&before, &after, &mut changes)?,
+            b"hhea" => diff_hhea(&before, &after, &mut changes)?,
+            b"maxp" => diff_maxp(&before, &after, &mut changes)?,
+            b"OS/2" => diff_os2(&before, &after, &mut changes)?,
+            b"post" => diff_post(&before, &after, &mut changes)?,
+            b"name" => diff_name(&before, &after, &mut changes)?,
+            b"glyf" => diff_glyf(&before, &after, &mut changes)?,
+            b"hmtx" => diff_hmtx(&before, &after, &mut changes)?,
+            _ => false,
+        };
+        // If we couldn't say what changed, at least say that something did
+        if !detailed {
+            changes.push(FontChange::TableChanged(tag.to_string()));
+        }
+    }
+    Ok(changes)
+}
+
+/// Report changes to the `head` table
+///
+/// Always returns true, since a `head` table which differs only in its
+/// checksum is not worth reporting.
+fn diff_head(
+    before: &FontRef,
+    after: &FontRef,
+    changes: &mut Vec<FontChange>,
+) -> Result<bool, ReadError> {
+    let (before, after) = (before.head()?, after.head()?);
+    // checksum_adjustment is deliberately omitted; it changes whenever anything else does.
+    compare_fields!(
+        changes,
+        "head",
+        before,
+        after,
+        [
+            font_revision,
+            flags,
+            units_per_em,
+            created,
+            modified,
+            x_min,
+            y_min,
+            x_max,
+            y_max,
+            mac_style,
+            lowest_rec_ppem,
+            font_direction_hint,
+            index_to_loc_format,
+        ]
+    );
+    Ok(true)
+}
+
+/// Report changes to the `hhea` table; returns true if any changes were found
+fn diff_hhea(
+    before: &FontRef,
+    after: &FontRef,
+    changes: &mut Vec<FontChange>,
+) -> Result<bool, ReadError> {
+    let count = changes.len();
+    let (before, after) = (before.hhea()?, after.hhea()?);
+    compare_fields!(
+        changes,
+        "hhea",
+        before,
+        after,
+        [
+            ascender,
+            descender,
+            line_gap,
+            advance_width_max,
+            min_left_side_bearing,
+            min_right_side_bearing,
+            x_max_extent,
+            caret_slope_rise,
+            caret_slope_run,
+            caret_offset,
+            number_of_h_metrics,
+        ]
+    );
+    Ok(changes.len() > count)
+}
+
+/// Report changes to the `maxp` table; returns true if any changes were found
+fn diff_maxp(
+    before: &FontRef,
+    after: &FontRef,
+    changes: &mut Vec<FontChange>,
+) -> Result<bool, ReadError> {
+    let count = changes.len();
+    let (before, after) = (before.maxp()?, after.maxp()?);
+    compare_fields!(
+        changes,
+        "maxp",
+        before,
+        after,
+        [
+            num_glyphs,
+            max_points,
+            max_contours,
+            max_composite_points,
+            max_composite_contours,
+            max_zones,
+            max_twilight_points,
+            max_storage,
+            max_function_defs,
+            max_instruction_defs,
+            max_stack_elements,
+            max_size_of_instructions,
+            max_component_elements,
+            max_component_depth,
+        ]
+    );
+    Ok(changes.len() > count)
+}
+
+/// Report changes to the `OS/2` table; returns true if any changes were found
+fn diff_os2(
+    before: &FontRef,
+    after: &FontRef,
+    changes: &mut Vec<FontChange>,
+) -> Result<bool, ReadError> {
+    let count = changes.len();
+    let (before, after) = (before.os2()?, after.os2()?);
+    compare_fields!(
+        changes,
+        "OS/2",
+        before,
+        after,
+        [
+            version,
+            x_avg_char_width,
+            us_weight_class,
+            us_width_class,
+            fs_type,
+            y_subscript_x_size,
+            y_subscript_y_size,
+            y_subscript_x_offset,
+            y_subscript_y_offset,
+            y_superscript_x_size,
+            y_superscript_y_size,
+            y_superscript_x_offset,
+            y_superscript_y_offset,
+            y_strikeout_size,
+            y_strikeout_position,
+            s_family_class,
+            panose_10,
+            ul_unicode_range_1,
+            ul_unicode_range_2,
+            ul_unicode_range_3,
+            ul_unicode_range_4,
+            ach_vend_id,
+            fs_selection,
+            us_first_char_index,
+            us_last_char_index,
+            s_typo_ascender,
+            s_typo_descender,
+            s_typo_line_gap,
+            us_win_ascent,
+            us_win_descent,
+            ul_code_page_range_1,
+            ul_code_page_range_2,
+            sx_height,
+            s_cap_height,
+            us_default_char,
+            us_break_char,
+            us_max_context,
+            us_lower_optical_point_size,
+            us_upper_optical_point_size,
+        ]
+    );
+    Ok(changes.len() > count)
+}
+
+/// Report changes to the `post` table; returns true if any changes were found
+fn diff_post(
+    before: &FontRef,
+    after: &FontRef,
+    changes: &mut Vec<FontChange>,
+) -> Result<bool, ReadError> {
+    let count = changes.len();
+    let (before, after) = (before.post()?, after.post()?);
+    compare_fields!(
+        changes,
+        "post",
+        before,
+        after,
+        [
+            version,
+            italic_angle,
+            underline_position,
+            underline_thickness,
+            is_fixed_pitch,
+        ]
+    );
+    Ok(changes.len() > count)
+}
+
+/// Gather the name table into a map of (platform, encoding, language, name ID) to string
+fn name_records(font: &FontRef) -> Result<BTreeMap<(u16, u16, u16, u16), String>, ReadError> {
+    let name = font.name()?;
+    Ok(name
+        .name_record()
+        .iter()
+        .filter_map(|record| {
+            let string = record.string(name.string_data()).ok()?;
+            Some((
+                (
+                    record.platform_id(),
+                    record.encoding_id(),
+                    record.language_id(),
+                    record.name_id().to_u16(),
+                ),
+                string.to_string(),
+            ))
+        })
+        .collect())
+}
+
+/// Report changes to the `name` table; returns true if any changes were found
+fn diff_name(
+    before: &FontRef,
+    after: &FontRef,
+    changes: &mut Vec<FontChange>,
+) -> Result<bool, ReadError> {
+    let count = changes.len();
+    let before = name_records(before)?;
+    let after = name_records(after)?;
+    let keys: BTreeSet<_> = before.keys().chain(after.keys()).collect();
+    for key @ (platform_id, encoding_id, language_id, name_id) in keys {
+        let (old, new) = (before.get(key), after.get(key));
+        if old != new {
+            changes.push(FontChange::NameChanged {
+                platform: (*platform_id, *encoding_id, *language_id),
+                name_id: *name_id,
+                before: old.cloned(),
+                after: new.cloned(),
+            });
+        }
+    }
+    Ok(changes.len() > count)
+}
+
+/// The name of a glyph in the font after the change, for reporting
+fn glyph_name(font: &FontRef, gid: GlyphId) -> String {
+    GlyphNames::new(font)
+        .get(gid)
+        .map(|name| name.as_str().to_string())
+        .unwrap_or_else(|| format!("gid{}", gid.to_u32()))
+}
+
+/// Report changes to the `glyf` table; returns true if any changes were found
+fn diff_glyf(
+    before: &FontRef,
+    after: &FontRef,
+    changes: &mut Vec<FontChange>,
+) -> Result<bool, ReadError> {
+    let count = changes.len();
+    let (before_loca, before_glyf) = (before.loca(None)?, before.glyf()?);
+    let (after_loca, after_glyf) = (after.loca(None)?, after.glyf()?);
+    let glyph_count = before_loca.len().max(after_loca.len()).saturating_sub(1);
+    for gid in (0..glyph_count as u32).map(GlyphId::new) {
+        let old = before_loca
+            .get_glyf(gid, &before_glyf)
+            .ok()
+            .flatten()
+            .map(|g| g.offset_data().as_bytes());
+        let new = after_loca
+            .get_glyf(gid, &after_glyf)
+            .ok()
+            .flatten()
+            .map(|g| g.offset_data().as_bytes());
+        if old == new {
+            continue;
+        }
+        let description = match (old, new) {
+            (None, Some(_)) => "outline added",
+            (Some(_), None) => "outline removed",
+            _ => "outline changed",
+        };
+        changes.push(FontChange::GlyphChanged {
+            glyph_id: gid.to_u32(),
+            glyph_name: glyph_name(after, gid),
+            description: description.to_string(),
+        });
+    }
+    Ok(changes.len() > count)
+}
+
+/// Report changes to the `hmtx` table; returns true if any changes were found
+fn diff_hmtx(
+    before: &FontRef,
+    after: &FontRef,
+    changes: &mut Vec<FontChange>,
+) -> Result<bool, ReadError> {
+    let count = changes.len();
+    let (before_hmtx, after_hmtx) = (before.hmtx()?, after.hmtx()?);
+    let glyph_count = before.maxp()?.num_glyphs().max(after.maxp()?.num_glyphs());
+    for gid in (0..glyph_count as u32).map(GlyphId::new) {
+        let old = (before_hmtx.advance(gid), before_hmtx.side_bearing(gid));
+        let new = (after_hmtx.advance(gid), after_hmtx.side_bearing(gid));
+        if old.0 != new.0 {
+            changes.push(FontChange::GlyphChanged {
+                glyph_id: gid.to_u32(),
+                glyph_name: glyph_name(after, gid),
+                description: format!(
+                    "advance width {} → {}",
+                    old.0.unwrap_or_default(),
+                    new.0.unwrap_or_default()
+                ),
+            });
+        }
+        if old.1 != new.1 {
+            changes.push(FontChange::GlyphChanged {
+                glyph_id: gid.to_u32(),
+                glyph_name: glyph_name(after, gid),
+                description: format!(
+                    "left side bearing {} → {}",
+                    old.1.unwrap_or_default(),
+                    new.1.unwrap_or_default()
+                ),
+            });
+        }
+    }
+    Ok(changes.len() > count)
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used, clippy::indexing_slicing)]
+    use super::*;
+    use crate::codetesting::{
+        edit_font, remove_table, set_name_entry, set_weight_class, test_able,
+    };
+    use fontations::{
+        skrifa::{raw::types::NameId, MetadataProvider},
+        write::tables::glyf::Glyph,
+    };
+
+    const FONT: &str = "nunito/Nunito-Regular.ttf";
+
+    fn diff_after(edit: impl FnOnce(&mut crate::Testable)) -> Vec<FontChange> {
+        let before = test_able(FONT);
+        let mut after = before.clone();
+        edit(&mut after);
+        diff_fonts(&before.contents, &after.contents).unwrap()
+    }
+
+    #[test]
+    fn test_diff_unchanged() {
+        let font = test_able(FONT);
+        assert_eq!(diff_fonts(&font.contents, &font.contents).unwrap(), vec![]);
+    }
+
+    #[test]
+    fn test_diff_name() {
+        let changes = diff_after(|font| {
+            set_name_entry(
+                font,
+                3,
+                1,
+                0x409,
+                NameId::FAMILY_NAME,
+                "Changed".to_string(),
+            )
+        });
+        assert!(matches!(
+            changes.as_slice(),
+            [FontChange::NameChanged {
+                platform: (3, 1, 0x409),
+                name_id: 1,
+                before: Some(_),
+                after: Some(after),
+            }] if after == "Changed"
+        ));
+    }
+
+    #[test]
+    fn test_diff_os2() {
+        let changes = diff_after(|font| set_weight_class(font, 333).unwrap());
+        assert_eq!(
+            changes,
+            vec![FontChange::FieldChanged {
+                table: "OS/2".to_string(),
+                field: "us_weight_class".to_string(),
+                before: "400".to_string(),
+                after: "333".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_diff_head() {
+        let font = test_able(FONT);
+        let ppem = FontRef::new(&font.contents)
+            .unwrap()
+            .head()
+            .unwrap()
+            .lowest_rec_ppem();
+        let changes = diff_after(|font| {
+            edit_font(font).head(|head| head.lowest_rec_ppem = ppem + 1);
+        });
+        assert_eq!(
+            changes,
+            vec![FontChange::FieldChanged {
+                table: "head".to_string(),
+                field: "lowest_rec_ppem".to_string(),
+                before: ppem.to_string(),
+                after: (ppem + 1).to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_diff_glyf() {
+        let font = test_able(FONT);
+        let gid = FontRef::new(&font.contents)
+            .unwrap()
+            .charmap()
+            .map('A')
+            .unwrap();
+        let changes = diff_after(|font| {
+            edit_font(font).glyph(gid, |glyph| *glyph = Glyph::Empty);
+        });
+        // Rebuilding the glyf table may also re-encode other glyphs
+        assert!(changes.iter().any(|change| matches!(
+            change,
+            FontChange::GlyphChanged { glyph_id, description, .. }
+                if *glyph_id == gid.to_u32() && description == "outline removed"
+        )));
+    }
+
+    #[test]
+    fn test_diff_hmtx() {
+        let changes = diff_after(|font| {
+            let offset = FontRef::new(&font.contents)
+                .unwrap()
+                .table_directory
+                .table_records()
+                .iter()
+                .find(|record| record.tag() == Tag::new(b"hmtx"))
+                .unwrap()
+                .offset() as usize;
+            // The advance width of the first glyph
+            let advance = u16::from_be_bytes([font.contents[offset], font.contents[offset + 1]]);
+            font.contents[offset..offset + 2].copy_from_slice(&(advance + 1).to_be_bytes());
+        });
+        assert!(matches!(
+            changes.as_slice(),
+            [FontChange::GlyphChanged { glyph_id: 0, description, .. }]
+                if description.starts_with("advance width")
+        ));
+    }
+
+    #[test]
+    fn test_diff_tables() {
+        let before = test_able(FONT);
+        let mut after = before.clone();
+        remove_table(&mut after, b"DSIG");
+        assert_eq!(
+            diff_fonts(&before.contents, &after.contents).unwrap(),
+            vec![FontChange::TableRemoved("DSIG".to_string())]
+        );
+        assert_eq!(
+            diff_fonts(&after.contents, &before.contents).unwrap(),
+            vec![FontChange::TableAdded("DSIG".to_string())]
+        );
+    }
+}
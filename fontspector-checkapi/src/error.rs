@@ -1,4 +1,4 @@
-use std::sync::PoisonError;
+use std::{sync::PoisonError, time::Duration};
 
 use thiserror::Error;
 
@@ -68,6 +68,12 @@ pub enum FontspectorError {
     /// Something else happened when checking the font
     #[error("Something went wrong: {0}")]
     General(String),
+    /// The check panicked
+    #[error("Check panicked: {0}")]
+    Panic(String),
+    /// The check ran for longer than its time limit
+    #[error("Check took longer than its time limit of {0:?}")]
+    Timeout(Duration),
     /// Something else happened when fixing the font
     #[error("Something went wrong while fixing: {0}")]
     Fix(String),
@@ -79,9 +85,25 @@ pub enum FontspectorError {
         /// The unknown filetype
         filetype: String,
     },
+    /// Check depends on a condition that isn't registered in the registry
+    #[error("Check {check_id} depends on unknown condition {condition}")]
+    UnknownCondition {
+        /// The id of the check with the unknown condition
+        check_id: String,
+        /// The condition, as written in the check definition
+        condition: String,
+    },
     /// A profile includes another profile that isn't registered in the registry
     #[error("Profile includes unknown profile: {0}")]
     UnknownProfile(String),
+    /// A profile could not be parsed from its TOML definition
+    #[error("Could not parse profile {name}: {message}")]
+    ProfileParse {
+        /// The name of the profile
+        name: String,
+        /// The error message from the TOML parser
+        message: String,
+    },
 }
 
 impl From<std::string::FromUtf8Error> for FontspectorError {
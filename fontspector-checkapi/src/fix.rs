@@ -1,9 +1,9 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, path::Path};
 
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-use crate::{FontspectorError, Testable};
+use crate::{CheckId, FontspectorError, Testable};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 /// The result of a fix operation.
@@ -118,6 +118,119 @@ impl DialogField {
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct MoreInfoReplies(pub HashMap<String, Value>);
 
+/// Answers to hotfix dialogs supplied ahead of time, keyed by check ID
+///
+/// This allows hotfixes which return [FixResult::MoreInfoNeeded] to be applied
+/// without a user present to answer questions, e.g. on a CI server. In TOML,
+/// an answers file looks like this:
+///
+/// ```toml
+/// ["googlefonts/vendor_id"]
+/// vendor_id = "ABCD"
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct HotfixAnswers(pub HashMap<CheckId, MoreInfoReplies>);
+
+impl HotfixAnswers {
+    /// Load answers from a TOML or JSON file
+    pub fn from_file(path: &Path) -> Result<Self, FontspectorError> {
+        let contents = std::fs::read_to_string(path)?;
+        match path.extension().and_then(|x| x.to_str()) {
+            Some("toml") => toml::from_str(&contents).map_err(|e| {
+                FontspectorError::Fix(format!(
+                    "Could not parse answers file {}: {e}",
+                    path.display()
+                ))
+            }),
+            Some("json") => Ok(serde_json::from_str(&contents)?),
+            _ => Err(FontspectorError::Fix(format!(
+                "Answers file must be in TOML or JSON format: {}",
+                path.display()
+            ))),
+        }
+    }
+
+    /// Answer a dialog request for a check
+    ///
+    /// Returns an error if there are no answers for this check, or if any of
+    /// the fields in the request are not answered (or, for choice fields, are
+    /// not answered with one of the available choices).
+    pub fn answer(
+        &self,
+        check_id: &str,
+        request: &MoreInfoRequest,
+    ) -> Result<MoreInfoReplies, FontspectorError> {
+        let replies = self.0.get(check_id).ok_or_else(|| {
+            FontspectorError::Fix(format!("No answers were provided for check {check_id}"))
+        })?;
+        for field in request.0.iter() {
+            let Some(value) = replies.0.get(&field.key) else {
+                return Err(FontspectorError::Fix(format!(
+                    "No answer was provided for {check_id} field '{}' ({})",
+                    field.key, field.prompt
+                )));
+            };
+            let valid = match &field.field_type {
+                DialogFieldType::Choice(choices) => value
+                    .as_str()
+                    .is_some_and(|v| choices.iter().any(|c| c.value == v)),
+                DialogFieldType::Text => value.is_string(),
+                DialogFieldType::Number => value.is_number(),
+                DialogFieldType::Boolean => value.is_boolean(),
+            };
+            if !valid {
+                return Err(FontspectorError::Fix(format!(
+                    "Invalid answer {value} for {check_id} field '{}'",
+                    field.key
+                )));
+            }
+        }
+        Ok(replies.clone())
+    }
+}
+
 /// The function signature for a hotfix function
 pub type HotfixFunction =
     dyn Fn(&mut Testable, Option<MoreInfoReplies>) -> Result<FixResult, FontspectorError>;
+
+/// The function signature for a source fix function
+///
+/// The function is given the binary which was checked, whose `source` field
+/// identifies the source to be fixed; see [crate::source] for helpers.
+pub type SourceFixFunction =
+    dyn Fn(&Testable, Option<MoreInfoReplies>) -> Result<FixResult, FontspectorError>;
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hotfix_answers() {
+        let answers: HotfixAnswers = toml::from_str(
+            r#"
+            ["vendor_id"]
+            vendor_id = "ABCD"
+            license = "OFL"
+            "#,
+        )
+        .unwrap();
+        let request = MoreInfoRequest(vec![
+            DialogField::new_text("vendor_id", "Vendor ID?"),
+            DialogField::new_choice("license", "License?", vec![("OFL", "SIL OFL")]),
+        ]);
+        assert!(answers.answer("vendor_id", &request).is_ok());
+        assert!(answers.answer("other_check", &request).is_err());
+
+        let mut request = request;
+        request.0.push(DialogField::new_boolean("confirm", "Sure?"));
+        assert!(answers.answer("vendor_id", &request).is_err());
+
+        let request = MoreInfoRequest(vec![DialogField::new_choice(
+            "license",
+            "License?",
+            vec![("Apache", "Apache 2.0")],
+        )]);
+        assert!(answers.answer("vendor_id", &request).is_err());
+    }
+}
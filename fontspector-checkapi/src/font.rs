@@ -1,12 +1,16 @@
+#[cfg(feature = "kurbo")]
+use crate::pens::BezGlyph;
 use crate::{
     constants::{OutlineType, RIBBI_STYLE_NAMES, STATIC_STYLE_NAMES},
     error::FontspectorError,
     filetype::FileTypeConvert,
+    gsub::{gsub_closure, ClosureOptions, GetSubstitutionMap, GsubClosure, SubstitutionMap},
     Context, FileType, Testable,
 };
 use fontations::{
     read::{tables::name::NameString, TopLevelTable},
     skrifa::{
+        charmap::MapVariant,
         font::FontRef,
         outline::{DrawSettings, OutlinePen},
         prelude::Size,
@@ -30,10 +34,13 @@ use fontations::{
 use fontdrasil::coords::{CoordConverter, DesignCoord, NormalizedCoord, UserCoord};
 use itertools::Either;
 use std::{
-    collections::{BTreeMap, HashMap, HashSet},
+    any::Any,
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     error::Error,
     fmt::{Debug, Formatter},
+    hash::{Hash, Hasher},
     path::{Path, PathBuf},
+    sync::Arc,
 };
 
 /// A Font to be tested
@@ -245,6 +252,8 @@ impl TestFont<'_> {
     pub fn outline_type(&self) -> OutlineType {
         if self.has_table(b"glyf") {
             OutlineType::TrueType
+        } else if self.has_table(b"CFF2") {
+            OutlineType::CFF2
         } else {
             OutlineType::CFF
         }
@@ -273,33 +282,40 @@ impl TestFont<'_> {
         )
     }
 
+    /// Compute some data derived from the font, or fetch it if it has been computed already
+    ///
+    /// `what` names the data; together with the font's filename, it makes the
+    /// key under which the answer is kept in the context's [memo](Context::memo).
+    /// Without a context, the data is computed every time.
+    pub fn memoize<T>(
+        &self,
+        context: Option<&Context>,
+        what: &str,
+        func: impl FnOnce() -> Result<T, FontspectorError>,
+    ) -> Result<Arc<T>, FontspectorError>
+    where
+        T: Any + Send + Sync,
+    {
+        match context {
+            Some(context) => {
+                context.memoize(&format!("{what}:{}", self.filename.to_string_lossy()), func)
+            }
+            None => func().map(Arc::new),
+        }
+    }
+
     /// The set of Unicode codepoints in the font
     pub fn codepoints(&self, context: Option<&Context>) -> HashSet<u32> {
-        let get_codepoints = || {
+        self.memoize(context, "codepoints", || {
             Ok(self
                 .font()
                 .charmap()
                 .mappings()
                 .map(|(u, _gid)| u)
                 .collect::<HashSet<u32>>())
-        };
-        if let Some(context) = context {
-            let key = "codepoints:".to_string() + &self.filename.to_string_lossy();
-            #[allow(clippy::unwrap_used)] // How can it fail?!
-            context
-                .cached_question(
-                    &key,
-                    get_codepoints,
-                    |hashset| serde_json::to_value(hashset).unwrap(),
-                    |value| {
-                        serde_json::from_value(value.clone())
-                            .map_err(|e| FontspectorError::CacheSerialization(e.to_string()))
-                    },
-                )
-                .unwrap_or_default()
-        } else {
-            get_codepoints().unwrap_or_default()
-        }
+        })
+        .map(|codepoints| codepoints.as_ref().clone())
+        .unwrap_or_default()
     }
 
     /// Returns an iterator over the named instances in the font.
@@ -357,6 +373,25 @@ impl TestFont<'_> {
         Ok(())
     }
 
+    /// A glyph's outline at the default location, as Kurbo paths
+    ///
+    /// Stops with a timeout error if the check has run out of time.
+    #[cfg(feature = "kurbo")]
+    pub fn bez_glyph(
+        &self,
+        gid: GlyphId,
+        context: Option<&Context>,
+    ) -> Result<Arc<BezGlyph>, FontspectorError> {
+        if let Some(context) = context {
+            context.check_deadline()?;
+        }
+        self.memoize(context, &format!("bez_glyph:{}", gid.to_u32()), || {
+            let mut pen = BezGlyph::default();
+            self.draw_glyph(gid, &mut pen, DEFAULT_LOCATION)?;
+            Ok(pen)
+        })
+    }
+
     /// Returns the font's FeatureRecord and associated Feature tables
     ///
     /// If `gsub_only` is true, only searches in the `GSUB` table.
@@ -401,6 +436,9 @@ impl TestFont<'_> {
     }
 
     /// An iterator of all glyphs in the font
+    ///
+    /// Checks which do much work for each glyph should call
+    /// [Context::check_deadline] as they go.
     pub fn all_glyphs(&self) -> impl Iterator<Item = GlyphId> {
         (0..self.glyph_count as u32).map(GlyphId::from)
     }
@@ -455,6 +493,68 @@ impl TestFont<'_> {
         )
     }
 
+    /// The glyphs reachable from a set of codepoints through the cmap and GSUB
+    ///
+    /// The starting glyphs are those the codepoints are mapped to, including
+    /// through Unicode variation sequences. From there, the GSUB closure is
+    /// computed as described in [gsub_closure](crate::gsub_closure), following
+    /// only the features, script and language allowed by `options`. If a
+    /// context is given, the answer is cached in it.
+    pub fn gsub_closure(
+        &self,
+        codepoints: impl IntoIterator<Item = u32>,
+        options: &ClosureOptions,
+        context: Option<&Context>,
+    ) -> Result<Arc<GsubClosure>, FontspectorError> {
+        let codepoints: BTreeSet<u32> = codepoints.into_iter().collect();
+        let get_closure = || -> Result<GsubClosure, FontspectorError> {
+            let charmap = self.font().charmap();
+            let mut initial: HashSet<GlyphId16> = codepoints
+                .iter()
+                .filter_map(|cp| charmap.map(*cp))
+                .filter_map(|gid| GlyphId16::try_from(gid).ok())
+                .collect();
+            for (cp, _, variant) in charmap.variant_mappings() {
+                if let MapVariant::Variant(gid) = variant {
+                    if codepoints.contains(&cp) {
+                        initial.extend(GlyphId16::try_from(gid).ok());
+                    }
+                }
+            }
+            match self.font().gsub() {
+                Ok(gsub) => Ok(gsub_closure(&gsub, initial, options)?),
+                // No GSUB table: only the encoded glyphs are reachable
+                Err(_) => Ok(GsubClosure::new(initial)),
+            }
+        };
+        let mut hasher = std::hash::DefaultHasher::new();
+        codepoints.hash(&mut hasher);
+        options.hash(&mut hasher);
+        let what = format!("gsub_closure:{:x}", hasher.finish());
+        self.memoize(context, &what, get_closure)
+    }
+
+    /// The substitutions made by each lookup in the font's GSUB table
+    ///
+    /// The map for a lookup is found at the lookup's index. Contextual
+    /// lookups make no substitutions of their own, so their maps are empty.
+    pub fn gsub_substitutions(
+        &self,
+        context: Option<&Context>,
+    ) -> Result<Arc<Vec<SubstitutionMap>>, FontspectorError> {
+        self.memoize(context, "gsub_substitutions", || {
+            self.font()
+                .gsub()?
+                .lookup_list()?
+                .lookups()
+                .iter()
+                .map(|lookup| -> Result<SubstitutionMap, FontspectorError> {
+                    Ok(lookup?.subtables()?.substitutions()?)
+                })
+                .collect()
+        })
+    }
+
     /// Get the best name from a list of name IDs
     pub fn get_best_name(&self, ids: &[StringId]) -> Option<String> {
         for id in ids {
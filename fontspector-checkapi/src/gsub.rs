@@ -1,18 +1,24 @@
 // Code to make GSUB tables easier to work with
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
 use fontations::skrifa::{
     raw::{
         tables::{
             gsub::{
-                AlternateSubstFormat1, ExtensionSubstFormat1, LigatureSubstFormat1,
+                AlternateSubstFormat1, ExtensionSubstFormat1, Gsub, LigatureSubstFormat1,
                 MultipleSubstFormat1, ReverseChainSingleSubstFormat1, SingleSubst,
-                SubstitutionSubtables,
+                SubstitutionLookupList, SubstitutionSubtables,
+            },
+            layout::{
+                ChainedSequenceContext, ClassDef, CoverageTable, Feature, SequenceContext,
+                SequenceLookupRecord, Subtables,
             },
-            layout::Subtables,
         },
         ReadError,
     },
-    GlyphId16,
+    GlyphId, GlyphId16, Tag,
 };
+use serde::{Deserialize, Serialize};
 
 /// A map of substitutions, input glyphs on the left, output glyphs on the right
 pub type SubstitutionMap = Vec<(Vec<GlyphId16>, Vec<GlyphId16>)>;
@@ -148,3 +154,689 @@ impl GetSubstitutionMap for SubstitutionSubtables<'_> {
         }
     }
 }
+
+/// Which parts of the GSUB table to follow when computing a closure
+///
+/// Each field left as `None` places no restriction on the closure.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ClosureOptions {
+    /// Only follow lookups in these features
+    pub features: Option<Vec<String>>,
+    /// Only follow features available to this script
+    pub script: Option<String>,
+    /// Only follow features available to this language system. If a script
+    /// does not have this language system, its default is used instead.
+    pub language: Option<String>,
+}
+
+/// The glyphs which can be reached from a set of glyphs through substitution
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GsubClosure {
+    /// Each reachable glyph ID, and the features whose lookups can produce it
+    glyphs: BTreeMap<u32, BTreeSet<String>>,
+    /// Each glyph ID which starts a substitution, and the features whose
+    /// lookups make it
+    substituted: BTreeMap<u32, BTreeSet<String>>,
+}
+
+impl GsubClosure {
+    /// A closure holding only the starting glyphs
+    pub(crate) fn new(initial: impl IntoIterator<Item = GlyphId16>) -> Self {
+        let mut closure = Self::default();
+        let no_features = BTreeSet::new();
+        for gid in initial {
+            closure.add(gid, &no_features);
+        }
+        closure
+    }
+
+    /// Is this glyph reachable?
+    pub fn contains(&self, gid: impl Into<GlyphId>) -> bool {
+        self.glyphs.contains_key(&gid.into().to_u32())
+    }
+
+    /// The features whose lookups can produce this glyph
+    ///
+    /// This is empty both for glyphs which are only reachable because they
+    /// were in the starting set, and for glyphs which are not reachable at all.
+    pub fn features(&self, gid: impl Into<GlyphId>) -> impl Iterator<Item = &str> {
+        self.glyphs
+            .get(&gid.into().to_u32())
+            .into_iter()
+            .flatten()
+            .map(|x| x.as_str())
+    }
+
+    /// The features whose lookups can replace this glyph with something else
+    ///
+    /// A glyph counts as replaced if it is the first glyph of a substitution
+    /// which can happen; the other components of a ligature don't.
+    pub fn substituted_by(&self, gid: impl Into<GlyphId>) -> impl Iterator<Item = &str> {
+        self.substituted
+            .get(&gid.into().to_u32())
+            .into_iter()
+            .flatten()
+            .map(|x| x.as_str())
+    }
+
+    /// All reachable glyphs
+    pub fn glyphs(&self) -> impl Iterator<Item = GlyphId> + '_ {
+        self.glyphs.keys().map(|gid| GlyphId::new(*gid))
+    }
+
+    /// The number of reachable glyphs
+    pub fn len(&self) -> usize {
+        self.glyphs.len()
+    }
+
+    /// Are there no reachable glyphs?
+    pub fn is_empty(&self) -> bool {
+        self.glyphs.is_empty()
+    }
+
+    /// Add a glyph, returning true if the closure grew
+    fn add(&mut self, gid: GlyphId16, features: &BTreeSet<String>) -> bool {
+        let entry = self.glyphs.entry(gid.to_u32()).or_default();
+        let before = entry.len();
+        entry.extend(features.iter().cloned());
+        entry.len() != before
+    }
+
+    /// Record that a substitution starting with this glyph can happen
+    fn substitute(&mut self, gid: GlyphId16, features: &BTreeSet<String>) {
+        self.substituted
+            .entry(gid.to_u32())
+            .or_default()
+            .extend(features.iter().cloned());
+    }
+
+    fn has(&self, gid: GlyphId16) -> bool {
+        self.glyphs.contains_key(&gid.to_u32())
+    }
+
+    fn has_all(&self, gids: &[GlyphId16]) -> bool {
+        gids.iter().all(|gid| self.has(*gid))
+    }
+}
+
+/// How deeply to follow lookups which are called from contextual lookups
+const MAX_NESTING: u8 = 8;
+
+fn tag_matches(tag: Tag, wanted: &str) -> bool {
+    tag.to_string().trim_end() == wanted.trim_end()
+}
+
+/// Find the lookups to follow, and the features they belong to
+fn lookups_for_options(
+    gsub: &Gsub,
+    options: &ClosureOptions,
+) -> Result<BTreeMap<u16, BTreeSet<String>>, ReadError> {
+    let feature_list = gsub.feature_list()?;
+    let feature_records = feature_list.feature_records();
+
+    // Which feature indices can be reached by the requested script and language?
+    let mut feature_indices: BTreeSet<u16> = BTreeSet::new();
+    if options.script.is_none() && options.language.is_none() {
+        feature_indices.extend(0..feature_records.len() as u16);
+    } else {
+        let script_list = gsub.script_list()?;
+        for script_record in script_list.script_records() {
+            if let Some(script) = &options.script {
+                if !tag_matches(script_record.script_tag(), script) {
+                    continue;
+                }
+            }
+            let script = script_record.script(script_list.offset_data())?;
+            let mut lang_syses = vec![];
+            let language = options.language.as_ref().and_then(|language| {
+                script
+                    .lang_sys_records()
+                    .iter()
+                    .find(|record| tag_matches(record.lang_sys_tag(), language))
+            });
+            match (language, &options.language) {
+                (Some(record), _) => lang_syses.push(record.lang_sys(script.offset_data())?),
+                (None, Some(_)) => lang_syses.extend(script.default_lang_sys().transpose()?),
+                (None, None) => {
+                    lang_syses.extend(script.default_lang_sys().transpose()?);
+                    for record in script.lang_sys_records() {
+                        lang_syses.push(record.lang_sys(script.offset_data())?);
+                    }
+                }
+            }
+            for lang_sys in lang_syses {
+                if lang_sys.required_feature_index() != 0xFFFF {
+                    feature_indices.insert(lang_sys.required_feature_index());
+                }
+                feature_indices.extend(lang_sys.feature_indices().iter().map(|i| i.get()));
+            }
+        }
+    }
+
+    // Alternate versions of these features used in some regions of a
+    // variable font's design space
+    let mut alternates: HashMap<u16, Vec<Feature>> = HashMap::new();
+    if let Some(variations) = gsub.feature_variations().transpose()? {
+        for record in variations.feature_variation_records() {
+            let Some(substitution) = record
+                .feature_table_substitution(variations.offset_data())
+                .transpose()?
+            else {
+                continue;
+            };
+            for sub in substitution.substitutions() {
+                alternates
+                    .entry(sub.feature_index())
+                    .or_default()
+                    .push(sub.alternate_feature(substitution.offset_data())?);
+            }
+        }
+    }
+
+    let mut lookups: BTreeMap<u16, BTreeSet<String>> = BTreeMap::new();
+    for index in feature_indices {
+        let Some(record) = feature_records.get(index as usize) else {
+            continue;
+        };
+        let tag = record.feature_tag().to_string();
+        if let Some(features) = &options.features {
+            if !features
+                .iter()
+                .any(|f| tag_matches(record.feature_tag(), f))
+            {
+                continue;
+            }
+        }
+        let feature = record.feature(feature_list.offset_data())?;
+        for feature in
+            std::iter::once(feature).chain(alternates.remove(&index).into_iter().flatten())
+        {
+            for lookup in feature.lookup_list_indices() {
+                lookups.entry(lookup.get()).or_default().insert(tag.clone());
+            }
+        }
+    }
+    Ok(lookups)
+}
+
+/// Work out the glyphs reachable from `initial` through the GSUB table
+///
+/// Lookups are followed until no more glyphs can be reached, including lookups
+/// called from contextual and chained contextual lookups, which are applied
+/// only to the glyphs which could match at that position in the context.
+/// Glyphs reached through a contextual lookup are attributed to the features
+/// of the contextual lookup. This is an over-approximation: a glyph may be
+/// listed as reachable if its context can be formed from reachable glyphs,
+/// even if no real text would shape that way.
+pub fn gsub_closure(
+    gsub: &Gsub,
+    initial: impl IntoIterator<Item = GlyphId16>,
+    options: &ClosureOptions,
+) -> Result<GsubClosure, ReadError> {
+    let lookup_list = gsub.lookup_list()?;
+    let lookups = lookups_for_options(gsub, options)?;
+    let mut closure = GsubClosure::new(initial);
+    loop {
+        let mut grew = false;
+        for (lookup, features) in lookups.iter() {
+            grew |= apply_lookup(&lookup_list, *lookup, None, features, &mut closure, 0)?;
+        }
+        if !grew {
+            return Ok(closure);
+        }
+    }
+}
+
+/// Apply a lookup to the closure, returning true if the closure grew
+///
+/// If `active` is given, only substitutions starting with those glyphs
+/// are applied.
+fn apply_lookup(
+    lookup_list: &SubstitutionLookupList,
+    lookup: u16,
+    active: Option<&HashSet<GlyphId16>>,
+    features: &BTreeSet<String>,
+    closure: &mut GsubClosure,
+    depth: u8,
+) -> Result<bool, ReadError> {
+    if depth > MAX_NESTING {
+        return Ok(false);
+    }
+    let Ok(lookup) = lookup_list.lookups().get(lookup as usize) else {
+        return Ok(false);
+    };
+    let subtables = lookup.subtables()?;
+    let mut grew = false;
+    match &subtables {
+        SubstitutionSubtables::Contextual(contexts) => {
+            for context in contexts.iter() {
+                for (nested, glyphs) in sequence_context_lookups(&context?, closure)? {
+                    grew |= apply_lookup(
+                        lookup_list,
+                        nested,
+                        Some(&filter_active(glyphs, active)),
+                        features,
+                        closure,
+                        depth + 1,
+                    )?;
+                }
+            }
+        }
+        SubstitutionSubtables::ChainContextual(contexts) => {
+            for context in contexts.iter() {
+                for (nested, glyphs) in chained_context_lookups(&context?, closure)? {
+                    grew |= apply_lookup(
+                        lookup_list,
+                        nested,
+                        Some(&filter_active(glyphs, active)),
+                        features,
+                        closure,
+                        depth + 1,
+                    )?;
+                }
+            }
+        }
+        _ => {
+            for (lhs, rhs) in subtables.substitutions()? {
+                let Some((first, rest)) = lhs.split_first() else {
+                    continue;
+                };
+                let first_active = match active {
+                    Some(active) => active.contains(first),
+                    None => closure.has(*first),
+                };
+                if first_active && closure.has_all(rest) {
+                    closure.substitute(*first, features);
+                    for gid in rhs {
+                        grew |= closure.add(gid, features);
+                    }
+                }
+            }
+        }
+    }
+    Ok(grew)
+}
+
+fn filter_active(
+    glyphs: HashSet<GlyphId16>,
+    active: Option<&HashSet<GlyphId16>>,
+) -> HashSet<GlyphId16> {
+    match active {
+        Some(active) => glyphs.intersection(active).copied().collect(),
+        None => glyphs,
+    }
+}
+
+/// The reachable glyphs in a coverage table
+fn covered(coverage: &CoverageTable, closure: &GsubClosure) -> HashSet<GlyphId16> {
+    coverage.iter().filter(|gid| closure.has(*gid)).collect()
+}
+
+/// The glyph on its own if it is reachable, or nothing if it isn't
+fn reachable(gid: GlyphId16, closure: &GsubClosure) -> HashSet<GlyphId16> {
+    if closure.has(gid) {
+        HashSet::from([gid])
+    } else {
+        HashSet::new()
+    }
+}
+
+/// The reachable glyphs in each class
+///
+/// Class 0 holds the reachable glyphs which the class definition doesn't
+/// mention.
+fn classes(class_def: &ClassDef, closure: &GsubClosure) -> HashMap<u16, HashSet<GlyphId16>> {
+    let mut classes: HashMap<u16, HashSet<GlyphId16>> = HashMap::new();
+    for gid in closure
+        .glyphs
+        .keys()
+        .filter_map(|gid| GlyphId16::try_from(GlyphId::new(*gid)).ok())
+    {
+        classes.entry(class_def.get(gid)).or_default().insert(gid);
+    }
+    classes
+}
+
+fn in_class(classes: &HashMap<u16, HashSet<GlyphId16>>, class: u16) -> HashSet<GlyphId16> {
+    classes.get(&class).cloned().unwrap_or_default()
+}
+
+/// Given the glyphs which can appear at each position of a rule's input,
+/// find the lookups it calls and the glyphs they can be applied to
+///
+/// Rules are only followed if every position of the context, including
+/// backtrack and lookahead, can be filled with reachable glyphs.
+fn rule_lookups(
+    input: Vec<HashSet<GlyphId16>>,
+    context_possible: bool,
+    records: &[SequenceLookupRecord],
+) -> Vec<(u16, HashSet<GlyphId16>)> {
+    if !context_possible || input.iter().any(|glyphs| glyphs.is_empty()) {
+        return vec![];
+    }
+    records
+        .iter()
+        .filter_map(|record| {
+            let glyphs = input.get(record.sequence_index() as usize)?;
+            Some((record.lookup_list_index(), glyphs.clone()))
+        })
+        .collect()
+}
+
+fn sequence_context_lookups(
+    context: &SequenceContext,
+    closure: &GsubClosure,
+) -> Result<Vec<(u16, HashSet<GlyphId16>)>, ReadError> {
+    let mut result = vec![];
+    match context {
+        SequenceContext::Format1(table) => {
+            for (first, rule_set) in table.coverage()?.iter().zip(table.seq_rule_sets().iter()) {
+                let Some(rule_set) = rule_set.transpose()? else {
+                    continue;
+                };
+                if !closure.has(first) {
+                    continue;
+                }
+                for rule in rule_set.seq_rules().iter() {
+                    let rule = rule?;
+                    let input = std::iter::once(first)
+                        .chain(rule.input_sequence().iter().map(|g| g.get()))
+                        .map(|gid| reachable(gid, closure))
+                        .collect();
+                    result.extend(rule_lookups(input, true, rule.seq_lookup_records()));
+                }
+            }
+        }
+        SequenceContext::Format2(table) => {
+            let class_def = table.class_def()?;
+            let input_classes = classes(&class_def, closure);
+            let coverage = covered(&table.coverage()?, closure);
+            for (class, rule_set) in table.class_seq_rule_sets().iter().enumerate() {
+                let Some(rule_set) = rule_set.transpose()? else {
+                    continue;
+                };
+                let first: HashSet<GlyphId16> = coverage
+                    .iter()
+                    .copied()
+                    .filter(|gid| class_def.get(*gid) as usize == class)
+                    .collect();
+                for rule in rule_set.class_seq_rules().iter() {
+                    let rule = rule?;
+                    let input = std::iter::once(first.clone())
+                        .chain(
+                            rule.input_sequence()
+                                .iter()
+                                .map(|class| in_class(&input_classes, class.get())),
+                        )
+                        .collect();
+                    result.extend(rule_lookups(input, true, rule.seq_lookup_records()));
+                }
+            }
+        }
+        SequenceContext::Format3(table) => {
+            let input = table
+                .coverages()
+                .iter()
+                .map(|coverage| Ok(covered(&coverage?, closure)))
+                .collect::<Result<Vec<_>, ReadError>>()?;
+            result.extend(rule_lookups(input, true, table.seq_lookup_records()));
+        }
+    }
+    Ok(result)
+}
+
+fn chained_context_lookups(
+    context: &ChainedSequenceContext,
+    closure: &GsubClosure,
+) -> Result<Vec<(u16, HashSet<GlyphId16>)>, ReadError> {
+    let mut result = vec![];
+    match context {
+        ChainedSequenceContext::Format1(table) => {
+            for (first, rule_set) in table
+                .coverage()?
+                .iter()
+                .zip(table.chained_seq_rule_sets().iter())
+            {
+                let Some(rule_set) = rule_set.transpose()? else {
+                    continue;
+                };
+                if !closure.has(first) {
+                    continue;
+                }
+                for rule in rule_set.chained_seq_rules().iter() {
+                    let rule = rule?;
+                    let context_possible = rule
+                        .backtrack_sequence()
+                        .iter()
+                        .chain(rule.lookahead_sequence().iter())
+                        .all(|gid| closure.has(gid.get()));
+                    let input = std::iter::once(first)
+                        .chain(rule.input_sequence().iter().map(|g| g.get()))
+                        .map(|gid| reachable(gid, closure))
+                        .collect();
+                    result.extend(rule_lookups(
+                        input,
+                        context_possible,
+                        rule.seq_lookup_records(),
+                    ));
+                }
+            }
+        }
+        ChainedSequenceContext::Format2(table) => {
+            let backtrack_classes = classes(&table.backtrack_class_def()?, closure);
+            let input_class_def = table.input_class_def()?;
+            let input_classes = classes(&input_class_def, closure);
+            let lookahead_classes = classes(&table.lookahead_class_def()?, closure);
+            let coverage = covered(&table.coverage()?, closure);
+            for (class, rule_set) in table.chained_class_seq_rule_sets().iter().enumerate() {
+                let Some(rule_set) = rule_set.transpose()? else {
+                    continue;
+                };
+                let first: HashSet<GlyphId16> = coverage
+                    .iter()
+                    .copied()
+                    .filter(|gid| input_class_def.get(*gid) as usize == class)
+                    .collect();
+                for rule in rule_set.chained_class_seq_rules().iter() {
+                    let rule = rule?;
+                    let context_possible = rule
+                        .backtrack_sequence()
+                        .iter()
+                        .all(|class| backtrack_classes.contains_key(&class.get()))
+                        && rule
+                            .lookahead_sequence()
+                            .iter()
+                            .all(|class| lookahead_classes.contains_key(&class.get()));
+                    let input = std::iter::once(first.clone())
+                        .chain(
+                            rule.input_sequence()
+                                .iter()
+                                .map(|class| in_class(&input_classes, class.get())),
+                        )
+                        .collect();
+                    result.extend(rule_lookups(
+                        input,
+                        context_possible,
+                        rule.seq_lookup_records(),
+                    ));
+                }
+            }
+        }
+        ChainedSequenceContext::Format3(table) => {
+            let mut context_possible = true;
+            for coverage in table
+                .backtrack_coverages()
+                .iter()
+                .chain(table.lookahead_coverages().iter())
+            {
+                context_possible &= !covered(&coverage?, closure).is_empty();
+            }
+            let input = table
+                .input_coverages()
+                .iter()
+                .map(|coverage| Ok(covered(&coverage?, closure)))
+                .collect::<Result<Vec<_>, ReadError>>()?;
+            result.extend(rule_lookups(
+                input,
+                context_possible,
+                table.seq_lookup_records(),
+            ));
+        }
+    }
+    Ok(result)
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{codetesting::test_able, FileTypeConvert, TTF};
+
+    #[test]
+    fn test_gsub_closure() {
+        let testable = test_able("nunito/Nunito-Regular.ttf");
+        let f = TTF.from_testable(&testable).unwrap();
+        let (f_glyph, i_glyph, fi_ligature, i_turkish) = (306u32, 319u32, 502u32, 327u32);
+
+        let closure = f
+            .gsub_closure(['f' as u32, 'i' as u32], &ClosureOptions::default(), None)
+            .unwrap();
+        assert!(closure.contains(GlyphId::new(f_glyph)));
+        assert_eq!(closure.features(GlyphId::new(i_glyph)).count(), 0);
+        assert!(closure.contains(GlyphId::new(i_turkish)));
+        assert_eq!(
+            closure
+                .features(GlyphId::new(fi_ligature))
+                .collect::<Vec<_>>(),
+            vec!["liga"]
+        );
+
+        let liga_only = ClosureOptions {
+            features: Some(vec!["liga".to_string()]),
+            ..Default::default()
+        };
+        let closure = f
+            .gsub_closure(['f' as u32, 'i' as u32], &liga_only, None)
+            .unwrap();
+        assert!(closure.contains(GlyphId::new(fi_ligature)));
+        assert!(!closure.contains(GlyphId::new(i_turkish)));
+        // The ligature replaces the f, which comes first, but not the i
+        assert_eq!(
+            closure
+                .substituted_by(GlyphId::new(f_glyph))
+                .collect::<Vec<_>>(),
+            vec!["liga"]
+        );
+        assert_eq!(closure.substituted_by(GlyphId::new(i_glyph)).count(), 0);
+        assert_eq!(closure.substituted_by(GlyphId::new(fi_ligature)).count(), 0);
+
+        let closure = f
+            .gsub_closure(['f' as u32], &ClosureOptions::default(), None)
+            .unwrap();
+        assert!(!closure.contains(GlyphId::new(fi_ligature)));
+    }
+
+    #[test]
+    fn test_gsub_closure_chained_context() {
+        // Mada's rlig feature is a chained contextual lookup which calls
+        // lookup 8, used by no feature, on lam-alef sequences
+        let testable = test_able("mada/Mada-Regular.ttf");
+        let f = TTF.from_testable(&testable).unwrap();
+        let (lam, alef) = (0x0644u32, 0x0627u32);
+        let (lam_init, lam_init_1, alef_fina_1) = (47u32, 48u32, 13u32);
+
+        let closure = f
+            .gsub_closure([lam, alef], &ClosureOptions::default(), None)
+            .unwrap();
+        assert!(closure.contains(GlyphId::new(lam_init)));
+        assert!(closure.contains(GlyphId::new(lam_init_1)));
+        assert!(closure.contains(GlyphId::new(alef_fina_1)));
+        // Attributed to the contextual lookup's feature
+        assert_eq!(
+            closure
+                .features(GlyphId::new(lam_init_1))
+                .collect::<Vec<_>>(),
+            vec!["rlig"]
+        );
+
+        // Without an alef, the context can't be formed
+        let closure = f
+            .gsub_closure([lam], &ClosureOptions::default(), None)
+            .unwrap();
+        assert!(closure.contains(GlyphId::new(lam_init)));
+        assert!(!closure.contains(GlyphId::new(lam_init_1)));
+
+        // rlig alone can't produce the initial lam its context needs
+        let rlig_only = ClosureOptions {
+            features: Some(vec!["rlig".to_string()]),
+            ..Default::default()
+        };
+        let closure = f.gsub_closure([lam, alef], &rlig_only, None).unwrap();
+        assert!(!closure.contains(GlyphId::new(lam_init_1)));
+    }
+
+    #[test]
+    fn test_gsub_closure_script_and_language() {
+        // Source Sans Pro's locl feature has i.t for Turkish, Azeri and
+        // Crimean Tatar, and a Serbian be for Cyrillic
+        let testable = test_able("source-sans-pro/TTF/SourceSansPro-Regular.ttf");
+        let f = TTF.from_testable(&testable).unwrap();
+        let (i_t, be_srb) = (1228u32, 1097u32);
+        let locl = |script: &str, language: Option<&str>| ClosureOptions {
+            features: Some(vec!["locl".to_string()]),
+            script: Some(script.to_string()),
+            language: language.map(|l| l.to_string()),
+        };
+        let reaches = |options: ClosureOptions, codepoint: u32, gid: u32| {
+            f.gsub_closure([codepoint], &options, None)
+                .unwrap()
+                .contains(GlyphId::new(gid))
+        };
+
+        assert!(reaches(locl("latn", Some("TRK")), 'i' as u32, i_t));
+        assert!(reaches(locl("latn", Some("AZE")), 'i' as u32, i_t));
+        // All the language systems of the script
+        assert!(reaches(locl("latn", None), 'i' as u32, i_t));
+        // Only the script's default language system
+        assert!(!reaches(locl("latn", Some("dflt")), 'i' as u32, i_t));
+        // Languages the script doesn't have fall back to its default
+        assert!(!reaches(locl("latn", Some("DEU")), 'i' as u32, i_t));
+        assert!(!reaches(locl("cyrl", None), 'i' as u32, i_t));
+
+        assert!(reaches(locl("cyrl", Some("SRB")), 0x0431, be_srb));
+        assert!(!reaches(locl("cyrl", Some("dflt")), 0x0431, be_srb));
+        assert!(!reaches(locl("latn", Some("SRB")), 0x0431, be_srb));
+    }
+
+    #[test]
+    fn test_gsub_closure_feature_variations() {
+        // MutatorSans's rvrn feature has no lookups of its own; I.narrow and
+        // S.closed are only substituted in some regions of the design space
+        let testable = test_able("mutatorsans-vf/MutatorSans-VF.ttf");
+        let f = TTF.from_testable(&testable).unwrap();
+        let (i_narrow, s_closed) = (30u32, 29u32);
+
+        let closure = f
+            .gsub_closure(['I' as u32, 'S' as u32], &ClosureOptions::default(), None)
+            .unwrap();
+        assert!(closure.contains(GlyphId::new(i_narrow)));
+        assert!(closure.contains(GlyphId::new(s_closed)));
+        assert_eq!(
+            closure.features(GlyphId::new(i_narrow)).collect::<Vec<_>>(),
+            vec!["rvrn"]
+        );
+
+        let closure = f
+            .gsub_closure(['I' as u32], &ClosureOptions::default(), None)
+            .unwrap();
+        assert!(closure.contains(GlyphId::new(i_narrow)));
+        assert!(!closure.contains(GlyphId::new(s_closed)));
+
+        let liga_only = ClosureOptions {
+            features: Some(vec!["liga".to_string()]),
+            ..Default::default()
+        };
+        let closure = f.gsub_closure(['I' as u32], &liga_only, None).unwrap();
+        assert!(!closure.contains(GlyphId::new(i_narrow)));
+    }
+}
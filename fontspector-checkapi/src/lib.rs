@@ -18,10 +18,14 @@ mod check;
 mod checkresult;
 /// Routines for testing checks
 pub mod codetesting;
+/// Named preconditions which checks can declare
+mod condition;
 /// Font-related constants which may be useful to check implementors
 pub mod constants;
 /// Data structures for managing the context in which a check is run
 mod context;
+/// Computing the differences between two versions of a font
+mod diff;
 /// Error types
 mod error;
 
@@ -41,6 +45,8 @@ pub mod plugin;
 mod profile;
 /// The registry of checks and profiles
 mod registry;
+/// Locating and editing the font sources which binaries were built from
+pub mod source;
 /// Data structures representing the most basic elements of a check's result
 mod status;
 /// Wraps a file or "thing" to be tested
@@ -49,18 +55,20 @@ mod testable;
 mod utils;
 pub use check::{return_result, Check, CheckFlags, CheckId, CheckImplementation};
 pub use checkresult::CheckResult;
-pub use context::Context;
+pub use condition::ConditionFunction;
+pub use context::{Context, Memo};
+pub use diff::{diff_fonts, FontChange};
 pub use error::FontspectorError;
 pub use filetype::{FileType, FileTypeConvert};
 pub use fix::{
-    Choice, DialogField, DialogFieldType, FixResult, HotfixFunction, MoreInfoReplies,
-    MoreInfoRequest,
+    Choice, DialogField, DialogFieldType, FixResult, HotfixAnswers, HotfixFunction,
+    MoreInfoReplies, MoreInfoRequest, SourceFixFunction,
 };
 pub use font::{
     get_name_entry_string, get_name_platform_tuples, PlatformSelector, TestFont, DEFAULT_LOCATION,
     TTF,
 };
-pub use gsub::{GetSubstitutionMap, SubstitutionMap};
+pub use gsub::{gsub_closure, ClosureOptions, GetSubstitutionMap, GsubClosure, SubstitutionMap};
 pub use profile::{Override, Profile, ProfileBuilder};
 pub use registry::Registry;
 pub use status::{CheckFnResult, Metadata, Status, StatusCode, StatusList};
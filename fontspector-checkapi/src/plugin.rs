@@ -1,5 +1,8 @@
 use std::{
-    collections::HashMap, path::PathBuf, process::Command as ProcessCommand, sync::LazyLock,
+    collections::HashMap,
+    path::PathBuf,
+    process::Command as ProcessCommand,
+    sync::{Arc, LazyLock},
 };
 
 use crate::{prelude::*, CheckResult, ProfileProvider};
@@ -119,7 +122,7 @@ impl From<Check<'_>> for CheckMetadata {
     }
 }
 /// Build a default context for a check, which can be used when running a check from the plugin without any user configuration
-fn default_context(check: &Check<'_>) -> Context {
+fn default_context(check: &Check<'_>, registry: &Registry) -> Context {
     Context {
         skip_network: false,
         network_timeout: None,
@@ -127,8 +130,13 @@ fn default_context(check: &Check<'_>) -> Context {
         check_metadata: check.metadata(),
         full_lists: false,
         cache: Default::default(),
+        memo: Default::default(),
         overrides: vec![],
         check_id: None,
+        conditions: Arc::new(registry.conditions.clone()),
+        family: None,
+        check_timeout: None,
+        deadline: None,
     }
 }
 
@@ -149,7 +157,7 @@ fn run_single_check(
             .map_err(|e| format!("Could not build testable collection: {e}"))?;
         let testable = TestableType::Collection(&collection);
         check
-            .run(&testable, &default_context(check), Some("test"))
+            .run(&testable, &default_context(check, registry), Some("test"))
             .ok_or_else(|| "Check did not run for this collection".to_string())
     } else {
         if files.len() != 1 {
@@ -160,7 +168,7 @@ fn run_single_check(
             Testable::new(&files[0]).map_err(|e| format!("Could not open testable file: {e}"))?;
         let testable = TestableType::Single(&testable);
         check
-            .run(&testable, &default_context(check), Some("test"))
+            .run(&testable, &default_context(check, registry), Some("test"))
             .ok_or_else(|| "Check did not run for this file".to_string())
     }
 }
@@ -511,6 +519,8 @@ pub fn load_external_plugin(
             hotfix: None,
             fix_source: None,
             applies_to: Box::leak(Box::new(check_meta.applies_to.clone())),
+            // The plugin evaluates the check's conditions itself
+            conditions: &[],
             flags: CheckFlags::default(),
             _metadata: None,
         };
@@ -3,10 +3,10 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 use crate::{
-    plugin::current_executable_is_plugin, Check, CheckId, Context, FontspectorError, Registry,
-    StatusCode, TestableType,
+    condition, plugin::current_executable_is_plugin, Check, CheckId, Context, FontspectorError,
+    Registry, StatusCode, TestableCollection, TestableType,
 };
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Arc};
 
 #[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -101,7 +101,7 @@ impl Profile {
     ///
     /// This function checks that all the checks in the profile are known to the
     /// registry, resolving any included profiles and excluded checks, and that
-    /// any filetypes used in checks are known to the registry.
+    /// any filetypes and conditions used in checks are known to the registry.
     pub fn validate(&mut self, registry: &Registry) -> Result<(), FontspectorError> {
         // Resolve "include_profiles" and "exclude_checks" here
         for included_profile_str in self.include_profiles.iter() {
@@ -155,6 +155,14 @@ impl Profile {
                     filetype: check.applies_to.to_string(),
                 });
             }
+            for condition in check.conditions {
+                if !condition::is_known(condition, &registry.conditions) {
+                    return Err(FontspectorError::UnknownCondition {
+                        check_id: check.id.to_string(),
+                        condition: condition.to_string(),
+                    });
+                }
+            }
         }
         Ok(())
     }
@@ -163,7 +171,9 @@ impl Profile {
     ///
     /// This applies any user-provided command line configuration (include/exclude checks)
     /// and works out which checks apply to the set of [TestableType]s provided.
-    /// It returns everything needed to run each check, in order.
+    /// It returns everything needed to run each check, in order. Each testable's
+    /// context is given the family, from `families`, which it belongs to.
+    #[allow(clippy::too_many_arguments)]
     pub fn check_order<'t, 'r>(
         &self,
         include_checks: &[String],
@@ -172,11 +182,20 @@ impl Profile {
         general_context: Context,
         configuration: &HashMap<CheckId, serde_json::Value>,
         testables: &'t [TestableType],
+        families: &[Arc<TestableCollection>],
     ) -> Vec<(String, &'t TestableType<'t>, &'r Check<'r>, Context)> {
-        // Each testable gets its own context-specific cache.
-        let testable_and_cache = testables
-            .iter()
-            .map(|t| (t, general_context.with_new_cache()));
+        let general_context = Context {
+            conditions: Arc::new(registry.conditions.clone()),
+            ..general_context
+        };
+        // Each testable gets its own context-specific cache, and knows its family.
+        let testable_and_cache = testables.iter().map(|t| {
+            let context = Context {
+                family: families.iter().find(|family| in_family(t, family)).cloned(),
+                ..general_context.with_new_cache()
+            };
+            (t, context)
+        });
         // I'm just going to cheat and use nested for loops instead of iterator madness.
         let mut order = vec![];
         let mut sections_and_checks = vec![];
@@ -226,6 +245,17 @@ impl Profile {
     }
 }
 
+/// Whether a testable is, or is part of, a family
+///
+/// Files are compared by identity rather than by contents, as the same
+/// file may be loaded into more than one collection.
+fn in_family(testable: &TestableType, family: &TestableCollection) -> bool {
+    match testable {
+        TestableType::Single(t) => family.iter().any(|sibling| std::ptr::eq(sibling, *t)),
+        TestableType::Collection(c) => std::ptr::eq(*c, family),
+    }
+}
+
 /// Apply inclusions and exclusions to a list of checks
 ///
 /// Returns true if the check should be included, false if it should be excluded.
@@ -1,6 +1,9 @@
 use std::collections::HashMap;
 
-use crate::{Check, CheckId, FileType, FontspectorError, Profile, Testable, TTF};
+use crate::{
+    condition::BUILTIN_CONDITIONS, Check, CheckId, ConditionFunction, FileType, FontspectorError,
+    Profile, Testable, TTF,
+};
 
 #[derive(Default)]
 /// The Registry object
@@ -13,6 +16,8 @@ pub struct Registry<'a> {
     pub profiles: HashMap<String, Profile>,
     /// All known filetypes, by name
     pub(crate) filetypes: HashMap<String, FileType<'a>>,
+    /// All known conditions which checks may depend on, by name
+    pub(crate) conditions: HashMap<String, ConditionFunction>,
 }
 
 impl<'a> Registry<'a> {
@@ -20,6 +25,9 @@ impl<'a> Registry<'a> {
     pub fn new() -> Registry<'static> {
         let mut reg = Registry::default();
         reg.register_filetype("TTF", TTF);
+        for (name, condition) in BUILTIN_CONDITIONS {
+            reg.register_condition(name, *condition);
+        }
         reg
     }
 
@@ -47,6 +55,22 @@ impl<'a> Registry<'a> {
         Ok(())
     }
 
+    /// Parse, validate and register a [Profile] from a TOML definition
+    ///
+    /// This is used by front ends which allow the user to supply their own
+    /// profile, rather than only using the ones baked into fontspector.
+    pub fn register_toml_profile(
+        &mut self,
+        name: &str,
+        toml: &str,
+    ) -> Result<(), FontspectorError> {
+        let profile = Profile::from_toml(toml).map_err(|e| FontspectorError::ProfileParse {
+            name: name.to_string(),
+            message: e.to_string(),
+        })?;
+        self.register_profile(name, profile, true)
+    }
+
     /// Get a profile by name
     pub fn get_profile(&self, name: &str) -> Option<&Profile> {
         self.profiles.get(name)
@@ -62,6 +86,13 @@ impl<'a> Registry<'a> {
         self.checks.insert(check.id.to_string(), check);
     }
 
+    /// Register a new condition, which checks can name in their `conditions`
+    ///
+    /// Registering a condition with the name of a built-in condition replaces it.
+    pub fn register_condition(&mut self, name: &str, condition: ConditionFunction) {
+        self.conditions.insert(name.to_string(), condition);
+    }
+
     /// Register a simple profile with a single section from a list of checks
     pub fn register_simple_profile(
         &mut self,
@@ -0,0 +1,189 @@
+use std::path::{Path, PathBuf};
+
+use norad::designspace::DesignSpaceDocument;
+pub use plist::{Dictionary, Value};
+
+use crate::{FixResult, FontspectorError, Testable};
+
+/// A font source from which a binary was built
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FontSource {
+    /// A single UFO
+    Ufo(PathBuf),
+    /// A designspace document and its UFO masters
+    Designspace(PathBuf),
+    /// A Glyphs file or package
+    Glyphs(PathBuf),
+}
+
+impl FontSource {
+    /// Identify a source by its file extension
+    pub fn from_path<P: Into<PathBuf> + AsRef<Path>>(path: P) -> Result<Self, FontspectorError> {
+        match path.as_ref().extension().and_then(|x| x.to_str()) {
+            Some("ufo") => Ok(FontSource::Ufo(path.into())),
+            Some("designspace") => Ok(FontSource::Designspace(path.into())),
+            Some("glyphs") | Some("glyphspackage") => Ok(FontSource::Glyphs(path.into())),
+            _ => Err(FontspectorError::Fix(format!(
+                "{} is not a UFO, designspace or Glyphs source",
+                path.as_ref().display()
+            ))),
+        }
+    }
+
+    /// The source of a binary testable
+    pub fn for_testable(t: &Testable) -> Result<Self, FontspectorError> {
+        let source = t.source.as_ref().ok_or_else(|| {
+            FontspectorError::Fix(format!(
+                "No source is known for {}; use --source to provide one",
+                t.filename.display()
+            ))
+        })?;
+        Self::from_path(source)
+    }
+
+    /// The UFOs which make up this source
+    ///
+    /// For a designspace, these are the masters, resolved relative to the
+    /// designspace file; sparse masters which only refer to a layer of
+    /// another UFO are not repeated.
+    pub fn ufos(&self) -> Result<Vec<PathBuf>, FontspectorError> {
+        match self {
+            FontSource::Ufo(path) => Ok(vec![path.clone()]),
+            FontSource::Designspace(path) => {
+                let ds = DesignSpaceDocument::load(path).map_err(|e| {
+                    FontspectorError::Fix(format!("Could not load {}: {e}", path.display()))
+                })?;
+                let dirname = path.parent().unwrap_or(Path::new(""));
+                let mut ufos: Vec<PathBuf> = vec![];
+                for source in ds.sources.iter().filter(|s| s.layer.is_none()) {
+                    let ufo = dirname.join(&source.filename);
+                    if !ufos.contains(&ufo) {
+                        ufos.push(ufo);
+                    }
+                }
+                Ok(ufos)
+            }
+            FontSource::Glyphs(path) => Err(FontspectorError::Fix(format!(
+                "Fixing Glyphs sources is not supported yet ({})",
+                path.display()
+            ))),
+        }
+    }
+}
+
+/// Set a key in a UFO's font info, returning whether its value changed
+pub fn set_fontinfo_value(fontinfo: &mut Dictionary, key: &str, value: Value) -> bool {
+    if fontinfo.get(key) == Some(&value) {
+        return false;
+    }
+    fontinfo.insert(key.to_string(), value);
+    true
+}
+
+/// Apply a fix to the font info of every UFO in a binary's source
+///
+/// The fix function is given the contents of each UFO's `fontinfo.plist`,
+/// and returns whether it changed anything. Only the UFOs which were changed
+/// are written back to disk.
+pub fn fix_fontinfo(
+    t: &Testable,
+    fix: impl Fn(&mut Dictionary) -> bool,
+) -> Result<FixResult, FontspectorError> {
+    let mut fixed = false;
+    for ufo in FontSource::for_testable(t)?.ufos()? {
+        if !ufo.is_dir() {
+            return Err(FontspectorError::Fix(format!(
+                "{} is not a UFO directory",
+                ufo.display()
+            )));
+        }
+        let path = ufo.join("fontinfo.plist");
+        let mut fontinfo = if path.exists() {
+            Value::from_file(&path)
+                .map_err(|e| {
+                    FontspectorError::Fix(format!("Could not read {}: {e}", path.display()))
+                })?
+                .into_dictionary()
+                .ok_or_else(|| {
+                    FontspectorError::Fix(format!("{} is not a dictionary", path.display()))
+                })?
+        } else {
+            Dictionary::new()
+        };
+        if fix(&mut fontinfo) {
+            Value::Dictionary(fontinfo)
+                .to_file_xml(&path)
+                .map_err(|e| {
+                    FontspectorError::Fix(format!("Could not write {}: {e}", path.display()))
+                })?;
+            fixed = true;
+        }
+    }
+    Ok(if fixed {
+        FixResult::Fixed
+    } else {
+        FixResult::NotBroken
+    })
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_designspace_ufos() {
+        let source = FontSource::from_path(
+            PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("../resources/test/designspace/Test.designspace"),
+        )
+        .unwrap();
+        let ufos = source.ufos().unwrap();
+        assert!(!ufos.is_empty());
+        assert!(ufos.iter().all(|ufo| ufo.is_dir()));
+    }
+
+    #[test]
+    fn test_unknown_source() {
+        assert!(FontSource::from_path("Test-Regular.ttf").is_err());
+        assert!(FontSource::from_path("Test.glyphs")
+            .unwrap()
+            .ufos()
+            .is_err());
+    }
+
+    #[test]
+    fn test_fix_fontinfo() {
+        let dir = std::env::temp_dir().join(format!("fontspector-fix-{}", std::process::id()));
+        let ufo = dir.join("Test.ufo");
+        std::fs::create_dir_all(&ufo).unwrap();
+        let mut fontinfo = Dictionary::new();
+        fontinfo.insert(
+            "openTypeOS2Type".to_string(),
+            Value::Array(vec![Value::Integer(1i64.into())]),
+        );
+        Value::Dictionary(fontinfo)
+            .to_file_xml(ufo.join("fontinfo.plist"))
+            .unwrap();
+
+        let mut testable = Testable::new_with_contents("Test-Regular.ttf", vec![]);
+        testable.source = Some(ufo.clone());
+        let clear_fstype = |fontinfo: &mut Dictionary| {
+            set_fontinfo_value(fontinfo, "openTypeOS2Type", Value::Array(vec![]))
+        };
+        assert!(matches!(
+            fix_fontinfo(&testable, clear_fstype).unwrap(),
+            FixResult::Fixed
+        ));
+        assert!(matches!(
+            fix_fontinfo(&testable, clear_fstype).unwrap(),
+            FixResult::NotBroken
+        ));
+        let fontinfo = Value::from_file(ufo.join("fontinfo.plist"))
+            .unwrap()
+            .into_dictionary()
+            .unwrap();
+        assert_eq!(fontinfo.get("openTypeOS2Type"), Some(&Value::Array(vec![])));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
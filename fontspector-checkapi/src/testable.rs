@@ -111,6 +111,9 @@ pub struct TestableCollection {
     pub testables: Vec<Testable>,
     /// The directory or identifier for this collection
     pub directory: String,
+    /// What distinguishes this collection from others in the same directory,
+    /// such as a family name, if the directory was split into several
+    pub label: Option<String>,
 }
 
 impl TestableCollection {
@@ -126,6 +129,7 @@ impl TestableCollection {
             directory: identifier
                 .map(|x| x.to_string())
                 .unwrap_or("A collection".to_string()),
+            label: None,
         })
     }
 
@@ -134,9 +138,17 @@ impl TestableCollection {
         Self {
             testables,
             directory: identifier.unwrap_or("A collection".to_string()),
+            label: None,
         }
     }
 
+    /// Give the collection a label, to tell it apart from other collections
+    /// from the same directory
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
     /// Return each [Testable] in the collection.
     pub fn iter(&self) -> impl Iterator<Item = &Testable> {
         self.testables.iter()
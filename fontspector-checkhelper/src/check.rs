@@ -4,7 +4,7 @@ use darling::{ast::NestedMeta, Error, FromMeta};
 use proc_macro::TokenStream;
 use proc_macro2::Span;
 use quote::quote;
-use syn::{parse_macro_input, Ident, ItemFn};
+use syn::{parse_macro_input, Expr, ExprLit, Ident, ItemFn, Lit};
 
 #[derive(Default)]
 /// Does this check apply to a single file, or to a collection of files?
@@ -39,6 +39,45 @@ impl FromMeta for Implementation {
     }
 }
 
+/// The names of the conditions a check depends on
+///
+/// These are written as an array of strings, e.g.
+/// `conditions = ["is_variable_font", "has_table:STAT"]`; a single
+/// condition may also be given as a plain string.
+#[derive(Default)]
+struct Conditions {
+    /// The condition names, in the order they should be evaluated
+    names: Vec<String>,
+}
+
+impl FromMeta for Conditions {
+    fn from_string(s: &str) -> darling::Result<Self> {
+        Ok(Conditions {
+            names: vec![s.to_string()],
+        })
+    }
+
+    fn from_expr(expr: &Expr) -> darling::Result<Self> {
+        match expr {
+            Expr::Array(array) => array
+                .elems
+                .iter()
+                .map(|elem| match elem {
+                    Expr::Lit(ExprLit {
+                        lit: Lit::Str(s), ..
+                    }) => Ok(s.value()),
+                    _ => Err(Error::unexpected_expr_type(elem).with_span(elem)),
+                })
+                .collect::<darling::Result<Vec<_>>>()
+                .map(|names| Conditions { names }),
+            Expr::Lit(ExprLit {
+                lit: Lit::Str(s), ..
+            }) => Self::from_string(&s.value()),
+            _ => Err(Error::unexpected_expr_type(expr).with_span(expr)),
+        }
+    }
+}
+
 /// Dedent the rationale string and unwrap it from the `rationale` attribute.
 ///
 /// `rationale` text may be written with any degree of indentation, and
@@ -105,6 +144,9 @@ struct CheckParams {
     implementation: Implementation,
     /// File type identifiers which the check applies to.
     applies_to: Option<String>,
+    /// Names of conditions which must hold for the check to run.
+    #[darling(default)]
+    conditions: Conditions,
     /// Optionally, the name of a function which can be used to fix the issue on the binary font.
     hotfix: Option<Ident>,
     /// Optionally, the name of a function which can be used to fix the issue on the source font.
@@ -167,6 +209,14 @@ pub(crate) fn check_impl(args: TokenStream, input: TokenStream) -> TokenStream {
         Span::call_site(),
     );
 
+    let condition_items: Vec<syn::LitStr> = params
+        .conditions
+        .names
+        .iter()
+        .map(|condition| syn::LitStr::new(condition, Span::call_site()))
+        .collect();
+    let conditions = quote!(&[#(#condition_items),*]);
+
     let hotfix = match params.hotfix {
         Some(hotfix) => quote!(Some(&#hotfix)),
         None => quote!(None),
@@ -210,6 +260,7 @@ pub(crate) fn check_impl(args: TokenStream, input: TokenStream) -> TokenStream {
             title: #title,
             rationale: #rationale,
             applies_to: #applies_to,
+            conditions: #conditions,
             implementation: #implementation,
             hotfix: #hotfix,
             fix_source: #fix_source,
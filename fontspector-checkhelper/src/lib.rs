@@ -17,6 +17,12 @@
 //!
 //! A function which takes a `TestableCollection` should have the argument
 //! `implementation = "all"` in the `#[check(...)]` attribute. This will
+//!
+//! Checks which only make sense for some fonts can declare the conditions
+//! they depend on, e.g. `conditions = ["is_variable_font", "has_table:STAT"]`.
+//! Conditions are named functions registered with fontspector's `Registry`;
+//! a leading `!` negates a condition. If any condition does not hold, the
+//! check is not run, and is reported as skipped with the condition's name.
 
 /// Check parser and macro implementation
 mod check;
@@ -1,4 +1,5 @@
 use crate::build;
+use crate::grouping::GroupBy;
 use clap::{ArgAction, Parser};
 use fontspector_checkapi::StatusCode;
 
@@ -38,10 +39,25 @@ pub struct Args {
     #[clap(short = 'x', long)]
     pub exclude_checkid: Option<Vec<String>>,
 
+    /// How to split the inputs into families for family-level checks.
+    /// Defaults to one family per directory.
+    #[clap(long, value_enum)]
+    pub group_by: Option<GroupBy>,
+
+    /// Regular expression matched against filenames when grouping by regex;
+    /// its first capture group, or else the whole match, names the family
+    #[clap(long)]
+    pub group_regex: Option<String>,
+
     /// Report full lists of items instead of abbreviated lists
     #[clap(long)]
     pub full_lists: bool,
 
+    /// Stop any check which runs for longer than this many seconds, and
+    /// report an error instead
+    #[clap(long)]
+    pub check_timeout: Option<u64>,
+
     /// Threshold for emitting process error code 1
     #[clap(short, long, value_enum, default_value_t=StatusCode::Fail)]
     pub error_code_on: StatusCode,
@@ -108,10 +124,19 @@ pub struct Args {
     #[clap(long, help_heading = "Fix problems")]
     pub hotfix: bool,
 
-    /// Fix sources
+    /// TOML/JSON file of answers to hotfix questions, keyed by check ID
+    #[clap(long, help_heading = "Fix problems")]
+    pub hotfix_answers: Option<String>,
+
+    /// Fix problems found in the binaries in the sources they were built from
     #[clap(long, help_heading = "Fix problems")]
     pub fix_sources: bool,
 
-    /// Input files
+    /// UFO, designspace or Glyphs source which the binaries were built from.
+    /// Use BINARY=SOURCE to give a source for one binary only.
+    #[clap(long, help_heading = "Fix problems")]
+    pub source: Vec<String>,
+
+    /// Input files, directories, glob patterns or .zip archives
     pub inputs: Vec<String>,
 }
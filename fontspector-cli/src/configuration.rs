@@ -4,7 +4,7 @@ use fontspector_checkapi::{CheckId, Override};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-use crate::args::Args;
+use crate::{args::Args, grouping::GroupBy};
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub(crate) struct UserConfigurationFile {
@@ -14,6 +14,10 @@ pub(crate) struct UserConfigurationFile {
     pub explicit_checks: Option<Vec<String>>,
     #[serde(default)]
     pub overrides: Option<Vec<Override>>,
+    #[serde(default)]
+    pub group_by: Option<GroupBy>,
+    #[serde(default)]
+    pub group_regex: Option<String>,
 
     #[serde(flatten)]
     pub per_check_config: HashMap<CheckId, Value>,
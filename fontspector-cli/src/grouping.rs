@@ -0,0 +1,183 @@
+use std::collections::BTreeMap;
+
+use clap::ValueEnum;
+use fontations::skrifa::{raw::TableProvider, string::StringId, MetadataProvider};
+use fontspector_checkapi::{FileTypeConvert, Testable, TestableCollection, TTF};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// How to split the input files into families for family-level checks
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum GroupBy {
+    /// One family per directory
+    #[default]
+    Directory,
+    /// One family per typographic family name (name ID 16, or 1 if absent)
+    Family,
+    /// One family per match of --group-regex against the filename
+    Regex,
+}
+
+/// The family a font belongs to, according to its name table
+pub(crate) fn family_name(testable: &Testable) -> Option<String> {
+    let font = TTF.from_testable(testable)?;
+    // Make sure there is a name table before asking it for strings
+    font.font().name().ok()?;
+    [StringId::TYPOGRAPHIC_FAMILY_NAME, StringId::FAMILY_NAME]
+        .into_iter()
+        .find_map(|id| font.font().localized_strings(id).english_or_first())
+        .map(|name| name.chars().collect())
+}
+
+/// The group a file belongs to, according to its filename
+///
+/// If the regex has a capture group, the first group names the family;
+/// otherwise the whole match does.
+pub(crate) fn regex_group(testable: &Testable, regex: &Regex) -> Option<String> {
+    let basename = testable.basename()?;
+    let captures = regex.captures(&basename)?;
+    captures
+        .get(1)
+        .or_else(|| captures.get(0))
+        .map(|m| m.as_str().to_string())
+}
+
+/// Split each per-directory collection into families, using `family` to
+/// find the family each file belongs to
+///
+/// Each family keeps its directory, and is labelled with the family name.
+/// Files which cannot be assigned to a family, such as a directory's
+/// METADATA.pb, go in the family if the directory only has one; otherwise
+/// they are kept once, in an unlabelled collection of their own.
+pub(crate) fn split_collections(
+    collections: Vec<TestableCollection>,
+    family: impl Fn(&Testable) -> Option<String>,
+) -> Vec<TestableCollection> {
+    collections
+        .into_iter()
+        .flat_map(|collection| {
+            let directory = collection.directory;
+            let mut families: BTreeMap<String, Vec<Testable>> = BTreeMap::new();
+            let mut shared = vec![];
+            for testable in collection.testables {
+                match family(&testable) {
+                    Some(family) => families.entry(family).or_default().push(testable),
+                    None => shared.push(testable),
+                }
+            }
+            if families.len() == 1 {
+                if let Some((family, testables)) = families.iter_mut().next() {
+                    testables.append(&mut shared);
+                    return vec![TestableCollection::from_testables(
+                        std::mem::take(testables),
+                        Some(directory),
+                    )
+                    .with_label(family.clone())];
+                }
+            }
+            let mut split: Vec<TestableCollection> = families
+                .into_iter()
+                .map(|(family, testables)| {
+                    TestableCollection::from_testables(testables, Some(directory.clone()))
+                        .with_label(family)
+                })
+                .collect();
+            if !shared.is_empty() || split.is_empty() {
+                split.push(TestableCollection::from_testables(shared, Some(directory)));
+            }
+            split
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used, clippy::indexing_slicing)]
+
+    use fontspector_checkapi::codetesting::test_able;
+
+    use super::*;
+
+    fn basenames(collection: &TestableCollection) -> Vec<String> {
+        collection
+            .iter()
+            .map(|t| t.basename().unwrap_or_default())
+            .collect()
+    }
+
+    #[test]
+    fn test_family_name() {
+        assert_eq!(
+            family_name(&test_able("nunito/Nunito-Regular.ttf")),
+            Some("Nunito".to_string())
+        );
+        assert_eq!(
+            family_name(&test_able("mada/Mada-Regular.ttf")),
+            Some("Mada".to_string())
+        );
+        assert_eq!(family_name(&test_able("cabin/METADATA.pb")), None);
+    }
+
+    #[test]
+    fn test_regex_group() {
+        let testable = test_able("nunito/Nunito-Regular.ttf");
+        let with_group = Regex::new(r"^([A-Za-z]+)-").unwrap();
+        assert_eq!(
+            regex_group(&testable, &with_group),
+            Some("Nunito".to_string())
+        );
+        let without_group = Regex::new(r"^[A-Za-z]+").unwrap();
+        assert_eq!(
+            regex_group(&testable, &without_group),
+            Some("Nunito".to_string())
+        );
+        let no_match = Regex::new(r"^Mada").unwrap();
+        assert_eq!(regex_group(&testable, &no_match), None);
+    }
+
+    #[test]
+    fn test_split_collections() {
+        let mixed = TestableCollection::from_testables(
+            vec![
+                test_able("nunito/Nunito-Regular.ttf"),
+                test_able("mada/Mada-Regular.ttf"),
+                test_able("mada/Mada-Bold.ttf"),
+                test_able("cabin/METADATA.pb"),
+            ],
+            Some("fonts".to_string()),
+        );
+        let single = TestableCollection::from_testables(
+            vec![
+                test_able("cabin/Cabin-Regular.ttf"),
+                test_able("cabin/METADATA.pb"),
+            ],
+            Some("cabin".to_string()),
+        );
+        let split = split_collections(vec![mixed, single], family_name);
+        assert_eq!(split.len(), 4);
+
+        // Families keep their directory, and are told apart by their label
+        assert_eq!(split[0].directory, "fonts");
+        assert_eq!(split[0].label.as_deref(), Some("Mada"));
+        assert_eq!(
+            basenames(&split[0]),
+            vec!["Mada-Regular.ttf", "Mada-Bold.ttf"]
+        );
+        assert_eq!(split[1].directory, "fonts");
+        assert_eq!(split[1].label.as_deref(), Some("Nunito"));
+        assert_eq!(basenames(&split[1]), vec!["Nunito-Regular.ttf"]);
+        // Files in no family are kept once, not copied into each family
+        assert_eq!(split[2].directory, "fonts");
+        assert_eq!(split[2].label, None);
+        assert_eq!(basenames(&split[2]), vec!["METADATA.pb"]);
+
+        // With only one family, they stay with it
+        assert_eq!(split[3].directory, "cabin");
+        assert_eq!(split[3].label.as_deref(), Some("Cabin"));
+        assert_eq!(
+            basenames(&split[3]),
+            vec!["Cabin-Regular.ttf", "METADATA.pb"]
+        );
+    }
+}
@@ -1,5 +1,8 @@
 use dialoguer::{theme::ColorfulTheme, Confirm, Input, Select};
-use fontspector_checkapi::{prelude::*, CheckResult, DialogFieldType, HotfixFunction, Metadata};
+use fontspector_checkapi::{
+    diff_fonts, prelude::*, CheckResult, DialogFieldType, HotfixAnswers, HotfixFunction, Metadata,
+    SourceFixFunction,
+};
 use serde_json::Value;
 use std::io::Write;
 use termimad::MadSkin;
@@ -11,9 +14,56 @@ pub(crate) fn run_hotfix(
     modified: &mut bool,
     result: &mut CheckResult,
     fix: &HotfixFunction,
+    answers: Option<&HotfixAnswers>,
 ) {
+    let before = testable.contents.clone();
+    let filename = testable.filename.to_string_lossy().to_string();
+    match run_with_dialog(&filename, result, answers, |options| fix(testable, options)) {
+        Ok(hotfix_result) => {
+            if matches!(hotfix_result, FixResult::Fixed) {
+                *modified = true;
+                match diff_fonts(&before, &testable.contents) {
+                    Ok(changes) => result.hotfix_changes = changes,
+                    Err(e) => log::warn!(
+                        "Could not work out what the hotfix for {} changed: {e}",
+                        result.check_id
+                    ),
+                }
+            }
+            result.hotfix_result = Some(hotfix_result);
+        }
+        Err(e) => {
+            result.hotfix_result = Some(FixResult::FixFailed(e.to_string()));
+        }
+    }
+}
+
+pub(crate) fn run_source_fix(
+    testable: &Testable,
+    result: &mut CheckResult,
+    fix: &SourceFixFunction,
+    answers: Option<&HotfixAnswers>,
+) {
+    let filename = testable.filename.to_string_lossy().to_string();
+    result.sourcefix_result = Some(
+        run_with_dialog(&filename, result, answers, |options| fix(testable, options))
+            .unwrap_or_else(|e| FixResult::FixFailed(e.to_string())),
+    );
+}
+
+/// Run a fix function, asking for more information for as long as it needs it
+///
+/// Answers come from the answers file if one was given, and otherwise from
+/// the user.
+fn run_with_dialog(
+    filename: &str,
+    result: &CheckResult,
+    answers: Option<&HotfixAnswers>,
+    mut fix: impl FnMut(Option<MoreInfoReplies>) -> Result<FixResult, FontspectorError>,
+) -> Result<FixResult, FontspectorError> {
     let mut options = None;
     let mut header_shown = false;
+    let mut answers_tried = false;
 
     // If we have a metadata containing a FixNeedsMoreInformation, we can run the dialog first to get the options for the fix
     if let Some(Metadata::FixNeedsMoreInformation(dialog)) = result
@@ -22,22 +72,44 @@ pub(crate) fn run_hotfix(
         .flat_map(|s| &s.metadata)
         .find(|m| matches!(m, Metadata::FixNeedsMoreInformation(_)))
     {
-        show_header(
-            testable,
-            &result.section,
-            &result.check_id,
-            &result.check_name,
-        );
-        options = run_dialog(dialog);
-        header_shown = true;
+        if let Some(answers) = answers {
+            match answer_from_file(answers, &result.check_id, dialog) {
+                Ok(replies) => options = Some(replies),
+                Err(failed) => return Ok(failed),
+            }
+            answers_tried = true;
+        } else {
+            show_header(
+                filename,
+                &result.section,
+                &result.check_id,
+                &result.check_name,
+            );
+            options = run_dialog(dialog);
+            header_shown = true;
+        }
     }
 
     loop {
-        match fix(testable, options) {
-            Ok(FixResult::MoreInfoNeeded(dialog)) => {
+        match fix(options)? {
+            FixResult::MoreInfoNeeded(dialog) => {
+                if let Some(answers) = answers {
+                    if answers_tried {
+                        return Ok(FixResult::FixFailed(format!(
+                            "still needs more information after using the answers provided for {}",
+                            result.check_id
+                        )));
+                    }
+                    match answer_from_file(answers, &result.check_id, &dialog) {
+                        Ok(replies) => options = Some(replies),
+                        Err(failed) => return Ok(failed),
+                    }
+                    answers_tried = true;
+                    continue;
+                }
                 if !header_shown {
                     show_header(
-                        testable,
+                        filename,
                         &result.section,
                         &result.check_id,
                         &result.check_name,
@@ -46,31 +118,30 @@ pub(crate) fn run_hotfix(
                 }
 
                 options = run_dialog(&dialog);
-                continue;
-            }
-            Ok(hotfix_result) => {
-                if matches!(hotfix_result, FixResult::Fixed) {
-                    *modified = true;
-                }
-                result.hotfix_result = Some(hotfix_result);
-                return;
-            }
-            Err(e) => {
-                result.hotfix_result = Some(FixResult::FixFailed(e.to_string()));
-                return;
             }
+            fix_result => return Ok(fix_result),
         }
     }
 }
 
-fn show_header(
-    testable: &mut Testable,
-    section: &Option<String>,
+/// Answer a dialog from the answers file
+///
+/// If the user has given us an answers file, they are not expecting to be
+/// asked questions, so a missing answer fails this fix, and we carry on
+/// with the others.
+fn answer_from_file(
+    answers: &HotfixAnswers,
     check_id: &str,
-    check_name: &str,
-) {
+    dialog: &MoreInfoRequest,
+) -> Result<MoreInfoReplies, FixResult> {
+    answers.answer(check_id, dialog).map_err(|e| {
+        log::error!("Could not apply hotfix for {check_id}: {e}");
+        FixResult::FixFailed(format!("no answer for {check_id}: {e}"))
+    })
+}
+
+fn show_header(filename: &str, section: &Option<String>, check_id: &str, check_name: &str) {
     let skin = MadSkin::default();
-    let filename = testable.filename.to_string_lossy();
     let _ = writeln!(std::io::stdout(), "Testing: {filename}");
     if let Some(sectionname) = section {
         let _ = writeln!(std::io::stdout(), "  Section: {sectionname}\n");
@@ -145,3 +216,67 @@ fn run_dialog(dialog: &MoreInfoRequest) -> Option<MoreInfoReplies> {
     }
     Some(replies)
 }
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use std::{collections::HashMap, time::Duration};
+
+    use fontspector_checkapi::DialogField;
+
+    use super::*;
+
+    fn check_result() -> CheckResult {
+        CheckResult {
+            check_id: "test/check".to_string(),
+            check_name: "A test check".to_string(),
+            check_rationale: String::new(),
+            filename: None,
+            label: None,
+            source_filename: None,
+            section: None,
+            subresults: vec![],
+            hotfix_result: None,
+            hotfix_changes: vec![],
+            sourcefix_result: None,
+            time: Duration::ZERO,
+            hotfix_available: true,
+            sourcefix_available: false,
+        }
+    }
+
+    /// A fix which needs to be told a value before it can do anything
+    fn fix(options: Option<MoreInfoReplies>) -> Result<FixResult, FontspectorError> {
+        Ok(match options {
+            Some(_) => FixResult::Fixed,
+            None => FixResult::MoreInfoNeeded(MoreInfoRequest(vec![DialogField {
+                key: "value".to_string(),
+                prompt: "Which value?".to_string(),
+                field_type: DialogFieldType::Text,
+            }])),
+        })
+    }
+
+    #[test]
+    fn test_unanswered_question_fails_the_fix() {
+        let answers = HotfixAnswers::default();
+        let result = run_with_dialog("font.ttf", &check_result(), Some(&answers), fix).unwrap();
+        assert!(
+            matches!(&result, FixResult::FixFailed(reason) if reason.starts_with("no answer for test/check")),
+            "{result:?}"
+        );
+    }
+
+    #[test]
+    fn test_answered_question() {
+        let answers = HotfixAnswers(HashMap::from([(
+            "test/check".to_string(),
+            MoreInfoReplies(HashMap::from([(
+                "value".to_string(),
+                Value::String("42".to_string()),
+            )])),
+        )]));
+        let result = run_with_dialog("font.ttf", &check_result(), Some(&answers), fix).unwrap();
+        assert!(matches!(result, FixResult::Fixed), "{result:?}");
+    }
+}
@@ -0,0 +1,262 @@
+use std::{
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+use fontspector_checkapi::{Registry, Testable};
+use walkdir::WalkDir;
+
+/// The largest archive member we are prepared to read into memory
+const MAX_MEMBER_SIZE: u64 = 256 * 1024 * 1024;
+
+/// Turn the inputs given on the command line into the files to be tested
+///
+/// Files named explicitly are always tested. Directories are walked
+/// recursively, glob patterns are expanded, and the members of `.zip`
+/// archives are read; files found in these ways are only tested if some
+/// profile knows what to do with them. Archive members are named by their
+/// path inside the archive, appended to the path of the archive itself.
+/// An archive named explicitly which cannot be read is a fatal error; one
+/// found while walking a directory or expanding a pattern is skipped with a
+/// warning.
+pub(crate) fn expand_inputs(inputs: &[String], registry: &Registry) -> Vec<Testable> {
+    let mut testables = vec![];
+    for input in inputs {
+        let path = PathBuf::from(input);
+        if path.exists() {
+            add_path(&path, true, registry, &mut testables);
+            continue;
+        }
+        let matches = glob::glob(input).unwrap_or_else(|e| {
+            log::error!("Could not understand the input pattern {input}: {e}");
+            std::process::exit(1)
+        });
+        let mut matched = false;
+        for entry in matches {
+            match entry {
+                Ok(path) => add_path(&path, false, registry, &mut testables),
+                Err(e) => log::warn!("Could not read {}: {e}", e.path().display()),
+            }
+            matched = true;
+        }
+        if !matched {
+            log::error!("Could not find any files matching {input}");
+            std::process::exit(1);
+        }
+    }
+    testables
+}
+
+fn add_path(path: &Path, explicit: bool, registry: &Registry, testables: &mut Vec<Testable>) {
+    if is_archive(path) {
+        add_archive(path, explicit, registry, testables);
+        return;
+    }
+    let known = is_known(path, registry);
+    if path.is_dir() && !known {
+        add_directory(path, registry, testables);
+    } else if explicit || known {
+        testables.push(load(path));
+    }
+}
+
+fn add_directory(directory: &Path, registry: &Registry, testables: &mut Vec<Testable>) {
+    let mut walker = WalkDir::new(directory)
+        .min_depth(1)
+        .sort_by_file_name()
+        .into_iter()
+        // Don't wander into .git and friends
+        .filter_entry(|entry| !entry.file_name().to_string_lossy().starts_with('.'));
+    while let Some(entry) = walker.next() {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => {
+                log::warn!("Could not read {directory:?}: {e}");
+                continue;
+            }
+        };
+        let path = entry.path();
+        if entry.file_type().is_file() && is_archive(path) {
+            add_archive(path, false, registry, testables);
+            continue;
+        }
+        if is_known(path, registry) {
+            // A known directory, such as a UFO, is tested as a whole
+            if entry.file_type().is_dir() {
+                walker.skip_current_dir();
+            }
+            testables.push(load(path));
+        }
+    }
+}
+
+fn add_archive(archive: &Path, explicit: bool, registry: &Registry, testables: &mut Vec<Testable>) {
+    let zip = std::fs::File::open(archive)
+        .map_err(|e| e.to_string())
+        .and_then(|file| zip::ZipArchive::new(file).map_err(|e| e.to_string()));
+    let mut zip = match zip {
+        Ok(zip) => zip,
+        Err(e) if explicit => {
+            log::error!("Could not read archive {archive:?}: {e}");
+            std::process::exit(1)
+        }
+        Err(e) => {
+            log::warn!("Skipping archive {archive:?}, which could not be read: {e}");
+            return;
+        }
+    };
+    for index in 0..zip.len() {
+        let mut member = match zip.by_index(index) {
+            Ok(member) => member,
+            Err(e) => {
+                log::warn!("Could not read member {index} of {archive:?}: {e}");
+                continue;
+            }
+        };
+        if member.is_dir() {
+            continue;
+        }
+        // Refuse member names which would escape the archive
+        let Some(name) = member.enclosed_name() else {
+            log::warn!("Skipping {} in {archive:?}", member.name());
+            continue;
+        };
+        let path = archive.join(name);
+        if !is_known(&path, registry) {
+            continue;
+        }
+        // Archives are often downloaded releases, so don't trust the size they declare
+        if member.size() > MAX_MEMBER_SIZE {
+            log::warn!("Skipping {path:?}, which is larger than {MAX_MEMBER_SIZE} bytes");
+            continue;
+        }
+        let mut contents = vec![];
+        if let Err(e) = (&mut member)
+            .take(MAX_MEMBER_SIZE + 1)
+            .read_to_end(&mut contents)
+        {
+            log::warn!("Could not read {path:?}: {e}");
+            continue;
+        }
+        if contents.len() as u64 > MAX_MEMBER_SIZE {
+            log::warn!("Skipping {path:?}, which is larger than {MAX_MEMBER_SIZE} bytes");
+            continue;
+        }
+        testables.push(Testable::new_with_contents(path, contents));
+    }
+}
+
+fn is_archive(path: &Path) -> bool {
+    path.is_file()
+        && path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("zip"))
+}
+
+/// Whether some profile knows what to do with a file of this name
+fn is_known(path: &Path, registry: &Registry) -> bool {
+    registry.is_known_file(&Testable::new_with_contents(path, vec![]))
+}
+
+fn load(path: &Path) -> Testable {
+    Testable::new(path).unwrap_or_else(|e| {
+        log::error!("Could not load files from {path:?}: {e}");
+        std::process::exit(1)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used, clippy::indexing_slicing)]
+    use std::io::Write;
+
+    use fontspector_checkapi::codetesting::test_file;
+
+    use super::*;
+
+    /// An empty scratch directory for a test
+    fn scratch_directory(name: &str) -> PathBuf {
+        let directory =
+            std::env::temp_dir().join(format!("fontspector-inputs-{}-{name}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&directory);
+        std::fs::create_dir_all(&directory).unwrap();
+        directory
+    }
+
+    fn basenames(testables: &[Testable]) -> Vec<String> {
+        testables
+            .iter()
+            .map(|t| t.basename().unwrap_or_default())
+            .collect()
+    }
+
+    #[test]
+    fn test_directory() {
+        let registry = Registry::new();
+        let directory = test_file("merriweather");
+        let testables = expand_inputs(&[directory.to_string_lossy().to_string()], &registry);
+        // Only the fonts are picked up, in order
+        assert_eq!(
+            basenames(&testables),
+            vec![
+                "Merriweather-Black.ttf",
+                "Merriweather-BlackItalic.ttf",
+                "Merriweather-Bold.ttf",
+                "Merriweather-BoldItalic.ttf",
+                "Merriweather-Italic.ttf",
+                "Merriweather-Light.ttf",
+                "Merriweather-LightItalic.ttf",
+                "Merriweather-Regular.ttf",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_glob() {
+        let registry = Registry::new();
+        let pattern = test_file("merriweather/Merriweather-*Italic.ttf");
+        let testables = expand_inputs(&[pattern.to_string_lossy().to_string()], &registry);
+        assert_eq!(testables.len(), 4);
+        assert!(basenames(&testables)
+            .iter()
+            .all(|name| name.ends_with("Italic.ttf")));
+    }
+
+    #[test]
+    fn test_zip() {
+        let registry = Registry::new();
+        let directory = scratch_directory("zip");
+        let archive = directory.join("release.zip");
+        let font = std::fs::read(test_file("merriweather/Merriweather-Regular.ttf")).unwrap();
+        let mut zip = zip::ZipWriter::new(std::fs::File::create(&archive).unwrap());
+        let options = zip::write::SimpleFileOptions::default();
+        zip.start_file("fonts/Merriweather-Regular.ttf", options)
+            .unwrap();
+        zip.write_all(&font).unwrap();
+        zip.start_file("README.txt", options).unwrap();
+        zip.write_all(b"Not a font").unwrap();
+        zip.finish().unwrap();
+
+        let testables = expand_inputs(&[archive.to_string_lossy().to_string()], &registry);
+        assert_eq!(testables.len(), 1);
+        assert_eq!(
+            testables[0].filename,
+            archive.join("fonts/Merriweather-Regular.ttf")
+        );
+        assert_eq!(testables[0].contents, font);
+
+        // A broken archive found in a directory is skipped, not fatal
+        std::fs::write(directory.join("broken.zip"), b"Not a zip file").unwrap();
+        std::fs::copy(
+            test_file("merriweather/Merriweather-Bold.ttf"),
+            directory.join("Merriweather-Bold.ttf"),
+        )
+        .unwrap();
+        let testables = expand_inputs(&[directory.to_string_lossy().to_string()], &registry);
+        assert_eq!(
+            basenames(&testables),
+            vec!["Merriweather-Bold.ttf", "Merriweather-Regular.ttf"]
+        );
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
@@ -2,14 +2,17 @@
 
 mod args;
 mod configuration;
+mod grouping;
 mod hotfix;
+mod inputs;
 mod profiles;
 mod reporters;
 
 use std::{
     collections::HashMap,
     io::Write,
-    path::PathBuf,
+    path::{Path, PathBuf},
+    sync::Arc,
     time::{Duration, Instant},
 };
 
@@ -17,8 +20,8 @@ use args::Args;
 use clap::{CommandFactory, FromArgMatches};
 
 use fontspector_checkapi::{
-    plugin::load_external_plugin, Check, CheckResult, Context, HotfixFunction, Registry,
-    StatusCode, Testable, TestableCollection, TestableType,
+    plugin::load_external_plugin, Check, CheckResult, Context, FixResult, HotfixAnswers,
+    HotfixFunction, Registry, StatusCode, Testable, TestableCollection, TestableType,
 };
 
 #[cfg(not(debug_assertions))]
@@ -27,12 +30,14 @@ use indicatif::ParallelProgressIterator;
 use indicatif::ProgressIterator;
 
 use configuration::{load_configuration, UserConfigurationFile};
+use grouping::{family_name, regex_group, split_collections, GroupBy};
 use itertools::Either;
 use profiles::{register_and_return_toml_profile, register_core_profiles};
 
 #[cfg(not(debug_assertions))]
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 
+use regex::Regex;
 use reporters::{process_reporter_args, terminal::TerminalReporter, Reporter, RunResults};
 use serde_json::json;
 
@@ -128,16 +133,23 @@ fn main() {
     if args.list_checks || args.list_checks_json {
         list_checks(&args, &registry, profile);
     }
-    // We create one collection for each set of testable files in a directory.
-    // So let's group the inputs per directory, and then map them into a FontCollection
-    let grouped_inputs = group_inputs(&mut args);
+    // Load configuration
+    let configuration: UserConfigurationFile = load_configuration(&args);
+
+    // We create one collection for each set of testable files in a directory,
+    // and then split those into families if we were asked to.
+    let mut grouped_inputs =
+        group_families(group_inputs(&mut args, &registry), &args, &configuration);
+    assign_sources(&mut grouped_inputs, &args.source);
 
     if grouped_inputs.is_empty() {
         log::error!("No input files");
         std::process::exit(1);
     }
 
-    let testables: Vec<TestableType> = grouped_inputs
+    // Checks on each file can see the rest of its family through their context.
+    let families: Vec<Arc<TestableCollection>> = grouped_inputs.into_iter().map(Arc::new).collect();
+    let testables: Vec<TestableType> = families
         .iter()
         .flat_map(|x| x.collection_and_files())
         .collect();
@@ -147,8 +159,6 @@ fn main() {
         std::process::exit(1);
     }
 
-    // Load configuration
-    let configuration: UserConfigurationFile = load_configuration(&args);
     let overrides = configuration.overrides.clone().unwrap_or_default();
     let mut includes = args.checkid.clone().unwrap_or_default();
     let mut excludes = args.exclude_checkid.clone().unwrap_or_default();
@@ -171,11 +181,17 @@ fn main() {
             check_metadata: serde_json::Value::Null,
             full_lists: args.full_lists,
             cache: Default::default(),
+            memo: Default::default(),
             overrides,
             check_id: None,
+            conditions: Default::default(),
+            family: None,
+            check_timeout: args.check_timeout.map(Duration::from_secs),
+            deadline: None,
         },
         &configuration.per_check_config,
         &testables,
+        &families,
     );
 
     // The testables are the collection object plus the files; only count the files.
@@ -309,8 +325,9 @@ fn list_checks(args: &Args, registry: &Registry<'static>, profile: &fontspector_
 
 // Group each file into a set per directory, and wrap that in a TestableCollection.
 // It feels like this takes an inordinately long time, but remember that this also
-// reads the input files.
-fn group_inputs(args: &mut Args) -> Vec<TestableCollection> {
+// reads the input files. Files inside archives are grouped by their directory
+// within the archive.
+fn group_inputs(args: &mut Args, registry: &Registry) -> Vec<TestableCollection> {
     // As a fun Easter egg, if the input file is a single source file, we will
     // pass it to fontc, compile it and stick it in the source map.
     #[cfg(feature = "fontc")]
@@ -358,58 +375,121 @@ fn group_inputs(args: &mut Args) -> Vec<TestableCollection> {
                 }
                 Ok(font) => {
                     let ttf_filename = path.with_extension("ttf");
-                    let testable = Testable::new_with_contents(&ttf_filename, font);
+                    let mut testable = Testable::new_with_contents(&ttf_filename, font);
+                    testable.source = Some(path.clone());
                     return vec![TestableCollection::from_testables(vec![testable], None)];
                 }
             }
         }
     }
 
-    let inputs = args
-        .inputs
-        .iter()
-        .map(PathBuf::from)
-        .filter(|x| x.parent().is_some());
-    inputs
-        .map(|file| {
-            #[allow(clippy::unwrap_used)] // We tested for parent
+    inputs::expand_inputs(&args.inputs, registry)
+        .into_iter()
+        .filter_map(|testable| {
+            let parent = testable.filename.parent()?.to_owned();
             if COLLAPSED_SUBDIRECTORIES
                 .iter()
-                .any(|subdir| file.parent().unwrap().ends_with(subdir))
+                .any(|subdir| parent.ends_with(subdir))
             {
-                (file.parent().unwrap().parent().unwrap().to_owned(), file)
+                Some((parent.parent()?.to_owned(), testable))
             } else {
-                (file.parent().unwrap().to_owned(), file)
+                Some((parent, testable))
             }
         })
         .fold(
             HashMap::new(),
-            |mut acc: HashMap<PathBuf, Vec<PathBuf>>, (directory, file)| {
-                acc.entry(directory).or_default().push(file);
+            |mut acc: HashMap<PathBuf, Vec<Testable>>, (directory, testable)| {
+                acc.entry(directory).or_default().push(testable);
                 acc
             },
         )
         .into_iter()
         .map(|(directory, group)| {
-            TestableCollection::from_filenames(&group, directory.to_str()).unwrap_or_else(|e| {
-                log::error!(
-                    "Could not load files from {:?}: {:}",
-                    group.first().map(|p| p.parent()),
-                    e
-                );
-                std::process::exit(1)
-            })
+            TestableCollection::from_testables(group, directory.to_str().map(|x| x.to_string()))
         })
         .collect()
 }
 
+/// Split the per-directory collections into families, as chosen on the
+/// command line or in the configuration file
+fn group_families(
+    collections: Vec<TestableCollection>,
+    args: &Args,
+    configuration: &UserConfigurationFile,
+) -> Vec<TestableCollection> {
+    let group_regex = args
+        .group_regex
+        .as_ref()
+        .or(configuration.group_regex.as_ref());
+    let group_by = args
+        .group_by
+        .or(configuration.group_by)
+        .unwrap_or(if group_regex.is_some() {
+            GroupBy::Regex
+        } else {
+            GroupBy::Directory
+        });
+    match group_by {
+        GroupBy::Directory => collections,
+        GroupBy::Family => split_collections(collections, family_name),
+        GroupBy::Regex => {
+            let Some(group_regex) = group_regex else {
+                log::error!("Grouping by regex needs a --group-regex");
+                std::process::exit(1);
+            };
+            let regex = Regex::new(group_regex).unwrap_or_else(|e| {
+                log::error!("Could not parse group regex {group_regex}: {e}");
+                std::process::exit(1)
+            });
+            split_collections(collections, |testable| regex_group(testable, &regex))
+        }
+    }
+}
+
+/// Record which source each font binary was built from, as given by `--source`
+///
+/// A bare source applies to every binary, and `BINARY=SOURCE` to the binary
+/// with that path or filename only, which takes precedence.
+fn assign_sources(collections: &mut [TestableCollection], sources: &[String]) {
+    let general = sources.iter().rfind(|source| !source.contains('='));
+    for testable in collections
+        .iter_mut()
+        .flat_map(|collection| collection.testables.iter_mut())
+        .filter(|testable| matches!(testable.extension().as_deref(), Some("ttf" | "otf")))
+    {
+        let basename = testable.basename();
+        let specific = sources.iter().rev().find_map(|source| {
+            let (binary, source) = source.split_once('=')?;
+            (Path::new(binary) == testable.filename || Some(binary) == basename.as_deref())
+                .then_some(source)
+        });
+        if let Some(source) = specific.or(general.map(|x| x.as_str())) {
+            testable.source = Some(PathBuf::from(source));
+        }
+    }
+}
+
 fn try_fixing_stuff(results: &mut RunResults, args: &Args, registry: &Registry) {
+    let answers = args.hotfix_answers.as_ref().map(|answers_file| {
+        HotfixAnswers::from_file(Path::new(answers_file)).unwrap_or_else(|e| {
+            log::error!("Could not load hotfix answers from {answers_file}: {e}");
+            std::process::exit(1)
+        })
+    });
+    if args.hotfix {
+        fix_binaries(results, registry, answers.as_ref());
+    }
+    if args.fix_sources {
+        fix_sources(results, registry, answers.as_ref());
+    }
+}
+
+fn fix_binaries(results: &mut RunResults, registry: &Registry, answers: Option<&HotfixAnswers>) {
     let failed_checks = results
         .iter_mut()
         .filter(|x| x.worst_status() >= StatusCode::Warn)
         .collect::<Vec<_>>();
     // Group the fixes by filename because we want to provide testables
-    // // let mut fix_sources = HashMap::new();
     let mut fix_binaries: HashMap<String, Vec<(&HotfixFunction, &mut CheckResult)>> =
         HashMap::new();
     for result in failed_checks.into_iter() {
@@ -421,23 +501,30 @@ fn try_fixing_stuff(results: &mut RunResults, args: &Args, registry: &Registry)
             continue;
         };
         if let (Some(hotfix), Some(filename)) = (check.hotfix, result.filename.as_ref()) {
-            if args.hotfix {
-                fix_binaries
-                    .entry(filename.clone())
-                    .or_default()
-                    .push((hotfix, result));
-            }
+            fix_binaries
+                .entry(filename.clone())
+                .or_default()
+                .push((hotfix, result));
         }
     }
 
     for (file, fixes) in fix_binaries.into_iter() {
+        if !Path::new(&file).is_file() {
+            // Probably a member of a .zip archive, which we won't rewrite
+            for (_, result) in fixes.into_iter() {
+                result.hotfix_result = Some(FixResult::FixFailed(format!(
+                    "{file} is not a file on disk, so cannot be hotfixed"
+                )));
+            }
+            continue;
+        }
         let mut testable = Testable::new(&file).unwrap_or_else(|e| {
             log::error!("Could not load files from {file:?}: {e:}");
             std::process::exit(1)
         });
         let mut modified = false;
         for (fix, result) in fixes.into_iter() {
-            hotfix::run_hotfix(&mut testable, &mut modified, result, fix);
+            hotfix::run_hotfix(&mut testable, &mut modified, result, fix, answers);
         }
         if modified {
             // save it
@@ -448,3 +535,99 @@ fn try_fixing_stuff(results: &mut RunResults, args: &Args, registry: &Registry)
         }
     }
 }
+
+fn fix_sources(results: &mut RunResults, registry: &Registry, answers: Option<&HotfixAnswers>) {
+    // A family's binaries are often all built from the same source, so each
+    // check's fix is only applied once per source and the outcome shared.
+    let mut outcomes: HashMap<(String, String), FixResult> = HashMap::new();
+    for result in results
+        .iter_mut()
+        .filter(|x| x.worst_status() >= StatusCode::Warn)
+    {
+        let Some(fix) = registry
+            .checks
+            .get(&result.check_id)
+            .and_then(|check| check.fix_source)
+        else {
+            continue;
+        };
+        let Some(filename) = result.filename.as_ref() else {
+            continue;
+        };
+        let key = result
+            .source_filename
+            .clone()
+            .map(|source| (source, result.check_id.clone()));
+        if let Some(outcome) = key.as_ref().and_then(|key| outcomes.get(key)) {
+            result.sourcefix_result = Some(outcome.clone());
+            continue;
+        }
+        // Source fixes only look at the source, so don't read the binary
+        // again; a font compiled from its source in memory isn't on disk.
+        let mut testable = Testable::new_with_contents(filename, vec![]);
+        testable.source = result.source_filename.as_ref().map(PathBuf::from);
+        hotfix::run_source_fix(&testable, result, fix, answers);
+        if let (Some(key), Some(outcome)) = (key, result.sourcefix_result.as_ref()) {
+            outcomes.insert(key, outcome.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use clap::Parser;
+    use fontspector_checkapi::{
+        codetesting::{edit_font, test_able},
+        source::{Dictionary, Value},
+        ProfileProvider,
+    };
+    use profile_googlefonts::GoogleFonts;
+
+    use super::*;
+
+    #[test]
+    fn test_fix_sources_for_compiled_font() {
+        let directory =
+            std::env::temp_dir().join(format!("fontspector-fix-sources-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&directory);
+        let ufo = directory.join("Test.ufo");
+        std::fs::create_dir_all(&ufo).unwrap();
+        let mut fontinfo = Dictionary::new();
+        fontinfo.insert(
+            "openTypeOS2Type".to_string(),
+            Value::Array(vec![Value::Integer(4i64.into())]),
+        );
+        Value::Dictionary(fontinfo)
+            .to_file_xml(ufo.join("fontinfo.plist"))
+            .unwrap();
+
+        // As if compiled from the UFO: the binary only exists in memory
+        let mut testable = test_able("nunito/Nunito-Regular.ttf");
+        edit_font(&mut testable).os2(|os2| os2.fs_type = 4);
+        testable.set_filename(ufo.with_extension("ttf"));
+        testable.source = Some(ufo.clone());
+
+        let mut registry = Registry::new();
+        GoogleFonts.register(&mut registry).unwrap();
+        let check = registry.checks.get("googlefonts/fstype").unwrap();
+        let result = check
+            .run(&TestableType::Single(&testable), &Context::default(), None)
+            .unwrap();
+        let mut results: RunResults = vec![result].into();
+
+        let args =
+            Args::try_parse_from(["fontspector", "--fix-sources", ufo.to_str().unwrap()]).unwrap();
+        try_fixing_stuff(&mut results, &args, &registry);
+
+        let result = results.iter().next().unwrap();
+        assert!(matches!(result.sourcefix_result, Some(FixResult::Fixed)));
+        let fontinfo = Value::from_file(ufo.join("fontinfo.plist"))
+            .unwrap()
+            .into_dictionary()
+            .unwrap();
+        assert_eq!(fontinfo.get("openTypeOS2Type"), Some(&Value::Array(vec![])));
+        assert!(!ufo.with_extension("ttf").exists());
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
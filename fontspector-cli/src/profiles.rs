@@ -1,8 +1,14 @@
-use fontspector_checkapi::{Profile, ProfileProvider, Registry};
+use fontspector_checkapi::{ProfileProvider, Registry};
+#[cfg(feature = "adobe")]
+use profile_adobe::Adobe;
+use profile_designspace::Designspace;
 use profile_fontwerk::Fontwerk;
 use profile_googlefonts::GoogleFonts;
 use profile_iso15008::Iso15008;
+#[cfg(feature = "microsoft")]
+use profile_microsoft::Microsoft;
 use profile_opentype::OpenType;
+use profile_typenetwork::TypeNetwork;
 use profile_universal::Universal;
 use std::{io::Read, path::PathBuf};
 
@@ -23,13 +29,8 @@ pub(crate) fn register_and_return_toml_profile(
                 log::error!("Could not read profile {name:}: {e:}");
                 std::process::exit(1);
             }
-            let profile: Profile = Profile::from_toml(&toml).unwrap_or_else(|e| {
-                log::error!("Could not parse profile {name:}: {e:}");
-                std::process::exit(1);
-            });
-
             registry
-                .register_profile(&name, profile, true)
+                .register_toml_profile(&name, &toml)
                 .unwrap_or_else(|e| {
                     log::error!("Could not register profile {name:}: {e:}");
                     std::process::exit(1);
@@ -50,6 +51,10 @@ pub(crate) fn register_core_profiles(args: &Args, registry: &mut Registry<'stati
         .register(registry)
         .expect("Couldn't register opentype profile, fontspector bug");
     #[allow(clippy::expect_used)] // If this fails, I *want* to panic
+    Designspace
+        .register(registry)
+        .expect("Couldn't register designspace profile, fontspector bug");
+    #[allow(clippy::expect_used)] // If this fails, I *want* to panic
     Universal
         .register(registry)
         .expect("Couldn't register universal profile, fontspector bug");
@@ -68,4 +73,21 @@ pub(crate) fn register_core_profiles(args: &Args, registry: &mut Registry<'stati
     Fontwerk
         .register(registry)
         .expect("Couldn't register fontwerk profile, fontspector bug");
+
+    #[allow(clippy::expect_used)] // If this fails, I *want* to panic
+    TypeNetwork
+        .register(registry)
+        .expect("Couldn't register typenetwork profile, fontspector bug");
+
+    #[cfg(feature = "adobe")]
+    #[allow(clippy::expect_used)] // If this fails, I *want* to panic
+    Adobe
+        .register(registry)
+        .expect("Couldn't register adobefonts profile, fontspector bug");
+
+    #[cfg(feature = "microsoft")]
+    #[allow(clippy::expect_used)] // If this fails, I *want* to panic
+    Microsoft
+        .register(registry)
+        .expect("Couldn't register microsoft profile, fontspector bug");
 }
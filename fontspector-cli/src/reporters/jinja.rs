@@ -4,7 +4,7 @@ use crate::{
     reporters::{Reporter, RunResults},
     Args,
 };
-use fontspector_checkapi::{CheckResult, Registry, StatusCode};
+use fontspector_checkapi::{CheckResult, FontChange, Registry, StatusCode};
 use indexmap::IndexMap;
 use serde_json::json;
 use std::io::Write;
@@ -67,6 +67,12 @@ fn basename(v: &Value, _options: &HashMap<String, Value>) -> tera::Result<Value>
     Ok(v.into())
 }
 
+fn describe_change(v: &Value, _options: &HashMap<String, Value>) -> tera::Result<Value> {
+    let change: FontChange = serde_json::from_value(v.clone())
+        .map_err(|e| tera::Error::msg(format!("Error parsing font change: {e}")))?;
+    Ok(change.to_string().into())
+}
+
 impl JinjaTemplatedReporter {
     pub fn new_html(filename: &str, update_templates: bool) -> Self {
         Self::new(filename, update_templates, "HTML", "html", "main.html")
@@ -107,6 +113,7 @@ impl JinjaTemplatedReporter {
         tera.register_filter("emoticon", emoticon);
         tera.register_filter("markdown", markdown);
         tera.register_filter("basename", basename);
+        tera.register_filter("describe_change", describe_change);
         Self {
             tera: RefCell::new(tera),
             filename: filename.to_string(),
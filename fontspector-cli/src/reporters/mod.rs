@@ -57,14 +57,16 @@ impl RunResults {
     pub fn organize(&self) -> OrganisedResults<'_> {
         let mut organised_results: OrganisedResults = HashMap::new();
         for checkresult in self.iter() {
-            let section = organised_results
-                .entry(
-                    checkresult
-                        .filename
-                        .clone()
-                        .unwrap_or("All fonts".to_string()),
-                )
-                .or_default();
+            let filename = checkresult
+                .filename
+                .clone()
+                .unwrap_or("All fonts".to_string());
+            // Families split out of the same directory are reported separately
+            let filename = match &checkresult.label {
+                Some(label) => format!("{filename} ({label})"),
+                None => filename,
+            };
+            let section = organised_results.entry(filename).or_default();
             let results = section
                 .entry(
                     checkresult
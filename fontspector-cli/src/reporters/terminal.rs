@@ -120,7 +120,10 @@ impl Reporter for TerminalReporter {
                             termimad::print_inline("  This issue can be fixed automatically. Run with `--hotfix` to apply the fix.\n")
                         }
                         Some(FixResult::Fixed) => {
-                            termimad::print_inline("  Hotfix applied.\n")
+                            termimad::print_inline("  Hotfix applied.\n");
+                            for change in result.hotfix_changes.iter() {
+                                let _ = writeln!(std::io::stdout(), "    - {change}");
+                            }
                         }
                         Some(FixResult::FixFailed(e)) => {
                             termimad::print_inline(&format!("  Hotfix failed: {e:}\n"))
@@ -0,0 +1,321 @@
+//! # fontspector-fuzz
+//!
+//! A fuzzing harness for fontspector's checks.
+//!
+//! Checks are given whatever bytes our users upload, so they must report
+//! problems with a broken font rather than panicking on it. This crate
+//! makes mutated copies of the files in `resources/test` and runs them
+//! through every check in every built-in profile, looking for checks which
+//! panic or run out of time.
+//!
+//! To fuzz for a while:
+//!
+//! ```sh
+//! cargo run --release -p fontspector-fuzz -- --iterations 10000
+//! ```
+//!
+//! Any input which makes a check panic or time out is saved in the regression corpus in
+//! `fontspector-fuzz/corpus`, under the seed which produced it. Every file in
+//! the corpus is run through the checks again by `cargo test -p fontspector-fuzz`,
+//! so once the check has been fixed, commit the input to keep it fixed.
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use fontspector_checkapi::{
+    codetesting::run_check_with_timeout, ProfileProvider, Registry, Testable, TestableCollection,
+    TestableType,
+};
+use profile_adobe::Adobe;
+use profile_designspace::Designspace;
+use profile_fontwerk::Fontwerk;
+use profile_googlefonts::GoogleFonts;
+use profile_iso15008::Iso15008;
+use profile_microsoft::Microsoft;
+use profile_opentype::OpenType;
+use profile_typenetwork::TypeNetwork;
+use profile_universal::Universal;
+use walkdir::WalkDir;
+
+/// A registry containing every built-in profile
+#[allow(clippy::expect_used)]
+pub fn get_registry() -> Registry<'static> {
+    let mut registry = Registry::new();
+    OpenType
+        .register(&mut registry)
+        .expect("Couldn't register opentype profile");
+    Designspace
+        .register(&mut registry)
+        .expect("Couldn't register designspace profile");
+    Universal
+        .register(&mut registry)
+        .expect("Couldn't register universal profile");
+    GoogleFonts
+        .register(&mut registry)
+        .expect("Couldn't register googlefonts profile");
+    Iso15008
+        .register(&mut registry)
+        .expect("Couldn't register iso15008 profile");
+    Fontwerk
+        .register(&mut registry)
+        .expect("Couldn't register fontwerk profile");
+    Adobe
+        .register(&mut registry)
+        .expect("Couldn't register adobefonts profile");
+    Microsoft
+        .register(&mut registry)
+        .expect("Couldn't register microsoft profile");
+    TypeNetwork
+        .register(&mut registry)
+        .expect("Couldn't register typenetwork profile");
+    registry
+}
+
+/// The longest any check may spend on a single input
+///
+/// Checks are expected to finish in well under a second on the test files,
+/// so anything which takes this long has found a pathological case.
+pub const CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The directory holding the regression corpus
+pub fn corpus_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("corpus")
+}
+
+/// Load every file under a directory which some check knows how to test
+///
+/// Directories, such as UFO sources, are not loaded, as there are no bytes
+/// to mutate.
+pub fn load_files(registry: &Registry, directory: &Path) -> Vec<Testable> {
+    WalkDir::new(directory)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .filter_map(|entry| Testable::new(entry.path()).ok())
+        .filter(|testable| registry.is_known_file(testable))
+        .collect()
+}
+
+/// Result codes which mean a check could not cope with an input
+const CRASH_CODES: [&str; 2] = ["panic", "timeout"];
+
+/// A check which panicked or ran out of time on an input
+#[derive(Debug, Clone)]
+pub struct Crash {
+    /// The ID of the check
+    pub check_id: String,
+    /// What went wrong: `panic` or `timeout`
+    pub code: String,
+    /// The message the check reported
+    pub message: String,
+}
+
+impl std::fmt::Display for Crash {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({}): {}", self.check_id, self.code, self.message)
+    }
+}
+
+/// Run every check which applies to a file, returning the checks which
+/// panicked or ran out of time
+///
+/// Checks on single files are run on the file; checks on collections are
+/// run on a collection containing just the file. Network access is disabled,
+/// and each check is held to [CHECK_TIMEOUT].
+pub fn crashes(registry: &Registry, testable: &Testable) -> Vec<Crash> {
+    let collection = TestableCollection::from_testables(vec![testable.clone()], None);
+    let single = TestableType::Single(testable);
+    let mut crashes = vec![];
+    for check in registry.iter() {
+        let things = if check.runs_on_collection() {
+            TestableType::Collection(&collection)
+        } else if check.applies(&single, registry) {
+            TestableType::Single(testable)
+        } else {
+            continue;
+        };
+        let Some(result) = run_check_with_timeout(
+            check.clone(),
+            things,
+            HashMap::new(),
+            true,
+            Some(CHECK_TIMEOUT),
+        ) else {
+            continue;
+        };
+        crashes.extend(result.subresults.iter().filter_map(|status| {
+            let code = status.code.as_deref()?;
+            CRASH_CODES.contains(&code).then(|| Crash {
+                check_id: check.id.to_string(),
+                code: code.to_string(),
+                message: status.message.clone().unwrap_or_default(),
+            })
+        }));
+    }
+    crashes
+}
+
+/// A small pseudo-random number generator
+///
+/// Fuzzing runs need to be reproducible from their seed, and this is all
+/// the randomness we need, so we don't depend on a random number crate.
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    /// Create a generator from a seed
+    pub fn new(seed: u64) -> Self {
+        // xorshift gets stuck at zero
+        Self(seed.max(1))
+    }
+
+    /// The next random number
+    pub fn next_u64(&mut self) -> u64 {
+        // xorshift64*
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    /// A random number less than `n`, or zero if `n` is zero
+    pub fn below(&mut self, n: usize) -> usize {
+        if n == 0 {
+            0
+        } else {
+            (self.next_u64() % n as u64) as usize
+        }
+    }
+}
+
+/// Values which are likely to be at the edges of what a parser expects
+const INTERESTING_BYTES: [u8; 6] = [0x00, 0x01, 0x7f, 0x80, 0xfe, 0xff];
+
+/// The byte range of a random table in an OpenType font, if it is one
+///
+/// Mutations inside a table are much more likely to get past the font
+/// parser and reach the checks than mutations anywhere in the file. The
+/// range is not checked against the length of the file.
+fn random_table(contents: &[u8], rng: &mut Rng) -> Option<(usize, usize)> {
+    let read_u32 = |offset: usize| -> Option<usize> {
+        let bytes = contents.get(offset..offset + 4)?;
+        Some(u32::from_be_bytes(bytes.try_into().ok()?) as usize)
+    };
+    let num_tables = u16::from_be_bytes(contents.get(4..6)?.try_into().ok()?) as usize;
+    if num_tables == 0 {
+        return None;
+    }
+    let record = 12 + 16 * rng.below(num_tables);
+    let offset = read_u32(record + 8)?;
+    let length = read_u32(record + 12)?;
+    Some((offset, offset.saturating_add(length)))
+}
+
+/// Make a mutated copy of a file's contents
+///
+/// Between one and four mutations are applied, each of which may flip
+/// some bits, write an interesting value, truncate the file or copy one
+/// part of it over another; mutations in fonts favour the table data.
+pub fn mutate(contents: &[u8], rng: &mut Rng) -> Vec<u8> {
+    let mut contents = contents.to_vec();
+    for _ in 0..=rng.below(4) {
+        if contents.is_empty() {
+            break;
+        }
+        // Where to mutate: within a table, or anywhere at all
+        let (start, end) = random_table(&contents, rng)
+            .filter(|_| rng.below(4) != 0)
+            .map(|(start, end)| (start.min(contents.len()), end.min(contents.len())))
+            .filter(|(start, end)| start < end)
+            .unwrap_or((0, contents.len()));
+        let position = start + rng.below(end - start);
+        match rng.below(5) {
+            0 => {
+                if let Some(byte) = contents.get_mut(position) {
+                    *byte ^= 1 << rng.below(8);
+                }
+            }
+            1 => {
+                let value = INTERESTING_BYTES
+                    .get(rng.below(INTERESTING_BYTES.len()))
+                    .copied()
+                    .unwrap_or_default();
+                let width = 1 << rng.below(3);
+                for byte in contents.iter_mut().skip(position).take(width) {
+                    *byte = value;
+                }
+            }
+            2 => contents.truncate(position),
+            3 => {
+                let length = 1 + rng.below(64);
+                let source = rng.below(contents.len());
+                let chunk = contents
+                    .iter()
+                    .skip(source)
+                    .take(length)
+                    .copied()
+                    .collect::<Vec<_>>();
+                for (byte, new) in contents.iter_mut().skip(position).zip(chunk) {
+                    *byte = new;
+                }
+            }
+            _ => {
+                if let Some(byte) = contents.get_mut(position) {
+                    *byte = rng.next_u64() as u8;
+                }
+            }
+        }
+    }
+    contents
+}
+
+/// Save an input which made a check panic or time out to the regression corpus
+///
+/// The file keeps its name, as many checks look at it, and is stored in a
+/// directory named after the seed which produced it.
+pub fn save_to_corpus(testable: &Testable, seed: u64) -> std::io::Result<PathBuf> {
+    let directory = corpus_dir().join(format!("{seed:016x}"));
+    std::fs::create_dir_all(&directory)?;
+    let path = directory.join(testable.filename.file_name().unwrap_or_default());
+    std::fs::write(&path, &testable.contents)?;
+    Ok(path)
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fontspector_checkapi::codetesting::test_file;
+
+    #[test]
+    fn test_mutate_is_reproducible() {
+        let font = std::fs::read(test_file("nunito/Nunito-Regular.ttf")).unwrap();
+        let first = mutate(&font, &mut Rng::new(42));
+        assert_eq!(first, mutate(&font, &mut Rng::new(42)));
+        assert!(mutate(&[], &mut Rng::new(42)).is_empty());
+    }
+
+    #[test]
+    fn test_regression_corpus() {
+        let registry = get_registry();
+        let corpus = load_files(&registry, &corpus_dir());
+        assert!(!corpus.is_empty(), "The regression corpus is empty");
+        for testable in corpus {
+            let crashes = crashes(&registry, &testable);
+            assert!(
+                crashes.is_empty(),
+                "Checks failed on {:?}:\n{}",
+                testable.filename,
+                crashes
+                    .iter()
+                    .map(|crash| crash.to_string())
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            );
+        }
+    }
+}
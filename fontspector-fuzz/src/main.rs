@@ -0,0 +1,92 @@
+//! Run mutated test files through every fontspector check, saving any which cause a panic or timeout
+use std::{
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use clap::Parser;
+use fontspector_checkapi::Testable;
+use fontspector_fuzz::{crashes, get_registry, load_files, mutate, save_to_corpus, Rng};
+
+#[derive(Parser)]
+#[command(name = "fontspector-fuzz")]
+#[command(about = "Look for fontspector checks which panic or hang on broken files", long_about = None)]
+struct Args {
+    /// Directories of files to mutate (default: the test resources)
+    #[arg(value_name = "DIR")]
+    inputs: Vec<PathBuf>,
+
+    /// Number of mutated files to check
+    #[arg(short = 'n', long = "iterations", default_value_t = 100)]
+    iterations: usize,
+
+    /// Seed for the random number generator (default: the current time)
+    #[arg(short = 's', long = "seed")]
+    seed: Option<u64>,
+}
+
+fn main() {
+    let args = Args::parse();
+    let registry = get_registry();
+    let inputs = if args.inputs.is_empty() {
+        vec![PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../resources/test")]
+    } else {
+        args.inputs
+    };
+    let originals = inputs
+        .iter()
+        .flat_map(|input| load_files(&registry, input))
+        .filter(|testable| !testable.contents.is_empty())
+        .collect::<Vec<_>>();
+    if originals.is_empty() {
+        eprintln!("No files to mutate");
+        std::process::exit(1);
+    }
+    let seed = args.seed.unwrap_or_else(|| {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_nanos() as u64)
+            .unwrap_or_default()
+    });
+    println!(
+        "Fuzzing {} files with seed {seed} for {} iterations",
+        originals.len(),
+        args.iterations
+    );
+
+    // Panics are reported as check results, so we don't need them on the terminal too.
+    std::panic::set_hook(Box::new(|_| {}));
+
+    let mut rng = Rng::new(seed);
+    let mut found = 0;
+    for _ in 0..args.iterations {
+        let Some(original) = originals.get(rng.below(originals.len())) else {
+            continue;
+        };
+        // Each input gets its own seed, so it can be reproduced on its own.
+        let input_seed = rng.next_u64();
+        let mutated = Testable::new_with_contents(
+            original.filename.clone(),
+            mutate(&original.contents, &mut Rng::new(input_seed)),
+        );
+        let crashes = crashes(&registry, &mutated);
+        if crashes.is_empty() {
+            continue;
+        }
+        found += 1;
+        match save_to_corpus(&mutated, input_seed) {
+            Ok(path) => println!("Checks failed on {}:", path.display()),
+            Err(e) => println!(
+                "Checks failed on {} mutated with seed {input_seed} (could not save it: {e}):",
+                original.filename.display()
+            ),
+        }
+        for crash in crashes {
+            println!("  {crash}");
+        }
+    }
+    println!("Found {found} inputs which made checks panic or time out");
+    if found > 0 {
+        std::process::exit(1);
+    }
+}
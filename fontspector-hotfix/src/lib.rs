@@ -12,25 +12,42 @@
 //!
 //! let mut testable = Testable::new("path/to/font.ttf")?;
 //! let check_ids = vec!["com/google/fonts/check/example"];
-//! let modified = apply_hotfixes(&mut testable, &check_ids)?;
+//! let modified = apply_hotfixes(&mut testable, &check_ids, false)?;
 //!
 //! if modified {
 //!     testable.save()?;
 //! }
 //! ```
+//!
+//! To fix several fonts in one go, use a [Hotfixer], which can remember
+//! the answers to a hotfix's questions between fonts of the same family:
+//!
+//! ```ignore
+//! use fontspector_hotfix::Hotfixer;
+//!
+//! let mut hotfixer = Hotfixer::new(true).batch(true);
+//! for testable in testables.iter_mut() {
+//!     let results = hotfixer.apply(testable, &check_ids)?;
+//! }
+//! ```
 
 use dialoguer::{theme::ColorfulTheme, Confirm, Input, Select};
 use fontspector_checkapi::{
-    CheckId, DialogFieldType, FixResult, FontspectorError, MoreInfoReplies, MoreInfoRequest,
-    ProfileProvider, Registry,
+    CheckId, DialogFieldType, FileTypeConvert, FixResult, FontspectorError, HotfixAnswers,
+    MoreInfoReplies, MoreInfoRequest, ProfileProvider, Registry, TTF,
 };
+#[cfg(feature = "adobe")]
+use profile_adobe::Adobe;
+use profile_designspace::Designspace;
 use profile_fontwerk::Fontwerk;
 use profile_googlefonts::GoogleFonts;
 use profile_iso15008::Iso15008;
+#[cfg(feature = "microsoft")]
+use profile_microsoft::Microsoft;
 use profile_opentype::OpenType;
 use profile_universal::Universal;
 use serde_json::Value;
-use std::io::Write;
+use std::{collections::HashMap, io::Write};
 use termimad::MadSkin;
 
 #[doc(hidden)]
@@ -39,6 +56,9 @@ pub fn get_registry() -> Registry<'static> {
     OpenType
         .register(&mut registry)
         .expect("Couldn't register opentype profile");
+    Designspace
+        .register(&mut registry)
+        .expect("Couldn't register designspace profile");
     Universal
         .register(&mut registry)
         .expect("Couldn't register universal profile");
@@ -51,6 +71,14 @@ pub fn get_registry() -> Registry<'static> {
     Fontwerk
         .register(&mut registry)
         .expect("Couldn't register fontwerk profile");
+    #[cfg(feature = "adobe")]
+    Adobe
+        .register(&mut registry)
+        .expect("Couldn't register adobefonts profile");
+    #[cfg(feature = "microsoft")]
+    Microsoft
+        .register(&mut registry)
+        .expect("Couldn't register microsoft profile");
     registry
 }
 
@@ -83,66 +111,181 @@ pub fn apply_hotfixes(
     check_ids: &[CheckId],
     interactive: bool,
 ) -> HotfixResult {
-    let mut any_modified = false;
-
-    let registry = get_registry();
-
-    for check_id in check_ids {
-        let Some(check) = registry.checks.get(check_id.as_str()) else {
-            log::warn!("Check ID '{}' not found in registry", check_id);
-            continue;
-        };
-
-        let Some(hotfix) = check.hotfix else {
-            log::debug!("Check '{}' has no hotfix function", check_id);
-            continue;
-        };
-
-        log::info!("Applying hotfix for check '{}'", check_id);
-        let mut options = None;
-        let mut header_shown = false;
-
-        loop {
-            match hotfix(testable, options) {
-                Ok(FixResult::MoreInfoNeeded(dialog)) => {
-                    if !interactive {
-                        log::error!(
-                            "Hotfix for '{}' requires more information, but interactive mode is disabled",
-                            check_id
-                        );
-                        break;
-                    } else {
+    let results = Hotfixer::new(interactive).apply(testable, check_ids)?;
+    Ok(results
+        .iter()
+        .any(|(_, result)| matches!(result, FixResult::Fixed)))
+}
+
+/// Applies hotfixes to a series of fonts, sharing state between them.
+///
+/// Using a `Hotfixer` rather than calling [apply_hotfixes] for each font
+/// means the registry is only built once, and (in batch mode) answers given
+/// to a hotfix's questions can be remembered and reused for the rest of the
+/// fonts in the same family, instead of asking the same questions once per file.
+pub struct Hotfixer {
+    /// The registry of checks to look up hotfix functions in
+    registry: Registry<'static>,
+    /// Whether we can ask the user for more information
+    interactive: bool,
+    /// Whether to offer to reuse answers for subsequent fonts
+    batch: bool,
+    /// Answers to reuse, by family name and check ID
+    remembered_answers: HashMap<(Option<String>, CheckId), MoreInfoReplies>,
+    /// Answers supplied ahead of time, e.g. from an answers file
+    answers: Option<HotfixAnswers>,
+}
+
+impl Hotfixer {
+    /// Create a new hotfixer
+    pub fn new(interactive: bool) -> Self {
+        Self {
+            registry: get_registry(),
+            interactive,
+            batch: false,
+            remembered_answers: HashMap::new(),
+            answers: None,
+        }
+    }
+
+    /// Offer to reuse answers to hotfix questions for subsequent fonts
+    pub fn batch(mut self, batch: bool) -> Self {
+        self.batch = batch;
+        self
+    }
+
+    /// Answer hotfix questions from a set of pre-supplied answers
+    ///
+    /// If a hotfix asks a question which is not answered, the user is asked
+    /// in interactive mode; otherwise, applying the hotfix fails.
+    pub fn with_answers(mut self, answers: HotfixAnswers) -> Self {
+        self.answers = Some(answers);
+        self
+    }
+
+    /// The registry used to look up checks
+    pub fn registry(&self) -> &Registry<'static> {
+        &self.registry
+    }
+
+    /// Apply hotfixes to a testable for the given check IDs.
+    ///
+    /// Returns the result of each hotfix which was run, in order. Checks
+    /// which are not found, or which have no hotfix function, are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any hotfix function encounters an error during execution.
+    pub fn apply(
+        &mut self,
+        testable: &mut Testable,
+        check_ids: &[CheckId],
+    ) -> Result<Vec<(CheckId, FixResult)>, FontspectorError> {
+        let mut results = vec![];
+        // Taken before any fixes run, in case they change the name table
+        let family = family_name(testable);
+
+        for check_id in check_ids {
+            let Some(check) = self.registry.checks.get(check_id.as_str()) else {
+                log::warn!("Check ID '{}' not found in registry", check_id);
+                continue;
+            };
+
+            let Some(hotfix) = check.hotfix else {
+                log::debug!("Check '{}' has no hotfix function", check_id);
+                continue;
+            };
+
+            log::info!("Applying hotfix for check '{}'", check_id);
+            let remember_key = (family.clone(), check_id.clone());
+            let mut options = self.remembered_answers.get(&remember_key).cloned();
+            let mut header_shown = false;
+            let mut answers_tried = false;
+
+            loop {
+                match hotfix(testable, options) {
+                    Ok(FixResult::MoreInfoNeeded(dialog)) => {
+                        if let Some(answers) = &self.answers {
+                            match answers.answer(check_id, &dialog) {
+                                Ok(replies) if !answers_tried => {
+                                    answers_tried = true;
+                                    options = Some(replies);
+                                    continue;
+                                }
+                                Ok(_) => {
+                                    return Err(FontspectorError::Fix(format!(
+                                        "Hotfix for '{check_id}' still needs more information after using the answers provided"
+                                    )));
+                                }
+                                Err(e) if !self.interactive => {
+                                    log::error!(
+                                        "Hotfix for '{}' could not be answered: {}",
+                                        check_id,
+                                        e
+                                    );
+                                    return Err(e);
+                                }
+                                Err(e) => log::warn!("{}", e),
+                            }
+                        }
+                        if !self.interactive {
+                            log::error!(
+                                "Hotfix for '{}' requires more information, but interactive mode is disabled",
+                                check_id
+                            );
+                            results.push((check_id.clone(), FixResult::MoreInfoNeeded(dialog)));
+                            break;
+                        }
                         if !header_shown {
                             show_header(testable, check_id, check.title);
                             header_shown = true;
                         }
 
                         options = run_dialog(&dialog);
+                        if let Some(answers) = &options {
+                            if self.batch && confirm_remember() {
+                                self.remembered_answers
+                                    .insert(remember_key.clone(), answers.clone());
+                            }
+                        }
+                        continue;
                     }
-                    continue;
-                }
-                Ok(hotfix_result) => {
-                    if matches!(hotfix_result, FixResult::Fixed) {
-                        log::info!("Check '{}' modified the font", check_id);
-                        any_modified = true;
-                    } else {
-                        log::debug!(
-                            "Check '{}' completed with result: {:?}",
-                            check_id,
-                            hotfix_result
-                        );
+                    Ok(hotfix_result) => {
+                        if matches!(hotfix_result, FixResult::Fixed) {
+                            log::info!("Check '{}' modified the font", check_id);
+                        } else {
+                            log::debug!(
+                                "Check '{}' completed with result: {:?}",
+                                check_id,
+                                hotfix_result
+                            );
+                        }
+                        results.push((check_id.clone(), hotfix_result));
+                        break;
+                    }
+                    Err(e) => {
+                        log::error!("Hotfix for '{}' failed: {}", check_id, e);
+                        return Err(e);
                     }
-                    break;
-                }
-                Err(e) => {
-                    log::error!("Hotfix for '{}' failed: {}", check_id, e);
-                    return Err(e);
                 }
             }
         }
+
+        Ok(results)
     }
+}
 
-    Ok(any_modified)
+/// The family a font belongs to, for sharing remembered answers
+fn family_name(testable: &Testable) -> Option<String> {
+    TTF.from_testable(testable)?.best_familyname()
+}
+
+fn confirm_remember() -> bool {
+    Confirm::with_theme(&ColorfulTheme::default())
+        .with_prompt("Use these answers for the remaining fonts in this family too?")
+        .default(true)
+        .interact()
+        .unwrap_or(false)
 }
 
 fn show_header(testable: &mut Testable, check_id: &str, check_name: &str) {
@@ -226,7 +369,19 @@ fn run_dialog(dialog: &MoreInfoRequest) -> Option<MoreInfoReplies> {
 
 #[cfg(test)]
 mod tests {
+    #![allow(clippy::unwrap_used)]
     use super::*;
+    use fontspector_checkapi::codetesting::test_able;
+
+    #[test]
+    fn test_family_name() {
+        let regular = test_able("merriweather/Merriweather-Regular.ttf");
+        let italic = test_able("merriweather/Merriweather-Italic.ttf");
+        let other = test_able("nunito/Nunito-Regular.ttf");
+        assert_eq!(family_name(&regular), Some("Merriweather".to_string()));
+        assert_eq!(family_name(&regular), family_name(&italic));
+        assert_ne!(family_name(&regular), family_name(&other));
+    }
 
     #[test]
     fn test_apply_hotfixes_empty() {
@@ -1,20 +1,27 @@
 //! Simple CLI tool for applying hotfixes to font binaries
 use std::io::IsTerminal as _;
+#[cfg(feature = "cli")]
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
 
 #[cfg(feature = "cli")]
 use clap::Parser;
 
 #[cfg(feature = "cli")]
-use fontspector_checkapi::Testable;
+use fontspector_checkapi::{diff_fonts, FixResult, HotfixAnswers, Testable};
+#[cfg(feature = "cli")]
+use fontspector_hotfix::Hotfixer;
 
 #[cfg(feature = "cli")]
 #[derive(Parser)]
 #[command(name = "fontspector-hotfix")]
 #[command(about = "Apply font QA hotfixes to font binaries", long_about = None)]
 struct Args {
-    /// Path to the font file to fix
-    #[arg(value_name = "FONT")]
-    font: String,
+    /// Paths to the font files to fix, or directories containing them
+    #[arg(value_name = "FONT", required = true)]
+    fonts: Vec<String>,
 
     /// Check IDs to apply hotfixes for (can be specified multiple times)
     #[arg(short = 'c', long = "check", value_name = "CHECK_ID")]
@@ -24,10 +31,27 @@ struct Args {
     #[arg(short = 'p', long = "profile", value_name = "PROFILE")]
     profile: Option<String>,
 
-    /// Output file (if not specified, overwrites input)
-    #[arg(short = 'o', long = "output", value_name = "OUTPUT")]
+    /// Output file (only for a single font; if not specified, overwrites input)
+    #[arg(short = 'o', long = "output", value_name = "OUTPUT", conflicts_with_all = ["output_dir", "in_place", "dry_run"])]
     output: Option<String>,
 
+    /// Directory to write fixed fonts to, keeping their filenames
+    #[arg(short = 'd', long = "output-dir", value_name = "DIR", conflicts_with_all = ["in_place", "dry_run"])]
+    output_dir: Option<String>,
+
+    /// Overwrite the input fonts (required when fixing more than one font
+    /// without --output-dir)
+    #[arg(short = 'i', long = "in-place", conflicts_with = "dry_run")]
+    in_place: bool,
+
+    /// Report which fixes would be applied, without writing anything
+    #[arg(long = "dry-run")]
+    dry_run: bool,
+
+    /// TOML or JSON file of answers to hotfix questions, keyed by check ID
+    #[arg(short = 'a', long = "answers", value_name = "FILE")]
+    answers: Option<String>,
+
     /// Non-interactive mode (don't prompt for additional information)
     /// Automatically set if input is not a TTY
     #[arg(short = 'n', long = "non-interactive")]
@@ -38,6 +62,166 @@ struct Args {
     verbose: bool,
 }
 
+#[cfg(feature = "cli")]
+/// Expand any directories in the arguments into the font files they contain
+fn expand_fonts(inputs: &[String]) -> Vec<PathBuf> {
+    let mut fonts = vec![];
+    for input in inputs {
+        let path = Path::new(input);
+        if !path.is_dir() {
+            fonts.push(path.to_path_buf());
+            continue;
+        }
+        let entries = std::fs::read_dir(path).unwrap_or_else(|e| {
+            eprintln!("Error: Could not read directory '{}': {}", input, e);
+            std::process::exit(1);
+        });
+        let mut found: Vec<PathBuf> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.is_file()
+                    && path
+                        .extension()
+                        .and_then(|ext| ext.to_str())
+                        .is_some_and(|ext| {
+                            ext.eq_ignore_ascii_case("ttf") || ext.eq_ignore_ascii_case("otf")
+                        })
+            })
+            .collect();
+        found.sort();
+        if found.is_empty() {
+            log::warn!("No font files found in directory '{}'", input);
+        }
+        fonts.extend(found);
+    }
+    fonts
+}
+
+#[cfg(feature = "cli")]
+/// Work out where each fixed font should be written; `None` for a dry run
+///
+/// Fails if two fonts would be written to the same file in the output
+/// directory, rather than letting one silently overwrite the other.
+fn output_paths(args: &Args, fonts: &[PathBuf]) -> Result<Vec<Option<PathBuf>>, String> {
+    if args.dry_run {
+        return Ok(vec![None; fonts.len()]);
+    }
+    if let Some(output) = &args.output {
+        return Ok(vec![Some(PathBuf::from(output)); fonts.len()]);
+    }
+    let Some(output_dir) = &args.output_dir else {
+        return Ok(fonts.iter().cloned().map(Some).collect());
+    };
+    let mut seen: HashMap<PathBuf, &PathBuf> = HashMap::new();
+    let mut outputs = vec![];
+    for font in fonts {
+        let basename = font
+            .file_name()
+            .ok_or_else(|| format!("'{}' has no filename", font.display()))?;
+        let output = Path::new(output_dir).join(basename);
+        if let Some(other) = seen.insert(output.clone(), font) {
+            return Err(format!(
+                "'{}' and '{}' would both be written to '{}'",
+                other.display(),
+                font.display(),
+                output.display()
+            ));
+        }
+        outputs.push(Some(output));
+    }
+    Ok(outputs)
+}
+
+#[cfg(feature = "cli")]
+/// Describe a fix result for the summary report
+fn describe(result: &FixResult, dry_run: bool) -> Option<String> {
+    match result {
+        FixResult::Fixed if dry_run => Some("would be fixed".to_string()),
+        FixResult::Fixed => Some("fixed".to_string()),
+        FixResult::FixFailed(reason) => Some(format!("fix failed ({reason})")),
+        FixResult::MoreInfoNeeded(_) => Some("needs more information, skipped".to_string()),
+        FixResult::Unfixable => Some("could not be fixed".to_string()),
+        FixResult::Available | FixResult::NotBroken => None,
+    }
+}
+
+#[cfg(feature = "cli")]
+/// Fix each font and write it to its output path
+///
+/// Returns false if applying the hotfixes to any font failed.
+fn fix_fonts(
+    fonts: &[PathBuf],
+    outputs: &[Option<PathBuf>],
+    hotfixer: &mut Hotfixer,
+    check_ids: &[String],
+    dry_run: bool,
+) -> bool {
+    let mut ok = true;
+    for (font, output) in fonts.iter().zip(outputs) {
+        let font_name = font.display().to_string();
+        // Load the font
+        let mut testable = Testable::new(font).unwrap_or_else(|e| {
+            eprintln!("Error: Could not load font '{}': {}", font_name, e);
+            std::process::exit(1);
+        });
+
+        let original = testable.contents.clone();
+
+        // Apply hotfixes
+        let results = match hotfixer.apply(&mut testable, check_ids) {
+            Ok(results) => results,
+            Err(e) => {
+                eprintln!("Error: Failed to apply hotfixes to '{}': {}", font_name, e);
+                ok = false;
+                continue;
+            }
+        };
+        for (check_id, result) in results.iter() {
+            if let Some(description) = describe(result, dry_run) {
+                println!("{font_name}: {check_id}: {description}");
+            }
+        }
+        let modified = results
+            .iter()
+            .any(|(_, result)| matches!(result, FixResult::Fixed));
+
+        if !modified {
+            println!("No modifications were made to '{}'", font_name);
+            continue;
+        }
+        match diff_fonts(&original, &testable.contents) {
+            Ok(changes) => {
+                for change in changes {
+                    println!("  {change}");
+                }
+            }
+            Err(e) => log::warn!("Could not work out what changed in '{font_name}': {e}"),
+        }
+        let Some(output) = output else {
+            continue;
+        };
+
+        // Save the file
+        testable.filename = output.clone();
+
+        testable.save().unwrap_or_else(|e| {
+            eprintln!(
+                "Error: Could not save font '{}': {}",
+                testable.filename.display(),
+                e
+            );
+            std::process::exit(1);
+        });
+
+        println!(
+            "Successfully applied hotfixes to '{}'",
+            testable.filename.display()
+        );
+    }
+    ok
+}
+
 #[cfg(feature = "cli")]
 fn main() {
     let args = Args::parse();
@@ -47,10 +231,28 @@ fn main() {
         if args.verbose { "info" } else { "warn" },
     ));
 
-    let registry = fontspector_hotfix::get_registry();
-
-    // Determine which check IDs to use
-    let mut check_ids = args.checks.clone();
+    let fonts = expand_fonts(&args.fonts);
+    if fonts.is_empty() {
+        eprintln!("Error: No fonts to fix.");
+        std::process::exit(1);
+    }
+    if fonts.len() > 1 && args.output_dir.is_none() && !args.in_place && !args.dry_run {
+        eprintln!("Error: Several fonts given. Use --output-dir, --in-place or --dry-run.");
+        std::process::exit(1);
+    }
+    if args.output.is_some() && fonts.len() > 1 {
+        eprintln!("Error: --output can only be used with a single font. Use --output-dir.");
+        std::process::exit(1);
+    }
+    if let Some(output_dir) = &args.output_dir {
+        std::fs::create_dir_all(output_dir).unwrap_or_else(|e| {
+            eprintln!(
+                "Error: Could not create output directory '{}': {}",
+                output_dir, e
+            );
+            std::process::exit(1);
+        });
+    }
 
     let mut interactive = !args.non_interactive;
     // If input is not a TTY, force non-interactive mode
@@ -58,12 +260,30 @@ fn main() {
         interactive = false;
     }
 
-    // If a profile is specified, get all check IDs from it
-    if let Some(profile_name) = &args.profile {
-        let profile = registry.get_profile(profile_name).unwrap_or_else(|| {
-            eprintln!("Error: Profile '{}' not found", profile_name);
+    let mut hotfixer = Hotfixer::new(interactive).batch(fonts.len() > 1);
+    if let Some(answers_file) = &args.answers {
+        let answers = HotfixAnswers::from_file(Path::new(answers_file)).unwrap_or_else(|e| {
+            eprintln!(
+                "Error: Could not load answers file '{}': {}",
+                answers_file, e
+            );
             std::process::exit(1);
         });
+        hotfixer = hotfixer.with_answers(answers);
+    }
+
+    // Determine which check IDs to use
+    let mut check_ids = args.checks.clone();
+
+    // If a profile is specified, get all check IDs from it
+    if let Some(profile_name) = &args.profile {
+        let profile = hotfixer
+            .registry()
+            .get_profile(profile_name)
+            .unwrap_or_else(|| {
+                eprintln!("Error: Profile '{}' not found", profile_name);
+                std::process::exit(1);
+            });
 
         // Collect all check IDs from the profile
         for checks_in_section in profile.sections.values() {
@@ -82,39 +302,12 @@ fn main() {
         std::process::exit(1);
     }
 
-    // Load the font
-    let mut testable = Testable::new(&args.font).unwrap_or_else(|e| {
-        eprintln!("Error: Could not load font '{}': {}", args.font, e);
+    let outputs = output_paths(&args, &fonts).unwrap_or_else(|e| {
+        eprintln!("Error: {e}");
         std::process::exit(1);
     });
-
-    // Apply hotfixes
-    match fontspector_hotfix::apply_hotfixes(&mut testable, &check_ids, interactive) {
-        Ok(modified) => {
-            if modified {
-                // Save the file
-                if let Some(output_path) = &args.output {
-                    // Save to a different file
-                    testable.filename = output_path.into();
-                }
-
-                testable.save().unwrap_or_else(|e| {
-                    eprintln!("Error: Could not save font '{}': {}", args.font, e);
-                    std::process::exit(1);
-                });
-
-                println!(
-                    "Successfully applied hotfixes to '{}'",
-                    args.output.as_ref().unwrap_or(&args.font)
-                );
-            } else {
-                println!("No modifications were made to '{}'", args.font);
-            }
-        }
-        Err(e) => {
-            eprintln!("Error: Failed to apply hotfixes: {}", e);
-            std::process::exit(1);
-        }
+    if !fix_fonts(&fonts, &outputs, &mut hotfixer, &check_ids, args.dry_run) {
+        std::process::exit(1);
     }
 }
 
@@ -123,3 +316,154 @@ fn main() {
     eprintln!("This binary requires the 'cli' feature to be enabled.");
     std::process::exit(1);
 }
+
+#[cfg(all(test, feature = "cli"))]
+mod tests {
+    #![allow(clippy::unwrap_used, clippy::indexing_slicing)]
+    use fontspector_checkapi::codetesting::{edit_font, test_able};
+
+    use super::*;
+
+    const FSTYPE: &str = "googlefonts/fstype";
+
+    /// An empty scratch directory for a test
+    fn scratch_directory(name: &str) -> PathBuf {
+        let directory =
+            std::env::temp_dir().join(format!("fontspector-hotfix-{}-{name}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&directory);
+        std::fs::create_dir_all(&directory).unwrap();
+        directory
+    }
+
+    /// Write a font which the fstype hotfix will change
+    fn write_broken_font(path: &Path) {
+        let mut testable = test_able("nunito/Nunito-Regular.ttf");
+        edit_font(&mut testable).os2(|os2| os2.fs_type = 4);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, &testable.contents).unwrap();
+    }
+
+    /// Does the fstype hotfix still have anything to fix in this font?
+    fn needs_fixing(path: &Path) -> bool {
+        let mut testable = Testable::new(path).unwrap();
+        let results = Hotfixer::new(false)
+            .apply(&mut testable, &[FSTYPE.to_string()])
+            .unwrap();
+        results
+            .iter()
+            .any(|(_, result)| matches!(result, FixResult::Fixed))
+    }
+
+    /// Parse the arguments and fix the fonts as `main` would
+    fn run(args: &[&str]) -> Result<Vec<PathBuf>, String> {
+        let args = Args::try_parse_from(
+            ["fontspector-hotfix", "-n", "-c", FSTYPE]
+                .iter()
+                .chain(args.iter()),
+        )
+        .map_err(|e| e.to_string())?;
+        let fonts = expand_fonts(&args.fonts);
+        let outputs = output_paths(&args, &fonts)?;
+        if let Some(output_dir) = &args.output_dir {
+            std::fs::create_dir_all(output_dir).unwrap();
+        }
+        let ok = fix_fonts(
+            &fonts,
+            &outputs,
+            &mut Hotfixer::new(false),
+            &args.checks,
+            args.dry_run,
+        );
+        assert!(ok);
+        Ok(outputs.into_iter().flatten().collect())
+    }
+
+    #[test]
+    fn test_expand_fonts() {
+        let directory = scratch_directory("expand");
+        for name in ["b.ttf", "a.OTF", "notes.txt", "nested/c.ttf"] {
+            let path = directory.join(name);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, b"").unwrap();
+        }
+        let fonts = expand_fonts(&[
+            directory.to_string_lossy().to_string(),
+            "elsewhere/d.ttf".to_string(),
+        ]);
+        // Only the fonts directly inside the directory, in order, then
+        // anything given as a file whether or not it exists
+        assert_eq!(
+            fonts,
+            vec![
+                directory.join("a.OTF"),
+                directory.join("b.ttf"),
+                PathBuf::from("elsewhere/d.ttf"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_output_dir() {
+        let directory = scratch_directory("output-dir");
+        let input = directory.join("in/Nunito-Regular.ttf");
+        write_broken_font(&input);
+        let original = std::fs::read(&input).unwrap();
+        let output_dir = directory.join("out");
+        let written = run(&[
+            "-d",
+            output_dir.to_str().unwrap(),
+            directory.join("in").to_str().unwrap(),
+        ])
+        .unwrap();
+        assert_eq!(written, vec![output_dir.join("Nunito-Regular.ttf")]);
+        // The input is left alone and the fixed font goes in the directory
+        assert_eq!(std::fs::read(&input).unwrap(), original);
+        assert!(needs_fixing(&input));
+        assert!(!needs_fixing(&written[0]));
+    }
+
+    #[test]
+    fn test_output_dir_collision() {
+        let directory = scratch_directory("collision");
+        let first = directory.join("a/Nunito-Regular.ttf");
+        let second = directory.join("b/Nunito-Regular.ttf");
+        write_broken_font(&first);
+        write_broken_font(&second);
+        let output_dir = directory.join("out");
+        let error = run(&[
+            "-d",
+            output_dir.to_str().unwrap(),
+            first.to_str().unwrap(),
+            second.to_str().unwrap(),
+        ])
+        .unwrap_err();
+        assert!(error.contains("would both be written to"), "{error}");
+        // Nothing was written
+        assert!(!output_dir.exists());
+    }
+
+    #[test]
+    fn test_in_place() {
+        let directory = scratch_directory("in-place");
+        let first = directory.join("Nunito-Regular.ttf");
+        let second = directory.join("Nunito-Copy.ttf");
+        write_broken_font(&first);
+        write_broken_font(&second);
+        let written = run(&["-i", directory.to_str().unwrap()]).unwrap();
+        assert_eq!(written, vec![second.clone(), first.clone()]);
+        assert!(!needs_fixing(&first));
+        assert!(!needs_fixing(&second));
+    }
+
+    #[test]
+    fn test_dry_run() {
+        let directory = scratch_directory("dry-run");
+        let input = directory.join("Nunito-Regular.ttf");
+        write_broken_font(&input);
+        let original = std::fs::read(&input).unwrap();
+        let written = run(&["--dry-run", input.to_str().unwrap()]).unwrap();
+        assert!(written.is_empty());
+        assert_eq!(std::fs::read(&input).unwrap(), original);
+        assert_eq!(std::fs::read_dir(&directory).unwrap().count(), 1);
+    }
+}
@@ -1,12 +1,24 @@
-use std::{collections::HashMap, env, path::Path, vec};
+use std::{
+    collections::HashMap,
+    env,
+    path::Path,
+    sync::{Arc, LazyLock, Mutex},
+    time::Duration,
+    vec,
+};
 // Provide an environment where we can run fontbakery tests
 // as-is, but have them call a Rust implementation underneath
 use fontspector_checkapi::{
     CheckImplementation, Context, ProfileProvider, Registry, StatusCode, Testable,
     TestableCollection, TestableType,
 };
+#[cfg(feature = "adobe")]
+use profile_adobe::Adobe;
+use profile_designspace::Designspace;
 use profile_fontwerk::Fontwerk;
 use profile_googlefonts::GoogleFonts;
+#[cfg(feature = "microsoft")]
+use profile_microsoft::Microsoft;
 use profile_opentype::OpenType;
 use profile_universal::Universal;
 use pyo3::{
@@ -16,6 +28,46 @@ use pyo3::{
 };
 use pythonize::depythonize;
 
+/// User-supplied TOML profiles, by name, added to every registry we build
+static USER_PROFILES: LazyLock<Mutex<Vec<(String, String)>>> = LazyLock::new(|| Mutex::new(vec![]));
+
+/// Build a registry containing the baked-in profiles and any user-supplied ones
+fn build_registry() -> PyResult<Registry<'static>> {
+    let mut registry = Registry::new();
+    OpenType.register(&mut registry).map_err(|_| {
+        PyValueError::new_err("Couldn't register opentype profile, fontspector bug")
+    })?;
+    Designspace.register(&mut registry).map_err(|_| {
+        PyValueError::new_err("Couldn't register designspace profile, fontspector bug")
+    })?;
+    Universal.register(&mut registry).map_err(|_| {
+        PyValueError::new_err("Couldn't register universal profile, fontspector bug")
+    })?;
+    GoogleFonts.register(&mut registry).map_err(|_| {
+        PyValueError::new_err("Couldn't register Google Fonts profile, fontspector bug")
+    })?;
+    Fontwerk.register(&mut registry).map_err(|_| {
+        PyValueError::new_err("Couldn't register Fontwerk profile, fontspector bug")
+    })?;
+    #[cfg(feature = "adobe")]
+    Adobe.register(&mut registry).map_err(|_| {
+        PyValueError::new_err("Couldn't register Adobe Fonts profile, fontspector bug")
+    })?;
+    #[cfg(feature = "microsoft")]
+    Microsoft.register(&mut registry).map_err(|_| {
+        PyValueError::new_err("Couldn't register Microsoft profile, fontspector bug")
+    })?;
+    let user_profiles = USER_PROFILES
+        .lock()
+        .map_err(|e| PyValueError::new_err(e.to_string()))?;
+    for (name, toml) in user_profiles.iter() {
+        registry
+            .register_toml_profile(name, toml)
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+    }
+    Ok(registry)
+}
+
 #[pyclass]
 struct CheckTester {
     check_id: String,
@@ -67,19 +119,7 @@ impl CheckTester {
         kwargs: Option<&Bound<'a, PyDict>>,
     ) -> PyResult<Vec<Bound<'a, PyAny>>> {
         // Spin up a new fontspector (each time, how extravagant)
-        let mut registry = Registry::new();
-        OpenType.register(&mut registry).map_err(|_| {
-            PyValueError::new_err("Couldn't register opentype profile, fontspector bug")
-        })?;
-        Universal.register(&mut registry).map_err(|_| {
-            PyValueError::new_err("Couldn't register universal profile, fontspector bug")
-        })?;
-        GoogleFonts.register(&mut registry).map_err(|_| {
-            PyValueError::new_err("Couldn't register Google Fonts profile, fontspector bug")
-        })?;
-        Fontwerk.register(&mut registry).map_err(|_| {
-            PyValueError::new_err("Couldn't register Fontwerk profile, fontspector bug")
-        })?;
+        let registry = build_registry()?;
 
         let check = registry
             .checks
@@ -99,22 +139,39 @@ impl CheckTester {
         } else {
             vec![obj_to_testable(py, &first_arg)?]
         };
-        let collection = TestableCollection {
+        let collection = Arc::new(TestableCollection {
             testables,
             directory: "".to_string(),
-        };
-        let newargs = if matches!(check.implementation, CheckImplementation::CheckOne(_)) {
-            let first = &collection
-                .testables
-                .first()
-                .ok_or_else(|| PyValueError::new_err("No testables found in the collection"))?;
-            TestableType::Single(first)
-        } else {
-            TestableType::Collection(&collection)
-        };
+            label: None,
+        });
+        // Single-font checks run on every font in the list which they apply to,
+        // so that checks which look at a font's siblings see each of them;
+        // collection checks run once on the whole list.
+        let candidates: Vec<TestableType> =
+            if matches!(check.implementation, CheckImplementation::CheckOne(_)) {
+                collection
+                    .testables
+                    .iter()
+                    .map(TestableType::Single)
+                    .collect()
+            } else {
+                vec![TestableType::Collection(&collection)]
+            };
+        let mut newargs: Vec<&TestableType> = candidates
+            .iter()
+            .filter(|t| check.applies(t, &registry))
+            .collect();
+        if newargs.is_empty() {
+            newargs.push(
+                candidates
+                    .first()
+                    .ok_or_else(|| PyValueError::new_err("No testables found in the collection"))?,
+            );
+        }
 
         let mut fontspector_config = HashMap::new();
         let mut skip_network = false;
+        let mut check_timeout = None;
 
         if let Some(kwargs) = kwargs {
             if let Some(config) = kwargs.get_item("config")? {
@@ -123,12 +180,21 @@ impl CheckTester {
             if let Some(skip_network_arg) = kwargs.get_item("skip_network")? {
                 skip_network = skip_network_arg.as_any().extract()?;
             }
+            // In seconds, as for the command line's --check-timeout
+            if let Some(timeout_arg) = kwargs.get_item("check_timeout")? {
+                let seconds: f64 = timeout_arg.as_any().extract()?;
+                check_timeout = Some(Duration::try_from_secs_f64(seconds).map_err(|e| {
+                    PyValueError::new_err(format!("Invalid check_timeout {seconds}: {e}"))
+                })?);
+            }
         }
 
         let mut context = Context {
             configuration: fontspector_config,
             full_lists: true,
             skip_network,
+            check_timeout,
+            family: Some(collection.clone()),
             ..Default::default()
         };
         if let Some(profile_name) = &self.profile {
@@ -139,15 +205,24 @@ impl CheckTester {
         }
 
         // Run the check!
-        let result = check
-            .run(&newargs, &context, None)
-            .ok_or_else(|| PyValueError::new_err("No results returned?"))?;
+        let mut subresults = vec![];
+        for testable in newargs.iter() {
+            let result = check
+                .run(testable, &context, None)
+                .ok_or_else(|| PyValueError::new_err("No results returned?"))?;
+            subresults.extend(result.subresults);
+        }
+        // When a per-font check ran over several fonts, the fonts it skipped
+        // are not interesting to the caller.
+        if newargs.len() > 1 && subresults.iter().any(|r| r.severity != StatusCode::Skip) {
+            subresults.retain(|r| r.severity != StatusCode::Skip);
+        }
         // Map results back to a Python list of subresults
         let status_module = py.import("fontbakery.status")?;
         let subresult_module = py.import("fontbakery.result")?;
         let message_class = py.import("fontbakery.message")?.getattr("Message")?;
         let mut py_subresults = vec![];
-        for subresult in result.subresults {
+        for subresult in subresults {
             let severity = match subresult.severity {
                 StatusCode::Skip => status_module.getattr("SKIP")?,
                 StatusCode::Info => status_module.getattr("INFO")?,
@@ -173,24 +248,31 @@ impl CheckTester {
 
 #[pyfunction]
 fn registered_checks() -> PyResult<Vec<String>> {
-    let mut registry = Registry::new();
-    OpenType.register(&mut registry).map_err(|_| {
-        PyValueError::new_err("Couldn't register opentype profile, fontspector bug")
-    })?;
-    Universal.register(&mut registry).map_err(|_| {
-        PyValueError::new_err("Couldn't register universal profile, fontspector bug")
-    })?;
-    GoogleFonts.register(&mut registry).map_err(|_| {
-        PyValueError::new_err("Couldn't register Google Fonts profile, fontspector bug")
-    })?;
-    Fontwerk.register(&mut registry).map_err(|_| {
-        PyValueError::new_err("Couldn't register Fontwerk profile, fontspector bug")
-    })?;
+    let registry = build_registry()?;
     Ok(registry.checks.keys().cloned().collect())
 }
 
+/// Add a user-supplied profile, written in TOML, to the available profiles
+///
+/// The profile is validated immediately, and can subsequently be used by
+/// passing its name as the `profile` argument to `CheckTester`.
+#[pyfunction]
+fn register_profile(name: &str, toml: &str) -> PyResult<()> {
+    let mut registry = build_registry()?;
+    registry
+        .register_toml_profile(name, toml)
+        .map_err(|e| PyValueError::new_err(e.to_string()))?;
+    let mut user_profiles = USER_PROFILES
+        .lock()
+        .map_err(|e| PyValueError::new_err(e.to_string()))?;
+    user_profiles.retain(|(existing, _)| existing != name);
+    user_profiles.push((name.to_string(), toml.to_string()));
+    Ok(())
+}
+
 #[pymodule(name = "fontspector")]
 fn fonspector(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<CheckTester>()?;
-    m.add_function(wrap_pyfunction!(registered_checks, m)?)
+    m.add_function(wrap_pyfunction!(registered_checks, m)?)?;
+    m.add_function(wrap_pyfunction!(register_profile, m)?)
 }
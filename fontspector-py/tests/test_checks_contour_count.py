@@ -2,7 +2,7 @@ from fontTools.ttLib import TTFont
 import pytest
 
 from conftest import check_id
-from fontbakery.status import FAIL, WARN, SKIP
+from fontbakery.status import ERROR, FAIL, WARN, SKIP
 from fontbakery.codetesting import (
     assert_PASS,
     assert_results_contain,
@@ -24,3 +24,12 @@ def test_check_contour_count(check):
     # Lets swap the glyf 'Idotaccent' (3 contours) with space (0 contour) to get a FAIL
     ttFont["glyf"]["Idotaccent"] = ttFont["glyf"]["space"]
     msg = assert_results_contain(check(ttFont), FAIL, "no-contour")
+
+
+@check_id("contour_count")
+def test_check_contour_count_timeout(check):
+    """A check which runs out of time reports an error"""
+    ttFont = TTFont(TEST_FILE("ibmplexsans-vf/IBMPlexSansVar-Roman.ttf"))
+
+    assert_results_contain(check(ttFont, check_timeout=0), ERROR, "timeout")
+    assert_PASS(check(ttFont, check_timeout=60))
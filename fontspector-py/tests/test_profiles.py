@@ -0,0 +1,32 @@
+import pytest
+from fontbakery.codetesting import TEST_FILE, assert_results_contain
+from fontbakery.status import WARN
+from fontspector import CheckTester, register_profile
+from fontTools.ttLib import TTFont
+
+HOUSE_PROFILE = """
+include_profiles = ["universal"]
+
+[overrides]
+"opentype/family/underline_thickness" = [
+    { code = "inconsistent-underline-thickness", status = "WARN", reason = "House style" },
+]
+"""
+
+
+def test_register_profile():
+    register_profile("house", HOUSE_PROFILE)
+    check = CheckTester("opentype/family/underline_thickness", profile="house")
+    ttFonts = [
+        TTFont(TEST_FILE("mada/Mada-Regular.ttf")),
+        TTFont(TEST_FILE("mada/Mada-Bold.ttf")),
+    ]
+    ttFonts[0]["post"].underlineThickness = 0
+    assert_results_contain(check(ttFonts), WARN, "inconsistent-underline-thickness")
+
+
+def test_register_bad_profile():
+    with pytest.raises(ValueError):
+        register_profile("broken", "include_profiles = [")
+    with pytest.raises(ValueError):
+        register_profile("missing", 'include_profiles = ["no-such-profile"]')
@@ -1,7 +1,9 @@
 #![allow(clippy::expect_used, clippy::unwrap_used)]
 use std::{
+    cell::RefCell,
     collections::{BTreeMap, BTreeSet, HashMap},
     path::PathBuf,
+    sync::Arc,
 };
 
 use fontspector_checkapi::prelude::*;
@@ -14,11 +16,13 @@ use fontspector_checkapi::{
     TestFont, Testable, TestableCollection, TestableType,
 };
 use profile_adobe::Adobe;
+use profile_designspace::Designspace;
 use profile_fontwerk::Fontwerk;
 use profile_googlefonts::GoogleFonts;
 use profile_iso15008::Iso15008;
 use profile_microsoft::Microsoft;
 use profile_opentype::OpenType;
+use profile_typenetwork::TypeNetwork;
 use profile_universal::Universal;
 use std::io::Write;
 use zip::ZipWriter;
@@ -29,6 +33,11 @@ extern "C" {
     fn log(s: &str);
 }
 
+thread_local! {
+    /// User-supplied TOML profiles, by name, added on top of the baked-in ones
+    static USER_PROFILES: RefCell<Vec<(String, String)>> = const { RefCell::new(vec![]) };
+}
+
 #[wasm_bindgen]
 pub fn version() -> String {
     env!("CARGO_PKG_VERSION").to_string()
@@ -39,6 +48,9 @@ fn register_profiles<'a>() -> Registry<'a> {
     OpenType
         .register(&mut registry)
         .expect("Couldn't register opentype profile, fontspector bug");
+    Designspace
+        .register(&mut registry)
+        .expect("Couldn't register designspace profile, fontspector bug");
     Universal
         .register(&mut registry)
         .expect("Couldn't register universal profile, fontspector bug");
@@ -57,6 +69,9 @@ fn register_profiles<'a>() -> Registry<'a> {
     Fontwerk
         .register(&mut registry)
         .expect("Couldn't register Fontwerk profile, fontspector bug");
+    TypeNetwork
+        .register(&mut registry)
+        .expect("Couldn't register Type Network profile, fontspector bug");
 
     {
         let (name, toml) = ("fontbureau", include_str!("../../profiles/fontbureau.toml"));
@@ -65,16 +80,6 @@ fn register_profiles<'a>() -> Registry<'a> {
             .register_profile(name, profile, true)
             .expect("Couldn't register profile, fontspector bug");
     }
-    {
-        let (name, toml) = (
-            "typenetwork",
-            include_str!("../../profiles/typenetwork.toml"),
-        );
-        let profile = Profile::from_toml(toml).expect("Couldn't load profile, fontspector bug");
-        registry
-            .register_profile(name, profile, true)
-            .expect("Couldn't register profile, fontspector bug");
-    }
     {
         let (name, toml) = ("workspace", include_str!("../../profiles/workspace.toml"));
         let profile = Profile::from_toml(toml).expect("Couldn't load profile, fontspector bug");
@@ -82,9 +87,35 @@ fn register_profiles<'a>() -> Registry<'a> {
             .register_profile(name, profile, true)
             .expect("Couldn't register profile, fontspector bug");
     }
+    USER_PROFILES.with_borrow(|profiles| {
+        for (name, toml) in profiles {
+            // These were validated when they were added, so this should not fail
+            if let Err(e) = registry.register_toml_profile(name, toml) {
+                log(&format!("Could not register profile {name}: {e}"));
+            }
+        }
+    });
     registry
 }
 
+/// Add a user-supplied profile, written in TOML, to the available profiles
+///
+/// The profile is validated against the baked-in checks and profiles, and
+/// can subsequently be used by name in `check_fonts` and `dump_checks`.
+#[wasm_bindgen]
+pub fn register_profile(name: &str, toml: &str) -> Result<(), JsValue> {
+    console_error_panic_hook::set_once();
+    let mut registry = register_profiles();
+    registry
+        .register_toml_profile(name, toml)
+        .map_err(|e| e.to_string())?;
+    USER_PROFILES.with_borrow_mut(|profiles| {
+        profiles.retain(|(existing, _)| existing != name);
+        profiles.push((name.to_string(), toml.to_string()));
+    });
+    Ok(())
+}
+
 #[wasm_bindgen]
 pub fn best_family_name(fonts: &JsValue) -> Result<String, JsValue> {
     let mut names: Vec<String> = Reflect::own_keys(fonts)?
@@ -121,6 +152,141 @@ fn fonts_to_testables(fonts: &JsValue) -> Result<Vec<Testable>, JsValue> {
         .collect::<Result<Vec<Testable>, JsValue>>()
 }
 
+/// A check scheduled by a [`CheckRun`]
+struct PlannedCheck {
+    section: String,
+    /// Index into the collection's `collection_and_files()`
+    testable: usize,
+    check_id: String,
+    context: Context,
+}
+
+/// A run of the checks in a profile, performed a few checks at a time
+///
+/// Running every check in one call blocks the web worker until the whole
+/// profile is done, so it cannot notice a request to cancel. A `CheckRun`
+/// instead works through the profile in chunks with [`CheckRun::step`],
+/// letting the caller report progress and yield to the event loop between
+/// chunks.
+#[wasm_bindgen]
+pub struct CheckRun {
+    registry: Registry<'static>,
+    collection: Arc<TestableCollection>,
+    plan: Vec<PlannedCheck>,
+    next: usize,
+    min_severity: Option<StatusCode>,
+}
+
+#[wasm_bindgen]
+impl CheckRun {
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        fonts: &JsValue,
+        profile: &str,
+        full_lists: bool,
+        loglevels: &str,
+    ) -> Result<CheckRun, JsValue> {
+        console_error_panic_hook::set_once();
+        let registry = register_profiles();
+        let testables: Vec<Testable> = fonts_to_testables(fonts)?;
+        let collection = Arc::new(TestableCollection::from_testables(testables, None));
+
+        let context = Context {
+            skip_network: true,
+            network_timeout: None,
+            configuration: HashMap::new(),
+            check_metadata: serde_json::Value::Null,
+            full_lists,
+            cache: Default::default(),
+            memo: Default::default(),
+            overrides: vec![],
+            check_id: None,
+            conditions: Default::default(),
+            family: None,
+            check_timeout: None,
+            deadline: None,
+        };
+        let all_testables: Vec<TestableType> = collection.collection_and_files().collect();
+        let plan = {
+            let profile = registry
+                .get_profile(profile)
+                .ok_or_else(|| format!("Could not find profile {profile:?}"))?;
+            profile
+                .check_order(
+                    &[],
+                    &[],
+                    &registry,
+                    context,
+                    &HashMap::new(),
+                    &all_testables,
+                    &[collection.clone()],
+                )
+                .into_iter()
+                .filter_map(|(section, testable, check, context)| {
+                    let testable = all_testables
+                        .iter()
+                        .position(|t| std::ptr::eq(t, testable))?;
+                    Some(PlannedCheck {
+                        section,
+                        testable,
+                        check_id: check.id.to_string(),
+                        context,
+                    })
+                })
+                .collect()
+        };
+        Ok(CheckRun {
+            registry,
+            collection,
+            plan,
+            next: 0,
+            min_severity: StatusCode::from_string(loglevels),
+        })
+    }
+
+    /// The total number of checks in this run
+    pub fn total(&self) -> usize {
+        self.plan.len()
+    }
+
+    /// The number of checks run so far, whatever their result
+    pub fn done(&self) -> usize {
+        self.next
+    }
+
+    /// Run up to `count` more checks
+    ///
+    /// Returns a JSON array of the results at or above the minimum severity;
+    /// every check run counts towards [`CheckRun::done`], whether or not its
+    /// result is returned.
+    pub fn step(&mut self, count: usize) -> Result<String, JsValue> {
+        let all_testables: Vec<TestableType> = self.collection.collection_and_files().collect();
+        let mut results: Vec<CheckResult> = vec![];
+        let end = (self.next + count).min(self.plan.len());
+        for planned in self.plan.get(self.next..end).unwrap_or_default() {
+            let (Some(testable), Some(check)) = (
+                all_testables.get(planned.testable),
+                self.registry.checks.get(&planned.check_id),
+            ) else {
+                continue;
+            };
+            let Some(result) = check.run(testable, &planned.context, Some(&planned.section)) else {
+                continue;
+            };
+            // Filter results by severity
+            if self
+                .min_severity
+                .is_some_and(|severity| result.worst_status() < severity)
+            {
+                continue;
+            }
+            results.push(result);
+        }
+        self.next = end;
+        serde_json::to_string(&results).map_err(|e| e.to_string().into())
+    }
+}
+
 #[wasm_bindgen]
 pub fn check_fonts(
     fonts: &JsValue,
@@ -128,52 +294,8 @@ pub fn check_fonts(
     full_lists: bool,
     loglevels: &str,
 ) -> Result<String, JsValue> {
-    console_error_panic_hook::set_once();
-    let registry = register_profiles();
-    let testables: Vec<Testable> = fonts_to_testables(fonts)?;
-    let min_severity = StatusCode::from_string(loglevels);
-    let collection = TestableCollection::from_testables(testables, None);
-
-    let profile = registry
-        .get_profile(profile)
-        .ok_or_else(|| format!("Could not find profile {profile:?}"))?;
-    let context = Context {
-        skip_network: true,
-        network_timeout: None,
-        configuration: HashMap::new(),
-        check_metadata: serde_json::Value::Null,
-        full_lists,
-        cache: Default::default(),
-        overrides: vec![],
-        check_id: None,
-    };
-    let all_testables: Vec<TestableType> = collection.collection_and_files().collect();
-
-    let checkorder: Vec<(String, &TestableType, &Check, Context)> = profile.check_order(
-        &[],
-        &[],
-        &registry,
-        context,
-        &HashMap::new(),
-        &all_testables,
-    );
-
-    let mut results: Vec<CheckResult> = checkorder
-        .iter()
-        .map(|(sectionname, testable, check, context)| {
-            (
-                testable,
-                check,
-                check.run(testable, context, Some(sectionname)),
-            )
-        })
-        .flat_map(|(_, _, result)| result)
-        .collect();
-    if let Some(severity) = min_severity {
-        // Filter results by severity
-        results.retain(|result| result.worst_status() >= severity);
-    }
-    serde_json::to_string(&results).map_err(|e| e.to_string().into())
+    let mut run = CheckRun::new(fonts, profile, full_lists, loglevels)?;
+    run.step(run.total())
 }
 
 #[wasm_bindgen]
@@ -24,6 +24,7 @@ export const state = reactive({
   activeCheckId: null as string | null,
   fonts: {} as Record<string, FontInfo>,
   error: null as string | null,
+  progress: null as { done: number; total: number } | null,
   counts: {
     FATAL: 0,
     FAIL: 0,
@@ -37,6 +38,7 @@ export const state = reactive({
 
 export function updateResults(results: CheckResult[]) {
   state.lastResults = results;
+  state.progress = null;
 
   // Reset counts
   for (const status of Object.keys(state.counts) as StatusCode[]) {
@@ -49,10 +51,22 @@ export function updateResults(results: CheckResult[]) {
   if (results.length > 0) state.activeCheckId = results[0].check_id;
 }
 
+export function addProgress(
+  results: CheckResult[],
+  done: number,
+  total: number,
+) {
+  state.progress = { done, total };
+  for (const result of results) {
+    state.counts[result.worst_status]++;
+  }
+}
+
 export function resetState() {
   state.view = "start";
   state.lastResults = null;
   state.fonts = {};
+  state.progress = null;
   for (const status of Object.keys(state.counts) as StatusCode[]) {
     state.counts[status] = 0;
   }
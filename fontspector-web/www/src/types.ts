@@ -197,12 +197,29 @@ export interface FixReply {
   download?: boolean;
 }
 
+export interface CheckProgressReply {
+  id: "check_progress";
+  // The results of the checks run since the last progress message which
+  // meet the requested log level; `done` counts every check run
+  results: CheckResult[];
+  done: number;
+  total: number;
+}
+
+export interface ProfileRegisteredReply {
+  id: "profile_registered";
+  name: string;
+  checks: Record<string, Check>;
+}
+
 export type ReplyMessage =
   | ErrorReply
   | NameReply
   | ReadyReply
   | FixReply
-  | { id: "check_result"; results: CheckResult[] };
+  | ProfileRegisteredReply
+  | CheckProgressReply
+  | { id: "check_result"; results: CheckResult[]; cancelled: boolean };
 
 export type Profile = keyof typeof PROFILES;
 
@@ -214,13 +231,23 @@ export interface FixRequest {
 
 export interface RunCheckRequest {
   id: "run_checks";
-  profile: Profile;
+  // Either a built-in profile, or one added with a RegisterProfileRequest
+  profile: Profile | string;
   files: Record<string, Uint8Array>;
   loglevels: string;
   fulllists: boolean;
 }
+export interface RegisterProfileRequest {
+  id: "register_profile";
+  name: string;
+  toml: string;
+}
 export type LoadFontspectorRequest = { id: "justload" };
+// Stop a run started by a RunCheckRequest; the results so far are returned
+export type CancelChecksRequest = { id: "cancel_checks" };
 export type RequestMessage =
   | FixRequest
   | RunCheckRequest
-  | LoadFontspectorRequest;
+  | RegisterProfileRequest
+  | LoadFontspectorRequest
+  | CancelChecksRequest;
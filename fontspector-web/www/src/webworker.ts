@@ -3,7 +3,8 @@ import {
   dump_checks,
   fix_fonts,
   best_family_name,
-  check_fonts,
+  CheckRun,
+  register_profile,
 } from "../../pkg/fontspector_web.js";
 import {
   CheckResult,
@@ -11,11 +12,16 @@ import {
   FixRequest,
   NameReply,
   ReadyReply,
+  RegisterProfileRequest,
   ReplyMessage,
   RequestMessage,
 } from "./types";
 
 let loadedFonts: Record<string, Uint8Array> = {};
+// Set by a "cancel_checks" message, and polled between chunks of a run
+let cancelRequested = false;
+// How many checks to run before yielding to the event loop
+const CHECKS_PER_CHUNK = 10;
 
 function post(message: ReplyMessage) {
   try {
@@ -53,6 +59,8 @@ async function init() {
 
     if (msg.id == "justload") {
       return;
+    } else if (msg.id == "cancel_checks") {
+      cancelRequested = true;
     } else if (msg.id == "fix") {
       const { requests, download } = msg as FixRequest;
       try {
@@ -61,14 +69,56 @@ async function init() {
       } catch (error: any) {
         post({ id: "error", error: error.toString() });
       }
+    } else if (msg.id == "register_profile") {
+      const { name, toml } = msg as RegisterProfileRequest;
+      try {
+        register_profile(name, toml);
+        post({
+          id: "profile_registered",
+          name,
+          checks: JSON.parse(dump_checks()),
+        });
+      } catch (error: any) {
+        post({ id: "error", error: error.toString() });
+      }
     } else if (msg.id == "run_checks") {
       const name = best_family_name(msg.files);
       post({ id: "name", name });
       loadedFonts = msg.files;
-      const results: CheckResult[] = JSON.parse(
-        check_fonts(msg.files, msg.profile, msg.fulllists, msg.loglevels),
-      );
-      post({ id: "check_result", results });
+      const results: CheckResult[] = [];
+      cancelRequested = false;
+      let run: CheckRun;
+      try {
+        run = new CheckRun(
+          msg.files,
+          msg.profile,
+          msg.fulllists,
+          msg.loglevels,
+        );
+      } catch (error: any) {
+        post({ id: "error", error: error.toString() });
+        return;
+      }
+      try {
+        while (run.done() < run.total() && !cancelRequested) {
+          const chunk: CheckResult[] = JSON.parse(run.step(CHECKS_PER_CHUNK));
+          results.push(...chunk);
+          post({
+            id: "check_progress",
+            results: chunk,
+            done: run.done(),
+            total: run.total(),
+          });
+          // Let any pending "cancel_checks" message be delivered
+          await new Promise((resolve) => setTimeout(resolve, 0));
+        }
+      } catch (error: any) {
+        post({ id: "error", error: error.toString() });
+        return;
+      } finally {
+        run.free();
+      }
+      post({ id: "check_result", results, cancelled: cancelRequested });
     } else {
       post({ id: "error", error: "Unknown message:" + JSON.stringify(msg) });
     }
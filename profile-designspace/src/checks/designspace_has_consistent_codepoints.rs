@@ -0,0 +1,89 @@
+use std::collections::BTreeSet;
+
+use fontspector_checkapi::prelude::*;
+
+use crate::sources::{default_and_other_masters, load_designspace};
+
+fn codepoints(glyph: &norad::Glyph) -> BTreeSet<char> {
+    glyph.codepoints.iter().collect()
+}
+
+fn describe(codepoints: &BTreeSet<char>) -> String {
+    if codepoints.is_empty() {
+        return "none".to_string();
+    }
+    codepoints
+        .iter()
+        .map(|c| format!("U+{:04X}", *c as u32))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[check(
+    id = "designspace_has_consistent_codepoints",
+    rationale = "
+        This check ensures that Unicode assignments are consistent
+        across all sources specified in a designspace file.
+    ",
+    proposal = "https://github.com/fonttools/fontbakery/issues/4829", // legacy check
+    title = "Check codepoints consistency in a designspace file.",
+    applies_to = "DESIGNSPACE"
+)]
+fn designspace_has_consistent_codepoints(t: &Testable, context: &Context) -> CheckFnResult {
+    let ds = load_designspace(t, context)?;
+    let (default, others) = default_and_other_masters(&ds)?;
+    let mut problems = vec![];
+    for (source_name, font) in others.iter() {
+        let mismatches = default
+            .iter_names()
+            .filter_map(|name| {
+                let expected = codepoints(default.get_glyph(&name)?);
+                let actual = codepoints(font.get_glyph(&name)?);
+                (expected != actual).then(|| {
+                    format!(
+                        "{name}: {} in the default master, {} here",
+                        describe(&expected),
+                        describe(&actual)
+                    )
+                })
+            })
+            .collect::<Vec<_>>();
+        if !mismatches.is_empty() {
+            problems.push(Status::fail(
+                "inconsistent-codepoints",
+                &format!(
+                    "Source {source_name} has different codepoints from the default master:\n\n{}",
+                    bullet_list(context, mismatches)
+                ),
+            ));
+        }
+    }
+    return_result(problems)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fontspector_checkapi::{
+        codetesting::{assert_pass, assert_results_contain, run_check, test_able},
+        StatusCode,
+    };
+
+    #[test]
+    fn test_designspace_has_consistent_codepoints_pass() {
+        let testable = test_able("designspace/Test.designspace");
+        let result = run_check(designspace_has_consistent_codepoints, testable);
+        assert_pass(&result);
+    }
+
+    #[test]
+    fn test_designspace_has_consistent_codepoints_fail() {
+        let testable = test_able("designspace/TestInconsistent.designspace");
+        let result = run_check(designspace_has_consistent_codepoints, testable);
+        assert_results_contain(
+            &result,
+            StatusCode::Fail,
+            Some("inconsistent-codepoints".to_string()),
+        );
+    }
+}
@@ -0,0 +1,91 @@
+use std::collections::BTreeSet;
+
+use fontspector_checkapi::prelude::*;
+
+use crate::sources::{default_and_other_masters, load_designspace};
+
+#[check(
+    id = "designspace_has_consistent_glyphset",
+    rationale = "
+        This check ensures that non-default masters don't have glyphs
+        not present in the default one, and vice versa. Sparse masters,
+        which take their glyphs from a layer of a UFO, are not checked.
+    ",
+    proposal = "https://github.com/fonttools/fontbakery/issues/4829", // legacy check
+    title = "Check consistency of glyphset in a designspace file.",
+    applies_to = "DESIGNSPACE"
+)]
+fn designspace_has_consistent_glyphset(t: &Testable, context: &Context) -> CheckFnResult {
+    let ds = load_designspace(t, context)?;
+    let (default, others) = default_and_other_masters(&ds)?;
+    let default_glyphs = default
+        .iter_names()
+        .map(|name| name.to_string())
+        .collect::<BTreeSet<_>>();
+    let mut problems = vec![];
+    for (source_name, font) in others.iter() {
+        let glyphs = font
+            .iter_names()
+            .map(|name| name.to_string())
+            .collect::<BTreeSet<_>>();
+        let missing = default_glyphs.difference(&glyphs).collect::<Vec<_>>();
+        if !missing.is_empty() {
+            problems.push(Status::fail(
+                "inconsistent-glyphset",
+                &format!(
+                    "Source {source_name} is missing glyphs found in the default master:\n\n{}",
+                    bullet_list(context, missing)
+                ),
+            ));
+        }
+        let extra = glyphs.difference(&default_glyphs).collect::<Vec<_>>();
+        if !extra.is_empty() {
+            problems.push(Status::fail(
+                "inconsistent-glyphset",
+                &format!(
+                    "Source {source_name} has glyphs not found in the default master:\n\n{}",
+                    bullet_list(context, extra)
+                ),
+            ));
+        }
+    }
+    return_result(problems)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fontspector_checkapi::{
+        codetesting::{assert_pass, assert_results_contain, run_check, test_able},
+        StatusCode,
+    };
+
+    #[test]
+    fn test_designspace_has_consistent_glyphset_pass() {
+        let testable = test_able("designspace/Test.designspace");
+        let result = run_check(designspace_has_consistent_glyphset, testable);
+        assert_pass(&result);
+    }
+
+    #[test]
+    fn test_designspace_has_consistent_glyphset_fail() {
+        let testable = test_able("designspace/TestInconsistent.designspace");
+        let result = run_check(designspace_has_consistent_glyphset, testable);
+        assert_results_contain(
+            &result,
+            StatusCode::Fail,
+            Some("inconsistent-glyphset".to_string()),
+        );
+    }
+
+    #[test]
+    fn test_designspace_has_consistent_glyphset_no_default() {
+        let testable = test_able("designspace/TestNoDefault.designspace");
+        let result = run_check(designspace_has_consistent_glyphset, testable);
+        assert_results_contain(
+            &result,
+            StatusCode::Skip,
+            Some("no-default-master".to_string()),
+        );
+    }
+}
@@ -0,0 +1,84 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use fontspector_checkapi::prelude::*;
+
+use crate::sources::{default_and_other_masters, load_designspace};
+
+fn kerning_groups(font: &norad::Font) -> BTreeMap<String, BTreeSet<String>> {
+    font.groups
+        .iter()
+        .filter(|(name, _)| name.starts_with("public.kern"))
+        .map(|(name, members)| {
+            (
+                name.to_string(),
+                members.iter().map(|member| member.to_string()).collect(),
+            )
+        })
+        .collect()
+}
+
+#[check(
+    id = "designspace_has_consistent_groups",
+    rationale = "
+        Often designers add kerning groups only to the default master, or
+        update them there and forget the other masters. Since kerning is
+        interpolated between masters, every master needs the same kerning
+        groups, with the same members.
+    ",
+    proposal = "https://github.com/fonttools/fontbakery/issues/4829", // legacy check
+    title = "Ensure kerning groups are consistent across the masters of a designspace.",
+    applies_to = "DESIGNSPACE"
+)]
+fn designspace_has_consistent_groups(t: &Testable, context: &Context) -> CheckFnResult {
+    let ds = load_designspace(t, context)?;
+    let (default, others) = default_and_other_masters(&ds)?;
+    let default_groups = kerning_groups(default);
+    let mut problems = vec![];
+    for (source_name, font) in others.iter() {
+        let groups = kerning_groups(font);
+        let mismatched = default_groups
+            .keys()
+            .chain(groups.keys())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .filter(|name| default_groups.get(*name) != groups.get(*name))
+            .collect::<Vec<_>>();
+        if !mismatched.is_empty() {
+            problems.push(Status::warn(
+                "mismatched-kerning-groups",
+                &format!(
+                    "Source {source_name} has kerning groups which differ from the default master:\n\n{}",
+                    bullet_list(context, mismatched)
+                ),
+            ));
+        }
+    }
+    return_result(problems)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fontspector_checkapi::{
+        codetesting::{assert_pass, assert_results_contain, run_check, test_able},
+        StatusCode,
+    };
+
+    #[test]
+    fn test_designspace_has_consistent_groups_pass() {
+        let testable = test_able("designspace/Test.designspace");
+        let result = run_check(designspace_has_consistent_groups, testable);
+        assert_pass(&result);
+    }
+
+    #[test]
+    fn test_designspace_has_consistent_groups_warn() {
+        let testable = test_able("designspace/TestInconsistent.designspace");
+        let result = run_check(designspace_has_consistent_groups, testable);
+        assert_results_contain(
+            &result,
+            StatusCode::Warn,
+            Some("mismatched-kerning-groups".to_string()),
+        );
+    }
+}
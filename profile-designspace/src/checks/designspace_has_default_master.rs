@@ -0,0 +1,49 @@
+use fontspector_checkapi::prelude::*;
+
+use crate::sources::{default_source, designspace};
+
+#[check(
+    id = "designspace_has_default_master",
+    rationale = "
+        We expect that designspace files declare a master at the default
+        location of every axis; font compilers use it as the source of the
+        default instance, and of everything that does not vary.
+    ",
+    proposal = "https://github.com/fonttools/fontbakery/issues/4829", // legacy check
+    title = "Ensure a default master is defined.",
+    applies_to = "DESIGNSPACE"
+)]
+fn designspace_has_default_master(t: &Testable, _context: &Context) -> CheckFnResult {
+    let ds = designspace(t)?;
+    Ok(if default_source(&ds).is_some() {
+        Status::just_one_pass()
+    } else {
+        Status::just_one_fail(
+            "not-found",
+            "Unable to find a default master: no source is placed at the default location of every axis.",
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fontspector_checkapi::{
+        codetesting::{assert_pass, assert_results_contain, run_check, test_able},
+        StatusCode,
+    };
+
+    #[test]
+    fn test_designspace_has_default_master_pass() {
+        let testable = test_able("designspace/Test.designspace");
+        let result = run_check(designspace_has_default_master, testable);
+        assert_pass(&result);
+    }
+
+    #[test]
+    fn test_designspace_has_default_master_fail() {
+        let testable = test_able("designspace/TestNoDefault.designspace");
+        let result = run_check(designspace_has_default_master, testable);
+        assert_results_contain(&result, StatusCode::Fail, Some("not-found".to_string()));
+    }
+}
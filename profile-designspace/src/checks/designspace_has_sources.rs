@@ -0,0 +1,74 @@
+use fontspector_checkapi::prelude::*;
+
+use crate::sources::load_designspace;
+
+#[check(
+    id = "designspace_has_sources",
+    rationale = "
+        This check parses a designspace file and tries to load the
+        source files specified.
+
+        This is meant to ensure that the file is not malformed,
+        can be properly parsed and does include valid source file references.
+    ",
+    proposal = "https://github.com/fonttools/fontbakery/issues/4829", // legacy check
+    title = "See that a designspace file has sources, and that they can be loaded.",
+    applies_to = "DESIGNSPACE"
+)]
+fn designspace_has_sources(t: &Testable, context: &Context) -> CheckFnResult {
+    let ds = load_designspace(t, context)?;
+    let sources = ds.sources();
+    if sources.is_empty() {
+        return Ok(Status::just_one_fail(
+            "no-sources",
+            "Unable to detect any sources in the designspace file.",
+        ));
+    }
+    let missing = sources
+        .iter()
+        .filter_map(|loaded| {
+            loaded
+                .font
+                .as_ref()
+                .err()
+                .map(|e| format!("{}: {e}", loaded.name()))
+        })
+        .collect::<Vec<_>>();
+    if !missing.is_empty() {
+        return Ok(Status::just_one_fail(
+            "missing-sources",
+            &format!(
+                "The following sources could not be loaded:\n\n{}",
+                bullet_list(context, missing)
+            ),
+        ));
+    }
+    Ok(Status::just_one_pass())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fontspector_checkapi::{
+        codetesting::{assert_pass, assert_results_contain, run_check, test_able},
+        StatusCode,
+    };
+
+    #[test]
+    fn test_designspace_has_sources_pass() {
+        let testable = test_able("designspace/Test.designspace");
+        let result = run_check(designspace_has_sources, testable);
+        assert_pass(&result);
+    }
+
+    #[test]
+    fn test_designspace_has_sources_missing() {
+        let testable = test_able("designspace/TestInconsistent.designspace");
+        let result = run_check(designspace_has_sources, testable);
+        assert_results_contain(
+            &result,
+            StatusCode::Fail,
+            Some("missing-sources".to_string()),
+        );
+    }
+}
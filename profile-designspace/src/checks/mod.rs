@@ -0,0 +1,18 @@
+mod designspace_has_consistent_codepoints;
+mod designspace_has_consistent_glyphset;
+mod designspace_has_consistent_groups;
+mod designspace_has_default_master;
+mod designspace_has_sources;
+mod path_direction;
+mod ufo_recommended_fields;
+mod ufo_required_fields;
+mod ufo_unnecessary_fields;
+pub use designspace_has_consistent_codepoints::designspace_has_consistent_codepoints;
+pub use designspace_has_consistent_glyphset::designspace_has_consistent_glyphset;
+pub use designspace_has_consistent_groups::designspace_has_consistent_groups;
+pub use designspace_has_default_master::designspace_has_default_master;
+pub use designspace_has_sources::designspace_has_sources;
+pub use path_direction::path_direction;
+pub use ufo_recommended_fields::ufo_recommended_fields;
+pub use ufo_required_fields::ufo_required_fields;
+pub use ufo_unnecessary_fields::ufo_unnecessary_fields;
@@ -0,0 +1,97 @@
+use fontspector_checkapi::{prelude::*, skip};
+
+use crate::sources::load_designspace;
+
+fn direction(contour: &norad::Contour) -> bool {
+    let total: f64 = contour
+        .points
+        .iter()
+        .zip(contour.points.iter().cycle().skip(1))
+        .map(|(point, next)| (next.x - point.x) * (next.y + point.y))
+        .sum();
+    total > 0.0
+}
+
+#[check(
+    id = "designspace/path_direction",
+    rationale = "Make sure the paths have the same direction across all masters.",
+    proposal = "chat",
+    title = "Check path direction.",
+    applies_to = "DESIGNSPACE"
+)]
+fn path_direction(t: &Testable, context: &Context) -> CheckFnResult {
+    let ds = load_designspace(t, context)?;
+    let ufos = ds
+        .sources()
+        .into_iter()
+        .filter_map(|loaded| {
+            loaded
+                .font
+                .as_ref()
+                .ok()
+                .map(|font| (loaded.source.filename.as_str(), font))
+        })
+        .collect::<Vec<_>>();
+    let Some(((_, first_ufo), others)) = ufos.split_first() else {
+        return Ok(Status::just_one_fail(
+            "no-sources",
+            "Couldn't load any sources",
+        ));
+    };
+    skip!(
+        others.is_empty(),
+        "not-enough-sources",
+        "Not enough sources to compare"
+    );
+    let mut problems = vec![];
+    for glyph in first_ufo.iter_names() {
+        let Some(first) = first_ufo.get_glyph(&glyph) else {
+            continue;
+        };
+        for (master_name, other_ufo) in others.iter() {
+            let Some(other) = other_ufo.get_glyph(&glyph) else {
+                continue;
+            };
+            for (contour_ix, (first_contour, other_contour)) in
+                first.contours.iter().zip(other.contours.iter()).enumerate()
+            {
+                if direction(first_contour) != direction(other_contour) {
+                    problems.push(Status::fail(
+                        "path-direction",
+                        &format!(
+                            "Glyph {glyph} has different path direction in master {master_name} contour {contour_ix}"
+                        ),
+                    ));
+                }
+            }
+        }
+    }
+    return_result(problems)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fontspector_checkapi::{
+        codetesting::{assert_pass, assert_results_contain, run_check, test_able},
+        StatusCode,
+    };
+
+    #[test]
+    fn test_path_direction_pass() {
+        let testable = test_able("designspace/Test.designspace");
+        let result = run_check(path_direction, testable);
+        assert_pass(&result);
+    }
+
+    #[test]
+    fn test_path_direction_fail() {
+        let testable = test_able("designspace/TestInconsistent.designspace");
+        let result = run_check(path_direction, testable);
+        assert_results_contain(
+            &result,
+            StatusCode::Fail,
+            Some("path-direction".to_string()),
+        );
+    }
+}
@@ -0,0 +1,74 @@
+use fontspector_checkapi::prelude::*;
+
+use crate::sources::ufo_font_info;
+
+#[check(
+    id = "ufo_recommended_fields",
+    rationale = "
+        This includes fields that should be in any production font.
+    ",
+    proposal = "https://github.com/fonttools/fontbakery/issues/4829", // legacy check
+    title = "Check that recommended fields are present in the UFO fontinfo.",
+    applies_to = "UFO"
+)]
+fn ufo_recommended_fields(t: &Testable, context: &Context) -> CheckFnResult {
+    let info = ufo_font_info(t)?;
+    let fields = [
+        (
+            "postscriptUnderlineThickness",
+            info.postscript_underline_thickness.is_some(),
+        ),
+        (
+            "postscriptUnderlinePosition",
+            info.postscript_underline_position.is_some(),
+        ),
+        ("versionMajor", info.version_major.is_some()),
+        ("versionMinor", info.version_minor.is_some()),
+        ("styleName", info.style_name.is_some()),
+        ("copyright", info.copyright.is_some()),
+        ("openTypeOS2Panose", info.open_type_os2_panose.is_some()),
+    ];
+    let missing = fields
+        .iter()
+        .filter(|(_, present)| !present)
+        .map(|(field, _)| field)
+        .collect::<Vec<_>>();
+    Ok(if missing.is_empty() {
+        Status::just_one_pass()
+    } else {
+        Status::just_one_warn(
+            "missing-recommended-fields",
+            &format!(
+                "Recommended field(s) missing:\n\n{}",
+                bullet_list(context, missing)
+            ),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fontspector_checkapi::{
+        codetesting::{assert_pass, assert_results_contain, run_check, test_able},
+        StatusCode,
+    };
+
+    #[test]
+    fn test_ufo_recommended_fields_pass() {
+        let testable = test_able("designspace/Test-Light.ufo");
+        let result = run_check(ufo_recommended_fields, testable);
+        assert_pass(&result);
+    }
+
+    #[test]
+    fn test_ufo_recommended_fields_warn() {
+        let testable = test_able("designspace/Test-Inconsistent.ufo");
+        let result = run_check(ufo_recommended_fields, testable);
+        assert_results_contain(
+            &result,
+            StatusCode::Warn,
+            Some("missing-recommended-fields".to_string()),
+        );
+    }
+}
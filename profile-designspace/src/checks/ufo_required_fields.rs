@@ -0,0 +1,68 @@
+use fontspector_checkapi::prelude::*;
+
+use crate::sources::ufo_font_info;
+
+#[check(
+    id = "ufo_required_fields",
+    rationale = "
+        ufo2ft requires these info fields to compile a font binary:
+        unitsPerEm, ascender, descender, xHeight, capHeight and familyName.
+    ",
+    proposal = "https://github.com/fonttools/fontbakery/issues/4829", // legacy check
+    title = "Check that required fields are present in the UFO fontinfo.",
+    applies_to = "UFO"
+)]
+fn ufo_required_fields(t: &Testable, context: &Context) -> CheckFnResult {
+    let info = ufo_font_info(t)?;
+    let fields = [
+        ("unitsPerEm", info.units_per_em.is_some()),
+        ("ascender", info.ascender.is_some()),
+        ("descender", info.descender.is_some()),
+        ("xHeight", info.x_height.is_some()),
+        ("capHeight", info.cap_height.is_some()),
+        ("familyName", info.family_name.is_some()),
+    ];
+    let missing = fields
+        .iter()
+        .filter(|(_, present)| !present)
+        .map(|(field, _)| field)
+        .collect::<Vec<_>>();
+    Ok(if missing.is_empty() {
+        Status::just_one_pass()
+    } else {
+        Status::just_one_fail(
+            "missing-required-fields",
+            &format!(
+                "Required field(s) missing:\n\n{}",
+                bullet_list(context, missing)
+            ),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fontspector_checkapi::{
+        codetesting::{assert_pass, assert_results_contain, run_check, test_able},
+        StatusCode,
+    };
+
+    #[test]
+    fn test_ufo_required_fields_pass() {
+        let testable = test_able("designspace/Test-Light.ufo");
+        let result = run_check(ufo_required_fields, testable);
+        assert_pass(&result);
+    }
+
+    #[test]
+    fn test_ufo_required_fields_fail() {
+        let testable = test_able("designspace/Test-Inconsistent.ufo");
+        let result = run_check(ufo_required_fields, testable);
+        assert_results_contain(
+            &result,
+            StatusCode::Fail,
+            Some("missing-required-fields".to_string()),
+        );
+    }
+}
@@ -0,0 +1,71 @@
+use fontspector_checkapi::prelude::*;
+
+use crate::sources::ufo_font_info;
+
+#[check(
+    id = "ufo_unnecessary_fields",
+    rationale = "
+        ufo2ft will generate these fields, so there is no need to set
+        them by hand, and setting them risks them going stale:
+        openTypeNameUniqueID, openTypeNameVersion, postscriptUniqueID
+        and year.
+    ",
+    proposal = "https://github.com/fonttools/fontbakery/issues/4829", // legacy check
+    title = "Check that no unnecessary fields are present in the UFO fontinfo.",
+    applies_to = "UFO"
+)]
+fn ufo_unnecessary_fields(t: &Testable, context: &Context) -> CheckFnResult {
+    let info = ufo_font_info(t)?;
+    let fields = [
+        (
+            "openTypeNameUniqueID",
+            info.open_type_name_unique_id.is_some(),
+        ),
+        ("openTypeNameVersion", info.open_type_name_version.is_some()),
+        ("postscriptUniqueID", info.postscript_unique_id.is_some()),
+        ("year", info.year.is_some()),
+    ];
+    let present = fields
+        .iter()
+        .filter(|(_, present)| *present)
+        .map(|(field, _)| field)
+        .collect::<Vec<_>>();
+    Ok(if present.is_empty() {
+        Status::just_one_pass()
+    } else {
+        Status::just_one_warn(
+            "unnecessary-fields",
+            &format!(
+                "Unnecessary field(s) present:\n\n{}",
+                bullet_list(context, present)
+            ),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fontspector_checkapi::{
+        codetesting::{assert_pass, assert_results_contain, run_check, test_able},
+        StatusCode,
+    };
+
+    #[test]
+    fn test_ufo_unnecessary_fields_pass() {
+        let testable = test_able("designspace/Test-Light.ufo");
+        let result = run_check(ufo_unnecessary_fields, testable);
+        assert_pass(&result);
+    }
+
+    #[test]
+    fn test_ufo_unnecessary_fields_warn() {
+        let testable = test_able("designspace/Test-Inconsistent.ufo");
+        let result = run_check(ufo_unnecessary_fields, testable);
+        assert_results_contain(
+            &result,
+            StatusCode::Warn,
+            Some("unnecessary-fields".to_string()),
+        );
+    }
+}
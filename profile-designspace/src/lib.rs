@@ -1,95 +1,29 @@
-use fontspector_checkapi::{prelude::*, skip};
+//! Designspace and UFO source checks for Fontspector
+mod checks;
+mod sources;
 
-struct Designspace;
+use fontspector_checkapi::{FileType, FontspectorError, ProfileBuilder, Registry};
 
-fn direction(contour: &norad::Contour) -> bool {
-    let mut total = 0.0;
-    for (i, point) in contour.points.iter().enumerate() {
-        let next = contour.points.get((i + 1) % contour.points.len()).unwrap();
-        total += (next.x - point.x) * (next.y + point.y);
-    }
-    total > 0.0
-}
-
-#[check(
-    id = "designspace/path_direction",
-    rationale = "Make sure the paths have the same direction across all masters.",
-    proposal = "chat",
-    title = "Check path direction.",
-    applies_to = "DESIGNSPACE"
-)]
-fn path_direction(t: &Testable, _context: &Context) -> CheckFnResult {
-    let ds_contents = std::str::from_utf8(&t.contents)
-        .map_err(|_| FontspectorError::General("designspace is not valid UTF-8".to_string()))?;
-    let ds = quick_xml::de::from_str::<norad::designspace::DesignSpaceDocument>(ds_contents)
-        .map_err(|e| FontspectorError::General(format!("Failed to parse designspace: {e}")))?;
-    #[allow(clippy::unwrap_used)] // Life is short
-    let dirname = t.filename.parent().unwrap();
-    let master_names = ds.sources.iter().map(|s| &s.filename).collect::<Vec<_>>();
-    let ufos = ds
-        .sources
-        .iter()
-        .flat_map(|s| {
-            let source_full_path = dirname.join(&s.filename);
-            norad::Font::load(&source_full_path)
-        })
-        .collect::<Vec<_>>();
-    if ufos.is_empty() {
-        return Ok(Status::just_one_fail(
-            "no-sources",
-            "Couldn't load any sources",
-        ));
-    }
-    skip!(
-        ufos.len() < 2,
-        "not-enough-sources",
-        "Not enough sources to compare"
-    );
-    let glyphnames = ufos.first().unwrap().iter_names().collect::<Vec<_>>();
-    let mut problems = vec![];
-    for glyph in glyphnames {
-        // println!("Checking glyph {}", glyph);
-        let all_glyphs = ufos
-            .iter()
-            .flat_map(|s| s.get_glyph(&glyph))
-            .collect::<Vec<_>>();
-        let first = all_glyphs.first().unwrap();
-        let others = all_glyphs.iter().skip(1);
-        for (other_ix, other) in others.enumerate() {
-            for (contour_ix, (first_contour, other_contour)) in
-                first.contours.iter().zip(other.contours.iter()).enumerate()
-            {
-                if direction(first_contour) != direction(other_contour) {
-                    problems.push((glyph.to_string(), other_ix, contour_ix));
-                }
-            }
-        }
-    }
-    return_result(
-        problems
-            .iter()
-            .map(|(glyph, other_ix, contour_ix)| {
-                Status::fail(
-                    "path-direction",
-                    &format!(
-                        "Glyph {} has different path direction in master {} contour {}",
-                        glyph,
-                        master_names[1 + other_ix],
-                        contour_ix
-                    ),
-                )
-            })
-            .collect::<Vec<_>>(),
-    )
-}
+/// Checks for font sources: designspace files and the UFOs they refer to
+pub struct Designspace;
 
 impl fontspector_checkapi::ProfileProvider for Designspace {
     fn register(&self, cr: &mut Registry) -> Result<(), FontspectorError> {
-        let designspace = FileType::new("*.designspace");
-        let ufo = FileType::new("*.ufo");
-        cr.register_filetype("DESIGNSPACE", designspace);
-        cr.register_filetype("UFO", ufo);
+        cr.register_filetype("DESIGNSPACE", FileType::new("*.designspace"));
+        cr.register_filetype("UFO", FileType::new("*.ufo"));
 
-        cr.register_simple_profile("designspace", vec![path_direction])
+        let builder = ProfileBuilder::new()
+            .add_section("Designspace Checks")
+            .add_and_register_check(checks::designspace_has_sources)
+            .add_and_register_check(checks::designspace_has_default_master)
+            .add_and_register_check(checks::designspace_has_consistent_glyphset)
+            .add_and_register_check(checks::designspace_has_consistent_codepoints)
+            .add_and_register_check(checks::designspace_has_consistent_groups)
+            .add_and_register_check(checks::path_direction)
+            .add_section("UFO Checks")
+            .add_and_register_check(checks::ufo_required_fields)
+            .add_and_register_check(checks::ufo_recommended_fields)
+            .add_and_register_check(checks::ufo_unnecessary_fields);
+        builder.build("designspace", cr)
     }
 }
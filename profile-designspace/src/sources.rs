@@ -0,0 +1,183 @@
+//! Loading designspace documents and their UFO sources
+use std::{collections::HashMap, path::Path, sync::Arc};
+
+use fontspector_checkapi::{Context, FontspectorError, Testable};
+use norad::designspace::{Axis, DesignSpaceDocument, Source};
+
+/// Two design locations closer than this are considered the same
+const LOCATION_TOLERANCE: f32 = 0.001;
+
+/// Parse the designspace document held in a testable
+pub(crate) fn designspace(t: &Testable) -> Result<DesignSpaceDocument, FontspectorError> {
+    let ds_contents = std::str::from_utf8(&t.contents)
+        .map_err(|_| FontspectorError::General("designspace is not valid UTF-8".to_string()))?;
+    quick_xml::de::from_str::<DesignSpaceDocument>(ds_contents)
+        .map_err(|e| FontspectorError::General(format!("Failed to parse designspace: {e}")))
+}
+
+/// Load a UFO testable, reading only its font info
+pub(crate) fn ufo_font_info(t: &Testable) -> Result<norad::FontInfo, FontspectorError> {
+    norad::Font::load_requested_data(&t.filename, norad::DataRequest::none())
+        .map(|font| font.font_info)
+        .map_err(|e| {
+            FontspectorError::General(format!("Failed to load UFO {}: {e}", t.filename.display()))
+        })
+}
+
+/// A designspace source, together with its UFO if it could be loaded
+pub(crate) struct LoadedSource<'a> {
+    pub source: &'a Source,
+    pub font: &'a Result<norad::Font, String>,
+}
+
+impl LoadedSource<'_> {
+    /// A name to identify the source in messages
+    pub fn name(&self) -> &str {
+        &self.source.filename
+    }
+}
+
+/// A designspace document with the UFOs of all its sources
+pub(crate) struct LoadedDesignspace {
+    pub document: DesignSpaceDocument,
+    /// The UFO of each source, in the same order as the document's sources
+    fonts: Vec<Result<norad::Font, String>>,
+}
+
+impl LoadedDesignspace {
+    /// The sources of the designspace, with their UFOs
+    pub fn sources(&self) -> Vec<LoadedSource<'_>> {
+        self.document
+            .sources
+            .iter()
+            .zip(self.fonts.iter())
+            .map(|(source, font)| LoadedSource { source, font })
+            .collect()
+    }
+}
+
+/// Parse a designspace and load all its UFO sources, resolving their
+/// filenames relative to the designspace file
+///
+/// The UFOs are only read once per designspace, however many checks
+/// look at them.
+pub(crate) fn load_designspace(
+    t: &Testable,
+    context: &Context,
+) -> Result<Arc<LoadedDesignspace>, FontspectorError> {
+    context.memoize(
+        &format!("designspace_sources:{}", t.filename.to_string_lossy()),
+        || {
+            let document = designspace(t)?;
+            let dirname = t.filename.parent().unwrap_or(Path::new(""));
+            let fonts = document
+                .sources
+                .iter()
+                .map(|source| {
+                    norad::Font::load(dirname.join(&source.filename)).map_err(|e| e.to_string())
+                })
+                .collect();
+            Ok(LoadedDesignspace { document, fonts })
+        },
+    )
+}
+
+/// Map a userspace axis value to design coordinates through the axis map
+fn user_to_design(axis: &Axis, value: f32) -> f32 {
+    let Some(map) = axis.map.as_ref().filter(|map| !map.is_empty()) else {
+        return value;
+    };
+    let mut map = map
+        .iter()
+        .map(|mapping| (mapping.input, mapping.output))
+        .collect::<Vec<_>>();
+    map.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let (Some(first), Some(last)) = (map.first(), map.last()) else {
+        return value;
+    };
+    if value <= first.0 {
+        return first.1;
+    }
+    if value >= last.0 {
+        return last.1;
+    }
+    for (lower, upper) in map.iter().zip(map.iter().skip(1)) {
+        if value >= lower.0 && value <= upper.0 {
+            if upper.0 == lower.0 {
+                return lower.1;
+            }
+            return lower.1 + (value - lower.0) / (upper.0 - lower.0) * (upper.1 - lower.1);
+        }
+    }
+    value
+}
+
+/// The design location of a source, filling in axis defaults for any
+/// dimensions it doesn't mention
+fn source_location(ds: &DesignSpaceDocument, source: &Source) -> HashMap<String, f32> {
+    ds.axes
+        .iter()
+        .map(|axis| {
+            let dimension = source.location.iter().find(|dim| dim.name == axis.name);
+            let value = dimension
+                .and_then(|dim| dim.xvalue)
+                .or_else(|| {
+                    dimension
+                        .and_then(|dim| dim.uservalue)
+                        .map(|value| user_to_design(axis, value))
+                })
+                .unwrap_or_else(|| user_to_design(axis, axis.default));
+            (axis.name.clone(), value)
+        })
+        .collect()
+}
+
+/// Find the index of the (non-sparse) source at the default location
+pub(crate) fn default_source(ds: &DesignSpaceDocument) -> Option<usize> {
+    let default_location = ds
+        .axes
+        .iter()
+        .map(|axis| (axis.name.clone(), user_to_design(axis, axis.default)))
+        .collect::<HashMap<_, _>>();
+    ds.sources.iter().position(|source| {
+        source.layer.is_none()
+            && source_location(ds, source)
+                .iter()
+                .all(|(axis_name, value)| {
+                    default_location
+                        .get(axis_name)
+                        .is_some_and(|default| (default - value).abs() < LOCATION_TOLERANCE)
+                })
+    })
+}
+
+/// Load the default master of a designspace and its other full (non-sparse)
+/// masters, skipping the check if there is no usable default master.
+///
+/// Sources which fail to load are left out; designspace_has_sources reports them.
+pub(crate) fn default_and_other_masters(
+    ds: &LoadedDesignspace,
+) -> Result<(&norad::Font, Vec<(&str, &norad::Font)>), FontspectorError> {
+    let default_index = default_source(&ds.document).ok_or_else(|| {
+        FontspectorError::skip("no-default-master", "The designspace has no default master")
+    })?;
+    let mut default = None;
+    let mut others = vec![];
+    for (index, loaded) in ds.sources().into_iter().enumerate() {
+        let Ok(font) = loaded.font else {
+            continue;
+        };
+        if index == default_index {
+            default = Some(font);
+        } else if loaded.source.layer.is_none() {
+            others.push((loaded.source.filename.as_str(), font));
+        }
+    }
+    let default = default.ok_or_else(|| {
+        FontspectorError::skip(
+            "no-default-master",
+            "The default master of the designspace could not be loaded",
+        )
+    })?;
+    Ok((default, others))
+}
@@ -3,7 +3,7 @@ use fontations::skrifa::{
     string::StringId,
     Tag,
 };
-use fontspector_checkapi::{prelude::*, skip, testfont, FileTypeConvert, TestFont};
+use fontspector_checkapi::{prelude::*, testfont, FileTypeConvert, TestFont};
 use google_fonts_axisregistry::AxisRegistry;
 use hashbrown::HashMap;
 
@@ -71,6 +71,7 @@ fn process_axis(
 
 #[check(
     id = "googlefonts/STAT/axisregistry",
+    conditions = ["is_variable_font"],
     rationale = "
         
         Check that particle names and values on STAT table match the fallback names
@@ -87,11 +88,6 @@ fn axisregistry(t: &Testable, _context: &Context) -> CheckFnResult {
     let f = testfont!(t);
     let mut problems = vec![];
     let mut format4_entries = false;
-    skip!(
-        !f.is_variable_font(),
-        "not-variable",
-        "Font is not a variable font."
-    );
     let axis_registry = AxisRegistry::new();
     if let Ok(stat) = f.font().stat() {
         let axes = stat
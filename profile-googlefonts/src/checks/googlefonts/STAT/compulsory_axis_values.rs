@@ -2,7 +2,7 @@ use fontations::skrifa::{
     raw::{tables::stat::AxisValue, TableProvider},
     FontRef, MetadataProvider,
 };
-use fontspector_checkapi::{prelude::*, skip, testfont, FileTypeConvert};
+use fontspector_checkapi::{prelude::*, testfont, FileTypeConvert};
 use hashbrown::HashMap;
 use itertools::Itertools;
 use tabled::builder::Builder;
@@ -107,6 +107,7 @@ fn stat_axis_values(
 }
 #[check(
     id = "googlefonts/STAT/compulsory_axis_values",
+    conditions = ["is_variable_font"],
     rationale = "
         
         Check a font's STAT table contains compulsory Axis Values which exist
@@ -122,11 +123,6 @@ fn stat_axis_values(
 fn compulsory_axis_values(t: &Testable, _context: &Context) -> CheckFnResult {
     let f = testfont!(t);
     let mut problems = vec![];
-    skip!(
-        !f.is_variable_font(),
-        "not-variable",
-        "Font is not a variable font."
-    );
     let font_axis_values = stat_axis_values(&f.font())?;
     // XXX this should probably involve siblings
     let expected_binary = build_expected_font(&f, &[])?;
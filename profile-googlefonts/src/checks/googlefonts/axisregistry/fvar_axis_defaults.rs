@@ -1,11 +1,12 @@
 use fontations::skrifa::MetadataProvider;
-use fontspector_checkapi::{prelude::*, skip, testfont, FileTypeConvert};
+use fontspector_checkapi::{prelude::*, testfont, FileTypeConvert};
 use itertools::Itertools;
 
 use google_fonts_axisregistry::AxisRegistry;
 
 #[check(
     id = "googlefonts/axisregistry/fvar_axis_defaults",
+    conditions = ["is_variable_font"],
     rationale = "
         
         Check that axis defaults have a corresponding fallback name registered at the
@@ -35,11 +36,6 @@ use google_fonts_axisregistry::AxisRegistry;
 fn fvar_axis_defaults(t: &Testable, _context: &Context) -> CheckFnResult {
     let f = testfont!(t);
     let mut problems = vec![];
-    skip!(
-        !f.is_variable_font(),
-        "not-variable",
-        "Font is not variable"
-    );
     let registry = AxisRegistry::new();
     for axis in f.font().axes().iter() {
         let tag = axis.tag().to_string();
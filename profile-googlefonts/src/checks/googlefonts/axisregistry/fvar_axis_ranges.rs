@@ -1,9 +1,10 @@
 use fontations::skrifa::MetadataProvider;
-use fontspector_checkapi::{prelude::*, skip, testfont, FileTypeConvert};
+use fontspector_checkapi::{prelude::*, testfont, FileTypeConvert};
 use google_fonts_axisregistry::AxisRegistry;
 
 #[check(
     id = "googlefonts/axisregistry/fvar_axis_ranges",
+    conditions = ["is_variable_font"],
     rationale = "
         Each axis range in the fvar table must be within the bounds defined in the
         Google Fonts Axis Registry, available at
@@ -14,11 +15,6 @@ use google_fonts_axisregistry::AxisRegistry;
 fn fvar_axis_ranges(t: &Testable, _context: &Context) -> CheckFnResult {
     let f = testfont!(t);
     let mut problems = vec![];
-    skip!(
-        !f.is_variable_font(),
-        "not-variable",
-        "Font is not variable"
-    );
 
     let registry = AxisRegistry::new();
     for axis in f.font().axes().iter() {
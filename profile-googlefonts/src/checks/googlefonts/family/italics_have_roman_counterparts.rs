@@ -1,4 +1,4 @@
-use fontspector_checkapi::prelude::*;
+use fontspector_checkapi::{prelude::*, skip};
 
 #[check(
     id = "googlefonts/family/italics_have_roman_counterparts",
@@ -14,61 +14,106 @@ use fontspector_checkapi::prelude::*;
     
     ",
     proposal = "https://github.com/fonttools/fontbakery/issues/1733",
-    title = "Ensure Italic styles have Roman counterparts.",
-    implementation = "all"
+    title = "Ensure Italic styles have Roman counterparts."
 )]
-fn italics_have_roman_counterparts(c: &TestableCollection, context: &Context) -> CheckFnResult {
-    let filenames: Vec<String> = c
-        .testables
-        .iter()
-        .map(|t| t.filename.to_string_lossy().to_string())
-        .filter(|x| x.ends_with(".ttf"))
-        .collect();
-    let italics: Vec<&String> = filenames
-        .iter()
-        .filter(|x| x.contains("Italic") && x.find("-") < x.find("Italic"))
-        .collect();
-    let mut missing_roman = vec![];
-    let mut problems = vec![];
-    for italic in italics {
-        if !italic.contains("-") {
-            problems.push(Status::warn(
-                "bad-filename",
-                &format!("Filename seems to be incorrect: '{italic}'"),
-            ));
-            continue;
-        }
-        #[allow(clippy::unwrap_used)] // We just tested for one
-        let after_hyphen = italic.split("-").last().unwrap();
-        let Some(style_from_filename) = after_hyphen.split(".").next() else {
-            problems.push(Status::warn(
-                "bad-filename",
-                &format!("Filename seems to be incorrect: '{italic}'"),
-            ));
-            continue;
-        };
-        let is_varfont = style_from_filename.contains("[");
-        let roman_counterpart = if style_from_filename == "Italic" {
-            if is_varfont {
-                italic.replace("-Italic", "")
-            } else {
-                italic.replace("Italic", "Regular")
-            }
-        } else {
-            italic.replace("-Italic", "")
-        };
-        if !filenames.contains(&roman_counterpart) {
-            missing_roman.push(italic);
-        }
-    }
-    if !missing_roman.is_empty() {
-        problems.push(Status::fail(
+fn italics_have_roman_counterparts(t: &Testable, context: &Context) -> CheckFnResult {
+    let italic = t.basename().unwrap_or_default();
+    skip!(
+        !(italic.contains("Italic") && italic.find("-") < italic.find("Italic")),
+        "not-italic",
+        "Font is not an Italic."
+    );
+    let Some(style_from_filename) = italic
+        .rsplit_once("-")
+        .and_then(|(_, after_hyphen)| after_hyphen.split(".").next())
+    else {
+        return Ok(Status::just_one_warn(
+            "bad-filename",
+            &format!("Filename seems to be incorrect: '{italic}'"),
+        ));
+    };
+    skip!(
+        context.family.is_none(),
+        "no-family",
+        "Font is not being checked as part of a family."
+    );
+    let is_varfont = style_from_filename.contains("[");
+    let roman_counterpart = if is_varfont {
+        italic.replace("-Italic", "")
+    } else if style_from_filename == "Italic" {
+        italic.replace("Italic", "Regular")
+    } else {
+        italic.replace("Italic", "")
+    };
+    if !context
+        .siblings(t)
+        .any(|sibling| sibling.basename().as_ref() == Some(&roman_counterpart))
+    {
+        return Ok(Status::just_one_fail(
             "missing-roman",
-            &format!(
-                "Italics missing a Roman counterpart:\n\n{}",
-                bullet_list(context, missing_roman)
-            ),
+            &format!("This Italic has no Roman counterpart; expected to find '{roman_counterpart}' in the family."),
         ));
     }
-    return_result(problems)
+    Ok(Status::just_one_pass())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use fontspector_checkapi::{
+        codetesting::{
+            assert_pass, assert_results_contain, assert_skip, run_check, run_check_in_family,
+            test_able,
+        },
+        StatusCode, TestableCollection,
+    };
+
+    fn family(files: &[&str]) -> Arc<TestableCollection> {
+        Arc::new(TestableCollection::from_testables(
+            files
+                .iter()
+                .map(|file| test_able(format!("merriweather/{file}")))
+                .collect(),
+            None,
+        ))
+    }
+
+    #[test]
+    fn test_italics_have_roman_counterparts() {
+        let merriweather = family(&[
+            "Merriweather-Regular.ttf",
+            "Merriweather-Italic.ttf",
+            "Merriweather-BoldItalic.ttf",
+        ]);
+        let results = run_check_in_family(
+            super::italics_have_roman_counterparts,
+            &merriweather,
+            "Merriweather-Italic.ttf",
+        );
+        assert_pass(&results);
+        let results = run_check_in_family(
+            super::italics_have_roman_counterparts,
+            &merriweather,
+            "Merriweather-Regular.ttf",
+        );
+        assert_skip(&results);
+        // The problem is reported against the Italic which is missing its Roman
+        let results = run_check_in_family(
+            super::italics_have_roman_counterparts,
+            &merriweather,
+            "Merriweather-BoldItalic.ttf",
+        );
+        assert_results_contain(
+            &results,
+            StatusCode::Fail,
+            Some("missing-roman".to_string()),
+        );
+        // Without a family, there is nothing to compare against
+        let results = run_check(
+            super::italics_have_roman_counterparts,
+            test_able("merriweather/Merriweather-BoldItalic.ttf"),
+        );
+        assert_skip(&results);
+    }
 }
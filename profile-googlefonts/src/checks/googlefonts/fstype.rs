@@ -1,5 +1,5 @@
 use fontations::{skrifa::raw::TableProvider, write::from_obj::ToOwnedTable};
-use fontspector_checkapi::{prelude::*, testfont, FileTypeConvert, Metadata};
+use fontspector_checkapi::{prelude::*, source, testfont, FileTypeConvert, Metadata};
 use serde_json::json;
 
 const FSTYPE_RESTRICTIONS: [(u16, &str); 5] = [
@@ -35,6 +35,7 @@ const FSTYPE_RESTRICTIONS: [(u16, &str); 5] = [
     proposal = "https://github.com/fonttools/fontbakery/issues/4829",
     title = "Checking OS/2 fsType does not impose restrictions.",
     hotfix = fix_fstype,
+    fix_source = fix_fstype_source,
 )]
 fn fstype(t: &Testable, _context: &Context) -> CheckFnResult {
     let f = testfont!(t);
@@ -80,6 +81,15 @@ fn fix_fstype(
     Ok(FixResult::Fixed)
 }
 
+fn fix_fstype_source(
+    t: &Testable,
+    _replies: Option<MoreInfoReplies>,
+) -> Result<FixResult, FontspectorError> {
+    source::fix_fontinfo(t, |fontinfo| {
+        source::set_fontinfo_value(fontinfo, "openTypeOS2Type", source::Value::Array(vec![]))
+    })
+}
+
 #[cfg(test)]
 mod tests {
     #![allow(clippy::unwrap_used, clippy::expect_used)]
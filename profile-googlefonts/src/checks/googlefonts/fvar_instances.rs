@@ -9,6 +9,7 @@ use crate::utils::build_expected_font;
 
 #[check(
     id = "googlefonts/fvar_instances",
+    conditions = ["is_variable_font"],
     rationale = "
         
         Check a font's fvar instance coordinates comply with our guidelines:
@@ -25,11 +26,6 @@ use crate::utils::build_expected_font;
 fn fvar_instances(t: &Testable, _context: &Context) -> CheckFnResult {
     let f = testfont!(t);
     let mut problems = vec![];
-    skip!(
-        !f.is_variable_font(),
-        "is-variable-font",
-        "Font is not a variable font"
-    );
     skip!(f.has_axis("MORF"), "has-morf", "Font has a MORF axis");
     let expected_font_data = build_expected_font(&f, &[])?;
     let expected_font = TestFont::new_from_data(&t.filename, &expected_font_data).map_err(|e| {
@@ -1,6 +1,7 @@
 use std::sync::LazyLock;
 
-use fontspector_checkapi::{prelude::*, FileTypeConvert};
+use fontations::skrifa::{GlyphId, MetadataProvider};
+use fontspector_checkapi::{prelude::*, ClosureOptions, FileTypeConvert};
 use google_fonts_subsets::{subsets_in_font, SUBSETS};
 use hashbrown::{HashMap, HashSet};
 use itertools::Itertools;
@@ -31,7 +32,8 @@ static CODEPOINTS_BY_SUBSET: LazyLock<HashMap<u32, HashSet<&'static str>>> = Laz
 
         Any encoded glyphs which are not by any of these subset definitions
         will not be served in the subsetted fonts, and so will be unreachable to
-        the end user.
+        the end user. So will any unencoded glyphs which can only be reached
+        from those codepoints through the GSUB table.
     
     ",
     proposal = "https://github.com/fonttools/fontbakery/issues/4097 and https://github.com/fonttools/fontbakery/pull/4273",
@@ -64,6 +66,34 @@ fn unreachable_subsetting(c: &TestableCollection, context: &Context) -> CheckFnR
         if unreachable.is_empty() {
             continue;
         }
+
+        // Unencoded glyphs which the subsetter would drop along with the
+        // unreachable codepoints
+        let everything = font.gsub_closure(
+            font_codepoints.iter().copied(),
+            &ClosureOptions::default(),
+            Some(context),
+        )?;
+        let served = font.gsub_closure(
+            font_codepoints
+                .iter()
+                .filter(|cp| !unreachable.contains(*cp))
+                .copied(),
+            &ClosureOptions::default(),
+            Some(context),
+        )?;
+        let encoded: HashSet<GlyphId> = font
+            .font()
+            .charmap()
+            .mappings()
+            .map(|(_, gid)| gid)
+            .collect();
+        let lost_glyphs: Vec<String> = everything
+            .glyphs()
+            .filter(|gid| !served.contains(*gid) && !encoded.contains(gid))
+            .map(|gid| font.glyph_name_for_id_synthesise(gid))
+            .collect();
+
         let mut bullets = vec![];
 
         for codepoint in unreachable.into_iter().sorted() {
@@ -84,15 +114,19 @@ fn unreachable_subsetting(c: &TestableCollection, context: &Context) -> CheckFnR
             let name = format!("U+{codepoint:04X} {name}");
             bullets.push(format!("{name}: {message}"));
         }
-        problems.push(Status::warn(
-            "unreachable-subsetting",
-            &format!(
-                "{}: The following codepoints supported by the font are not covered by any subsets defined in the font's metadata file, and will never be served. You can solve this by either manually adding additional subset declarations to METADATA.pb, or by editing the glyphset definitions.\n\n{}\n\nOr you can add the above codepoints to one of the subsets supported by the font: {}",
-                font.filename.to_string_lossy(),
-                bullet_list(context, bullets),
-                font_subsets.join(", ")
-            ),
-        ))
+        let mut message = format!(
+            "{}: The following codepoints supported by the font are not covered by any subsets defined in the font's metadata file, and will never be served. You can solve this by either manually adding additional subset declarations to METADATA.pb, or by editing the glyphset definitions.\n\n{}\n\nOr you can add the above codepoints to one of the subsets supported by the font: {}",
+            font.filename.to_string_lossy(),
+            bullet_list(context, bullets),
+            font_subsets.join(", ")
+        );
+        if !lost_glyphs.is_empty() {
+            message.push_str(&format!(
+                "\n\nThe following glyphs can only be reached from these codepoints through the GSUB table, so will not be served either:\n\n{}",
+                bullet_list(context, lost_glyphs)
+            ));
+        }
+        problems.push(Status::warn("unreachable-subsetting", &message))
     }
 
     return_result(problems)
@@ -1,11 +1,12 @@
 use fontations::skrifa::string::StringId;
-use fontspector_checkapi::{prelude::*, skip, testfont, FileTypeConvert};
+use fontspector_checkapi::{prelude::*, testfont, FileTypeConvert};
 
 // This is not actually googlefonts/metadata (in the sense of METADATA.pb) related, but we
 // keep the check ID for legacy reasons.
 
 #[check(
     id = "googlefonts/metadata/valid_nameid25",
+    conditions = ["is_variable_font"],
     rationale = "
         
         Due to a bug in (at least) Adobe Indesign, name ID 25
@@ -19,11 +20,6 @@ use fontspector_checkapi::{prelude::*, skip, testfont, FileTypeConvert};
 fn valid_nameid25(t: &Testable, _context: &Context) -> CheckFnResult {
     let f = testfont!(t);
     let mut problems = vec![];
-    skip!(
-        !f.is_variable_font(),
-        "not-variable",
-        "Font is not a variable font."
-    );
     if let Some(style) = f.style() {
         if style.contains("Italic") {
             for name in f.get_name_entry_strings(StringId::new(25)) {
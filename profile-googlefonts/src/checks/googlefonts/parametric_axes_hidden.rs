@@ -1,5 +1,5 @@
 use fontations::skrifa::MetadataProvider;
-use fontspector_checkapi::{prelude::*, skip, testfont, FileTypeConvert, Metadata};
+use fontspector_checkapi::{prelude::*, testfont, FileTypeConvert, Metadata};
 use google_fonts_axisregistry::AxisRegistry;
 use serde_json::json;
 
@@ -20,6 +20,7 @@ const PARAMETRIC_AXES: &[&str] = &[
 
 #[check(
     id = "googlefonts/parametric_axes_hidden",
+    conditions = ["is_variable_font"],
     title = "Ensure parametric axes have the hidden flag set.",
     rationale = "
         Parametric axes (XOPQ, YOPQ, XTRA, YTAS, YTDE, YTFI, YTLC, YTUC, XTFI)
@@ -32,11 +33,6 @@ const PARAMETRIC_AXES: &[&str] = &[
 )]
 fn parametric_axes_hidden(t: &Testable, _context: &Context) -> CheckFnResult {
     let f = testfont!(t);
-    skip!(
-        !f.is_variable_font(),
-        "not-variable",
-        "Font is not a variable font."
-    );
     let registry = AxisRegistry::new();
     let mut problems = vec![];
 
@@ -1,8 +1,9 @@
-use fontspector_checkapi::{prelude::*, skip, testfont, FileTypeConvert, Metadata};
+use fontspector_checkapi::{prelude::*, testfont, FileTypeConvert, Metadata};
 use serde_json::json;
 
 #[check(
     id = "googlefonts/varfont/has_HVAR",
+    conditions = ["is_variable_font"],
     rationale = "
         
         Not having a HVAR table can lead to costly text-layout operations on some
@@ -20,11 +21,6 @@ use serde_json::json;
 )]
 fn has_HVAR(t: &Testable, _context: &Context) -> CheckFnResult {
     let f = testfont!(t);
-    skip!(
-        !f.is_variable_font(),
-        "variable-font",
-        "Font is not a variable font."
-    );
     let mut problems = vec![];
     if f.has_table(b"HVAR") {
         problems.push(Status::pass());
@@ -3,6 +3,7 @@ use fontspector_checkapi::{prelude::*, skip, testfont, FileTypeConvert};
 
 #[check(
     id = "googlefonts/varfont/slnt_needs_italic",
+    conditions = ["is_variable_font"],
     rationale = "
         Google Workspace applications (Docs, Sheets, Slides) do not currently
         support the 'slnt' (Slant) axis for selecting italic styles. If a
@@ -18,11 +19,6 @@ use fontspector_checkapi::{prelude::*, skip, testfont, FileTypeConvert};
 )]
 fn slnt_needs_italic(t: &Testable, _context: &Context) -> CheckFnResult {
     let f = testfont!(t);
-    skip!(
-        !f.is_variable_font(),
-        "not-variable",
-        "Font is not a variable font."
-    );
     let has_slnt = f.font().axes().iter().any(|a| a.tag() == Tag::new(b"slnt"));
     skip!(!has_slnt, "no-slnt", "Font does not have a 'slnt' axis.");
     let has_ital = f.font().axes().iter().any(|a| a.tag() == Tag::new(b"ital"));
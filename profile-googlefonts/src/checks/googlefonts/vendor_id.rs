@@ -1,7 +1,11 @@
 use std::sync::LazyLock;
 
-use fontations::skrifa::raw::TableProvider;
-use fontspector_checkapi::{prelude::*, testfont, FileTypeConvert};
+use fontations::{
+    skrifa::raw::TableProvider,
+    types::Tag,
+    write::{from_obj::ToOwnedTable, tables::os2::Os2},
+};
+use fontspector_checkapi::{prelude::*, source, testfont, DialogField, FileTypeConvert};
 use hashbrown::HashSet;
 
 const VENDOR_IDS_FILE: &str = include_str!("../../../resources/vendor_ids.txt");
@@ -40,7 +44,9 @@ const SUGGEST_MICROSOFT_VENDORLIST_WEBSITE: &str = "If you registered it recentl
     ",
     proposal = "https://github.com/fonttools/fontbakery/issues/3943",
     proposal = "https://github.com/fonttools/fontbakery/issues/4829",
-    title = "Checking OS/2 achVendID."
+    title = "Checking OS/2 achVendID.",
+    hotfix = fix_vendor_id,
+    fix_source = fix_vendor_id_source,
 )]
 fn vendor_id(t: &Testable, _context: &Context) -> CheckFnResult {
     let f = testfont!(t);
@@ -69,6 +75,66 @@ fn vendor_id(t: &Testable, _context: &Context) -> CheckFnResult {
     return_result(problems)
 }
 
+/// Get the new vendor ID from the user's replies, or ask for one
+fn requested_vendor_id(replies: Option<MoreInfoReplies>) -> Result<Tag, FixResult> {
+    let vendor_id = replies
+        .as_ref()
+        .and_then(|replies| replies.0.get("vendor_id"))
+        .and_then(|v| v.as_str())
+        .map(|v| v.to_string());
+    let Some(vendor_id) = vendor_id else {
+        return Err(FixResult::MoreInfoNeeded(MoreInfoRequest(vec![
+            DialogField::new_text("vendor_id", "What is your four-character vendor ID?"),
+        ])));
+    };
+    let bytes: [u8; 4] = format!("{vendor_id:4}")
+        .into_bytes()
+        .try_into()
+        .map_err(|_| {
+            FixResult::FixFailed(format!(
+                "Vendor ID '{vendor_id}' is longer than four characters"
+            ))
+        })?;
+    if !bytes.iter().all(|b| (0x20..=0x7E).contains(b)) {
+        return Err(FixResult::FixFailed(format!(
+            "Vendor ID '{vendor_id}' must be printable ASCII"
+        )));
+    }
+    Ok(Tag::new(&bytes))
+}
+
+fn fix_vendor_id(
+    t: &mut Testable,
+    replies: Option<MoreInfoReplies>,
+) -> Result<FixResult, FontspectorError> {
+    let vendor_id = match requested_vendor_id(replies) {
+        Ok(vendor_id) => vendor_id,
+        Err(result) => return Ok(result),
+    };
+    let f = testfont!(t);
+    let mut os2: Os2 = f.font().os2()?.to_owned_table();
+    os2.ach_vend_id = vendor_id;
+    t.set(f.rebuild_with_new_table(&os2)?);
+    Ok(FixResult::Fixed)
+}
+
+fn fix_vendor_id_source(
+    t: &Testable,
+    replies: Option<MoreInfoReplies>,
+) -> Result<FixResult, FontspectorError> {
+    let vendor_id = match requested_vendor_id(replies) {
+        Ok(vendor_id) => vendor_id,
+        Err(result) => return Ok(result),
+    };
+    source::fix_fontinfo(t, |fontinfo| {
+        source::set_fontinfo_value(
+            fontinfo,
+            "openTypeOS2VendorID",
+            source::Value::String(vendor_id.to_string().trim_end().to_string()),
+        )
+    })
+}
+
 #[cfg(test)]
 mod tests {
     #![allow(clippy::unwrap_used, clippy::expect_used)]
@@ -125,4 +191,26 @@ mod tests {
         let results = run_check(vendor_id, testable);
         assert_pass(&results);
     }
+
+    #[test]
+    fn test_fix_vendor_id() {
+        use fontspector_checkapi::{FixResult, MoreInfoReplies};
+
+        let mut testable = test_able("merriweather/Merriweather-Regular.ttf");
+        set_vendor_id(&mut testable, "UKWN");
+        assert!(matches!(
+            super::fix_vendor_id(&mut testable, None).unwrap(),
+            FixResult::MoreInfoNeeded(_)
+        ));
+        let mut replies = MoreInfoReplies::default();
+        replies
+            .0
+            .insert("vendor_id".to_string(), serde_json::json!("GOOG"));
+        assert!(matches!(
+            super::fix_vendor_id(&mut testable, Some(replies)).unwrap(),
+            FixResult::Fixed
+        ));
+        let results = run_check(vendor_id, testable);
+        assert_pass(&results);
+    }
 }
@@ -76,7 +76,7 @@ fn weightclass(t: &Testable, _context: &Context) -> CheckFnResult {
             });
             problems.push(status);
         }
-        if f.outline_type() == OutlineType::CFF && value != 250 {
+        if matches!(f.outline_type(), OutlineType::CFF | OutlineType::CFF2) && value != 250 {
             let msg = "OS/2 usWeightClass is invalid for Thin weight CFF";
             let mut status = Status::fail(
                 "bad-value",
@@ -112,7 +112,7 @@ fn weightclass(t: &Testable, _context: &Context) -> CheckFnResult {
             });
             problems.push(status);
         }
-        if f.outline_type() == OutlineType::CFF && value != 275 {
+        if matches!(f.outline_type(), OutlineType::CFF | OutlineType::CFF2) && value != 275 {
             let msg = "OS/2 usWeightClass is invalid for ExtraLight weight CFF";
             let mut status = Status::fail(
                 "bad-value",
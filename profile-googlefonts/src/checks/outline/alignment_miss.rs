@@ -107,6 +107,7 @@ fn alignment_miss(t: &Testable, context: &Context) -> CheckFnResult {
     }
     let mut all_warnings = vec![];
     for glyph in f.all_glyphs() {
+        context.check_deadline()?;
         let mut name = f.glyph_name_for_id_synthesise(glyph);
         if let Some((cp, _gid)) = f
             .font()
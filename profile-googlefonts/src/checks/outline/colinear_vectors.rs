@@ -46,7 +46,7 @@ fn colinear_vectors(t: &Testable, context: &Context) -> CheckFnResult {
         "variable-font",
         "This check produces too many false positives with variable fonts."
     );
-    for (glyph_id, (name, result)) in name_and_bezglyph(&f).enumerate() {
+    for (glyph_id, (name, result)) in name_and_bezglyph(&f, context).enumerate() {
         let pen = result?;
         for contour in pen.iter() {
             let segs = contour.segments().collect::<Vec<_>>();
@@ -38,7 +38,7 @@ fn direction(t: &Testable, context: &Context) -> CheckFnResult {
     };
     let mut problems = vec![];
     let mut all_warnings = vec![];
-    for (name, result) in name_and_bezglyph(&f) {
+    for (name, result) in name_and_bezglyph(&f, context) {
         let pen = result?;
         let bounds: Vec<Rect> = pen.iter().map(|path| path.bounding_box()).collect();
         let mut is_within = vec![vec![]; bounds.len()];
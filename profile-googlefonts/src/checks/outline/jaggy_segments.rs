@@ -64,7 +64,7 @@ fn jaggy_segments(t: &Testable, context: &Context) -> CheckFnResult {
     let mut problems = vec![];
     let mut all_warnings: Vec<JaggyWarning> = vec![];
 
-    for (glyph_id, (name, result)) in name_and_bezglyph(&f).enumerate() {
+    for (glyph_id, (name, result)) in name_and_bezglyph(&f, context).enumerate() {
         let pen = result?;
         for path in pen.iter() {
             let segs = path.segments().collect::<Vec<_>>();
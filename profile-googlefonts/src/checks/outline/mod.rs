@@ -1,6 +1,6 @@
 use fontations::skrifa::MetadataProvider;
-use fontspector_checkapi::{pens::BezGlyph, FontspectorError, TestFont, DEFAULT_LOCATION};
-use std::ops::Sub;
+use fontspector_checkapi::{pens::BezGlyph, Context, FontspectorError, TestFont};
+use std::{ops::Sub, sync::Arc};
 
 mod alignment_miss;
 mod colinear_vectors;
@@ -26,7 +26,8 @@ where
 
 pub(crate) fn name_and_bezglyph<'a>(
     f: &'a TestFont,
-) -> impl Iterator<Item = (String, Result<BezGlyph, FontspectorError>)> + 'a {
+    context: &'a Context,
+) -> impl Iterator<Item = (String, Result<Arc<BezGlyph>, FontspectorError>)> + 'a {
     let reverse_char_map = f
         .font()
         .charmap()
@@ -38,8 +39,7 @@ pub(crate) fn name_and_bezglyph<'a>(
         if let Some(cp) = reverse_char_map.get(&glyph) {
             name = format!("{name} (U+{cp:04X})");
         }
-        let mut pen = BezGlyph::default();
-        let result = f.draw_glyph(glyph, &mut pen, DEFAULT_LOCATION);
-        (name, result.map(|_| pen))
+        // This stops drawing glyphs once the check has run out of time
+        (name, f.bez_glyph(glyph, Some(context)))
     })
 }
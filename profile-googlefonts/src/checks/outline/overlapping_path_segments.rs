@@ -23,7 +23,7 @@ use crate::checks::outline::name_and_bezglyph;
 fn overlapping_path_segments(t: &Testable, context: &Context) -> CheckFnResult {
     let f = testfont!(t);
     let mut all_warnings = vec![];
-    for (name, result) in name_and_bezglyph(&f) {
+    for (name, result) in name_and_bezglyph(&f, context) {
         let mut seen = HashSet::new();
         let pen = result?;
         for contour in pen.iter() {
@@ -59,7 +59,7 @@ fn semi_vertical(t: &Testable, context: &Context) -> CheckFnResult {
         "This check produces too many false positives with italic fonts."
     );
 
-    for (glyph_id, (name, result)) in name_and_bezglyph(&f).enumerate() {
+    for (glyph_id, (name, result)) in name_and_bezglyph(&f, context).enumerate() {
         let pen = result?;
         for path in pen.iter() {
             for seg in path.segments() {
@@ -69,7 +69,7 @@ fn short_segments(t: &Testable, context: &Context) -> CheckFnResult {
         "variable-font",
         "This check produces too many false positives with variable fonts."
     );
-    for (glyph_id, (name, result)) in name_and_bezglyph(&f).enumerate() {
+    for (glyph_id, (name, result)) in name_and_bezglyph(&f, context).enumerate() {
         let pen = result?;
         for path in pen.iter() {
             let outline_length = path.perimeter(0.01);
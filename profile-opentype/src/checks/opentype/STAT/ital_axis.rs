@@ -285,6 +285,7 @@ mod tests {
         let collection = TestableCollection {
             testables,
             directory: "".to_string(),
+            label: None,
         };
         let result = run_check_with_config(
             super::ital_axis,
@@ -303,6 +304,7 @@ mod tests {
         let collection = TestableCollection {
             testables,
             directory: "".to_string(),
+            label: None,
         };
         let result = run_check_with_config(
             super::ital_axis,
@@ -319,6 +321,7 @@ mod tests {
         let collection = TestableCollection {
             testables,
             directory: "".to_string(),
+            label: None,
         };
         let result = run_check_with_config(
             super::ital_axis,
@@ -337,6 +340,7 @@ mod tests {
         let collection = TestableCollection {
             testables,
             directory: "".to_string(),
+            label: None,
         };
         let results = run_check_with_config(
             ital_axis,
@@ -358,6 +362,7 @@ mod tests {
         let collection = TestableCollection {
             testables,
             directory: "".to_string(),
+            label: None,
         };
         let results = run_check_with_config(
             ital_axis,
@@ -381,6 +386,7 @@ mod tests {
         let collection = TestableCollection {
             testables,
             directory: "".to_string(),
+            label: None,
         };
         let fonts = TTF.from_collection(&collection);
         let pairs = segment_collection(fonts);
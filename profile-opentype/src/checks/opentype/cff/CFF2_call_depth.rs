@@ -0,0 +1,54 @@
+use fontspector_checkapi::{prelude::*, skip, testfont, FileTypeConvert};
+
+use super::{charstrings::CffAnalysis, CFF_call_depth::call_depth_problems};
+
+#[check(
+    id = "opentype/CFF2_call_depth",
+    rationale = "
+        Per \"The CFF2 CharString Format\", the \"Subr nesting, stack limit\"
+        is 10. Rasterizers may refuse to render glyphs whose subroutine calls
+        nest more deeply than this.
+    ",
+    proposal = "https://github.com/fonttools/fontbakery/pull/2425",
+    title = "Is the CFF2 subr/gsubr call depth > 10?"
+)]
+fn CFF2_call_depth(t: &Testable, context: &Context) -> CheckFnResult {
+    let f = testfont!(t);
+    skip!(
+        !f.has_table(b"CFF2"),
+        "no-cff2",
+        "This check only applies to CFF2 fonts."
+    );
+    let analysis = CffAnalysis::from_font(&f, true, context)?;
+    call_depth_problems(&f, &analysis, context)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fontspector_checkapi::{
+        codetesting::{assert_pass, assert_results_contain, run_check, test_able},
+        StatusCode,
+    };
+
+    #[test]
+    fn test_cff2_call_depth_pass() {
+        let testable = test_able("source-sans-pro/VAR/SourceSansVariable-Italic.otf");
+        let result = run_check(CFF2_call_depth, testable);
+        assert_pass(&result);
+    }
+
+    #[test]
+    fn test_cff2_call_depth_fail() {
+        let testable = test_able("subr_test_fonts/var_subr_test_font_infinite_recursion.otf");
+        let result = run_check(CFF2_call_depth, testable);
+        assert_results_contain(&result, StatusCode::Fail, Some("max-depth".to_string()));
+    }
+
+    #[test]
+    fn test_cff2_call_depth_skip() {
+        let testable = test_able("source-sans-pro/OTF/SourceSansPro-Regular.otf");
+        let result = run_check(CFF2_call_depth, testable);
+        assert_results_contain(&result, StatusCode::Skip, Some("no-cff2".to_string()));
+    }
+}
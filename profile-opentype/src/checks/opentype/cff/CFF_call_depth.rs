@@ -0,0 +1,113 @@
+use fontations::skrifa::GlyphId;
+use fontspector_checkapi::{prelude::*, skip, testfont, FileTypeConvert, TestFont};
+
+use super::charstrings::{CffAnalysis, MAX_CALL_DEPTH};
+
+#[check(
+    id = "opentype/CFF_call_depth",
+    rationale = "
+        Per \"The Type 2 Charstring Format, Technical Note #5177\",
+        the \"Subr nesting, stack limit\" is 10. Rasterizers may refuse to
+        render glyphs whose subroutine calls nest more deeply than this.
+    ",
+    proposal = "https://github.com/fonttools/fontbakery/pull/2425",
+    title = "Is the CFF subr/gsubr call depth > 10?"
+)]
+fn CFF_call_depth(t: &Testable, context: &Context) -> CheckFnResult {
+    let f = testfont!(t);
+    skip!(
+        !f.has_table(b"CFF "),
+        "no-cff",
+        "This check only applies to CFF fonts."
+    );
+    let analysis = CffAnalysis::from_font(&f, false, context)?;
+    call_depth_problems(&f, &analysis, context)
+}
+
+/// Report glyphs which nest subroutines too deeply or can't be interpreted
+pub(super) fn call_depth_problems(
+    f: &TestFont,
+    analysis: &CffAnalysis,
+    context: &Context,
+) -> CheckFnResult {
+    let mut problems = vec![];
+    let too_deep = analysis
+        .glyphs
+        .iter()
+        .enumerate()
+        .filter(|(_, glyph)| glyph.max_depth > MAX_CALL_DEPTH)
+        .map(|(gid, _)| f.glyph_name_for_id_synthesise(GlyphId::new(gid as u32)))
+        .collect::<Vec<_>>();
+    if !too_deep.is_empty() {
+        problems.push(Status::fail(
+            "max-depth",
+            &format!(
+                "The following glyphs nest subroutine calls more than {MAX_CALL_DEPTH} deep:\n\n{}",
+                bullet_list(context, too_deep)
+            ),
+        ));
+    }
+    let broken = analysis
+        .glyphs
+        .iter()
+        .enumerate()
+        .filter_map(|(gid, glyph)| {
+            glyph.error.as_ref().map(|error| {
+                format!(
+                    "{}: {error}",
+                    f.glyph_name_for_id_synthesise(GlyphId::new(gid as u32))
+                )
+            })
+        })
+        .collect::<Vec<_>>();
+    if !broken.is_empty() {
+        problems.push(Status::fail(
+            "charstring-error",
+            &format!(
+                "The charstrings of the following glyphs could not be interpreted:\n\n{}",
+                bullet_list(context, broken)
+            ),
+        ));
+    }
+    return_result(problems)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fontspector_checkapi::{
+        codetesting::{
+            assert_messages_contain, assert_messages_dont_contain, assert_pass,
+            assert_results_contain, run_check, test_able,
+        },
+        StatusCode,
+    };
+
+    #[test]
+    fn test_cff_call_depth_pass() {
+        let testable = test_able("source-sans-pro/OTF/SourceSansPro-Regular.otf");
+        let result = run_check(CFF_call_depth, testable);
+        assert_pass(&result);
+    }
+
+    #[test]
+    fn test_cff_call_depth_fail() {
+        // Glyphs D and E nest 11 and 12 deep, and F calls itself
+        let testable = test_able("subr_test_fonts/subr_test_font_infinite_recursion.otf");
+        let result = run_check(CFF_call_depth, testable);
+        assert_results_contain(&result, StatusCode::Fail, Some("max-depth".to_string()));
+        assert_messages_contain(&result, "* D\n");
+        assert_messages_contain(&result, "* F");
+        assert_messages_dont_contain(&result, "* C\n");
+    }
+
+    #[test]
+    fn test_cff_call_depth_skip() {
+        let testable = test_able("source-sans-pro/TTF/SourceSansPro-Regular.ttf");
+        let result = run_check(CFF_call_depth, testable);
+        assert_results_contain(&result, StatusCode::Skip, Some("no-cff".to_string()));
+        let testable = test_able("source-sans-pro/VAR/SourceSansVariable-Italic.otf");
+        let result = run_check(CFF_call_depth, testable);
+        assert_results_contain(&result, StatusCode::Skip, Some("no-cff".to_string()));
+    }
+}
@@ -0,0 +1,104 @@
+use fontations::skrifa::GlyphId;
+use fontspector_checkapi::{prelude::*, skip, testfont, FileTypeConvert};
+
+use super::charstrings::{CffAnalysis, GlyphReport};
+
+#[check(
+    id = "opentype/CFF_deprecated_operators",
+    rationale = "
+        The 'dotsection' operator and the use of 'endchar' to build accented
+        characters from the Adobe Standard Encoding Character Set ('seac')
+        are deprecated in CFF. Adobe recommends repairing any fonts that use
+        these, especially endchar-as-seac, because a rendering issue was
+        discovered in Microsoft Word with a font that makes use of this
+        operation. The check treats that usage as a FAIL. There are no
+        known ill effects of using dotsection, so that check is a WARN.
+    ",
+    proposal = "https://github.com/fonttools/fontbakery/pull/3033",
+    title = "Does the font use deprecated CFF operators or operations?"
+)]
+fn CFF_deprecated_operators(t: &Testable, context: &Context) -> CheckFnResult {
+    let f = testfont!(t);
+    skip!(
+        !f.has_table(b"CFF "),
+        "no-cff",
+        "This check only applies to CFF fonts."
+    );
+    let analysis = CffAnalysis::from_font(&f, false, context)?;
+    let glyph_names = |wanted: fn(&GlyphReport) -> bool| {
+        analysis
+            .glyphs
+            .iter()
+            .enumerate()
+            .filter(|(_, glyph)| wanted(glyph))
+            .map(|(gid, _)| f.glyph_name_for_id_synthesise(GlyphId::new(gid as u32)))
+            .collect::<Vec<_>>()
+    };
+    let mut problems = vec![];
+    let dotsection = glyph_names(|glyph| glyph.dotsection);
+    if !dotsection.is_empty() {
+        problems.push(Status::warn(
+            "deprecated-operator-dotsection",
+            &format!(
+                "The following glyphs use the deprecated 'dotsection' operator:\n\n{}",
+                bullet_list(context, dotsection)
+            ),
+        ));
+    }
+    let seac = glyph_names(|glyph| glyph.seac);
+    if !seac.is_empty() {
+        problems.push(Status::fail(
+            "deprecated-operation-endchar-seac",
+            &format!(
+                "The following glyphs use the deprecated 'endchar' operator to build accented characters (seac):\n\n{}",
+                bullet_list(context, seac)
+            ),
+        ));
+    }
+    return_result(problems)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fontspector_checkapi::{
+        codetesting::{assert_pass, assert_results_contain, run_check, test_able},
+        StatusCode,
+    };
+
+    #[test]
+    fn test_cff_deprecated_operators_pass() {
+        let testable = test_able("source-sans-pro/OTF/SourceSansPro-Bold.otf");
+        let result = run_check(CFF_deprecated_operators, testable);
+        assert_pass(&result);
+    }
+
+    #[test]
+    fn test_cff_deprecated_operators_dotsection() {
+        let testable = test_able("deprecated_operators/cff1_dotsection.otf");
+        let result = run_check(CFF_deprecated_operators, testable);
+        assert_results_contain(
+            &result,
+            StatusCode::Warn,
+            Some("deprecated-operator-dotsection".to_string()),
+        );
+    }
+
+    #[test]
+    fn test_cff_deprecated_operators_seac() {
+        let testable = test_able("deprecated_operators/cff1_endchar_seac.otf");
+        let result = run_check(CFF_deprecated_operators, testable);
+        assert_results_contain(
+            &result,
+            StatusCode::Fail,
+            Some("deprecated-operation-endchar-seac".to_string()),
+        );
+    }
+
+    #[test]
+    fn test_cff_deprecated_operators_skip() {
+        let testable = test_able("source-sans-pro/TTF/SourceSansPro-Bold.ttf");
+        let result = run_check(CFF_deprecated_operators, testable);
+        assert_results_contain(&result, StatusCode::Skip, Some("no-cff".to_string()));
+    }
+}
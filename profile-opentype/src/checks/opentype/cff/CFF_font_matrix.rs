@@ -0,0 +1,123 @@
+use fontations::skrifa::raw::TableProvider;
+use fontspector_checkapi::{prelude::*, skip, testfont, FileTypeConvert, Metadata};
+use serde_json::json;
+
+use super::charstrings::CffAnalysis;
+
+const TOLERANCE: f64 = 1e-6;
+
+#[check(
+    id = "opentype/CFF_font_matrix",
+    rationale = "
+        The FontMatrix of a CFF font maps glyph space to text space, and so
+        must scale by 1/unitsPerEm of the 'head' table; otherwise some
+        rasterizers will draw the glyphs at the wrong size. (For CID-keyed
+        fonts, this applies to the combination of the top-level FontMatrix
+        and that of each Font DICT.) CFF2 fonts usually omit the FontMatrix,
+        but if they do include one it must agree with 'head' too.
+
+        The FontBBox of the Top DICT duplicates the font bounding box in the
+        'head' table, and the two should be consistent, as there is no
+        guarantee which one an application will use.
+    ",
+    proposal = "https://adobe-type-tools.github.io/font-tech-notes/pdfs/5176.CFF.pdf",
+    title = "Are the CFF FontMatrix and FontBBox consistent with the head table?"
+)]
+fn CFF_font_matrix(t: &Testable, context: &Context) -> CheckFnResult {
+    let f = testfont!(t);
+    skip!(
+        !f.has_table(b"CFF ") && !f.has_table(b"CFF2"),
+        "no-cff",
+        "This check only applies to CFF and CFF2 fonts."
+    );
+    let analysis = CffAnalysis::from_font(&f, f.has_table(b"CFF2"), context)?;
+    let head = f.font().head()?;
+    let scale = 1.0 / head.units_per_em() as f64;
+    let expected = [scale, 0.0, 0.0, scale, 0.0, 0.0];
+    let mut problems = vec![];
+    for matrix in analysis.font_matrices.iter() {
+        if matrix
+            .iter()
+            .zip(expected.iter())
+            .any(|(actual, expected)| (actual - expected).abs() > TOLERANCE)
+        {
+            let msg = format!(
+                "FontMatrix is {matrix:?}, but head.unitsPerEm is {} so it should be {expected:?}.",
+                head.units_per_em()
+            );
+            let mut status = Status::fail("fontmatrix-mismatch", &msg);
+            status.add_metadata(Metadata::TableProblem {
+                table_tag: if f.has_table(b"CFF2") { "CFF2" } else { "CFF " }.to_string(),
+                field_name: Some("FontMatrix".to_string()),
+                actual: Some(json!(matrix)),
+                expected: Some(json!(expected)),
+                message: msg,
+            });
+            problems.push(status);
+            break;
+        }
+    }
+    if let Some(bbox) = &analysis.font_bbox {
+        let head_bbox = [head.x_min(), head.y_min(), head.x_max(), head.y_max()]
+            .map(|value| value as f64)
+            .to_vec();
+        if *bbox != head_bbox {
+            let msg = format!(
+                "CFF FontBBox is {bbox:?}, but the head table bounding box is {head_bbox:?}."
+            );
+            let mut status = Status::warn("fontbbox-mismatch", &msg);
+            status.add_metadata(Metadata::TableProblem {
+                table_tag: "CFF ".to_string(),
+                field_name: Some("FontBBox".to_string()),
+                actual: Some(json!(bbox)),
+                expected: Some(json!(head_bbox)),
+                message: msg,
+            });
+            problems.push(status);
+        }
+    }
+    return_result(problems)
+}
+
+#[allow(clippy::unwrap_used)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fontations::write::from_obj::ToOwnedTable;
+    use fontspector_checkapi::{
+        codetesting::{assert_pass, assert_results_contain, run_check, test_able},
+        StatusCode,
+    };
+
+    #[test]
+    fn test_cff_font_matrix_pass() {
+        let testable = test_able("source-sans-pro/OTF/SourceSansPro-Regular.otf");
+        let result = run_check(CFF_font_matrix, testable);
+        assert_pass(&result);
+        let testable = test_able("source-sans-pro/VAR/SourceSansVariable-Italic.otf");
+        let result = run_check(CFF_font_matrix, testable);
+        assert_pass(&result);
+    }
+
+    #[test]
+    fn test_cff_font_matrix_mismatch() {
+        let mut testable = test_able("source-sans-pro/OTF/SourceSansPro-Regular.otf");
+        let f = TTF.from_testable(&testable).unwrap();
+        let mut head: fontations::write::tables::head::Head =
+            f.font().head().unwrap().to_owned_table();
+        head.units_per_em = 2048;
+        head.x_min -= 1;
+        testable.set(f.rebuild_with_new_table(&head).unwrap());
+        let result = run_check(CFF_font_matrix, testable);
+        assert_results_contain(
+            &result,
+            StatusCode::Fail,
+            Some("fontmatrix-mismatch".to_string()),
+        );
+        assert_results_contain(
+            &result,
+            StatusCode::Warn,
+            Some("fontbbox-mismatch".to_string()),
+        );
+    }
+}
@@ -0,0 +1,121 @@
+use fontations::skrifa::GlyphId;
+use fontspector_checkapi::{prelude::*, skip, testfont, FileTypeConvert};
+
+use super::charstrings::{CffAnalysis, HintMaskProblem};
+
+#[check(
+    id = "opentype/CFF_hintmask",
+    rationale = "
+        The hintmask and cntrmask operators select which of the stem hints
+        declared at the start of a charstring are active. The mask must have
+        one bit per declared stem, padded with zero bits to a whole number of
+        bytes; it is meaningless to use a mask before any stems have been
+        declared.
+
+        Setting padding bits, or masking a glyph which has no stems, suggests
+        that the hinting was generated by a buggy tool, and rasterizers differ
+        in how they deal with it.
+    ",
+    proposal = "https://adobe-type-tools.github.io/font-tech-notes/pdfs/5177.Type2.pdf",
+    title = "Do CFF hint masks match the declared stems?"
+)]
+fn CFF_hintmask(t: &Testable, context: &Context) -> CheckFnResult {
+    let f = testfont!(t);
+    skip!(
+        !f.has_table(b"CFF ") && !f.has_table(b"CFF2"),
+        "no-cff",
+        "This check only applies to CFF and CFF2 fonts."
+    );
+    let analysis = CffAnalysis::from_font(&f, f.has_table(b"CFF2"), context)?;
+    let glyph_names = |wanted: HintMaskProblem| {
+        analysis
+            .glyphs
+            .iter()
+            .enumerate()
+            .filter(|(_, glyph)| glyph.hintmask_problems.contains(&wanted))
+            .map(|(gid, _)| f.glyph_name_for_id_synthesise(GlyphId::new(gid as u32)))
+            .collect::<Vec<_>>()
+    };
+    let mut problems = vec![];
+    let extra_bits = glyph_names(HintMaskProblem::ExtraBits);
+    if !extra_bits.is_empty() {
+        problems.push(Status::fail(
+            "hintmask-extra-bits",
+            &format!(
+                "The following glyphs have hint masks which refer to stems that were never declared:\n\n{}",
+                bullet_list(context, extra_bits)
+            ),
+        ));
+    }
+    let no_stems = glyph_names(HintMaskProblem::NoStems);
+    if !no_stems.is_empty() {
+        problems.push(Status::warn(
+            "hintmask-without-stems",
+            &format!(
+                "The following glyphs use hint masks but declare no stems:\n\n{}",
+                bullet_list(context, no_stems)
+            ),
+        ));
+    }
+    return_result(problems)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checks::opentype::cff::charstrings::tests::add_cff_charstrings;
+    use fontspector_checkapi::{
+        codetesting::{
+            assert_messages_contain, assert_messages_dont_contain, assert_pass,
+            assert_results_contain, run_check, test_able,
+        },
+        StatusCode,
+    };
+
+    #[test]
+    fn test_cff_hintmask_pass() {
+        let testable = test_able("source-sans-pro/OTF/SourceSansPro-Regular.otf");
+        let result = run_check(CFF_hintmask, testable);
+        assert_pass(&result);
+        let testable = test_able("source-sans-pro/VAR/SourceSansVariable-Italic.otf");
+        let result = run_check(CFF_hintmask, testable);
+        assert_pass(&result);
+    }
+
+    #[test]
+    fn test_cff_hintmask_fail() {
+        let mut testable = test_able("source-sans-pro/TTF/SourceSansPro-Regular.ttf");
+        add_cff_charstrings(
+            &mut testable,
+            &[
+                // .notdef: three stems, and a mask using only those
+                &[139, 139, 139, 139, 139, 139, 1, 19, 0xe0, 14],
+                // space: a hintmask with no stems
+                &[19, 14],
+                // A: three stems, but a mask for four
+                &[139, 139, 139, 139, 139, 139, 1, 19, 0xf0, 14],
+            ],
+        );
+        let result = run_check(CFF_hintmask, testable);
+        assert_results_contain(
+            &result,
+            StatusCode::Fail,
+            Some("hintmask-extra-bits".to_string()),
+        );
+        assert_results_contain(
+            &result,
+            StatusCode::Warn,
+            Some("hintmask-without-stems".to_string()),
+        );
+        assert_messages_contain(&result, "* A");
+        assert_messages_contain(&result, "* space");
+        assert_messages_dont_contain(&result, ".notdef");
+    }
+
+    #[test]
+    fn test_cff_hintmask_skip() {
+        let testable = test_able("source-sans-pro/TTF/SourceSansPro-Regular.ttf");
+        let result = run_check(CFF_hintmask, testable);
+        assert_results_contain(&result, StatusCode::Skip, Some("no-cff".to_string()));
+    }
+}
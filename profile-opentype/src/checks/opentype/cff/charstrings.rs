@@ -0,0 +1,939 @@
+//! A minimal reader for the CFF and CFF2 tables.
+//!
+//! fontations will happily draw CFF glyphs for us, but it hides the
+//! charstring operators along the way, so we walk the charstrings
+//! ourselves and record the things our checks care about: how deeply
+//! subroutines nest, which deprecated operators are used, and whether
+//! hint masks agree with the stems that have been declared.
+//!
+//! read-fonts' `postscript` module does have a charstring evaluator, but
+//! it only passes the outline and the hints on to its sink. Operators
+//! which draw nothing, like `dotsection`, and the way an `endchar` was
+//! used never reach us, and it refuses to nest subroutines beyond the
+//! limit instead of telling us how deep a glyph went - which is exactly
+//! what the call depth checks need to report.
+use std::{collections::HashMap, sync::Arc};
+
+use fontations::skrifa::Tag;
+use fontspector_checkapi::{Context, FontspectorError, TestFont};
+
+/// Type 2 and CFF2 charstrings may nest subroutine calls at most this deep
+pub(crate) const MAX_CALL_DEPTH: usize = 10;
+
+// DICT operators we need; two-byte operators are stored as 12 << 8 | b1
+const FONT_BBOX: u16 = 5;
+const CHARSTRINGS: u16 = 17;
+const PRIVATE: u16 = 18;
+const SUBRS: u16 = 19;
+const VSINDEX: u16 = 22;
+const VSTORE: u16 = 24;
+const CHARSTRING_TYPE: u16 = (12 << 8) | 6;
+const FONT_MATRIX: u16 = (12 << 8) | 7;
+const FD_ARRAY: u16 = (12 << 8) | 36;
+const FD_SELECT: u16 = (12 << 8) | 37;
+
+const DEFAULT_FONT_MATRIX: [f64; 6] = [0.001, 0.0, 0.0, 0.001, 0.0, 0.0];
+
+/// Problems with the hint masks of a glyph
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum HintMaskProblem {
+    /// A hintmask or cntrmask operator was used before any stems were declared
+    NoStems,
+    /// A mask set bits beyond the number of stems declared
+    ExtraBits,
+}
+
+/// What we learnt from interpreting a single glyph's charstring
+#[derive(Debug, Default, Clone)]
+pub(crate) struct GlyphReport {
+    /// The deepest level of subroutine nesting reached
+    pub max_depth: usize,
+    /// The glyph uses the deprecated `dotsection` operator
+    pub dotsection: bool,
+    /// The glyph uses `endchar` to build an accented glyph, `seac`-style
+    pub seac: bool,
+    /// Problems found with the glyph's hint masks
+    pub hintmask_problems: Vec<HintMaskProblem>,
+    /// The charstring could not be interpreted
+    pub error: Option<String>,
+}
+
+/// The results of walking every charstring in a CFF or CFF2 table
+pub(crate) struct CffAnalysis {
+    /// One report per glyph, indexed by glyph ID
+    pub glyphs: Vec<GlyphReport>,
+    /// The effective FontMatrix of each Font DICT; empty for CFF2 fonts
+    /// which do not declare one
+    pub font_matrices: Vec<[f64; 6]>,
+    /// The FontBBox of the Top DICT, if any (CFF2 has none)
+    pub font_bbox: Option<Vec<f64>>,
+}
+
+impl CffAnalysis {
+    /// Analyse the CFF (or, if `cff2` is true, the CFF2) table of a font
+    ///
+    /// The analysis is shared between all the checks which need it. Stops
+    /// with a timeout error if the check runs out of time.
+    pub(crate) fn from_font(
+        f: &TestFont,
+        cff2: bool,
+        context: &Context,
+    ) -> Result<Arc<Self>, FontspectorError> {
+        let table = if cff2 { "CFF2" } else { "CFF" };
+        let what = if cff2 {
+            "cff2_analysis"
+        } else {
+            "cff_analysis"
+        };
+        f.memoize(Some(context), what, || {
+            let tag = if cff2 {
+                Tag::new(b"CFF2")
+            } else {
+                Tag::new(b"CFF ")
+            };
+            let data = f
+                .font()
+                .table_data(tag)
+                .ok_or_else(|| FontspectorError::General(format!("No {table} table")))?;
+            let analysis = analyse(data.as_bytes(), cff2, context).map_err(|e| {
+                FontspectorError::General(format!("Error reading {table} table: {e}"))
+            })?;
+            // The analysis stops early if it runs out of time, and a partial
+            // analysis mustn't be remembered
+            context.check_deadline()?;
+            Ok(analysis)
+        })
+    }
+}
+
+/// `offset + size`, as an error rather than an overflow if the table is corrupt
+fn add_offset(offset: usize, size: usize) -> Result<usize, String> {
+    offset
+        .checked_add(size)
+        .ok_or_else(|| format!("offset {offset} + {size} is out of range"))
+}
+
+fn read_uint(data: &[u8], offset: usize, size: usize) -> Result<usize, String> {
+    let bytes = data
+        .get(offset..add_offset(offset, size)?)
+        .ok_or_else(|| format!("unexpected end of data at offset {offset}"))?;
+    Ok(bytes
+        .iter()
+        .fold(0usize, |acc, byte| (acc << 8) | *byte as usize))
+}
+
+/// A CFF INDEX structure
+struct Index<'a> {
+    items: Vec<&'a [u8]>,
+    /// The offset of the first byte after the INDEX
+    end: usize,
+}
+
+impl<'a> Index<'a> {
+    fn read(data: &'a [u8], offset: usize, cff2: bool) -> Result<Self, String> {
+        let count_size = if cff2 { 4 } else { 2 };
+        let count = read_uint(data, offset, count_size)?;
+        let mut pos = add_offset(offset, count_size)?;
+        if count == 0 {
+            return Ok(Index {
+                items: vec![],
+                end: pos,
+            });
+        }
+        let off_size = read_uint(data, pos, 1)?;
+        if !(1..=4).contains(&off_size) {
+            return Err(format!("bad INDEX offSize {off_size} at offset {offset}"));
+        }
+        pos += 1;
+        let offsets_size = count
+            .checked_add(1)
+            .and_then(|count| count.checked_mul(off_size))
+            .ok_or_else(|| format!("INDEX at offset {offset} is too large"))?;
+        let offsets_end = add_offset(pos, offsets_size)?;
+        if offsets_end > data.len() {
+            return Err(format!(
+                "INDEX at offset {offset} runs past the end of the table"
+            ));
+        }
+        let offsets = (0..=count)
+            .map(|i| read_uint(data, pos + i * off_size, off_size))
+            .collect::<Result<Vec<_>, _>>()?;
+        // Offsets are relative to the byte before the object data
+        let base = offsets_end - 1;
+        let items = offsets
+            .iter()
+            .zip(offsets.iter().skip(1))
+            .map(|(start, end)| {
+                data.get(add_offset(base, *start)?..add_offset(base, *end)?)
+                    .ok_or_else(|| format!("bad object offsets in INDEX at offset {offset}"))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Index {
+            items,
+            end: add_offset(base, offsets.last().copied().unwrap_or(1))?,
+        })
+    }
+
+    fn bias(&self) -> i64 {
+        match self.items.len() {
+            0..1240 => 107,
+            1240..33900 => 1131,
+            _ => 32768,
+        }
+    }
+}
+
+fn parse_real(data: &[u8], start: usize) -> Result<(f64, usize), String> {
+    let mut repr = String::new();
+    let mut pos = start;
+    loop {
+        let byte = read_uint(data, pos, 1)? as u8;
+        pos += 1;
+        for nibble in [byte >> 4, byte & 0xf] {
+            match nibble {
+                0..=9 => repr.push((b'0' + nibble) as char),
+                0xa => repr.push('.'),
+                0xb => repr.push('E'),
+                0xc => repr.push_str("E-"),
+                0xe => repr.push('-'),
+                0xf => {
+                    let value = repr
+                        .parse()
+                        .map_err(|_| format!("bad real number '{repr}' in DICT"))?;
+                    return Ok((value, pos));
+                }
+                _ => return Err("reserved nibble in real number".to_string()),
+            }
+        }
+    }
+}
+
+/// Parse a DICT into a map of operator to operands
+fn parse_dict(data: &[u8]) -> Result<HashMap<u16, Vec<f64>>, String> {
+    let mut dict = HashMap::new();
+    let mut operands = vec![];
+    let mut pos = 0;
+    while let Some(&b0) = data.get(pos) {
+        match b0 {
+            12 => {
+                let b1 = read_uint(data, pos + 1, 1)? as u16;
+                dict.insert((12 << 8) | b1, std::mem::take(&mut operands));
+                pos += 2;
+            }
+            0..=27 => {
+                dict.insert(b0 as u16, std::mem::take(&mut operands));
+                pos += 1;
+            }
+            28 => {
+                operands.push(read_uint(data, pos + 1, 2)? as i16 as f64);
+                pos += 3;
+            }
+            29 => {
+                operands.push(read_uint(data, pos + 1, 4)? as i32 as f64);
+                pos += 5;
+            }
+            30 => {
+                let (value, next) = parse_real(data, pos + 1)?;
+                operands.push(value);
+                pos = next;
+            }
+            32..=246 => {
+                operands.push(b0 as f64 - 139.0);
+                pos += 1;
+            }
+            247..=250 => {
+                let b1 = read_uint(data, pos + 1, 1)? as f64;
+                operands.push((b0 as f64 - 247.0) * 256.0 + b1 + 108.0);
+                pos += 2;
+            }
+            251..=254 => {
+                let b1 = read_uint(data, pos + 1, 1)? as f64;
+                operands.push(-(b0 as f64 - 251.0) * 256.0 - b1 - 108.0);
+                pos += 2;
+            }
+            _ => return Err(format!("reserved byte {b0} in DICT")),
+        }
+    }
+    Ok(dict)
+}
+
+/// Convert a DICT operand to an offset, size or index
+///
+/// A plain `as usize` would quietly turn negative, fractional or huge
+/// values into something that looks valid.
+fn to_usize(value: f64) -> Result<usize, String> {
+    if value.fract() == 0.0 && (0.0..=u32::MAX as f64).contains(&value) {
+        Ok(value as usize)
+    } else {
+        Err(format!("bad offset or count {value} in DICT"))
+    }
+}
+
+fn dict_offset(dict: &HashMap<u16, Vec<f64>>, op: u16) -> Result<Option<usize>, String> {
+    dict.get(&op)
+        .and_then(|operands| operands.last())
+        .map(|value| to_usize(*value))
+        .transpose()
+}
+
+/// Read a Font DICT's Private DICT, returning its local subroutines and vsindex
+fn read_private<'a>(
+    data: &'a [u8],
+    dict: &HashMap<u16, Vec<f64>>,
+    cff2: bool,
+) -> Result<(Option<Index<'a>>, usize), String> {
+    let Some([size, offset]) = dict.get(&PRIVATE).map(|v| v.as_slice()) else {
+        return Ok((None, 0));
+    };
+    let (size, offset) = (to_usize(*size)?, to_usize(*offset)?);
+    let private = parse_dict(
+        data.get(offset..add_offset(offset, size)?)
+            .ok_or("Private DICT runs past the end of the table")?,
+    )?;
+    let subrs = dict_offset(&private, SUBRS)?
+        .map(|subrs| Index::read(data, add_offset(offset, subrs)?, cff2))
+        .transpose()?;
+    let vsindex = dict_offset(&private, VSINDEX)?.unwrap_or(0);
+    Ok((subrs, vsindex))
+}
+
+/// Map each glyph to its Font DICT
+fn read_fd_select(data: &[u8], offset: usize, num_glyphs: usize) -> Result<Vec<usize>, String> {
+    let mut fds = vec![0; num_glyphs];
+    match read_uint(data, offset, 1)? {
+        0 => {
+            for (gid, fd) in fds.iter_mut().enumerate() {
+                *fd = read_uint(data, add_offset(offset, 1 + gid)?, 1)?;
+            }
+        }
+        format @ (3 | 4) => {
+            let (count_size, gid_size, fd_size) = if format == 3 { (2, 2, 1) } else { (4, 4, 2) };
+            let num_ranges = read_uint(data, add_offset(offset, 1)?, count_size)?;
+            let ranges = add_offset(offset, 1 + count_size)?;
+            let range_size = gid_size + fd_size;
+            for range in 0..num_ranges {
+                let start = range
+                    .checked_mul(range_size)
+                    .ok_or_else(|| "FDSelect is too large".to_string())
+                    .and_then(|range_offset| add_offset(ranges, range_offset))?;
+                let first = read_uint(data, start, gid_size)?;
+                let fd = read_uint(data, start + gid_size, fd_size)?;
+                // The first glyph of the next range, or the sentinel
+                let next = read_uint(data, start + range_size, gid_size)?;
+                for slot in fds.iter_mut().take(next).skip(first) {
+                    *slot = fd;
+                }
+            }
+        }
+        format => return Err(format!("unknown FDSelect format {format}")),
+    }
+    Ok(fds)
+}
+
+/// The number of regions in each ItemVariationData of the CFF2 variation store
+fn read_region_counts(data: &[u8], offset: usize) -> Result<Vec<usize>, String> {
+    // The store is preceded by a uint16 length
+    let store = add_offset(offset, 2)?;
+    let count = read_uint(data, add_offset(store, 6)?, 2)?;
+    (0..count)
+        .map(|i| {
+            let item_data = add_offset(store, read_uint(data, add_offset(store, 8 + i * 4)?, 4)?)?;
+            read_uint(data, add_offset(item_data, 4)?, 2)
+        })
+        .collect()
+}
+
+fn multiply(m1: [f64; 6], m2: [f64; 6]) -> [f64; 6] {
+    let [a1, b1, c1, d1, e1, f1] = m1;
+    let [a2, b2, c2, d2, e2, f2] = m2;
+    [
+        a1 * a2 + b1 * c2,
+        a1 * b2 + b1 * d2,
+        c1 * a2 + d1 * c2,
+        c1 * b2 + d1 * d2,
+        e1 * a2 + f1 * c2 + e2,
+        e1 * b2 + f1 * d2 + f2,
+    ]
+}
+
+fn font_matrix(dict: &HashMap<u16, Vec<f64>>) -> Option<[f64; 6]> {
+    dict.get(&FONT_MATRIX)
+        .and_then(|v| <[f64; 6]>::try_from(v.as_slice()).ok())
+}
+
+fn analyse(data: &[u8], cff2: bool, context: &Context) -> Result<CffAnalysis, String> {
+    let header_size = read_uint(data, 2, 1)?;
+    let (top_dict, global_subrs) = if cff2 {
+        let top_dict_length = read_uint(data, 3, 2)?;
+        let top_dict = data
+            .get(header_size..add_offset(header_size, top_dict_length)?)
+            .ok_or("Top DICT runs past the end of the table")?;
+        (
+            parse_dict(top_dict)?,
+            Index::read(data, add_offset(header_size, top_dict_length)?, true)?,
+        )
+    } else {
+        let names = Index::read(data, header_size, false)?;
+        let top_dicts = Index::read(data, names.end, false)?;
+        let strings = Index::read(data, top_dicts.end, false)?;
+        let top_dict = top_dicts.items.first().ok_or("no Top DICT")?;
+        (
+            parse_dict(top_dict)?,
+            Index::read(data, strings.end, false)?,
+        )
+    };
+    if let Some(cstype) = dict_offset(&top_dict, CHARSTRING_TYPE)? {
+        if cstype != 2 {
+            return Err(format!("unsupported charstring type {cstype}"));
+        }
+    }
+    let charstrings = Index::read(
+        data,
+        dict_offset(&top_dict, CHARSTRINGS)?.ok_or("no CharStrings in Top DICT")?,
+        cff2,
+    )?;
+    let fd_array = dict_offset(&top_dict, FD_ARRAY)?;
+    let is_cid = fd_array.is_some();
+    let font_dicts = if let Some(fd_array) = fd_array {
+        Index::read(data, fd_array, cff2)?
+            .items
+            .iter()
+            .map(|item| parse_dict(item))
+            .collect::<Result<Vec<_>, _>>()?
+    } else {
+        vec![top_dict.clone()]
+    };
+    let fd_select = match dict_offset(&top_dict, FD_SELECT)? {
+        Some(offset) => read_fd_select(data, offset, charstrings.items.len())?,
+        None => vec![0; charstrings.items.len()],
+    };
+    let privates = font_dicts
+        .iter()
+        .map(|dict| read_private(data, dict, cff2))
+        .collect::<Result<Vec<_>, _>>()?;
+    let region_counts = match dict_offset(&top_dict, VSTORE)? {
+        Some(offset) if cff2 => read_region_counts(data, offset)?,
+        _ => vec![],
+    };
+
+    // CFF2 fonts generally leave the scaling to head.unitsPerEm, so we
+    // only have something to say about their FontMatrix if they have one.
+    let mut font_matrices = vec![];
+    if !cff2 || font_matrix(&top_dict).is_some() {
+        let top_matrix = font_matrix(&top_dict).unwrap_or(DEFAULT_FONT_MATRIX);
+        if is_cid && !cff2 {
+            // CID-keyed fonts concatenate each Font DICT's matrix with the top one
+            font_matrices.extend(font_dicts.iter().map(|dict| {
+                font_matrix(dict).map_or(top_matrix, |matrix| multiply(matrix, top_matrix))
+            }));
+        } else {
+            font_matrices.push(top_matrix);
+        }
+    }
+
+    let mut glyphs = vec![];
+    for (gid, charstring) in charstrings.items.iter().enumerate() {
+        if context.check_deadline().is_err() {
+            break;
+        }
+        let fd = fd_select.get(gid).copied().unwrap_or(0);
+        let Some((local_subrs, vsindex)) = privates.get(fd) else {
+            glyphs.push(GlyphReport {
+                error: Some(format!("FDSelect refers to missing Font DICT {fd}")),
+                ..Default::default()
+            });
+            continue;
+        };
+        glyphs.push(
+            Interpreter::new(
+                cff2,
+                &global_subrs,
+                local_subrs.as_ref(),
+                &region_counts,
+                *vsindex,
+                context,
+            )
+            .run_glyph(charstring),
+        );
+    }
+    Ok(CffAnalysis {
+        glyphs,
+        font_matrices,
+        font_bbox: if cff2 {
+            None
+        } else {
+            Some(
+                top_dict
+                    .get(&FONT_BBOX)
+                    .cloned()
+                    .unwrap_or(vec![0.0, 0.0, 0.0, 0.0]),
+            )
+        },
+    })
+}
+
+enum Flow {
+    Continue,
+    Return,
+    End,
+}
+
+/// Enough of a Type 2 / CFF2 charstring interpreter to follow the hinting
+/// and subroutine structure; the outline itself is ignored.
+struct Interpreter<'a> {
+    cff2: bool,
+    global_subrs: &'a Index<'a>,
+    local_subrs: Option<&'a Index<'a>>,
+    region_counts: &'a [usize],
+    vsindex: usize,
+    context: &'a Context,
+    stack: Vec<f64>,
+    stems: usize,
+    report: GlyphReport,
+}
+
+impl<'a> Interpreter<'a> {
+    fn new(
+        cff2: bool,
+        global_subrs: &'a Index<'a>,
+        local_subrs: Option<&'a Index<'a>>,
+        region_counts: &'a [usize],
+        vsindex: usize,
+        context: &'a Context,
+    ) -> Self {
+        Self {
+            cff2,
+            global_subrs,
+            local_subrs,
+            region_counts,
+            vsindex,
+            context,
+            stack: vec![],
+            stems: 0,
+            report: GlyphReport::default(),
+        }
+    }
+
+    fn run_glyph(mut self, charstring: &[u8]) -> GlyphReport {
+        match self.run(charstring, 0) {
+            Ok(Flow::Continue | Flow::Return) if !self.cff2 => {
+                self.report.error = Some("charstring does not finish with endchar".to_string());
+            }
+            Err(e) => self.report.error = Some(e),
+            _ => {}
+        }
+        self.report
+    }
+
+    fn pop(&mut self) -> Result<f64, String> {
+        self.stack
+            .pop()
+            .ok_or_else(|| "argument stack underflow".to_string())
+    }
+
+    fn add_hintmask_problem(&mut self, problem: HintMaskProblem) {
+        if !self.report.hintmask_problems.contains(&problem) {
+            self.report.hintmask_problems.push(problem);
+        }
+    }
+
+    fn run(&mut self, code: &[u8], depth: usize) -> Result<Flow, String> {
+        self.report.max_depth = self.report.max_depth.max(depth);
+        let mut pos = 0;
+        while let Some(&b0) = code.get(pos) {
+            pos += 1;
+            match b0 {
+                // hstem, vstem, hstemhm, vstemhm; an odd argument is the width
+                1 | 3 | 18 | 23 => {
+                    self.stems += self.stack.len() / 2;
+                    self.stack.clear();
+                }
+                // hintmask, cntrmask; any arguments are an implicit vstem
+                19 | 20 => {
+                    self.stems += self.stack.len() / 2;
+                    self.stack.clear();
+                    if self.stems == 0 {
+                        self.add_hintmask_problem(HintMaskProblem::NoStems);
+                    }
+                    let mask_length = self.stems.div_ceil(8);
+                    let mask = code
+                        .get(pos..add_offset(pos, mask_length)?)
+                        .ok_or("hint mask runs past the end of the charstring")?;
+                    pos += mask_length;
+                    let unused_bits = mask_length * 8 - self.stems;
+                    if mask
+                        .last()
+                        .is_some_and(|last| last & ((1u16 << unused_bits) - 1) as u8 != 0)
+                    {
+                        self.add_hintmask_problem(HintMaskProblem::ExtraBits);
+                    }
+                }
+                // callsubr, callgsubr
+                10 | 29 => {
+                    let subrs = if b0 == 10 {
+                        self.local_subrs
+                            .ok_or("callsubr used but there are no local subroutines")?
+                    } else {
+                        self.global_subrs
+                    };
+                    let index = (self.pop()? as i64).saturating_add(subrs.bias());
+                    let subr = usize::try_from(index)
+                        .ok()
+                        .and_then(|index| subrs.items.get(index))
+                        .ok_or_else(|| format!("call to nonexistent subroutine {index}"))?;
+                    if depth + 1 > MAX_CALL_DEPTH {
+                        // Record the violation and stop here, in case of recursion
+                        self.report.max_depth = depth + 1;
+                        return Ok(Flow::End);
+                    }
+                    // Nested calls can multiply the work without bound; the
+                    // caller notices the deadline and reports the timeout
+                    if self.context.check_deadline().is_err() {
+                        return Ok(Flow::End);
+                    }
+                    if let Flow::End = self.run(subr, depth + 1)? {
+                        return Ok(Flow::End);
+                    }
+                }
+                // return
+                11 if !self.cff2 => return Ok(Flow::Return),
+                // endchar; four extra arguments make it seac
+                14 if !self.cff2 => {
+                    if self.stack.len() >= 4 {
+                        self.report.seac = true;
+                    }
+                    return Ok(Flow::End);
+                }
+                // vsindex
+                15 if self.cff2 => self.vsindex = self.pop()? as usize,
+                // blend
+                16 if self.cff2 => {
+                    let count = self.pop()? as usize;
+                    let regions = *self
+                        .region_counts
+                        .get(self.vsindex)
+                        .ok_or_else(|| format!("blend uses missing vsindex {}", self.vsindex))?;
+                    let operands = count
+                        .checked_mul(regions + 1)
+                        .ok_or("blend operand count overflows")?;
+                    if self.stack.len() < operands {
+                        return Err("argument stack underflow in blend".to_string());
+                    }
+                    self.stack.truncate(self.stack.len() - count * regions);
+                }
+                // Path construction operators
+                4..=8 | 21 | 22 | 24..=27 | 30 | 31 => self.stack.clear(),
+                12 => {
+                    let b1 = *code.get(pos).ok_or("truncated escape operator")?;
+                    pos += 1;
+                    self.escape(b1)?;
+                }
+                28 => {
+                    let value = read_uint(code, pos, 2)? as i16;
+                    self.stack.push(value as f64);
+                    pos += 2;
+                }
+                32..=246 => self.stack.push(b0 as f64 - 139.0),
+                247..=250 => {
+                    let b1 = read_uint(code, pos, 1)? as f64;
+                    self.stack.push((b0 as f64 - 247.0) * 256.0 + b1 + 108.0);
+                    pos += 1;
+                }
+                251..=254 => {
+                    let b1 = read_uint(code, pos, 1)? as f64;
+                    self.stack.push(-(b0 as f64 - 251.0) * 256.0 - b1 - 108.0);
+                    pos += 1;
+                }
+                255 => {
+                    let value = read_uint(code, pos, 4)? as i32;
+                    self.stack.push(value as f64 / 65536.0);
+                    pos += 4;
+                }
+                _ => return Err(format!("reserved or unsupported operator {b0}")),
+            }
+            if self.stack.len() > if self.cff2 { 513 } else { 48 } {
+                return Err("argument stack overflow".to_string());
+            }
+        }
+        Ok(Flow::Continue)
+    }
+
+    fn escape(&mut self, b1: u8) -> Result<(), String> {
+        match b1 {
+            // dotsection
+            0 => {
+                if self.cff2 {
+                    return Err("dotsection is not allowed in CFF2".to_string());
+                }
+                self.report.dotsection = true;
+                self.stack.clear();
+            }
+            // flex operators
+            34..=37 => self.stack.clear(),
+            // CFF2 dropped the arithmetic and storage operators
+            _ if self.cff2 => return Err(format!("unsupported operator 12 {b1}")),
+            // abs, not, neg, sqrt
+            5 | 9 | 14 | 26 => {
+                let a = self.pop()?;
+                self.stack.push(match b1 {
+                    5 => (a == 0.0) as u8 as f64,
+                    9 => a.abs(),
+                    14 => -a,
+                    _ => a.abs().sqrt(),
+                });
+            }
+            // and, or, add, sub, div, eq, mul
+            3 | 4 | 10 | 11 | 12 | 15 | 24 => {
+                let b = self.pop()?;
+                let a = self.pop()?;
+                self.stack.push(match b1 {
+                    3 => (a != 0.0 && b != 0.0) as u8 as f64,
+                    4 => (a != 0.0 || b != 0.0) as u8 as f64,
+                    10 => a + b,
+                    11 => a - b,
+                    12 if b != 0.0 => a / b,
+                    12 => 0.0,
+                    15 => (a == b) as u8 as f64,
+                    _ => a * b,
+                });
+            }
+            // drop
+            18 => {
+                self.pop()?;
+            }
+            // put
+            20 => {
+                self.pop()?;
+                self.pop()?;
+            }
+            // get
+            21 => {
+                self.pop()?;
+                self.stack.push(0.0);
+            }
+            // ifelse
+            22 => {
+                let v2 = self.pop()?;
+                let v1 = self.pop()?;
+                let s2 = self.pop()?;
+                let s1 = self.pop()?;
+                self.stack.push(if v1 <= v2 { s1 } else { s2 });
+            }
+            // random
+            23 => self.stack.push(0.5),
+            // dup
+            27 => {
+                let a = self.pop()?;
+                self.stack.extend([a, a]);
+            }
+            // exch
+            28 => {
+                let b = self.pop()?;
+                let a = self.pop()?;
+                self.stack.extend([b, a]);
+            }
+            // index
+            29 => {
+                let i = self.pop()?.max(0.0) as usize;
+                let value = self
+                    .stack
+                    .iter()
+                    .rev()
+                    .nth(i)
+                    .copied()
+                    .ok_or("index operator out of range")?;
+                self.stack.push(value);
+            }
+            // roll
+            30 => {
+                let j = self.pop()? as i64;
+                let n = self.pop()?.max(0.0) as usize;
+                let start = self
+                    .stack
+                    .len()
+                    .checked_sub(n)
+                    .ok_or("roll operator out of range")?;
+                if let Some(items) = self.stack.get_mut(start..) {
+                    if n > 0 {
+                        items.rotate_right(j.rem_euclid(n as i64) as usize);
+                    }
+                }
+            }
+            _ => return Err(format!("reserved operator 12 {b1}")),
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+    use fontations::{skrifa::FontRef, write::FontBuilder};
+    use fontspector_checkapi::{codetesting::test_able, FileTypeConvert, Testable, TTF};
+    use std::time::{Duration, Instant};
+
+    /// A CFF table with the given charstrings and no subroutines
+    fn cff_table(charstrings: &[&[u8]]) -> Vec<u8> {
+        // Header, and a Name INDEX with a single name
+        let mut cff = vec![1, 0, 4, 1, 0, 1, 1, 1, 2, b'A'];
+        // A Top DICT INDEX whose DICT points to the CharStrings INDEX, just
+        // after the (empty) String and Global Subr INDEXes
+        cff.extend([0, 1, 1, 1, 7, 29]);
+        cff.extend(25u32.to_be_bytes());
+        cff.push(17);
+        cff.extend([0, 0, 0, 0]);
+        cff.extend((charstrings.len() as u16).to_be_bytes());
+        cff.push(2);
+        let mut offset = 1u16;
+        cff.extend(offset.to_be_bytes());
+        for charstring in charstrings {
+            offset += charstring.len() as u16;
+            cff.extend(offset.to_be_bytes());
+        }
+        for charstring in charstrings {
+            cff.extend(*charstring);
+        }
+        cff
+    }
+
+    /// Add a CFF table holding the given charstrings to a font
+    pub(crate) fn add_cff_charstrings(testable: &mut Testable, charstrings: &[&[u8]]) {
+        let font = FontRef::new(&testable.contents).unwrap();
+        let mut builder = FontBuilder::new();
+        builder.add_raw(Tag::new(b"CFF "), cff_table(charstrings));
+        builder.copy_missing_tables(font);
+        let contents = builder.build();
+        testable.set(contents);
+    }
+
+    fn run(charstring: &[u8], subrs: &[&[u8]]) -> GlyphReport {
+        let global_subrs = Index {
+            items: vec![],
+            end: 0,
+        };
+        let local_subrs = Index {
+            items: subrs.to_vec(),
+            end: 0,
+        };
+        let context = Context::default();
+        Interpreter::new(false, &global_subrs, Some(&local_subrs), &[], 0, &context)
+            .run_glyph(charstring)
+    }
+
+    // Local subroutine 0 is called with operand -107, encoded as 139 - 107
+    const CALL_SUBR_0: [u8; 2] = [32, 10];
+
+    #[test]
+    fn test_call_depth() {
+        let report = run(&[CALL_SUBR_0[0], CALL_SUBR_0[1], 14], &[&[11]]);
+        assert_eq!(report.max_depth, 1);
+        assert!(report.error.is_none());
+
+        // A subroutine which calls itself
+        let report = run(&[CALL_SUBR_0[0], CALL_SUBR_0[1], 14], &[&CALL_SUBR_0]);
+        assert!(report.max_depth > MAX_CALL_DEPTH);
+    }
+
+    #[test]
+    fn test_deprecated_operators() {
+        // 0 0 hstem dotsection 0 0 0 0 endchar
+        let report = run(&[139, 139, 1, 12, 0, 139, 139, 139, 139, 14], &[]);
+        assert!(report.dotsection);
+        assert!(report.seac);
+        // Width-only endchar is fine
+        let report = run(&[139, 14], &[]);
+        assert!(!report.seac);
+    }
+
+    #[test]
+    fn test_hintmask() {
+        // Three stems, mask uses only the top three bits
+        let report = run(&[139, 139, 139, 139, 139, 139, 1, 19, 0xe0, 14], &[]);
+        assert!(report.hintmask_problems.is_empty());
+        // The fourth bit refers to a stem which does not exist
+        let report = run(&[139, 139, 139, 139, 139, 139, 1, 19, 0xf0, 14], &[]);
+        assert_eq!(report.hintmask_problems, vec![HintMaskProblem::ExtraBits]);
+        // No stems at all
+        let report = run(&[19, 14], &[]);
+        assert_eq!(report.hintmask_problems, vec![HintMaskProblem::NoStems]);
+    }
+
+    #[test]
+    fn test_errors() {
+        let report = run(&[139, 139], &[]);
+        assert!(report.error.is_some());
+        let report = run(&[10, 14], &[]);
+        assert_eq!(report.error, Some("argument stack underflow".to_string()));
+    }
+
+    #[test]
+    fn test_dict_offsets() {
+        assert_eq!(to_usize(1234.0), Ok(1234));
+        assert!(to_usize(-1.0).is_err());
+        assert!(to_usize(1.5).is_err());
+        assert!(to_usize(1e201).is_err());
+        assert!(to_usize(f64::NAN).is_err());
+
+        // A Private DICT at an offset near the top of the address space
+        let dict = HashMap::from([(PRIVATE, vec![66.0, u32::MAX as f64])]);
+        assert!(read_private(&[], &dict, false).is_err());
+        let dict = HashMap::from([(PRIVATE, vec![66.0, 1e201])]);
+        assert!(read_private(&[], &dict, false).is_err());
+
+        assert!(read_uint(&[1, 2, 3], usize::MAX, 2).is_err());
+    }
+
+    #[test]
+    fn test_blend_overflow() {
+        let global_subrs = Index {
+            items: vec![],
+            end: 0,
+        };
+        let context = Context::default();
+        // 3 blend, with an absurd number of regions
+        let report = Interpreter::new(true, &global_subrs, None, &[usize::MAX / 2], 0, &context)
+            .run_glyph(&[142, 16]);
+        assert_eq!(
+            report.error,
+            Some("blend operand count overflows".to_string())
+        );
+    }
+
+    #[test]
+    fn test_synthetic_cff() {
+        let mut testable = test_able("source-sans-pro/TTF/SourceSansPro-Regular.ttf");
+        add_cff_charstrings(&mut testable, &[&[14], &[139, 139, 1, 14]]);
+        let f = TTF.from_testable(&testable).unwrap();
+        let analysis = CffAnalysis::from_font(&f, false, &Context::default()).unwrap();
+        assert_eq!(analysis.glyphs.len(), 2);
+        assert!(analysis.glyphs.iter().all(|glyph| glyph.error.is_none()));
+    }
+
+    #[test]
+    fn test_analysis_is_shared() {
+        let testable = test_able("source-sans-pro/OTF/SourceSansPro-Regular.otf");
+        let f = TTF.from_testable(&testable).unwrap();
+        let context = Context::default();
+        let first = CffAnalysis::from_font(&f, false, &context).unwrap();
+        let second = CffAnalysis::from_font(&f, false, &context).unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+    }
+
+    #[test]
+    fn test_deadline() {
+        let testable = test_able("source-sans-pro/OTF/SourceSansPro-Regular.otf");
+        let f = TTF.from_testable(&testable).unwrap();
+        let context = Context {
+            check_timeout: Some(Duration::ZERO),
+            deadline: Some(Instant::now()),
+            ..Default::default()
+        };
+        std::thread::sleep(Duration::from_millis(1));
+        assert!(matches!(
+            CffAnalysis::from_font(&f, false, &context),
+            Err(FontspectorError::Timeout(_))
+        ));
+        assert!(CffAnalysis::from_font(&f, false, &Context::default()).is_ok());
+    }
+}
@@ -0,0 +1,12 @@
+mod CFF2_call_depth;
+mod CFF_call_depth;
+mod CFF_deprecated_operators;
+mod CFF_font_matrix;
+mod CFF_hintmask;
+mod charstrings;
+
+pub use CFF2_call_depth::CFF2_call_depth;
+pub use CFF_call_depth::CFF_call_depth;
+pub use CFF_deprecated_operators::CFF_deprecated_operators;
+pub use CFF_font_matrix::CFF_font_matrix;
+pub use CFF_hintmask::CFF_hintmask;
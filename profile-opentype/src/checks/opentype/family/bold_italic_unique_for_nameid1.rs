@@ -76,6 +76,7 @@ mod tests {
         let collection = TestableCollection {
             testables,
             directory: "".to_string(),
+            label: None,
         };
         let result = run_check_with_config(
             bold_italic_unique_for_nameid1,
@@ -108,6 +109,7 @@ mod tests {
         let collection = TestableCollection {
             testables,
             directory: "".to_string(),
+            label: None,
         };
         let result = run_check_with_config(
             bold_italic_unique_for_nameid1,
@@ -137,6 +139,7 @@ mod tests {
         let collection = TestableCollection {
             testables,
             directory: "".to_string(),
+            label: None,
         };
         let result = run_check_with_config(
             bold_italic_unique_for_nameid1,
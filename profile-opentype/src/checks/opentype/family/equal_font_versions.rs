@@ -59,6 +59,7 @@ mod tests {
         let collection = TestableCollection {
             testables,
             directory: "".to_string(),
+            label: None,
         };
         let result = run_check_with_config(
             equal_font_versions,
@@ -94,6 +95,7 @@ mod tests {
         let collection = TestableCollection {
             testables,
             directory: "".to_string(),
+            label: None,
         };
         let result = run_check_with_config(
             equal_font_versions,
@@ -90,6 +90,7 @@ mod tests {
         let collection = TestableCollection {
             testables,
             directory: "".to_string(),
+            label: None,
         };
         let result = run_check_with_config(
             panose_familytype,
@@ -125,6 +126,7 @@ mod tests {
         let collection = TestableCollection {
             testables,
             directory: "".to_string(),
+            label: None,
         };
         let result = run_check_with_config(
             panose_familytype,
@@ -70,6 +70,7 @@ mod tests {
         let collection = TestableCollection {
             testables,
             directory: "".to_string(),
+            label: None,
         };
         let result = run_check_with_config(
             super::underline_thickness,
@@ -93,6 +94,7 @@ mod tests {
         let collection = TestableCollection {
             testables,
             directory: "".to_string(),
+            label: None,
         };
         let result = run_check_with_config(
             super::underline_thickness,
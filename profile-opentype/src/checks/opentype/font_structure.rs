@@ -0,0 +1,727 @@
+use fontations::skrifa::{
+    raw::{ReadError, TableProvider},
+    FontRef, GlyphId,
+};
+use fontspector_checkapi::{prelude::*, Metadata, StatusCode, TestFont};
+use serde_json::{json, Value};
+
+const HEADER_SIZE: usize = 12;
+const RECORD_SIZE: usize = 16;
+const CHECKSUM_MAGIC: u32 = 0xB1B0AFBA;
+
+#[derive(Debug, Clone, Copy)]
+struct TableRecord {
+    tag: [u8; 4],
+    checksum: u32,
+    offset: usize,
+    length: usize,
+}
+
+impl TableRecord {
+    fn tag(&self) -> String {
+        String::from_utf8_lossy(&self.tag).to_string()
+    }
+
+    fn end(&self) -> usize {
+        self.offset.saturating_add(self.length)
+    }
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    data.get(offset..offset + 2)
+        .and_then(|bytes| bytes.try_into().ok())
+        .map(u16::from_be_bytes)
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    data.get(offset..offset + 4)
+        .and_then(|bytes| bytes.try_into().ok())
+        .map(u32::from_be_bytes)
+}
+
+/// The OpenType table checksum: the sum of the data as big-endian uint32s,
+/// padded with zeros to a multiple of four bytes
+fn checksum(data: &[u8]) -> u32 {
+    data.chunks(4).fold(0u32, |sum, chunk| {
+        let mut word = [0u8; 4];
+        for (slot, byte) in word.iter_mut().zip(chunk) {
+            *slot = *byte;
+        }
+        sum.wrapping_add(u32::from_be_bytes(word))
+    })
+}
+
+fn problem(
+    status: fn(&str, &str) -> Status,
+    code: &str,
+    table: &str,
+    field: Option<&str>,
+    actual: Option<Value>,
+    expected: Option<Value>,
+    message: String,
+) -> Status {
+    let mut status = status(code, &message);
+    status.add_metadata(Metadata::TableProblem {
+        table_tag: table.to_string(),
+        field_name: field.map(|f| f.to_string()),
+        actual,
+        expected,
+        message,
+    });
+    status
+}
+
+/// Check the sfnt header and table directory
+fn check_directory(data: &[u8], problems: &mut Vec<Status>) -> Vec<TableRecord> {
+    let (Some(sfnt_version), Some(num_tables)) = (read_u32(data, 0), read_u16(data, 4)) else {
+        problems.push(problem(
+            Status::fail,
+            "truncated-header",
+            "sfnt",
+            None,
+            Some(json!(data.len())),
+            Some(json!(HEADER_SIZE)),
+            "The font file is too short to contain an sfnt header.".to_string(),
+        ));
+        return vec![];
+    };
+    if ![
+        0x00010000,
+        u32::from_be_bytes(*b"OTTO"),
+        u32::from_be_bytes(*b"true"),
+    ]
+    .contains(&sfnt_version)
+    {
+        problems.push(problem(
+            Status::fail,
+            "bad-sfnt-version",
+            "sfnt",
+            Some("sfntVersion"),
+            Some(json!(format!("0x{sfnt_version:08X}"))),
+            None,
+            format!("The sfnt version 0x{sfnt_version:08X} is not recognised."),
+        ));
+    }
+
+    let num_tables = num_tables as usize;
+    if num_tables > 0 {
+        let power = 1usize << num_tables.ilog2();
+        let expected = [
+            ("searchRange", power * RECORD_SIZE),
+            ("entrySelector", power.ilog2() as usize),
+            ("rangeShift", (num_tables - power) * RECORD_SIZE),
+        ];
+        for (index, (field, expected)) in expected.into_iter().enumerate() {
+            let actual = read_u16(data, 6 + index * 2).unwrap_or_default() as usize;
+            if actual != expected {
+                problems.push(problem(
+                    Status::warn,
+                    "bad-search-parameters",
+                    "sfnt",
+                    Some(field),
+                    Some(json!(actual)),
+                    Some(json!(expected)),
+                    format!("The table directory's {field} is {actual}, but should be {expected}."),
+                ));
+            }
+        }
+    }
+
+    let mut records = vec![];
+    for index in 0..num_tables {
+        let start = HEADER_SIZE + index * RECORD_SIZE;
+        let record = data
+            .get(start..start + 4)
+            .and_then(|tag| <[u8; 4]>::try_from(tag).ok())
+            .zip(read_u32(data, start + 4))
+            .zip(read_u32(data, start + 8).zip(read_u32(data, start + 12)));
+        let Some(((tag, checksum), (offset, length))) = record else {
+            problems.push(problem(
+                Status::fail,
+                "truncated-directory",
+                "sfnt",
+                Some("tableRecords"),
+                Some(json!(index)),
+                Some(json!(num_tables)),
+                format!(
+                    "The table directory should have {num_tables} records, but the file ends after {index}."
+                ),
+            ));
+            break;
+        };
+        records.push(TableRecord {
+            tag,
+            checksum,
+            offset: offset as usize,
+            length: length as usize,
+        });
+    }
+
+    // Table records must be sorted by tag, with no duplicates
+    for (previous, record) in records.iter().zip(records.iter().skip(1)) {
+        if record.tag == previous.tag {
+            problems.push(problem(
+                Status::fail,
+                "duplicate-table",
+                &record.tag(),
+                None,
+                None,
+                None,
+                format!(
+                    "The '{}' table appears more than once in the table directory.",
+                    record.tag()
+                ),
+            ));
+        } else if record.tag < previous.tag {
+            problems.push(problem(
+                Status::fail,
+                "unsorted-tables",
+                &record.tag(),
+                None,
+                None,
+                None,
+                format!(
+                    "The table directory is not sorted by tag: '{}' comes after '{}'.",
+                    record.tag(),
+                    previous.tag()
+                ),
+            ));
+        }
+    }
+
+    let mut in_bounds = vec![];
+    for record in records.iter() {
+        if record.offset % 4 != 0 {
+            problems.push(problem(
+                Status::fail,
+                "misaligned-table",
+                &record.tag(),
+                Some("offset"),
+                Some(json!(record.offset)),
+                None,
+                format!(
+                    "The '{}' table starts at offset {}, which is not a multiple of four.",
+                    record.tag(),
+                    record.offset
+                ),
+            ));
+        }
+        let Some(table) = data.get(record.offset..record.end()) else {
+            problems.push(problem(
+                Status::fail,
+                "table-out-of-bounds",
+                &record.tag(),
+                Some("length"),
+                Some(json!(record.end())),
+                Some(json!(data.len())),
+                format!(
+                    "The '{}' table runs from offset {} to {}, beyond the end of the file ({} bytes).",
+                    record.tag(),
+                    record.offset,
+                    record.end(),
+                    data.len()
+                ),
+            ));
+            continue;
+        };
+        in_bounds.push(*record);
+
+        let actual = if &record.tag == b"head" {
+            // checkSumAdjustment is taken as zero when summing the head table
+            let adjustment = read_u32(table, 8).unwrap_or_default();
+            checksum(table).wrapping_sub(adjustment)
+        } else {
+            checksum(table)
+        };
+        if actual != record.checksum {
+            problems.push(problem(
+                Status::warn,
+                "bad-checksum",
+                &record.tag(),
+                Some("checksum"),
+                Some(json!(format!("0x{:08X}", record.checksum))),
+                Some(json!(format!("0x{actual:08X}"))),
+                format!(
+                    "The '{}' table's checksum is 0x{:08X}, but its contents sum to 0x{actual:08X}.",
+                    record.tag(),
+                    record.checksum
+                ),
+            ));
+        }
+    }
+
+    // Tables must not overlap, and should be padded with zeros
+    in_bounds.sort_by_key(|record| record.offset);
+    for (index, record) in in_bounds.iter().enumerate() {
+        let next = in_bounds.get(index + 1);
+        if let Some(next) = next {
+            if record.end() > next.offset {
+                problems.push(problem(
+                    Status::fail,
+                    "overlapping-tables",
+                    &record.tag(),
+                    Some("length"),
+                    Some(json!(record.end())),
+                    Some(json!(next.offset)),
+                    format!(
+                        "The '{}' table overlaps the '{}' table.",
+                        record.tag(),
+                        next.tag()
+                    ),
+                ));
+                continue;
+            }
+        }
+        let padded_end = record.end().next_multiple_of(4);
+        let limit = next.map_or(data.len(), |next| next.offset).min(padded_end);
+        if data
+            .get(record.end()..limit)
+            .is_some_and(|padding| padding.iter().any(|byte| *byte != 0))
+        {
+            problems.push(problem(
+                Status::warn,
+                "nonzero-padding",
+                &record.tag(),
+                None,
+                None,
+                None,
+                format!(
+                    "The padding after the '{}' table contains non-zero bytes.",
+                    record.tag()
+                ),
+            ));
+        }
+    }
+
+    if let Some(head) = in_bounds.iter().find(|record| &record.tag == b"head") {
+        if let Some(stored) = read_u32(data, head.offset + 8) {
+            let expected = CHECKSUM_MAGIC.wrapping_sub(checksum(data).wrapping_sub(stored));
+            if stored != expected {
+                problems.push(problem(
+                    Status::warn,
+                    "bad-checksum-adjustment",
+                    "head",
+                    Some("checkSumAdjustment"),
+                    Some(json!(format!("0x{stored:08X}"))),
+                    Some(json!(format!("0x{expected:08X}"))),
+                    format!(
+                        "head.checkSumAdjustment is 0x{stored:08X}, but should be 0x{expected:08X}."
+                    ),
+                ));
+            }
+        }
+    }
+    in_bounds
+}
+
+fn check_cmap(font: &FontRef, problems: &mut Vec<Status>) -> Result<(), ReadError> {
+    let cmap = font.cmap()?;
+    for (index, record) in cmap.encoding_records().iter().enumerate() {
+        if let Err(e) = record.subtable(cmap.offset_data()) {
+            problems.push(problem(
+                Status::fail,
+                "table-parse-error",
+                "cmap",
+                Some(&format!("encodingRecords[{index}]")),
+                None,
+                None,
+                format!(
+                    "Could not read the cmap subtable for platform {:?} encoding {}: {e}",
+                    record.platform_id(),
+                    record.encoding_id()
+                ),
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn check_name(font: &FontRef, problems: &mut Vec<Status>) -> Result<(), ReadError> {
+    let name = font.name()?;
+    for record in name.name_record().iter() {
+        if let Err(e) = record.string(name.string_data()) {
+            problems.push(problem(
+                Status::fail,
+                "table-parse-error",
+                "name",
+                Some(&format!("nameID {}", record.name_id())),
+                None,
+                None,
+                format!(
+                    "Could not read name ID {} (platform {}, encoding {}, language {}): {e}",
+                    record.name_id(),
+                    record.platform_id(),
+                    record.encoding_id(),
+                    record.language_id()
+                ),
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn check_hmtx(font: &FontRef, problems: &mut Vec<Status>) -> Result<(), ReadError> {
+    let number_of_h_metrics = font.hhea()?.number_of_h_metrics();
+    let num_glyphs = font.maxp()?.num_glyphs();
+    if number_of_h_metrics > num_glyphs {
+        problems.push(problem(
+            Status::fail,
+            "bad-number-of-hmetrics",
+            "hhea",
+            Some("numberOfHMetrics"),
+            Some(json!(number_of_h_metrics)),
+            Some(json!(num_glyphs)),
+            format!(
+                "hhea.numberOfHMetrics is {number_of_h_metrics}, but the font only has {num_glyphs} glyphs."
+            ),
+        ));
+    }
+    font.hmtx().map(|_| ())
+}
+
+fn check_glyf(
+    font: &FontRef,
+    test_font: &TestFont,
+    context: &Context,
+    problems: &mut Vec<Status>,
+) -> Result<(), ReadError> {
+    let loca = font.loca(None)?;
+    let glyf = font.glyf()?;
+    let offsets = (0..=loca.len())
+        .map(|index| loca.get_raw(index).unwrap_or_default())
+        .collect::<Vec<_>>();
+    if let Some(index) = offsets
+        .iter()
+        .zip(offsets.iter().skip(1))
+        .position(|(this, next)| next < this)
+    {
+        problems.push(problem(
+            Status::fail,
+            "loca-not-ascending",
+            "loca",
+            Some("offsets"),
+            None,
+            None,
+            format!("The loca offsets decrease after glyph {index}."),
+        ));
+    }
+    if let Some(last) = offsets.last() {
+        if *last as usize > glyf.offset_data().len() {
+            problems.push(problem(
+                Status::fail,
+                "loca-out-of-bounds",
+                "loca",
+                Some("offsets"),
+                Some(json!(last)),
+                Some(json!(glyf.offset_data().len())),
+                format!(
+                    "The loca table points to offset {last}, beyond the end of the glyf table ({} bytes).",
+                    glyf.offset_data().len()
+                ),
+            ));
+            return Ok(());
+        }
+    }
+    let bad_glyphs = (0..loca.len() as u32)
+        .map(GlyphId::new)
+        .filter_map(|gid| {
+            loca.get_glyf(gid, &glyf)
+                .err()
+                .map(|e| format!("{}: {e}", test_font.glyph_name_for_id_synthesise(gid)))
+        })
+        .collect::<Vec<_>>();
+    if !bad_glyphs.is_empty() {
+        problems.push(problem(
+            Status::fail,
+            "table-parse-error",
+            "glyf",
+            None,
+            None,
+            None,
+            format!(
+                "The following glyphs could not be read:\n\n{}",
+                bullet_list(context, bad_glyphs)
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// Parse a table as far as fontations will let us, reporting any problems
+fn check_table(
+    tag: &[u8; 4],
+    test_font: &TestFont,
+    context: &Context,
+    problems: &mut Vec<Status>,
+) -> Result<(), ReadError> {
+    let font = test_font.font();
+    match tag {
+        b"head" => font.head().map(|_| ()),
+        b"hhea" => font.hhea().map(|_| ()),
+        b"maxp" => font.maxp().map(|_| ()),
+        b"OS/2" => font.os2().map(|_| ()),
+        b"post" => font.post().map(|_| ()),
+        b"name" => check_name(&font, problems),
+        b"cmap" => check_cmap(&font, problems),
+        b"hmtx" => check_hmtx(&font, problems),
+        b"glyf" => check_glyf(&font, test_font, context, problems),
+        b"GDEF" => font.gdef().map(|_| ()),
+        b"GSUB" => {
+            let gsub = font.gsub()?;
+            gsub.script_list()?;
+            gsub.feature_list()?;
+            gsub.lookup_list()?
+                .lookups()
+                .iter()
+                .try_for_each(|lookup| lookup.map(|_| ()))
+        }
+        b"GPOS" => {
+            let gpos = font.gpos()?;
+            gpos.script_list()?;
+            gpos.feature_list()?;
+            gpos.lookup_list()?
+                .lookups()
+                .iter()
+                .try_for_each(|lookup| lookup.map(|_| ()))
+        }
+        b"BASE" => font.base().map(|_| ()),
+        b"MATH" => font.math().map(|_| ()),
+        b"fvar" => font.fvar().map(|_| ()),
+        b"avar" => font.avar().map(|_| ()),
+        b"gvar" => font.gvar().map(|_| ()),
+        b"HVAR" => font.hvar().map(|_| ()),
+        b"MVAR" => font.mvar().map(|_| ()),
+        b"STAT" => font.stat().map(|_| ()),
+        b"CFF " => font.cff().map(|_| ()),
+        b"CFF2" => font.cff2().map(|_| ()),
+        b"COLR" => font.colr().map(|_| ()),
+        b"CPAL" => font.cpal().map(|_| ()),
+        b"vhea" => font.vhea().map(|_| ()),
+        b"vmtx" => font.vmtx().map(|_| ()),
+        b"gasp" => font.gasp().map(|_| ()),
+        b"kern" => font.kern().map(|_| ()),
+        b"meta" => font.meta().map(|_| ()),
+        // Anything else is opaque to fontations, so we can only check
+        // its place in the table directory
+        _ => Ok(()),
+    }
+}
+
+#[check(
+    id = "opentype/font_structure",
+    rationale = "
+        Browsers pass web fonts through a sanitizer (such as OTS) before using
+        them, and reject the whole font if its binary structure is broken:
+        tables which run past the end of the file or overlap each other, a
+        table directory which is not sorted, offsets inside a table which point
+        nowhere, and so on. Other environments may instead crash or silently
+        misbehave.
+
+        This check validates the table directory (ordering, alignment, bounds,
+        padding and checksums, including head.checkSumAdjustment) and then
+        parses every table that fontations understands, reporting each problem
+        against the table and field responsible. Other checks may fail in
+        confusing ways on a structurally broken font, so problems reported
+        here should be fixed first.
+    ",
+    proposal = "https://github.com/khaledhosny/ots",
+    title = "Is the font's binary structure valid?"
+)]
+fn font_structure(t: &Testable, context: &Context) -> CheckFnResult {
+    let mut problems = vec![];
+    // The directory is checked on the raw bytes, so that fonts too broken
+    // for fontations to open still get a precise explanation.
+    let records = check_directory(&t.contents, &mut problems);
+    match TestFont::new_from_data(&t.filename, &t.contents) {
+        Ok(f) => {
+            for record in records.iter() {
+                if let Err(e) = check_table(&record.tag, &f, context, &mut problems) {
+                    problems.push(problem(
+                        Status::fail,
+                        "table-parse-error",
+                        &record.tag(),
+                        None,
+                        None,
+                        None,
+                        format!("Could not parse the '{}' table: {e}", record.tag()),
+                    ));
+                }
+            }
+        }
+        // The directory is fine, but the glyph count can't be read
+        Err(e) if FontRef::new(&t.contents).is_ok() => problems.push(problem(
+            Status::fail,
+            "table-parse-error",
+            "maxp",
+            None,
+            None,
+            None,
+            format!("Could not parse the 'maxp' table: {e}"),
+        )),
+        Err(e) => {
+            if !problems.iter().any(|p| p.severity == StatusCode::Fail) {
+                problems.push(problem(
+                    Status::fail,
+                    "unreadable-font",
+                    "sfnt",
+                    None,
+                    None,
+                    None,
+                    format!("The font could not be read: {e}"),
+                ));
+            }
+        }
+    }
+    return_result(problems)
+}
+
+#[allow(clippy::unwrap_used, clippy::expect_used, clippy::indexing_slicing)]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fontspector_checkapi::codetesting::{
+        assert_pass, assert_results_contain, run_check, test_able,
+    };
+
+    /// The (offset, length) of each record in a font's table directory
+    fn table_records(contents: &[u8]) -> Vec<(usize, usize)> {
+        let num_tables = read_u16(contents, 4).unwrap() as usize;
+        (0..num_tables)
+            .map(|index| {
+                let start = HEADER_SIZE + index * RECORD_SIZE;
+                (
+                    read_u32(contents, start + 8).unwrap() as usize,
+                    read_u32(contents, start + 12).unwrap() as usize,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_font_structure_pass() {
+        for font in [
+            "nunito/Nunito-Regular.ttf",
+            "source-sans-pro/OTF/SourceSansPro-Regular.otf",
+            "source-sans-pro/VAR/SourceSansVariable-Italic.otf",
+        ] {
+            let testable = test_able(font);
+            let result = run_check(font_structure, testable);
+            assert_pass(&result);
+        }
+    }
+
+    #[test]
+    fn test_font_structure_bad_checksum() {
+        let mut testable = test_able("nunito/Nunito-Regular.ttf");
+        // The checksum of the first table record
+        testable.contents[HEADER_SIZE + 4] ^= 0xff;
+        let result = run_check(font_structure, testable);
+        assert_results_contain(&result, StatusCode::Warn, Some("bad-checksum".to_string()));
+        assert_results_contain(
+            &result,
+            StatusCode::Warn,
+            Some("bad-checksum-adjustment".to_string()),
+        );
+    }
+
+    #[test]
+    fn test_font_structure_bad_search_parameters() {
+        let mut testable = test_able("nunito/Nunito-Regular.ttf");
+        testable.contents[6] ^= 0xff;
+        let result = run_check(font_structure, testable);
+        assert_results_contain(
+            &result,
+            StatusCode::Warn,
+            Some("bad-search-parameters".to_string()),
+        );
+    }
+
+    #[test]
+    fn test_font_structure_unsorted() {
+        let mut testable = test_able("nunito/Nunito-Regular.ttf");
+        // Swap the first two table records
+        let (first, second) = (HEADER_SIZE, HEADER_SIZE + RECORD_SIZE);
+        let record: Vec<u8> = testable.contents[first..second].to_vec();
+        testable
+            .contents
+            .copy_within(second..second + RECORD_SIZE, first);
+        testable.contents[second..second + RECORD_SIZE].copy_from_slice(&record);
+        let result = run_check(font_structure, testable);
+        assert_results_contain(
+            &result,
+            StatusCode::Fail,
+            Some("unsorted-tables".to_string()),
+        );
+    }
+
+    #[test]
+    fn test_font_structure_truncated_header() {
+        let mut testable = test_able("nunito/Nunito-Regular.ttf");
+        testable.contents.truncate(4);
+        let result = run_check(font_structure, testable);
+        assert_results_contain(
+            &result,
+            StatusCode::Fail,
+            Some("truncated-header".to_string()),
+        );
+    }
+
+    #[test]
+    fn test_font_structure_truncated_directory() {
+        let mut testable = test_able("nunito/Nunito-Regular.ttf");
+        testable.contents.truncate(HEADER_SIZE + RECORD_SIZE * 2);
+        let result = run_check(font_structure, testable);
+        assert_results_contain(
+            &result,
+            StatusCode::Fail,
+            Some("truncated-directory".to_string()),
+        );
+    }
+
+    #[test]
+    fn test_font_structure_out_of_bounds() {
+        let mut testable = test_able("nunito/Nunito-Regular.ttf");
+        // The length of the first table record
+        let length = HEADER_SIZE + 12;
+        testable.contents[length..length + 4].copy_from_slice(&0x7fff_ffffu32.to_be_bytes());
+        let result = run_check(font_structure, testable);
+        assert_results_contain(
+            &result,
+            StatusCode::Fail,
+            Some("table-out-of-bounds".to_string()),
+        );
+    }
+
+    #[test]
+    fn test_font_structure_overlapping() {
+        let mut testable = test_able("nunito/Nunito-Regular.ttf");
+        // Point the second table record at the first table's data
+        let offset = testable.contents[HEADER_SIZE + 8..HEADER_SIZE + 12].to_vec();
+        let second = HEADER_SIZE + RECORD_SIZE + 8;
+        testable.contents[second..second + 4].copy_from_slice(&offset);
+        let result = run_check(font_structure, testable);
+        assert_results_contain(
+            &result,
+            StatusCode::Fail,
+            Some("overlapping-tables".to_string()),
+        );
+    }
+
+    #[test]
+    fn test_font_structure_bad_padding() {
+        let mut testable = test_able("nunito/Nunito-Regular.ttf");
+        let records = table_records(&testable.contents);
+        let end = records
+            .iter()
+            .map(|(offset, length)| offset + length)
+            .find(|end| end % 4 != 0 && *end < testable.contents.len())
+            .expect("a table which needs padding");
+        testable.contents[end] = 0xff;
+        let result = run_check(font_structure, testable);
+        assert_results_contain(
+            &result,
+            StatusCode::Warn,
+            Some("nonzero-padding".to_string()),
+        );
+    }
+}
@@ -1,9 +1,10 @@
 use fontations::skrifa::MetadataProvider;
-use fontspector_checkapi::{prelude::*, skip, testfont, FileTypeConvert, Metadata};
+use fontspector_checkapi::{prelude::*, testfont, FileTypeConvert, Metadata};
 use serde_json::json;
 
 #[check(
     id = "opentype/fvar/valid_range",
+    conditions = ["is_variable_font"],
     title = "Validates fvar axis range and default values.",
     rationale = "
         Each axis defined in the fvar table must have:
@@ -18,11 +19,6 @@ use serde_json::json;
 )]
 fn valid_range(t: &Testable, _context: &Context) -> CheckFnResult {
     let f = testfont!(t);
-    skip!(
-        !f.is_variable_font(),
-        "not-variable",
-        "Font is not a variable font."
-    );
     let mut problems = vec![];
     for axis in f.font().axes().iter() {
         let tag = axis.tag();
@@ -76,7 +72,10 @@ mod tests {
         write::{from_obj::ToOwnedTable, tables::fvar::Fvar, FontBuilder},
     };
     use fontspector_checkapi::{
-        codetesting::{assert_pass, assert_results_contain, assert_skip, run_check, test_able},
+        codetesting::{
+            assert_pass, assert_result_codes, assert_results_contain, assert_skip, edit_font,
+            run_check, test_able,
+        },
         FileTypeConvert, StatusCode, TTF,
     };
 
@@ -205,4 +204,45 @@ mod tests {
             Some("invalid-default".to_string()),
         );
     }
+
+    #[test]
+    fn test_collapsed_range_reports_default_too() {
+        let mut testable = test_able("varfont/inter/Inter[slnt,wght].ttf");
+        edit_font(&mut testable).fvar(|fvar| {
+            for axis in &mut fvar.axis_instance_arrays.axes {
+                if axis.axis_tag == fontations::write::types::Tag::new(b"wght") {
+                    axis.max_value = axis.min_value;
+                }
+            }
+        });
+
+        // The default of 400 is no longer inside the collapsed range either
+        let results = run_check(valid_range, testable);
+        assert_result_codes(
+            &results,
+            &[
+                (StatusCode::Fail, Some("invalid-range")),
+                (StatusCode::Fail, Some("invalid-default")),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_every_axis_reported() {
+        let mut testable = test_able("varfont/inter/Inter[slnt,wght].ttf");
+        edit_font(&mut testable).fvar(|fvar| {
+            for axis in &mut fvar.axis_instance_arrays.axes {
+                axis.default_value = axis.max_value + fontations::write::types::Fixed::ONE;
+            }
+        });
+
+        let results = run_check(valid_range, testable);
+        assert_result_codes(
+            &results,
+            &[
+                (StatusCode::Fail, Some("invalid-default")),
+                (StatusCode::Fail, Some("invalid-default")),
+            ],
+        );
+    }
 }
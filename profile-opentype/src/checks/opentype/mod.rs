@@ -5,6 +5,7 @@ mod GDEF_spacing_marks;
 mod caret_slope;
 mod code_pages;
 mod family_naming_recommendations;
+mod font_structure;
 mod font_version;
 mod fsselection;
 mod fsselection_wws;
@@ -28,16 +29,19 @@ mod weight_class_fvar;
 mod xavgcharwidth;
 
 pub mod STAT;
+pub mod cff;
 pub mod family;
 pub mod fvar;
 pub mod kern_table;
 pub mod loca;
 pub mod name;
 pub mod varfont;
+pub mod vertical;
 
 pub use caret_slope::caret_slope;
 pub use code_pages::code_pages;
 pub use family_naming_recommendations::family_naming_recommendations;
+pub use font_structure::font_structure;
 pub use font_version::font_version;
 pub use fsselection::fsselection;
 pub use fsselection_wws::fsselection_wws;
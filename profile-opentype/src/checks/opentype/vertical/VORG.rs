@@ -0,0 +1,91 @@
+use fontspector_checkapi::{prelude::*, skip, testfont, FileTypeConvert};
+
+#[check(
+    id = "opentype/vertical/VORG",
+    rationale = "
+        In a font with TrueType outlines, the vertical origin of each glyph is
+        worked out from its top side bearing in 'vmtx' and the top of its
+        bounding box. CFF outlines carry no bounding box, so CFF and CFF2 fonts
+        which are meant to be set vertically should include a 'VORG' table to
+        give the vertical origin directly; without it, rasterizers need to
+        compute the bounding box of every glyph, and some will simply guess.
+
+        The 'VORG' table is only defined for CFF-flavoured fonts, and is
+        ignored by TrueType rasterizers, so its presence in a font with a
+        'glyf' table is a sign of a confused build process.
+    ",
+    proposal = "https://learn.microsoft.com/en-us/typography/opentype/spec/vorg",
+    title = "Do CFF fonts with vertical metrics have a VORG table?"
+)]
+fn VORG(t: &Testable, _context: &Context) -> CheckFnResult {
+    let f = testfont!(t);
+    skip!(
+        !f.has_table(b"vmtx") && !f.has_table(b"VORG"),
+        "not-vertical",
+        "Font does not have vertical metrics"
+    );
+    let is_cff = f.has_table(b"CFF ") || f.has_table(b"CFF2");
+    Ok(if is_cff && !f.has_table(b"VORG") {
+        Status::just_one_warn(
+            "missing-VORG",
+            "Font has CFF outlines and vertical metrics, but no 'VORG' table.",
+        )
+    } else if !is_cff && f.has_table(b"VORG") {
+        Status::just_one_warn(
+            "VORG-in-truetype",
+            "Font has a 'VORG' table, but this is only used by fonts with CFF outlines.",
+        )
+    } else {
+        Status::just_one_pass()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use fontspector_checkapi::{
+        codetesting::{
+            add_table, assert_pass, assert_results_contain, assert_skip, run_check, test_able,
+        },
+        StatusCode,
+    };
+
+    #[test]
+    fn test_VORG_pass() {
+        let testable = test_able("shantell/ShantellSans[BNCE,INFM,SPAC,wght].ttf");
+        let results = run_check(super::VORG, testable);
+        assert_pass(&results);
+
+        let mut testable = test_able("source-sans-pro/OTF/SourceSansPro-Regular.otf");
+        add_table(&mut testable, b"vmtx");
+        add_table(&mut testable, b"VORG");
+        let results = run_check(super::VORG, testable);
+        assert_pass(&results);
+    }
+
+    #[test]
+    fn test_VORG_skip() {
+        let testable = test_able("mada/Mada-Regular.ttf");
+        let results = run_check(super::VORG, testable);
+        assert_skip(&results);
+    }
+
+    #[test]
+    fn test_VORG_missing() {
+        let mut testable = test_able("source-sans-pro/OTF/SourceSansPro-Regular.otf");
+        add_table(&mut testable, b"vmtx");
+        let results = run_check(super::VORG, testable);
+        assert_results_contain(&results, StatusCode::Warn, Some("missing-VORG".to_string()));
+    }
+
+    #[test]
+    fn test_VORG_in_truetype() {
+        let mut testable = test_able("shantell/ShantellSans[BNCE,INFM,SPAC,wght].ttf");
+        add_table(&mut testable, b"VORG");
+        let results = run_check(super::VORG, testable);
+        assert_results_contain(
+            &results,
+            StatusCode::Warn,
+            Some("VORG-in-truetype".to_string()),
+        );
+    }
+}
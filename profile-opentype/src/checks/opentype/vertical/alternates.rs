@@ -0,0 +1,223 @@
+use std::collections::BTreeMap;
+
+use fontations::skrifa::{raw::ReadError, GlyphId16, MetadataProvider};
+use fontspector_checkapi::{prelude::*, skip, testfont, FileTypeConvert, TestFont};
+
+use super::{
+    has_vertical_features, has_vertical_metrics, orientation_data::VERTICAL_ALTERNATE_RANGES,
+};
+
+/// Unicode Vertical_Orientation values which call for a vertical alternate
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(super) enum Orientation {
+    /// Upright, but with a different glyph or position (Tu)
+    TransformedUpright,
+    /// Rotated, but with a different glyph if one is available (Tr)
+    TransformedRotated,
+}
+
+fn vertical_orientation(codepoint: u32) -> Option<Orientation> {
+    VERTICAL_ALTERNATE_RANGES
+        .iter()
+        .find(|(start, end, _)| (*start..=*end).contains(&codepoint))
+        .map(|(_, _, orientation)| *orientation)
+}
+
+/// The glyphs which are substituted by the font's vertical features
+fn vertically_substituted_glyphs(
+    f: &TestFont,
+    context: &Context,
+) -> Result<Vec<GlyphId16>, FontspectorError> {
+    let lookup_indices = f
+        .feature_records(true)
+        .filter(|(r, _l)| r.feature_tag() == "vert" || r.feature_tag() == "vrt2")
+        .flat_map(|(_r, l)| l)
+        .flat_map(|l| l.lookup_list_indices())
+        .map(|i| i.get())
+        .collect::<Vec<_>>();
+    if lookup_indices.is_empty() {
+        return Ok(vec![]);
+    }
+    let substitutions_by_lookup = f.gsub_substitutions(Some(context))?;
+    let mut glyphs = vec![];
+    for i in lookup_indices {
+        let substitutions = substitutions_by_lookup
+            .get(i as usize)
+            .ok_or(ReadError::OutOfBounds)?;
+        for (lhs, _rhs) in substitutions {
+            glyphs.extend(lhs);
+        }
+    }
+    Ok(glyphs)
+}
+
+#[check(
+    id = "opentype/vertical/alternates",
+    rationale = "
+        The Unicode Vertical_Orientation property (UAX #50) marks characters
+        which are set upright in vertical text but need a different glyph or
+        position to do so (Tu), such as the ideographic full stop and the small
+        kana, and characters which are normally rotated but should use a
+        purpose-designed vertical glyph where the font has one (Tr), such as
+        brackets and the prolonged sound mark.
+
+        Layout engines get these vertical forms from the 'vert' or 'vrt2' GSUB
+        features. A font with vertical metrics which maps these characters but
+        does not substitute them in either feature will set punctuation in the
+        wrong corner of the em box and brackets facing the wrong way.
+    ",
+    proposal = "https://www.unicode.org/reports/tr50/",
+    title = "Are characters needing vertical alternates covered by vert or vrt2?"
+)]
+fn alternates(t: &Testable, context: &Context) -> CheckFnResult {
+    let f = testfont!(t);
+    skip!(
+        !has_vertical_metrics(&f) && !has_vertical_features(&f),
+        "not-vertical",
+        "Font does not support vertical typesetting"
+    );
+    let mut needed: BTreeMap<u32, (GlyphId16, Orientation)> = f
+        .font()
+        .charmap()
+        .mappings()
+        .filter_map(|(cp, gid)| {
+            let orientation = vertical_orientation(cp)?;
+            Some((cp, (GlyphId16::try_from(gid).ok()?, orientation)))
+        })
+        .collect();
+    skip!(
+        needed.is_empty(),
+        "no-vertical-alternate-characters",
+        "Font does not map any characters which need vertical alternates"
+    );
+    let substituted = vertically_substituted_glyphs(&f, context)?;
+    needed.retain(|_cp, (gid, _)| !substituted.contains(gid));
+    if needed.is_empty() {
+        return Ok(Status::just_one_pass());
+    }
+    Ok(Status::just_one_warn(
+        "missing-vertical-alternates",
+        &format!(
+            "The following characters need a vertical alternate, but are not substituted by the 'vert' or 'vrt2' features:\n\n{}",
+            bullet_list(
+                context,
+                needed.iter().map(|(cp, (gid, orientation))| format!(
+                    "U+{cp:04X} {} ({})",
+                    f.glyph_name_for_id_synthesise(*gid),
+                    if *orientation == Orientation::TransformedUpright {
+                        "Tu"
+                    } else {
+                        "Tr"
+                    }
+                ))
+            )
+        ),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use fontations::{
+        skrifa::{GlyphId16, Tag},
+        write::tables::{
+            gsub::{SingleSubst, SubstitutionLookup},
+            layout::{CoverageTable, Feature, FeatureRecord, Lookup, LookupFlag},
+        },
+    };
+    use fontspector_checkapi::{
+        codetesting::{
+            assert_messages_contain, assert_pass, assert_results_contain, assert_skip, edit_font,
+            run_check, test_able,
+        },
+        StatusCode,
+    };
+
+    use super::{vertical_orientation, Orientation};
+
+    #[test]
+    fn test_vertical_orientation() {
+        assert_eq!(
+            vertical_orientation(0x3002),
+            Some(Orientation::TransformedUpright)
+        );
+        assert_eq!(
+            vertical_orientation(0x3063),
+            Some(Orientation::TransformedUpright)
+        );
+        assert_eq!(
+            vertical_orientation(0x300C),
+            Some(Orientation::TransformedRotated)
+        );
+        assert_eq!(
+            vertical_orientation(0x2329),
+            Some(Orientation::TransformedRotated)
+        );
+        assert_eq!(
+            vertical_orientation(0x1F201),
+            Some(Orientation::TransformedUpright)
+        );
+        // Full-size kana and ideographs are simply upright
+        assert_eq!(vertical_orientation(0x3064), None);
+        assert_eq!(vertical_orientation(0x4E00), None);
+        // Fullwidth less-than is rotated, with no special vertical form
+        assert_eq!(vertical_orientation(0xFF1C), None);
+    }
+
+    #[test]
+    fn test_alternates_skip() {
+        // Has vertical metrics, but no CJK punctuation or kana
+        let testable = test_able("shantell/ShantellSans[BNCE,INFM,SPAC,wght].ttf");
+        let results = run_check(super::alternates, testable);
+        assert_skip(&results);
+
+        let testable = test_able("mada/Mada-Regular.ttf");
+        let results = run_check(super::alternates, testable);
+        assert_skip(&results);
+    }
+
+    #[test]
+    fn test_alternates_missing() {
+        // Has vertical metrics and maps U+3030 WAVY DASH and U+1F201
+        // SQUARED KATAKANA KOKO, but has no 'vert' feature
+        let testable = test_able("notoemoji/NotoEmoji-Regular.ttf");
+        let results = run_check(super::alternates, testable);
+        assert_results_contain(
+            &results,
+            StatusCode::Warn,
+            Some("missing-vertical-alternates".to_string()),
+        );
+        assert_messages_contain(&results, "U+3030 wavyDash (Tr)");
+        assert_messages_contain(&results, "U+1F201 japaneseHereButton (Tu)");
+    }
+
+    #[test]
+    fn test_alternates_pass() {
+        let mut testable = test_able("notoemoji/NotoEmoji-Regular.ttf");
+        // Substitute wavyDash and japaneseHereButton in a new 'vert' feature
+        edit_font(&mut testable).gsub(|gsub| {
+            let lookup_index = gsub.lookup_list.lookups.len() as u16;
+            gsub.lookup_list
+                .lookups
+                .push(SubstitutionLookup::Single(Lookup::new(
+                    LookupFlag::empty(),
+                    vec![SingleSubst::format_1(
+                        CoverageTable::format_1(vec![GlyphId16::new(186), GlyphId16::new(233)]),
+                        1,
+                    )],
+                )));
+            let feature_index = gsub.feature_list.feature_records.len() as u16;
+            gsub.feature_list.feature_records.push(FeatureRecord::new(
+                Tag::new(b"vert"),
+                Feature::new(None, vec![lookup_index]),
+            ));
+            for script in gsub.script_list.script_records.iter_mut() {
+                if let Some(lang_sys) = script.script.default_lang_sys.as_mut() {
+                    lang_sys.feature_indices.push(feature_index);
+                }
+            }
+        });
+        let results = run_check(super::alternates, testable);
+        assert_pass(&results);
+    }
+}
@@ -0,0 +1,22 @@
+mod VORG;
+mod alternates;
+mod orientation_data;
+mod origins;
+mod vhea_vmtx;
+
+pub use alternates::alternates;
+pub use origins::origins;
+pub use vhea_vmtx::vhea_vmtx;
+pub use VORG::VORG;
+
+use fontspector_checkapi::TestFont;
+
+/// Does the font carry the metrics needed for vertical typesetting?
+fn has_vertical_metrics(f: &TestFont) -> bool {
+    f.has_table(b"vhea") && f.has_table(b"vmtx")
+}
+
+/// Does the font declare any intent to be set vertically?
+fn has_vertical_features(f: &TestFont) -> bool {
+    f.has_feature(true, "vert") || f.has_feature(true, "vrt2")
+}
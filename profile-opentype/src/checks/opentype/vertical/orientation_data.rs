@@ -0,0 +1,56 @@
+// Generated by scripts/vertical_orientation.py from
+// VerticalOrientation-15.0.0.txt; do not edit by hand.
+
+use super::alternates::Orientation::{self, TransformedRotated as Tr, TransformedUpright as Tu};
+
+/// Characters whose Vertical_Orientation is Tu or Tr, from UAX #50
+pub(super) const VERTICAL_ALTERNATE_RANGES: [(u32, u32, Orientation); 48] = [
+    (0x2329, 0x232A, Tr),
+    (0x3001, 0x3002, Tu),
+    (0x3008, 0x3011, Tr),
+    (0x3014, 0x301F, Tr),
+    (0x3030, 0x3030, Tr),
+    (0x3041, 0x3041, Tu),
+    (0x3043, 0x3043, Tu),
+    (0x3045, 0x3045, Tu),
+    (0x3047, 0x3047, Tu),
+    (0x3049, 0x3049, Tu),
+    (0x3063, 0x3063, Tu),
+    (0x3083, 0x3083, Tu),
+    (0x3085, 0x3085, Tu),
+    (0x3087, 0x3087, Tu),
+    (0x308E, 0x308E, Tu),
+    (0x3095, 0x3096, Tu),
+    (0x309B, 0x309C, Tu),
+    (0x30A0, 0x30A0, Tr),
+    (0x30A1, 0x30A1, Tu),
+    (0x30A3, 0x30A3, Tu),
+    (0x30A5, 0x30A5, Tu),
+    (0x30A7, 0x30A7, Tu),
+    (0x30A9, 0x30A9, Tu),
+    (0x30C3, 0x30C3, Tu),
+    (0x30E3, 0x30E3, Tu),
+    (0x30E5, 0x30E5, Tu),
+    (0x30E7, 0x30E7, Tu),
+    (0x30EE, 0x30EE, Tu),
+    (0x30F5, 0x30F6, Tu),
+    (0x30FC, 0x30FC, Tr),
+    (0x3127, 0x3127, Tu),
+    (0x31F0, 0x31FF, Tu),
+    (0x32FF, 0x3357, Tu),
+    (0x337B, 0x337F, Tu),
+    (0xFE50, 0xFE52, Tu),
+    (0xFE59, 0xFE5E, Tr),
+    (0xFF01, 0xFF01, Tu),
+    (0xFF08, 0xFF09, Tr),
+    (0xFF0C, 0xFF0C, Tu),
+    (0xFF0E, 0xFF0E, Tu),
+    (0xFF1A, 0xFF1B, Tr),
+    (0xFF1F, 0xFF1F, Tu),
+    (0xFF3B, 0xFF3B, Tr),
+    (0xFF3D, 0xFF3D, Tr),
+    (0xFF3F, 0xFF3F, Tr),
+    (0xFF5B, 0xFF60, Tr),
+    (0xFFE3, 0xFFE3, Tr),
+    (0x1F200, 0x1F201, Tu),
+];
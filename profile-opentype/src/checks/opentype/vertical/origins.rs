@@ -0,0 +1,151 @@
+use fontations::skrifa::{raw::TableProvider, GlyphId};
+use fontspector_checkapi::{
+    pens::BezGlyph, prelude::*, skip, testfont, FileTypeConvert, TestFont, DEFAULT_LOCATION,
+};
+use kurbo::Shape;
+
+use super::has_vertical_metrics;
+
+/// Vertical origins within this many units are considered to match
+const TOLERANCE: i32 = 1;
+
+/// The top of the glyph's bounding box, or None for empty glyphs
+fn glyph_y_max(f: &TestFont, gid: GlyphId) -> Result<Option<i32>, FontspectorError> {
+    if f.has_table(b"glyf") {
+        return Ok(f.get_glyf_glyph(gid)?.map(|glyph| glyph.y_max() as i32));
+    }
+    let mut pen = BezGlyph::default();
+    f.draw_glyph(gid, &mut pen, DEFAULT_LOCATION)?;
+    Ok(pen
+        .iter()
+        .filter(|path| !path.elements().is_empty())
+        .map(|path| path.bounding_box().y1)
+        .reduce(f64::max)
+        .map(|y_max| y_max.round() as i32))
+}
+
+#[check(
+    id = "opentype/vertical/origins",
+    rationale = "
+        When text is set vertically, each glyph is positioned from its vertical
+        origin, which sits at the top of the glyph's em box. For TrueType
+        outlines the origin is the top side bearing in 'vmtx' plus the top of
+        the glyph's bounding box; for CFF outlines it comes from the 'VORG'
+        table, and the 'vmtx' top side bearing must agree with it.
+
+        By convention the em box used for vertical setting is the one given by
+        the OS/2 typographic ascender and descender, so that horizontal and
+        vertical text share the same ideographic em box. Glyphs whose vertical
+        origin is somewhere else will be shifted up or down in vertical text,
+        which typically happens when outlines are edited without regenerating
+        the vertical metrics.
+    ",
+    proposal = "https://learn.microsoft.com/en-us/typography/opentype/spec/vmtx",
+    title = "Do the vertical origins match the OS/2 typographic metrics?"
+)]
+fn origins(t: &Testable, context: &Context) -> CheckFnResult {
+    let f = testfont!(t);
+    skip!(
+        !has_vertical_metrics(&f),
+        "not-vertical",
+        "Font does not have vertical metrics"
+    );
+    let typo_ascender = f.font().os2()?.s_typo_ascender() as i32;
+    let vmtx = f.font().vmtx()?;
+    let is_cff = f.has_table(b"CFF ") || f.has_table(b"CFF2");
+    let vorg = if is_cff { f.font().vorg().ok() } else { None };
+
+    let mut problems = vec![];
+    if let Some(vorg) = vorg.as_ref() {
+        let default_origin = vorg.default_vert_origin_y() as i32;
+        if default_origin != typo_ascender {
+            problems.push(Status::warn(
+                "VORG-default-origin",
+                &format!(
+                    "VORG.defaultVertOriginY is {default_origin}, but OS/2.sTypoAscender is {typo_ascender}."
+                ),
+            ));
+        }
+    }
+
+    let mut mismatches = vec![];
+    for gid in f.all_glyphs() {
+        context.check_deadline()?;
+        let Some(y_max) = glyph_y_max(&f, gid)? else {
+            continue;
+        };
+        let Some(tsb) = vmtx.side_bearing(gid) else {
+            continue;
+        };
+        let origin = tsb as i32 + y_max;
+        let expected = vorg
+            .as_ref()
+            .map(|vorg| vorg.vertical_origin_y(gid) as i32)
+            .unwrap_or(typo_ascender);
+        if (origin - expected).abs() > TOLERANCE {
+            mismatches.push(format!(
+                "{}: top side bearing + yMax is {origin}, expected {expected}",
+                f.glyph_name_for_id_synthesise(gid)
+            ));
+        }
+    }
+    if !mismatches.is_empty() {
+        problems.push(Status::warn(
+            "inconsistent-vertical-origin",
+            &format!(
+                "The following glyphs have a vertical origin which does not match {}:\n\n{}",
+                if vorg.is_some() {
+                    "the 'VORG' table"
+                } else {
+                    "OS/2.sTypoAscender"
+                },
+                bullet_list(context, mismatches)
+            ),
+        ));
+    }
+    return_result(problems)
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use fontations::{
+        skrifa::raw::TableProvider,
+        write::{from_obj::ToOwnedTable, tables::os2::Os2},
+    };
+    use fontspector_checkapi::{
+        codetesting::{assert_pass, assert_results_contain, assert_skip, run_check, test_able},
+        FileTypeConvert, StatusCode, TTF,
+    };
+
+    const SHANTELL: &str = "shantell/ShantellSans[BNCE,INFM,SPAC,wght].ttf";
+
+    #[test]
+    fn test_origins_pass() {
+        let testable = test_able(SHANTELL);
+        let results = run_check(super::origins, testable);
+        assert_pass(&results);
+    }
+
+    #[test]
+    fn test_origins_skip() {
+        let testable = test_able("mada/Mada-Regular.ttf");
+        let results = run_check(super::origins, testable);
+        assert_skip(&results);
+    }
+
+    #[test]
+    fn test_origins_mismatch() {
+        let mut testable = test_able(SHANTELL);
+        let f = TTF.from_testable(&testable).unwrap();
+        let mut os2: Os2 = f.font().os2().unwrap().to_owned_table();
+        os2.s_typo_ascender = 900;
+        testable.set(f.rebuild_with_new_table(&os2).unwrap());
+        let results = run_check(super::origins, testable);
+        assert_results_contain(
+            &results,
+            StatusCode::Warn,
+            Some("inconsistent-vertical-origin".to_string()),
+        );
+    }
+}
@@ -0,0 +1,177 @@
+use fontations::skrifa::{raw::TableProvider, GlyphId, Tag};
+use fontspector_checkapi::{prelude::*, skip, testfont, FileTypeConvert};
+
+use super::{has_vertical_features, has_vertical_metrics};
+
+#[check(
+    id = "opentype/vertical/vhea_vmtx",
+    rationale = "
+        Fonts which are intended to be set vertically need a 'vhea' and a 'vmtx'
+        table, which together provide the advance height and top side bearing
+        of every glyph. The two tables only make sense as a pair, and a font
+        which has vertical alternates or a 'VORG' table but no vertical metrics
+        will be set with synthesised advances, which rarely look right.
+
+        The 'vmtx' table is sized from the numberOfLongVerMetrics field of
+        'vhea' and maxp.numGlyphs: it must hold that many full metrics records,
+        followed by one top side bearing for each of the remaining glyphs.
+        Disagreement between these tables causes rasterizers to read past the
+        end of the table or to ignore the vertical metrics altogether.
+        vhea.advanceHeightMax should also be the largest advance in 'vmtx'.
+    ",
+    proposal = "https://learn.microsoft.com/en-us/typography/opentype/spec/vmtx",
+    title = "Are the vhea and vmtx tables present and consistent with maxp?"
+)]
+fn vhea_vmtx(t: &Testable, _context: &Context) -> CheckFnResult {
+    let f = testfont!(t);
+    let has_vhea = f.has_table(b"vhea");
+    let has_vmtx = f.has_table(b"vmtx");
+    skip!(
+        !has_vhea && !has_vmtx && !f.has_table(b"VORG") && !has_vertical_features(&f),
+        "not-vertical",
+        "Font does not support vertical typesetting"
+    );
+    if !has_vertical_metrics(&f) {
+        return Ok(if has_vhea {
+            Status::just_one_fail(
+                "missing-vmtx",
+                "Font has a 'vhea' table but no 'vmtx' table.",
+            )
+        } else if has_vmtx {
+            Status::just_one_fail(
+                "missing-vhea",
+                "Font has a 'vmtx' table but no 'vhea' table.",
+            )
+        } else {
+            Status::just_one_warn(
+                "missing-vertical-tables",
+                "Font has vertical alternates or a 'VORG' table, but no 'vhea' and 'vmtx' tables.",
+            )
+        });
+    }
+
+    let vhea = f.font().vhea()?;
+    let num_glyphs = f.font().maxp()?.num_glyphs();
+    let num_long_metrics = vhea.number_of_long_ver_metrics();
+    if num_long_metrics == 0 || num_long_metrics > num_glyphs {
+        return Ok(Status::just_one_fail(
+            "bad-number-of-metrics",
+            &format!(
+                "vhea.numberOfLongVerMetrics is {num_long_metrics}, but it must be between 1 and maxp.numGlyphs ({num_glyphs})."
+            ),
+        ));
+    }
+    let expected_length =
+        4 * num_long_metrics as usize + 2 * (num_glyphs - num_long_metrics) as usize;
+    let actual_length = f
+        .font()
+        .table_data(Tag::new(b"vmtx"))
+        .map(|data| data.len())
+        .unwrap_or(0);
+    if actual_length < expected_length {
+        return Ok(Status::just_one_fail(
+            "bad-vmtx-length",
+            &format!(
+                "The 'vmtx' table is {actual_length} bytes long, but {num_long_metrics} long metrics and {} top side bearings need {expected_length} bytes.",
+                num_glyphs - num_long_metrics
+            ),
+        ));
+    }
+
+    let mut problems = vec![];
+    let vmtx = f.font().vmtx()?;
+    let max_advance = (0..num_long_metrics)
+        .filter_map(|gid| vmtx.advance(GlyphId::from(gid)))
+        .max()
+        .unwrap_or(0);
+    let advance_height_max = vhea.advance_height_max().to_u16();
+    if advance_height_max != max_advance {
+        problems.push(Status::warn(
+            "bad-advance-height-max",
+            &format!(
+                "vhea.advanceHeightMax is {advance_height_max}, but the largest advance height in 'vmtx' is {max_advance}."
+            ),
+        ));
+    }
+    return_result(problems)
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use fontations::{
+        skrifa::raw::{types::UfWord, TableProvider},
+        write::{from_obj::ToOwnedTable, tables::vhea::Vhea},
+    };
+    use fontspector_checkapi::{
+        codetesting::{
+            assert_pass, assert_results_contain, assert_skip, remove_table, run_check, test_able,
+        },
+        FileTypeConvert, StatusCode, Testable, TTF,
+    };
+
+    const SHANTELL: &str = "shantell/ShantellSans[BNCE,INFM,SPAC,wght].ttf";
+
+    fn modify_vhea(testable: &mut Testable, modify: impl Fn(&mut Vhea)) {
+        let f = TTF.from_testable(testable).unwrap();
+        let mut vhea: Vhea = f.font().vhea().unwrap().to_owned_table();
+        modify(&mut vhea);
+        let new_font = f.rebuild_with_new_table(&vhea).unwrap();
+        testable.set(new_font);
+    }
+
+    #[test]
+    fn test_vhea_vmtx_pass() {
+        let testable = test_able(SHANTELL);
+        let results = run_check(super::vhea_vmtx, testable);
+        assert_pass(&results);
+    }
+
+    #[test]
+    fn test_vhea_vmtx_skip() {
+        let testable = test_able("mada/Mada-Regular.ttf");
+        let results = run_check(super::vhea_vmtx, testable);
+        assert_skip(&results);
+    }
+
+    #[test]
+    fn test_vhea_vmtx_missing_table() {
+        let mut testable = test_able(SHANTELL);
+        remove_table(&mut testable, b"vhea");
+        let results = run_check(super::vhea_vmtx, testable);
+        assert_results_contain(&results, StatusCode::Fail, Some("missing-vhea".to_string()));
+    }
+
+    #[test]
+    fn test_vhea_vmtx_bad_metrics() {
+        let mut testable = test_able(SHANTELL);
+        modify_vhea(&mut testable, |vhea| vhea.number_of_long_ver_metrics = 0);
+        let results = run_check(super::vhea_vmtx, testable);
+        assert_results_contain(
+            &results,
+            StatusCode::Fail,
+            Some("bad-number-of-metrics".to_string()),
+        );
+
+        // All glyphs claim a full record, but the table isn't long enough
+        let mut testable = test_able(SHANTELL);
+        modify_vhea(&mut testable, |vhea| vhea.number_of_long_ver_metrics = 1728);
+        let results = run_check(super::vhea_vmtx, testable);
+        assert_results_contain(
+            &results,
+            StatusCode::Fail,
+            Some("bad-vmtx-length".to_string()),
+        );
+
+        let mut testable = test_able(SHANTELL);
+        modify_vhea(&mut testable, |vhea| {
+            vhea.advance_height_max = UfWord::new(1000)
+        });
+        let results = run_check(super::vhea_vmtx, testable);
+        assert_results_contain(
+            &results,
+            StatusCode::Warn,
+            Some("bad-advance-height-max".to_string()),
+        );
+    }
+}
@@ -9,12 +9,17 @@ impl fontspector_checkapi::ProfileProvider for OpenType {
         let builder = ProfileBuilder::new();
         builder
             .add_section("Opentype Specification Checks")
+            // Run first, so that structural problems are reported before
+            // any other check trips over them
+            .add_and_register_check(checks::opentype::font_structure)
             .add_and_register_check(checks::opentype::caret_slope)
             // We don't have enough CFF support here:
-            //   - "opentype/CFF2_call_depth"
             //   - "opentype/CFF_ascii_strings"
-            //   - "opentype/CFF_call_depth"
-            //   - "opentype/CFF_deprecated_operators"
+            .add_and_register_check(checks::opentype::cff::CFF2_call_depth)
+            .add_and_register_check(checks::opentype::cff::CFF_call_depth)
+            .add_and_register_check(checks::opentype::cff::CFF_deprecated_operators)
+            .add_and_register_check(checks::opentype::cff::CFF_font_matrix)
+            .add_and_register_check(checks::opentype::cff::CFF_hintmask)
             .add_and_register_check(checks::opentype::code_pages)
             .add_and_register_check(checks::opentype::family::bold_italic_unique_for_nameid1)
             .add_and_register_check(checks::opentype::family::consistent_family_name)
@@ -62,6 +67,10 @@ impl fontspector_checkapi::ProfileProvider for OpenType {
             .add_and_register_check(checks::opentype::varfont::valid_default_instance_nameids)
             .add_and_register_check(checks::opentype::varfont::valid_nameids)
             .add_and_register_check(checks::opentype::vendor_id)
+            .add_and_register_check(checks::opentype::vertical::alternates)
+            .add_and_register_check(checks::opentype::vertical::origins)
+            .add_and_register_check(checks::opentype::vertical::vhea_vmtx)
+            .add_and_register_check(checks::opentype::vertical::VORG)
             .add_and_register_check(checks::opentype::weight_class_fvar)
             .add_and_register_check(checks::opentype::xavgcharwidth)
             .build("opentype", cr)
@@ -0,0 +1,70 @@
+use fontations::skrifa::MetadataProvider;
+use fontspector_checkapi::{prelude::*, testfont, FileTypeConvert};
+
+fn is_pua(codepoint: u32) -> bool {
+    (0xE000..=0xF8FF).contains(&codepoint)
+        || (0xF0000..=0xFFFFD).contains(&codepoint)
+        || (0x100000..=0x10FFFD).contains(&codepoint)
+}
+
+#[check(
+    id = "typenetwork/PUA_encoded_glyphs",
+    rationale = "
+        Using Private Use Area (PUA) codepoints to give glyphs access from
+        the keyboard is a bad practice: the meaning of those codepoints is
+        not standardised, so text relying on them is not portable, cannot be
+        searched and is inaccessible. Alternate glyphs should be reached
+        through OpenType features instead.
+    ",
+    proposal = "https://github.com/fonttools/fontbakery/issues/4829", // legacy check
+    title = "Check if fonts have glyphs with PUA codepoints."
+)]
+fn PUA_encoded_glyphs(t: &Testable, context: &Context) -> CheckFnResult {
+    let f = testfont!(t);
+    let pua_glyphs = f
+        .font()
+        .charmap()
+        .mappings()
+        .filter(|(codepoint, _)| is_pua(*codepoint))
+        .map(|(codepoint, gid)| {
+            format!(
+                "U+{codepoint:04X} ({})",
+                f.glyph_name_for_id_synthesise(gid)
+            )
+        })
+        .collect::<Vec<_>>();
+    Ok(if pua_glyphs.is_empty() {
+        Status::just_one_pass()
+    } else {
+        Status::just_one_warn(
+            "pua-encoded",
+            &format!(
+                "Glyphs with PUA codepoints:\n\n{}",
+                bullet_list(context, pua_glyphs)
+            ),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fontspector_checkapi::{
+        codetesting::{assert_pass, assert_results_contain, run_check, test_able},
+        StatusCode,
+    };
+
+    #[test]
+    fn test_PUA_encoded_glyphs_pass() {
+        let testable = test_able("cabin/Cabin-Regular.ttf");
+        let results = run_check(PUA_encoded_glyphs, testable);
+        assert_pass(&results);
+    }
+
+    #[test]
+    fn test_PUA_encoded_glyphs_warn() {
+        let testable = test_able("nunito/Nunito-Regular.ttf");
+        let results = run_check(PUA_encoded_glyphs, testable);
+        assert_results_contain(&results, StatusCode::Warn, Some("pua-encoded".to_string()));
+    }
+}
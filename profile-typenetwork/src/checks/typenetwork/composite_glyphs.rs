@@ -0,0 +1,86 @@
+use std::collections::HashSet;
+
+use fontations::skrifa::{raw::tables::glyf::Glyph, MetadataProvider};
+use fontspector_checkapi::{prelude::*, skip, testfont, FileTypeConvert};
+
+#[check(
+    id = "typenetwork/composite_glyphs",
+    rationale = "
+        For performance reasons, it is recommended that TTF fonts use composite
+        glyphs: accented letters and other glyphs built from existing shapes
+        should reference those shapes as components rather than repeat
+        their outlines.
+    ",
+    proposal = "https://github.com/fonttools/fontbakery/issues/4829", // legacy check
+    title = "Check if TTF font uses composite glyphs."
+)]
+fn composite_glyphs(t: &Testable, context: &Context) -> CheckFnResult {
+    let f = testfont!(t);
+    skip!(
+        !f.has_table(b"glyf"),
+        "not-ttf",
+        "Font does not have TrueType outlines"
+    );
+    // The printable ASCII characters are base shapes, and are never expected to be composites
+    let charmap = f.font().charmap();
+    let base_glyphs = (0x20u32..0x7F)
+        .filter_map(|codepoint| charmap.map(codepoint))
+        .collect::<HashSet<_>>();
+    let glyph_count = f.all_glyphs().count();
+    skip!(glyph_count == 0, "no-glyphs", "Font has no glyphs");
+    let mut not_composite = 0;
+    for gid in f.all_glyphs() {
+        context.check_deadline()?;
+        if base_glyphs.contains(&gid) {
+            continue;
+        }
+        if let Some(Glyph::Simple(simple)) = f.get_glyf_glyph(gid)? {
+            if simple.number_of_contours() > 0 {
+                not_composite += 1;
+            }
+        }
+    }
+    let percentage = not_composite * 100 / glyph_count;
+    Ok(if percentage > 50 {
+        Status::just_one_warn(
+            "low-composites",
+            &format!("{percentage}% of the glyphs are not composites."),
+        )
+    } else {
+        Status::just_one_pass()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fontspector_checkapi::{
+        codetesting::{assert_pass, assert_results_contain, assert_skip, run_check, test_able},
+        StatusCode,
+    };
+
+    #[test]
+    fn test_composite_glyphs_pass() {
+        let testable = test_able("cabin/Cabin-Regular.ttf");
+        let results = run_check(composite_glyphs, testable);
+        assert_pass(&results);
+    }
+
+    #[test]
+    fn test_composite_glyphs_skip_cff() {
+        let testable = test_able("source-sans-pro/OTF/SourceSansPro-Regular.otf");
+        let results = run_check(composite_glyphs, testable);
+        assert_skip(&results);
+    }
+
+    #[test]
+    fn test_composite_glyphs_warn() {
+        let testable = test_able("indic-font-without-rupee-sign/NotoSansOlChiki-Regular.ttf");
+        let results = run_check(composite_glyphs, testable);
+        assert_results_contain(
+            &results,
+            StatusCode::Warn,
+            Some("low-composites".to_string()),
+        );
+    }
+}
@@ -0,0 +1,97 @@
+use std::collections::HashMap;
+
+use fontations::skrifa::string::StringId;
+use fontspector_checkapi::{prelude::*, FileTypeConvert};
+
+#[check(
+    id = "typenetwork/family/duplicated_names",
+    rationale = "
+        Having duplicated full font names or PostScript names in a family
+        causes clashes when the fonts are installed, and one font will
+        shadow another.
+    ",
+    proposal = "https://github.com/fonttools/fontbakery/issues/4829", // legacy check
+    title = "Check if font doesn't have duplicated names within a family.",
+    implementation = "all"
+)]
+fn family_duplicated_names(c: &TestableCollection, _context: &Context) -> CheckFnResult {
+    let fonts = TTF.from_collection(c);
+    let mut problems = vec![];
+    for (name_id, label) in [
+        (StringId::FULL_NAME, "full font name"),
+        (StringId::POSTSCRIPT_NAME, "PostScript name"),
+    ] {
+        let mut seen: HashMap<String, Vec<String>> = HashMap::new();
+        for font in fonts.iter() {
+            let basename = font
+                .filename
+                .file_name()
+                .map(|x| x.to_string_lossy().to_string())
+                .unwrap_or("A font".to_string());
+            if let Some(name) = font.get_best_name(&[name_id]) {
+                seen.entry(name).or_default().push(basename);
+            }
+        }
+        let mut duplicates = seen
+            .into_iter()
+            .filter(|(_, files)| files.len() > 1)
+            .collect::<Vec<_>>();
+        duplicates.sort();
+        for (name, files) in duplicates {
+            problems.push(Status::fail(
+                "duplicated-font-names",
+                &format!(
+                    "The {label} '{name}' is used by more than one font: {}",
+                    files.join(", ")
+                ),
+            ));
+        }
+    }
+    return_result(problems)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::family_duplicated_names;
+    use fontspector_checkapi::{
+        codetesting::{assert_pass, assert_results_contain, run_check_with_config, test_able},
+        StatusCode, TestableCollection, TestableType,
+    };
+
+    #[test]
+    fn test_family_duplicated_names_pass() {
+        let testables = vec![
+            test_able("cabin/Cabin-Regular.ttf"),
+            test_able("cabin/Cabin-Bold.ttf"),
+            test_able("cabin/Cabin-Italic.ttf"),
+            test_able("cabin/Cabin-BoldItalic.ttf"),
+        ];
+        let collection = TestableCollection::from_testables(testables, None);
+        let results = run_check_with_config(
+            family_duplicated_names,
+            TestableType::Collection(&collection),
+            HashMap::new(),
+        );
+        assert_pass(&results);
+    }
+
+    #[test]
+    fn test_family_duplicated_names_fail() {
+        let mut duplicate = test_able("cabin/Cabin-Regular.ttf");
+        duplicate.set_filename("Cabin-Copy.ttf");
+        let testables = vec![test_able("cabin/Cabin-Regular.ttf"), duplicate];
+        let collection = TestableCollection::from_testables(testables, None);
+        let results = run_check_with_config(
+            family_duplicated_names,
+            TestableType::Collection(&collection),
+            HashMap::new(),
+        );
+        assert_results_contain(
+            &results,
+            StatusCode::Fail,
+            Some("duplicated-font-names".to_string()),
+        );
+    }
+}
@@ -0,0 +1,93 @@
+use fontations::skrifa::raw::TableProvider;
+use fontspector_checkapi::{prelude::*, FileTypeConvert};
+
+#[check(
+    id = "typenetwork/family/equal_numbers_of_glyphs",
+    rationale = "
+        Check if all fonts in a family have the same number of glyphs.
+        Fonts in a family are normally drawn from the same glyph set, so a
+        differing glyph count is a sign that a glyph was forgotten in one
+        of the styles.
+    ",
+    proposal = "https://github.com/fonttools/fontbakery/issues/4829", // legacy check
+    title = "Equal number of glyphs",
+    implementation = "all"
+)]
+fn family_equal_numbers_of_glyphs(c: &TestableCollection, context: &Context) -> CheckFnResult {
+    let fonts = TTF.from_collection(c);
+    let mut counts = vec![];
+    for font in fonts.iter() {
+        let basename = font
+            .filename
+            .file_name()
+            .map(|x| x.to_string_lossy().to_string())
+            .unwrap_or("A font".to_string());
+        counts.push((basename, font.font().maxp()?.num_glyphs()));
+    }
+    let Some(max_count) = counts.iter().map(|(_, count)| *count).max() else {
+        return Ok(Status::just_one_pass());
+    };
+    let diverging = counts
+        .iter()
+        .filter(|(_, count)| *count != max_count)
+        .map(|(name, count)| format!("{name}: {count} glyphs"))
+        .collect::<Vec<_>>();
+    Ok(if diverging.is_empty() {
+        Status::just_one_pass()
+    } else {
+        Status::just_one_warn(
+            "glyph-count-diverges",
+            &format!(
+                "The largest font in the family has {max_count} glyphs, but these fonts have fewer:\n\n{}",
+                bullet_list(context, diverging)
+            ),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::family_equal_numbers_of_glyphs;
+    use fontspector_checkapi::{
+        codetesting::{assert_pass, assert_results_contain, run_check_with_config, test_able},
+        StatusCode, TestableCollection, TestableType,
+    };
+
+    #[test]
+    fn test_family_equal_numbers_of_glyphs_pass() {
+        let testables = vec![
+            test_able("cabin/Cabin-Regular.ttf"),
+            test_able("cabin/Cabin-Bold.ttf"),
+            test_able("cabin/Cabin-Italic.ttf"),
+            test_able("cabin/Cabin-BoldItalic.ttf"),
+        ];
+        let collection = TestableCollection::from_testables(testables, None);
+        let results = run_check_with_config(
+            family_equal_numbers_of_glyphs,
+            TestableType::Collection(&collection),
+            HashMap::new(),
+        );
+        assert_pass(&results);
+    }
+
+    #[test]
+    fn test_family_equal_numbers_of_glyphs_warn() {
+        let testables = vec![
+            test_able("mada/Mada-Regular.ttf"),
+            test_able("mada/Mada-Black.ttf"),
+        ];
+        let collection = TestableCollection::from_testables(testables, None);
+        let results = run_check_with_config(
+            family_equal_numbers_of_glyphs,
+            TestableType::Collection(&collection),
+            HashMap::new(),
+        );
+        assert_results_contain(
+            &results,
+            StatusCode::Warn,
+            Some("glyph-count-diverges".to_string()),
+        );
+    }
+}
@@ -0,0 +1,122 @@
+use std::collections::BTreeMap;
+
+use fontations::skrifa::raw::TableProvider;
+use fontspector_checkapi::{prelude::*, skip, FileTypeConvert};
+
+#[check(
+    id = "typenetwork/family/tnum_horizontal_metrics",
+    rationale = "
+        Tabular figures need to have the same metrics in all styles in order
+        to allow tables to be set with proper typographic control, but to
+        maintain the placement of decimals and numeric columns between rows.
+    ",
+    proposal = "https://github.com/fonttools/fontbakery/issues/4829", // legacy check
+    title = "All tabular figures must have the same width across the RIBBI-family.",
+    implementation = "all"
+)]
+fn family_tnum_horizontal_metrics(c: &TestableCollection, context: &Context) -> CheckFnResult {
+    let fonts = TTF.from_collection(c);
+    // Width -> tabular glyphs with that width
+    let mut tnum_widths: BTreeMap<u16, Vec<String>> = BTreeMap::new();
+    for font in fonts.iter().filter(|font| font.is_ribbi()) {
+        let hmtx = font.font().hmtx()?;
+        for gid in font.all_glyphs() {
+            let Some(name) = font.glyph_name_for_id(gid) else {
+                continue;
+            };
+            if !name.ends_with(".tnum") {
+                continue;
+            }
+            if let Some(width) = hmtx.advance(gid) {
+                tnum_widths.entry(width).or_default().push(name);
+            }
+        }
+    }
+    skip!(
+        tnum_widths.is_empty(),
+        "no-tnum-glyphs",
+        "No tabular figures found in the RIBBI fonts of this family"
+    );
+    let most_common = tnum_widths
+        .iter()
+        .max_by_key(|(_, glyphs)| glyphs.len())
+        .map(|(width, _)| *width)
+        .unwrap_or_default();
+    let others = tnum_widths
+        .iter()
+        .filter(|(width, _)| **width != most_common)
+        .map(|(width, glyphs)| format!("{width}: {}", glyphs.join(", ")))
+        .collect::<Vec<_>>();
+    Ok(if others.is_empty() {
+        Status::just_one_pass()
+    } else {
+        Status::just_one_fail(
+            "inconsistent-widths",
+            &format!(
+                "The most common tabular glyph width is {most_common}, but there are other tabular glyphs with different widths:\n\n{}",
+                bullet_list(context, others)
+            ),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::family_tnum_horizontal_metrics;
+    use fontspector_checkapi::{
+        codetesting::{assert_pass, assert_results_contain, run_check_with_config, test_able},
+        StatusCode, TestableCollection, TestableType,
+    };
+
+    #[test]
+    fn test_family_tnum_horizontal_metrics_pass() {
+        let testables = vec![test_able("source-sans-pro/TTF/SourceSansPro-Regular.ttf")];
+        let collection = TestableCollection::from_testables(testables, None);
+        let results = run_check_with_config(
+            family_tnum_horizontal_metrics,
+            TestableType::Collection(&collection),
+            HashMap::new(),
+        );
+        assert_pass(&results);
+    }
+
+    #[test]
+    fn test_family_tnum_horizontal_metrics_skip() {
+        let testables = vec![
+            test_able("cabin/Cabin-Regular.ttf"),
+            test_able("cabin/Cabin-Bold.ttf"),
+        ];
+        let collection = TestableCollection::from_testables(testables, None);
+        let results = run_check_with_config(
+            family_tnum_horizontal_metrics,
+            TestableType::Collection(&collection),
+            HashMap::new(),
+        );
+        assert_results_contain(
+            &results,
+            StatusCode::Skip,
+            Some("no-tnum-glyphs".to_string()),
+        );
+    }
+
+    #[test]
+    fn test_family_tnum_horizontal_metrics_fail() {
+        let testables = vec![
+            test_able("source-sans-pro/TTF/SourceSansPro-Regular.ttf"),
+            test_able("source-sans-pro/TTF/SourceSansPro-Bold.ttf"),
+        ];
+        let collection = TestableCollection::from_testables(testables, None);
+        let results = run_check_with_config(
+            family_tnum_horizontal_metrics,
+            TestableType::Collection(&collection),
+            HashMap::new(),
+        );
+        assert_results_contain(
+            &results,
+            StatusCode::Fail,
+            Some("inconsistent-widths".to_string()),
+        );
+    }
+}
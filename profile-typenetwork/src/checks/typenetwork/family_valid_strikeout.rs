@@ -0,0 +1,84 @@
+use fontations::skrifa::raw::TableProvider;
+use fontspector_checkapi::{prelude::*, FileTypeConvert};
+
+#[check(
+    id = "typenetwork/family/valid_strikeout",
+    rationale = "
+        If the strikeout size in the OS/2 table is not set, some applications
+        (Figma, for example) do not draw a strikethrough at all.
+    ",
+    proposal = "https://github.com/fonttools/fontbakery/issues/4829", // legacy check
+    title = "Fonts have a valid strikeout size?",
+    implementation = "all"
+)]
+fn family_valid_strikeout(c: &TestableCollection, _context: &Context) -> CheckFnResult {
+    let fonts = TTF.from_collection(c);
+    let mut problems = vec![];
+    for font in fonts.iter() {
+        let basename = font
+            .filename
+            .file_name()
+            .map(|x| x.to_string_lossy().to_string())
+            .unwrap_or("A font".to_string());
+        let size = font.font().os2()?.y_strikeout_size();
+        if size <= 0 {
+            problems.push(Status::fail(
+                "invalid-strikeout-size",
+                &format!("{basename} has a strikeout size of {size}."),
+            ));
+        }
+    }
+    return_result(problems)
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use std::collections::HashMap;
+
+    use super::family_valid_strikeout;
+    use fontations::{
+        skrifa::raw::TableProvider,
+        write::{from_obj::ToOwnedTable, tables::os2::Os2},
+    };
+    use fontspector_checkapi::{
+        codetesting::{assert_pass, assert_results_contain, run_check_with_config, test_able},
+        FileTypeConvert, StatusCode, TestableCollection, TestableType, TTF,
+    };
+
+    #[test]
+    fn test_family_valid_strikeout_pass() {
+        let testables = vec![
+            test_able("cabin/Cabin-Regular.ttf"),
+            test_able("cabin/Cabin-Bold.ttf"),
+        ];
+        let collection = TestableCollection::from_testables(testables, None);
+        let results = run_check_with_config(
+            family_valid_strikeout,
+            TestableType::Collection(&collection),
+            HashMap::new(),
+        );
+        assert_pass(&results);
+    }
+
+    #[test]
+    fn test_family_valid_strikeout_fail() {
+        let mut bold = test_able("cabin/Cabin-Bold.ttf");
+        let f = TTF.from_testable(&bold).unwrap();
+        let mut os2: Os2 = f.font().os2().unwrap().to_owned_table();
+        os2.y_strikeout_size = 0;
+        bold.set(f.rebuild_with_new_table(&os2).unwrap());
+        let testables = vec![test_able("cabin/Cabin-Regular.ttf"), bold];
+        let collection = TestableCollection::from_testables(testables, None);
+        let results = run_check_with_config(
+            family_valid_strikeout,
+            TestableType::Collection(&collection),
+            HashMap::new(),
+        );
+        assert_results_contain(
+            &results,
+            StatusCode::Fail,
+            Some("invalid-strikeout-size".to_string()),
+        );
+    }
+}
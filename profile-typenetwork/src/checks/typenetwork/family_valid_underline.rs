@@ -0,0 +1,84 @@
+use fontations::skrifa::raw::TableProvider;
+use fontspector_checkapi::{prelude::*, FileTypeConvert};
+
+#[check(
+    id = "typenetwork/family/valid_underline",
+    rationale = "
+        If the underline thickness in the post table is not set, some
+        applications (Figma, for example) do not draw an underline at all.
+    ",
+    proposal = "https://github.com/fonttools/fontbakery/issues/4829", // legacy check
+    title = "Fonts have a valid underline thickness?",
+    implementation = "all"
+)]
+fn family_valid_underline(c: &TestableCollection, _context: &Context) -> CheckFnResult {
+    let fonts = TTF.from_collection(c);
+    let mut problems = vec![];
+    for font in fonts.iter() {
+        let basename = font
+            .filename
+            .file_name()
+            .map(|x| x.to_string_lossy().to_string())
+            .unwrap_or("A font".to_string());
+        let thickness = font.font().post()?.underline_thickness().to_i16();
+        if thickness <= 0 {
+            problems.push(Status::fail(
+                "invalid-underline-thickness",
+                &format!("{basename} has an underline thickness of {thickness}."),
+            ));
+        }
+    }
+    return_result(problems)
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use std::collections::HashMap;
+
+    use super::family_valid_underline;
+    use fontations::{
+        skrifa::raw::TableProvider,
+        write::{from_obj::ToOwnedTable, tables::post::Post, types::FWord},
+    };
+    use fontspector_checkapi::{
+        codetesting::{assert_pass, assert_results_contain, run_check_with_config, test_able},
+        FileTypeConvert, StatusCode, TestableCollection, TestableType, TTF,
+    };
+
+    #[test]
+    fn test_family_valid_underline_pass() {
+        let testables = vec![
+            test_able("cabin/Cabin-Regular.ttf"),
+            test_able("cabin/Cabin-Bold.ttf"),
+        ];
+        let collection = TestableCollection::from_testables(testables, None);
+        let results = run_check_with_config(
+            family_valid_underline,
+            TestableType::Collection(&collection),
+            HashMap::new(),
+        );
+        assert_pass(&results);
+    }
+
+    #[test]
+    fn test_family_valid_underline_fail() {
+        let mut bold = test_able("cabin/Cabin-Bold.ttf");
+        let f = TTF.from_testable(&bold).unwrap();
+        let mut post: Post = f.font().post().unwrap().to_owned_table();
+        post.underline_thickness = FWord::new(0);
+        bold.set(f.rebuild_with_new_table(&post).unwrap());
+        let testables = vec![test_able("cabin/Cabin-Regular.ttf"), bold];
+        let collection = TestableCollection::from_testables(testables, None);
+        let results = run_check_with_config(
+            family_valid_underline,
+            TestableType::Collection(&collection),
+            HashMap::new(),
+        );
+        assert_results_contain(
+            &results,
+            StatusCode::Fail,
+            Some("invalid-underline-thickness".to_string()),
+        );
+    }
+}
@@ -0,0 +1,72 @@
+use fontations::skrifa::raw::TableProvider;
+use fontspector_checkapi::{prelude::*, skip, testfont, FileTypeConvert};
+
+/// How far apart, as a proportion of the space outside the cap-height, the
+/// space above and below the capitals may be before we consider the font off-centre
+const TOLERANCE: f32 = 0.2;
+
+#[check(
+    id = "typenetwork/font_is_centered_vertically",
+    rationale = "
+        UI elements such as buttons and input fields usually centre
+        their text using the font's ascender and descender. If the capitals
+        do not sit in the middle of that range, text looks shifted up or
+        down inside these elements.
+
+        This check compares the space between the cap-height and the hhea
+        ascender with the space between the baseline and the hhea descender.
+    ",
+    proposal = "https://github.com/fonttools/fontbakery/issues/4829", // legacy check
+    title = "Checking if font is vertically centered."
+)]
+fn font_is_centered_vertically(t: &Testable, _context: &Context) -> CheckFnResult {
+    let f = testfont!(t);
+    let cap_height = f.font().os2()?.s_cap_height().unwrap_or_default();
+    skip!(
+        cap_height <= 0,
+        "no-cap-height",
+        "The font does not declare a cap-height in its OS/2 table"
+    );
+    let hhea = f.font().hhea()?;
+    let above = f32::from(hhea.ascender().to_i16()) - f32::from(cap_height);
+    let below = f32::from(hhea.descender().to_i16()).abs();
+    skip!(
+        above + below <= 0.0,
+        "bad-metrics",
+        "The font's hhea ascender does not reach its cap-height"
+    );
+    let off_centre = (above - below).abs() / (above + below);
+    Ok(if off_centre > TOLERANCE {
+        Status::just_one_warn(
+            "uncentered",
+            &format!(
+                "The font is not vertically centered: there are {above} units between the cap-height and the ascender, but {below} units between the baseline and the descender."
+            ),
+        )
+    } else {
+        Status::just_one_pass()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fontspector_checkapi::{
+        codetesting::{assert_pass, assert_results_contain, run_check, test_able},
+        StatusCode,
+    };
+
+    #[test]
+    fn test_font_is_centered_vertically_pass() {
+        let testable = test_able("cabin/Cabin-Regular.ttf");
+        let results = run_check(font_is_centered_vertically, testable);
+        assert_pass(&results);
+    }
+
+    #[test]
+    fn test_font_is_centered_vertically_warn() {
+        let testable = test_able("indic-font-with-rupee-sign/NotoSerifDevanagari-Regular.ttf");
+        let results = run_check(font_is_centered_vertically, testable);
+        assert_results_contain(&results, StatusCode::Warn, Some("uncentered".to_string()));
+    }
+}
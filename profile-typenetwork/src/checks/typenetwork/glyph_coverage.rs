@@ -0,0 +1,74 @@
+use fontspector_checkapi::{prelude::*, testfont, FileTypeConvert};
+
+/// The minimal character set Type Network expects: Basic Latin, Latin-1
+/// Supplement and the rest of the Windows-1252 and MacRoman repertoire
+const TN_LATIN_SET: [u32; 36] = [
+    0x0131, 0x0152, 0x0153, 0x0160, 0x0161, 0x0178, 0x017D, 0x017E, 0x0192, 0x02C6, 0x02C7, 0x02D8,
+    0x02D9, 0x02DA, 0x02DB, 0x02DC, 0x02DD, 0x2013, 0x2014, 0x2018, 0x2019, 0x201A, 0x201C, 0x201D,
+    0x201E, 0x2020, 0x2021, 0x2022, 0x2026, 0x2030, 0x2039, 0x203A, 0x2044, 0x20AC, 0x2122, 0x2212,
+];
+
+#[check(
+    id = "typenetwork/glyph_coverage",
+    rationale = "
+        Type Network expects that fonts in its catalog support at least the
+        minimal set of characters: Basic Latin, Latin-1 Supplement and the
+        remaining characters of the Windows-1252 and MacRoman encodings.
+    ",
+    proposal = "https://github.com/fonttools/fontbakery/issues/4829", // legacy check
+    title = "Check Type Network minimum glyph coverage."
+)]
+fn glyph_coverage(t: &Testable, context: &Context) -> CheckFnResult {
+    let f = testfont!(t);
+    let codepoints = f.codepoints(Some(context));
+    let missing = (0x0020..=0x007E)
+        .chain(0x00A0..=0x00FF)
+        .chain(TN_LATIN_SET)
+        .filter(|codepoint| !codepoints.contains(codepoint))
+        .map(|codepoint| {
+            let name = char::from_u32(codepoint)
+                .and_then(unicode_names2::name)
+                .map(|name| name.to_string())
+                .unwrap_or_default();
+            format!("U+{codepoint:04X} {name}")
+        })
+        .collect::<Vec<_>>();
+    Ok(if missing.is_empty() {
+        Status::just_one_pass()
+    } else {
+        Status::just_one_fail(
+            "missing-codepoints",
+            &format!(
+                "Missing required codepoints:\n\n{}",
+                bullet_list(context, missing)
+            ),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fontspector_checkapi::{
+        codetesting::{assert_pass, assert_results_contain, run_check, test_able},
+        StatusCode,
+    };
+
+    #[test]
+    fn test_glyph_coverage_pass() {
+        let testable = test_able("cabin/Cabin-Regular.ttf");
+        let results = run_check(glyph_coverage, testable);
+        assert_pass(&results);
+    }
+
+    #[test]
+    fn test_glyph_coverage_fail() {
+        let testable = test_able("mada/Mada-Regular.ttf");
+        let results = run_check(glyph_coverage, testable);
+        assert_results_contain(
+            &results,
+            StatusCode::Fail,
+            Some("missing-codepoints".to_string()),
+        );
+    }
+}
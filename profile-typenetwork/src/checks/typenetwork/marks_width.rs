@@ -0,0 +1,99 @@
+use fontations::skrifa::{raw::TableProvider, MetadataProvider};
+use fontspector_checkapi::{prelude::*, testfont, FileTypeConvert};
+use unicode_properties::{GeneralCategory, UnicodeGeneralCategory};
+
+#[check(
+    id = "typenetwork/marks_width",
+    rationale = "
+        Combining marks are positioned over (or under) the preceding base
+        glyph, so they should have zero advance width. Conversely, spacing
+        modifier letters and symbols (such as the standalone acute accent or
+        tilde) are meant to take up space of their own, so they should not
+        have zero width.
+    ",
+    proposal = "https://github.com/fonttools/fontbakery/issues/4829", // legacy check
+    title = "Check if marks glyphs have zero width and spacing marks have width."
+)]
+fn marks_width(t: &Testable, context: &Context) -> CheckFnResult {
+    let f = testfont!(t);
+    let hmtx = f.font().hmtx()?;
+    let mut marks_with_width = vec![];
+    let mut spacing_without_width = vec![];
+    for (codepoint, gid) in f.font().charmap().mappings() {
+        let Some(category) = char::from_u32(codepoint).map(|c| c.general_category()) else {
+            continue;
+        };
+        let width = hmtx.advance(gid).unwrap_or_default();
+        let description = || {
+            format!(
+                "U+{codepoint:04X} ({}): width {width}",
+                f.glyph_name_for_id_synthesise(gid)
+            )
+        };
+        match category {
+            GeneralCategory::NonspacingMark if width != 0 => marks_with_width.push(description()),
+            GeneralCategory::ModifierLetter | GeneralCategory::ModifierSymbol if width == 0 => {
+                spacing_without_width.push(description())
+            }
+            _ => {}
+        }
+    }
+    let mut problems = vec![];
+    if !marks_with_width.is_empty() {
+        problems.push(Status::fail(
+            "mark-has-width",
+            &format!(
+                "The following combining marks have a non-zero advance width:\n\n{}",
+                bullet_list(context, marks_with_width)
+            ),
+        ));
+    }
+    if !spacing_without_width.is_empty() {
+        problems.push(Status::fail(
+            "spacing-mark-without-width",
+            &format!(
+                "The following spacing modifiers have zero advance width:\n\n{}",
+                bullet_list(context, spacing_without_width)
+            ),
+        ));
+    }
+    return_result(problems)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fontspector_checkapi::{
+        codetesting::{assert_pass, assert_results_contain, run_check, test_able},
+        StatusCode,
+    };
+
+    #[test]
+    fn test_marks_width_pass() {
+        let testable = test_able("cabin/Cabin-Regular.ttf");
+        let results = run_check(marks_width, testable);
+        assert_pass(&results);
+    }
+
+    #[test]
+    fn test_marks_width_mark_has_width() {
+        let testable = test_able("notosans/static/NotoSans-Regular.ttf");
+        let results = run_check(marks_width, testable);
+        assert_results_contain(
+            &results,
+            StatusCode::Fail,
+            Some("mark-has-width".to_string()),
+        );
+    }
+
+    #[test]
+    fn test_marks_width_spacing_mark_without_width() {
+        let testable = test_able("lugrasimo/Lugrasimo-Regular.ttf");
+        let results = run_check(marks_width, testable);
+        assert_results_contain(
+            &results,
+            StatusCode::Fail,
+            Some("spacing-mark-without-width".to_string()),
+        );
+    }
+}
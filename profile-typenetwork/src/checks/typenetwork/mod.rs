@@ -0,0 +1,31 @@
+#![allow(non_snake_case)]
+mod PUA_encoded_glyphs;
+mod composite_glyphs;
+mod family_duplicated_names;
+mod family_equal_numbers_of_glyphs;
+mod family_tnum_horizontal_metrics;
+mod family_valid_strikeout;
+mod family_valid_underline;
+mod font_is_centered_vertically;
+mod glyph_coverage;
+mod marks_width;
+mod name_mandatory_entries;
+mod varfont_axes_have_variation;
+mod varfont_fvar_axes_order;
+mod vertical_metrics;
+mod weightclass;
+pub use composite_glyphs::composite_glyphs;
+pub use family_duplicated_names::family_duplicated_names;
+pub use family_equal_numbers_of_glyphs::family_equal_numbers_of_glyphs;
+pub use family_tnum_horizontal_metrics::family_tnum_horizontal_metrics;
+pub use family_valid_strikeout::family_valid_strikeout;
+pub use family_valid_underline::family_valid_underline;
+pub use font_is_centered_vertically::font_is_centered_vertically;
+pub use glyph_coverage::glyph_coverage;
+pub use marks_width::marks_width;
+pub use name_mandatory_entries::name_mandatory_entries;
+pub use varfont_axes_have_variation::varfont_axes_have_variation;
+pub use varfont_fvar_axes_order::varfont_fvar_axes_order;
+pub use vertical_metrics::vertical_metrics;
+pub use weightclass::weightclass;
+pub use PUA_encoded_glyphs::PUA_encoded_glyphs;
@@ -0,0 +1,87 @@
+use fontations::skrifa::string::StringId;
+use fontspector_checkapi::{prelude::*, testfont, FileTypeConvert};
+
+#[check(
+    id = "typenetwork/name/mandatory_entries",
+    rationale = "
+        For proper functioning, fonts must have some specific records.
+        Other name records are optional but desirable to be present.
+
+        Fonts whose style is not one of Regular, Italic, Bold or Bold Italic
+        must also have typographic family and subfamily names (name IDs 16
+        and 17), so that they can be grouped with the rest of their family.
+    ",
+    proposal = "https://github.com/fonttools/fontbakery/issues/4829", // legacy check
+    title = "Font has all mandatory 'name' table entries?"
+)]
+fn name_mandatory_entries(t: &Testable, _context: &Context) -> CheckFnResult {
+    let f = testfont!(t);
+    let mut required = vec![
+        StringId::FAMILY_NAME,
+        StringId::SUBFAMILY_NAME,
+        StringId::UNIQUE_ID,
+        StringId::FULL_NAME,
+        StringId::VERSION_STRING,
+        StringId::POSTSCRIPT_NAME,
+    ];
+    if !f.is_ribbi() {
+        required.push(StringId::TYPOGRAPHIC_FAMILY_NAME);
+        required.push(StringId::TYPOGRAPHIC_SUBFAMILY_NAME);
+    }
+    let problems = required
+        .into_iter()
+        .filter(|name_id| f.get_name_entry_strings(*name_id).next().is_none())
+        .map(|name_id| {
+            Status::fail(
+                "missing-entry",
+                &format!("Font lacks entry with nameId={name_id}"),
+            )
+        })
+        .collect::<Vec<_>>();
+    return_result(problems)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fontations::skrifa::raw::types::NameId;
+    use fontspector_checkapi::{
+        codetesting::{
+            assert_pass, assert_results_contain, remove_name_entry, run_check, test_able,
+        },
+        StatusCode,
+    };
+
+    #[test]
+    fn test_name_mandatory_entries_pass() {
+        let testable = test_able("cabin/Cabin-Regular.ttf");
+        let results = run_check(name_mandatory_entries, testable);
+        assert_pass(&results);
+    }
+
+    #[test]
+    fn test_name_mandatory_entries_missing_full_name() {
+        let mut testable = test_able("cabin/Cabin-Regular.ttf");
+        remove_name_entry(&mut testable, NameId::FULL_NAME);
+        let results = run_check(name_mandatory_entries, testable);
+        assert_results_contain(
+            &results,
+            StatusCode::Fail,
+            Some("missing-entry".to_string()),
+        );
+    }
+
+    #[test]
+    fn test_name_mandatory_entries_non_ribbi_needs_typographic_names() {
+        let mut testable = test_able("montserrat/Montserrat-Light.ttf");
+        let results = run_check(name_mandatory_entries, testable.clone());
+        assert_pass(&results);
+        remove_name_entry(&mut testable, NameId::TYPOGRAPHIC_FAMILY_NAME);
+        let results = run_check(name_mandatory_entries, testable);
+        assert_results_contain(
+            &results,
+            StatusCode::Fail,
+            Some("missing-entry".to_string()),
+        );
+    }
+}
@@ -0,0 +1,82 @@
+use fontations::skrifa::MetadataProvider;
+use fontspector_checkapi::{prelude::*, testfont, FileTypeConvert};
+
+#[check(
+    id = "typenetwork/varfont/axes_have_variation",
+    conditions = ["is_variable_font"],
+    rationale = "
+        Axes on a variable font must have variation. In other words min and
+        max values need to be different. It's common to find fonts with unnecessary
+        axes added like 'ital'.
+    ",
+    proposal = "https://github.com/fonttools/fontbakery/issues/4829", // legacy check
+    title = "Check if font axes have variation"
+)]
+fn varfont_axes_have_variation(t: &Testable, _context: &Context) -> CheckFnResult {
+    let f = testfont!(t);
+    let problems = f
+        .font()
+        .axes()
+        .iter()
+        .filter(|axis| axis.min_value() == axis.max_value())
+        .map(|axis| {
+            Status::fail(
+                "axis-has-no-variation",
+                &format!(
+                    "'{}' axis has no variation; its min and max values are both {}.",
+                    axis.tag(),
+                    axis.min_value()
+                ),
+            )
+        })
+        .collect::<Vec<_>>();
+    return_result(problems)
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+    use fontations::{
+        skrifa::raw::TableProvider,
+        write::{from_obj::ToOwnedTable, tables::fvar::Fvar, types::Tag},
+    };
+    use fontspector_checkapi::{
+        codetesting::{assert_pass, assert_results_contain, assert_skip, run_check, test_able},
+        StatusCode, TTF,
+    };
+
+    #[test]
+    fn test_varfont_axes_have_variation_pass() {
+        let testable = test_able("cabinvf/Cabin[wdth,wght].ttf");
+        let results = run_check(varfont_axes_have_variation, testable);
+        assert_pass(&results);
+    }
+
+    #[test]
+    fn test_varfont_axes_have_variation_skip_static() {
+        let testable = test_able("cabin/Cabin-Regular.ttf");
+        let results = run_check(varfont_axes_have_variation, testable);
+        assert_skip(&results);
+    }
+
+    #[test]
+    fn test_varfont_axes_have_variation_fail() {
+        let mut testable = test_able("cabinvf/Cabin[wdth,wght].ttf");
+        let f = TTF.from_testable(&testable).unwrap();
+        let mut fvar: Fvar = f.font().fvar().unwrap().to_owned_table();
+        for axis in &mut fvar.axis_instance_arrays.axes {
+            if axis.axis_tag == Tag::new(b"wdth") {
+                axis.min_value = axis.max_value;
+                axis.default_value = axis.max_value;
+            }
+        }
+        testable.set(f.rebuild_with_new_table(&fvar).unwrap());
+        let results = run_check(varfont_axes_have_variation, testable);
+        assert_results_contain(
+            &results,
+            StatusCode::Fail,
+            Some("axis-has-no-variation".to_string()),
+        );
+    }
+}
@@ -0,0 +1,85 @@
+use fontations::skrifa::MetadataProvider;
+use fontspector_checkapi::{prelude::*, testfont, FileTypeConvert};
+
+/// The order Type Network expects registered axes to appear in
+const PREFERRED_ORDER: [&str; 5] = ["wght", "wdth", "opsz", "ital", "slnt"];
+
+#[check(
+    id = "typenetwork/varfont/fvar_axes_order",
+    conditions = ["is_variable_font"],
+    rationale = "
+        If a font doesn't have a STAT table, instances get sorted better on
+        Adobe Apps when fvar axes follow a specific order: 'wght', 'wdth',
+        'opsz', 'ital', 'slnt'.
+
+        Custom axes should come after the registered ones.
+    ",
+    proposal = "https://github.com/fonttools/fontbakery/issues/4829", // legacy check
+    title = "Check fvar axes order"
+)]
+fn varfont_fvar_axes_order(t: &Testable, _context: &Context) -> CheckFnResult {
+    let f = testfont!(t);
+    let font_axes = f
+        .font()
+        .axes()
+        .iter()
+        .map(|axis| axis.tag().to_string())
+        .collect::<Vec<_>>();
+    let expected = PREFERRED_ORDER
+        .iter()
+        .map(|tag| tag.to_string())
+        .filter(|tag| font_axes.contains(tag))
+        .chain(
+            font_axes
+                .iter()
+                .filter(|tag| !PREFERRED_ORDER.contains(&tag.as_str()))
+                .cloned(),
+        )
+        .collect::<Vec<_>>();
+    Ok(if font_axes == expected {
+        Status::just_one_pass()
+    } else {
+        Status::just_one_fail(
+            "axes-incorrect-order",
+            &format!(
+                "Font axes are in the order {}, but the expected order is {}.",
+                font_axes.join(", "),
+                expected.join(", ")
+            ),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fontspector_checkapi::{
+        codetesting::{assert_pass, assert_results_contain, assert_skip, run_check, test_able},
+        StatusCode,
+    };
+
+    #[test]
+    fn test_varfont_fvar_axes_order_pass() {
+        let testable = test_able("cabinvf/Cabin[wdth,wght].ttf");
+        let results = run_check(varfont_fvar_axes_order, testable);
+        assert_pass(&results);
+    }
+
+    #[test]
+    fn test_varfont_fvar_axes_order_skip_static() {
+        let testable = test_able("cabin/Cabin-Regular.ttf");
+        let results = run_check(varfont_fvar_axes_order, testable);
+        assert_skip(&results);
+    }
+
+    #[test]
+    fn test_varfont_fvar_axes_order_fail() {
+        let testable = test_able("mutatorsans-vf/MutatorSans-VF.ttf");
+        let results = run_check(varfont_fvar_axes_order, testable);
+        assert_results_contain(
+            &results,
+            StatusCode::Fail,
+            Some("axes-incorrect-order".to_string()),
+        );
+    }
+}
@@ -0,0 +1,79 @@
+use fontspector_checkapi::{prelude::*, testfont, FileTypeConvert};
+
+#[check(
+    id = "typenetwork/vertical_metrics",
+    rationale = "
+        Different applications use different sets of vertical metrics:
+        macOS uses the hhea values, while Windows uses either the OS/2 win
+        values or, if the USE_TYPO_METRICS bit of OS/2 fsSelection is set, the
+        OS/2 typo values.
+
+        To get the same line spacing everywhere, Type Network expects the
+        USE_TYPO_METRICS bit to be set and the hhea ascender, descender and
+        line gap to match the OS/2 typo ones.
+    ",
+    proposal = "https://github.com/fonttools/fontbakery/issues/4829", // legacy check
+    title = "Checking vertical metrics."
+)]
+fn vertical_metrics(t: &Testable, _context: &Context) -> CheckFnResult {
+    let f = testfont!(t);
+    let metrics = f.vertical_metrics()?;
+    let mut problems = vec![];
+    if !f.use_typo_metrics()? {
+        problems.push(Status::warn(
+            "bad-fselection-bit7",
+            "OS/2 fsSelection bit 7 (USE_TYPO_METRICS) is not set.",
+        ));
+    }
+    for (field, hhea, typo) in [
+        ("ascender", metrics.hhea_ascent, metrics.os2_typo_ascender),
+        (
+            "descender",
+            metrics.hhea_descent,
+            metrics.os2_typo_descender,
+        ),
+        ("line gap", metrics.hhea_linegap, metrics.os2_typo_linegap),
+    ] {
+        if hhea != typo {
+            problems.push(Status::fail(
+                "hhea-typo-mismatch",
+                &format!(
+                    "The hhea {field} ({hhea}) does not match the OS/2 typo {field} ({typo})."
+                ),
+            ));
+        }
+    }
+    return_result(problems)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fontspector_checkapi::{
+        codetesting::{assert_pass, assert_results_contain, run_check, test_able},
+        StatusCode,
+    };
+
+    #[test]
+    fn test_vertical_metrics_pass() {
+        let testable = test_able("cabin/Cabin-Regular.ttf");
+        let results = run_check(vertical_metrics, testable);
+        assert_pass(&results);
+    }
+
+    #[test]
+    fn test_vertical_metrics_fail() {
+        let testable = test_able("mada/Mada-Regular.ttf");
+        let results = run_check(vertical_metrics, testable);
+        assert_results_contain(
+            &results,
+            StatusCode::Warn,
+            Some("bad-fselection-bit7".to_string()),
+        );
+        assert_results_contain(
+            &results,
+            StatusCode::Fail,
+            Some("hhea-typo-mismatch".to_string()),
+        );
+    }
+}
@@ -0,0 +1,125 @@
+use fontations::skrifa::raw::TableProvider;
+use fontspector_checkapi::{prelude::*, testfont, FileTypeConvert};
+
+/// Weight names, in the order they must be tried (longest match first), and
+/// the usWeightClass values they allow
+const WEIGHTS: [(&str, &[u16]); 12] = [
+    ("extralight", &[200, 275]),
+    ("ultralight", &[200, 275]),
+    ("semibold", &[600]),
+    ("demibold", &[600]),
+    ("extrabold", &[800]),
+    ("ultrabold", &[800]),
+    ("thin", &[100, 250]),
+    ("light", &[300]),
+    ("medium", &[500]),
+    ("bold", &[700]),
+    ("black", &[900]),
+    ("heavy", &[900]),
+];
+
+/// The usWeightClass values a style name allows
+fn expected_weights(style_name: &str) -> &'static [u16] {
+    let style_name = style_name.to_lowercase().replace([' ', '-'], "");
+    WEIGHTS
+        .iter()
+        .find(|(name, _)| style_name.contains(name))
+        .map(|(_, weights)| *weights)
+        .unwrap_or(&[400])
+}
+
+#[check(
+    id = "typenetwork/weightclass",
+    rationale = "
+        The OS/2 usWeightClass value must match the weight of the font.
+
+        For static fonts, the weight is taken from the style name: Thin is
+        100 (or 250, which some Windows applications require), ExtraLight
+        200 (or 275), Light 300, Regular 400, Medium 500, SemiBold 600, Bold
+        700, ExtraBold 800 and Black 900.
+
+        For variable fonts, usWeightClass must match the default value of
+        the 'wght' axis.
+    ",
+    proposal = "https://github.com/fonttools/fontbakery/issues/4829", // legacy check
+    title = "Checking OS/2 usWeightClass."
+)]
+fn weightclass(t: &Testable, _context: &Context) -> CheckFnResult {
+    let f = testfont!(t);
+    let value = f.font().os2()?.us_weight_class();
+    if f.is_variable_font() {
+        let Some(default_wght) = f
+            .default_location()
+            .and_then(|location| location.get("wght").copied())
+        else {
+            return Ok(Status::just_one_pass());
+        };
+        let expected = default_wght.clamp(1.0, 1000.0).round() as u16;
+        return Ok(if value == expected {
+            Status::just_one_pass()
+        } else {
+            Status::just_one_fail(
+                "bad-value",
+                &format!(
+                    "OS/2 usWeightClass is {value}, but should match the default 'wght' value of {default_wght}."
+                ),
+            )
+        });
+    }
+    let style_name = f.best_subfamilyname().unwrap_or("Regular".to_string());
+    let expected = expected_weights(&style_name);
+    Ok(if expected.contains(&value) {
+        Status::just_one_pass()
+    } else {
+        Status::just_one_fail(
+            "bad-value",
+            &format!(
+                "OS/2 usWeightClass is {value}, but for the style '{style_name}' it should be {}.",
+                expected
+                    .iter()
+                    .map(|weight| weight.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" or ")
+            ),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fontspector_checkapi::{
+        codetesting::{assert_pass, assert_results_contain, run_check, test_able},
+        StatusCode,
+    };
+
+    #[test]
+    fn test_expected_weights() {
+        assert_eq!(expected_weights("Regular"), &[400]);
+        assert_eq!(expected_weights("Italic"), &[400]);
+        assert_eq!(expected_weights("SemiBold Italic"), &[600]);
+        assert_eq!(expected_weights("Extra Light"), &[200, 275]);
+        assert_eq!(expected_weights("Condensed Bold"), &[700]);
+        assert_eq!(expected_weights("ExtraBold"), &[800]);
+    }
+
+    #[test]
+    fn test_weightclass_pass() {
+        for font in [
+            "cabin/Cabin-Regular.ttf",
+            "cabin/Cabin-SemiBold.ttf",
+            "montserrat/Montserrat-Light.ttf",
+            "cabinvf/Cabin[wdth,wght].ttf",
+        ] {
+            let results = run_check(weightclass, test_able(font));
+            assert_pass(&results);
+        }
+    }
+
+    #[test]
+    fn test_weightclass_fail() {
+        let testable = test_able("mada/Mada-Regular.ttf");
+        let results = run_check(weightclass, testable);
+        assert_results_contain(&results, StatusCode::Fail, Some("bad-value".to_string()));
+    }
+}
@@ -0,0 +1,31 @@
+//! Type Network Profile for Fontspector Checks
+mod checks;
+
+use fontspector_checkapi::{FontspectorError, Registry};
+
+/// The Type Network profile
+///
+/// The profile itself borrows checks from several other profiles, so it is
+/// defined in TOML and refers to those checks by ID; this crate provides the
+/// checks which are specific to Type Network.
+pub struct TypeNetwork;
+impl fontspector_checkapi::ProfileProvider for TypeNetwork {
+    fn register(&self, cr: &mut Registry) -> Result<(), FontspectorError> {
+        cr.register_check(checks::typenetwork::composite_glyphs);
+        cr.register_check(checks::typenetwork::family_duplicated_names);
+        cr.register_check(checks::typenetwork::family_equal_numbers_of_glyphs);
+        cr.register_check(checks::typenetwork::family_tnum_horizontal_metrics);
+        cr.register_check(checks::typenetwork::family_valid_strikeout);
+        cr.register_check(checks::typenetwork::family_valid_underline);
+        cr.register_check(checks::typenetwork::font_is_centered_vertically);
+        cr.register_check(checks::typenetwork::glyph_coverage);
+        cr.register_check(checks::typenetwork::marks_width);
+        cr.register_check(checks::typenetwork::name_mandatory_entries);
+        cr.register_check(checks::typenetwork::PUA_encoded_glyphs);
+        cr.register_check(checks::typenetwork::varfont_axes_have_variation);
+        cr.register_check(checks::typenetwork::varfont_fvar_axes_order);
+        cr.register_check(checks::typenetwork::vertical_metrics);
+        cr.register_check(checks::typenetwork::weightclass);
+        cr.register_toml_profile("typenetwork", include_str!("../typenetwork.toml"))
+    }
+}
@@ -0,0 +1,356 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use fontations::skrifa::{
+    raw::{
+        tables::bitmap::{BitmapContent, BitmapDataFormat, BitmapMetrics},
+        TableProvider,
+    },
+    GlyphId, GlyphId16, Tag,
+};
+use fontspector_checkapi::{prelude::*, skip, testfont, FileTypeConvert, TestFont};
+
+use super::png::validate_png;
+
+/// The glyphs found in each strike of a bitmap table, and any problems
+#[derive(Default)]
+struct StrikeReport {
+    /// Strike name to glyphs which have an image in that strike
+    coverage: Vec<(String, BTreeSet<GlyphId>)>,
+    problems: BTreeMap<&'static str, Vec<String>>,
+}
+
+impl StrikeReport {
+    fn report(&mut self, code: &'static str, message: String) {
+        self.problems.entry(code).or_default().push(message);
+    }
+}
+
+fn check_sbix(f: &TestFont, report: &mut StrikeReport) -> Result<(), FontspectorError> {
+    let sbix = f.font().sbix()?;
+    for strike in sbix.strikes().iter() {
+        let strike = strike?;
+        let name = format!("sbix strike at {} ppem", strike.ppem());
+        let mut covered = BTreeSet::new();
+        for gid in f.all_glyphs() {
+            match strike.glyph_data(gid) {
+                Ok(Some(data)) => {
+                    covered.insert(gid);
+                    if data.graphic_type() == Tag::new(b"png ") {
+                        if let Err(problem) = validate_png(data.data()) {
+                            report.report(
+                                "bad-png",
+                                format!(
+                                    "{} in {name}: image {problem}",
+                                    f.glyph_name_for_id_synthesise(gid)
+                                ),
+                            );
+                        }
+                    }
+                }
+                Ok(None) => {}
+                Err(e) => report.report(
+                    "malformed-bitmap",
+                    format!("{} in {name}: {e}", f.glyph_name_for_id_synthesise(gid)),
+                ),
+            }
+        }
+        report.coverage.push((name, covered));
+    }
+    Ok(())
+}
+
+fn check_cbdt(f: &TestFont, report: &mut StrikeReport) -> Result<(), FontspectorError> {
+    let cblc = f.font().cblc()?;
+    let cbdt = f.font().cbdt()?;
+    for size in cblc.bitmap_sizes() {
+        let name = format!("CBDT strike at {} ppem", size.ppem_y());
+        let mut covered = BTreeSet::new();
+        let (start, end) = (
+            size.start_glyph_index().to_u16(),
+            size.end_glyph_index().to_u16(),
+        );
+        for gid in (start..=end).map(|gid| GlyphId::from(GlyphId16::new(gid))) {
+            let Ok(location) = size.location(cblc.offset_data(), gid) else {
+                continue;
+            };
+            covered.insert(gid);
+            if location.is_empty() {
+                continue;
+            }
+            let glyph_name = f.glyph_name_for_id_synthesise(gid);
+            match cbdt.data(&location) {
+                Ok(data) => {
+                    let BitmapContent::Data(BitmapDataFormat::Png, image) = data.content else {
+                        continue;
+                    };
+                    let (width, height) = match data.metrics {
+                        BitmapMetrics::Small(metrics) => (metrics.width(), metrics.height()),
+                        BitmapMetrics::Big(metrics) => (metrics.width(), metrics.height()),
+                    };
+                    match validate_png(image) {
+                        Ok(info) => {
+                            if (info.width, info.height) != (width as u32, height as u32) {
+                                report.report(
+                                    "png-size-mismatch",
+                                    format!(
+                                        "{glyph_name} in {name}: image is {}x{}, but the glyph metrics say {width}x{height}",
+                                        info.width, info.height
+                                    ),
+                                );
+                            }
+                        }
+                        Err(problem) => report.report(
+                            "bad-png",
+                            format!("{glyph_name} in {name}: image {problem}"),
+                        ),
+                    }
+                }
+                Err(e) => report.report("malformed-bitmap", format!("{glyph_name} in {name}: {e}")),
+            }
+        }
+        report.coverage.push((name, covered));
+    }
+    Ok(())
+}
+
+#[check(
+    id = "color/bitmap_strikes",
+    rationale = "
+        Color bitmap fonts ('sbix' or 'CBDT'/'CBLC') contain one or more strikes:
+        sets of images drawn for a particular pixel size. Renderers choose the
+        strike closest to the requested size and take every glyph from it, so a
+        glyph which is missing from some strikes will vanish, or fall back to a
+        plain outline, at some sizes but not others. Every strike should cover
+        the same set of color glyphs.
+
+        Almost all color bitmap fonts store their images as PNG. Truncated or
+        corrupt images, and images whose size does not match the glyph metrics
+        in 'CBDT' (which FreeType refuses to load), produce blank glyphs or
+        crash image decoders.
+    ",
+    proposal = "https://learn.microsoft.com/en-us/typography/opentype/spec/cbdt",
+    title = "Do all bitmap strikes cover the color glyphs with valid PNG images?"
+)]
+fn bitmap_strikes(t: &Testable, context: &Context) -> CheckFnResult {
+    let f = testfont!(t);
+    let has_sbix = f.has_table(b"sbix");
+    let has_cbdt = f.has_table(b"CBDT") && f.has_table(b"CBLC");
+    skip!(
+        !has_sbix && !has_cbdt,
+        "no-color-bitmaps",
+        "Font has no sbix or CBDT/CBLC table."
+    );
+    let mut problems = vec![];
+    let mut reports = vec![];
+    if has_sbix {
+        let mut report = StrikeReport::default();
+        check_sbix(&f, &mut report)?;
+        reports.push(report);
+    }
+    if has_cbdt {
+        let mut report = StrikeReport::default();
+        check_cbdt(&f, &mut report)?;
+        reports.push(report);
+    }
+    for mut report in reports {
+        let all_glyphs = report
+            .coverage
+            .iter()
+            .flat_map(|(_, glyphs)| glyphs.iter().copied())
+            .collect::<BTreeSet<_>>();
+        for (strike, glyphs) in std::mem::take(&mut report.coverage) {
+            let missing = all_glyphs.difference(&glyphs).collect::<Vec<_>>();
+            if !missing.is_empty() {
+                report.report(
+                    "incomplete-strike",
+                    format!(
+                        "{strike} has no image for {}",
+                        missing
+                            .iter()
+                            .map(|gid| f.glyph_name_for_id_synthesise(**gid))
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ),
+                );
+            }
+        }
+        for (code, messages) in report.problems {
+            let message = bullet_list(context, messages);
+            problems.push(if code == "incomplete-strike" {
+                Status::warn(
+                    code,
+                    &format!("Some strikes do not cover all color glyphs:\n\n{message}"),
+                )
+            } else {
+                Status::fail(
+                    code,
+                    &format!("The following bitmap glyphs are broken:\n\n{message}"),
+                )
+            });
+        }
+    }
+    return_result(problems)
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use fontations::{
+        skrifa::{FontRef, Tag},
+        write::FontBuilder,
+    };
+    use fontspector_checkapi::{
+        codetesting::{assert_pass, assert_results_contain, assert_skip, run_check, test_able},
+        FileTypeConvert, StatusCode, Testable, TTF,
+    };
+
+    use super::super::png::tests::red_square;
+
+    const FONT: &str = "color_fonts/AmiriQuranColored.ttf";
+
+    fn with_tables(tables: &[(&[u8; 4], Vec<u8>)]) -> Testable {
+        let mut testable = test_able(FONT);
+        let font = FontRef::new(&testable.contents).unwrap();
+        let mut builder = FontBuilder::new();
+        for record in font.table_directory.table_records() {
+            let tag = record.tag.get();
+            if let Some(data) = font.table_data(tag) {
+                builder.add_raw(tag, data);
+            }
+        }
+        for (tag, data) in tables {
+            builder.add_raw(Tag::new(tag), data);
+        }
+        testable.contents = builder.build();
+        testable
+    }
+
+    /// Build an sbix table; each strike is a ppem and a list of (glyph, PNG) pairs
+    fn sbix(strikes: &[(u16, Vec<(u16, Vec<u8>)>)]) -> Vec<u8> {
+        let mut table = vec![0, 1, 0, 1];
+        table.extend((strikes.len() as u32).to_be_bytes());
+        let num_glyphs = TTF.from_testable(&test_able(FONT)).unwrap().glyph_count;
+        let mut strike_data = vec![];
+        let header_length = 8 + 4 * strikes.len();
+        for (ppem, images) in strikes {
+            table.extend(((header_length + strike_data.len()) as u32).to_be_bytes());
+            let mut strike = ppem.to_be_bytes().to_vec();
+            strike.extend(72u16.to_be_bytes());
+            let mut glyph_data = vec![];
+            let offsets_length = 4 + 4 * (num_glyphs + 1);
+            for gid in 0..=num_glyphs as u16 {
+                strike.extend(((offsets_length + glyph_data.len()) as u32).to_be_bytes());
+                if let Some((_, png)) = images.iter().find(|(g, _)| *g == gid) {
+                    glyph_data.extend([0, 0, 0, 0]);
+                    glyph_data.extend(b"png ");
+                    glyph_data.extend(png);
+                }
+            }
+            strike.extend(glyph_data);
+            strike_data.extend(strike);
+        }
+        table.extend(strike_data);
+        table
+    }
+
+    /// Build CBLC and CBDT tables; each strike is a ppem, a first glyph and
+    /// a list of (PNG, width, height) images for consecutive glyphs
+    fn cblc_cbdt(strikes: &[(u8, u16, Vec<(Vec<u8>, u8, u8)>)]) -> (Vec<u8>, Vec<u8>) {
+        let mut cbdt = vec![0, 3, 0, 0];
+        let mut sizes = vec![];
+        let mut subtables = vec![];
+        let subtables_start = 8 + 48 * strikes.len();
+        for (ppem, first, images) in strikes {
+            let last = first + images.len() as u16 - 1;
+            let list_offset = subtables_start + subtables.len();
+            let mut list = first.to_be_bytes().to_vec();
+            list.extend(last.to_be_bytes());
+            list.extend(8u32.to_be_bytes());
+            list.extend(1u16.to_be_bytes());
+            list.extend(17u16.to_be_bytes());
+            let image_data_offset = cbdt.len() as u32;
+            list.extend(image_data_offset.to_be_bytes());
+            for (png, width, height) in images {
+                list.extend((cbdt.len() as u32 - image_data_offset).to_be_bytes());
+                cbdt.extend([*height, *width, 0, 0, *width]);
+                cbdt.extend((png.len() as u32).to_be_bytes());
+                cbdt.extend(png);
+            }
+            list.extend((cbdt.len() as u32 - image_data_offset).to_be_bytes());
+
+            sizes.extend((list_offset as u32).to_be_bytes());
+            sizes.extend((list.len() as u32).to_be_bytes());
+            sizes.extend(1u32.to_be_bytes());
+            sizes.extend(0u32.to_be_bytes());
+            sizes.extend([*ppem, 0, *ppem, 0, 1, 0, 0, 0, *ppem, 0, 0, 0]);
+            sizes.extend([*ppem, 0, *ppem, 0, 1, 0, 0, 0, *ppem, 0, 0, 0]);
+            sizes.extend(first.to_be_bytes());
+            sizes.extend(last.to_be_bytes());
+            sizes.extend([*ppem, *ppem, 32, 1]);
+            subtables.extend(list);
+        }
+        let mut cblc = vec![0, 3, 0, 0];
+        cblc.extend((strikes.len() as u32).to_be_bytes());
+        cblc.extend(sizes);
+        cblc.extend(subtables);
+        (cblc, cbdt)
+    }
+
+    #[test]
+    fn test_bitmap_strikes_skip() {
+        let testable = test_able(FONT);
+        let results = run_check(super::bitmap_strikes, testable);
+        assert_skip(&results);
+    }
+
+    #[test]
+    fn test_bitmap_strikes_sbix() {
+        let images = vec![(1, red_square()), (2, red_square())];
+        let testable = with_tables(&[(b"sbix", sbix(&[(20, images.clone()), (40, images)]))]);
+        let results = run_check(super::bitmap_strikes, testable);
+        assert_pass(&results);
+
+        let mut truncated = red_square();
+        truncated.truncate(40);
+        let testable = with_tables(&[(
+            b"sbix",
+            sbix(&[
+                (20, vec![(1, red_square()), (2, red_square())]),
+                (40, vec![(1, truncated)]),
+            ]),
+        )]);
+        let results = run_check(super::bitmap_strikes, testable);
+        assert_results_contain(
+            &results,
+            StatusCode::Warn,
+            Some("incomplete-strike".to_string()),
+        );
+        assert_results_contain(&results, StatusCode::Fail, Some("bad-png".to_string()));
+    }
+
+    #[test]
+    fn test_bitmap_strikes_cbdt() {
+        let images = vec![(red_square(), 2, 2), (red_square(), 2, 2)];
+        let (cblc, cbdt) = cblc_cbdt(&[(20, 1, images.clone()), (40, 1, images)]);
+        let testable = with_tables(&[(b"CBLC", cblc), (b"CBDT", cbdt)]);
+        let results = run_check(super::bitmap_strikes, testable);
+        assert_pass(&results);
+
+        let (cblc, cbdt) = cblc_cbdt(&[
+            (20, 1, vec![(red_square(), 2, 2), (red_square(), 2, 2)]),
+            (40, 1, vec![(red_square(), 3, 3)]),
+        ]);
+        let testable = with_tables(&[(b"CBLC", cblc), (b"CBDT", cbdt)]);
+        let results = run_check(super::bitmap_strikes, testable);
+        assert_results_contain(
+            &results,
+            StatusCode::Warn,
+            Some("incomplete-strike".to_string()),
+        );
+        assert_results_contain(
+            &results,
+            StatusCode::Fail,
+            Some("png-size-mismatch".to_string()),
+        );
+    }
+}
@@ -0,0 +1,267 @@
+use std::collections::HashMap;
+
+use fontations::skrifa::{
+    raw::{
+        tables::colr::{Colr, CompositeMode, Paint},
+        ReadError, TableProvider,
+    },
+    GlyphId,
+};
+use fontspector_checkapi::{prelude::*, skip, testfont, FileTypeConvert};
+
+use super::{is_colrv1, paint_id, transformed_child, MAX_PAINT_DEPTH};
+
+/// Works out whether paints are confined to a glyph outline
+struct BoundsFinder<'a> {
+    colr: &'a Colr<'a>,
+    memo: HashMap<usize, bool>,
+}
+
+impl<'a> BoundsFinder<'a> {
+    fn is_child_bounded(&mut self, paint: Result<Paint<'a>, ReadError>, depth: usize) -> bool {
+        match paint {
+            Ok(paint) => self.is_bounded(&paint, depth + 1),
+            Err(_) => true,
+        }
+    }
+
+    /// Does this paint only draw inside the outline of some glyph?
+    ///
+    /// Malformed or cyclic graphs are treated as bounded; they are reported
+    /// by the colrv1_paint_graph check.
+    fn is_bounded(&mut self, paint: &Paint<'a>, depth: usize) -> bool {
+        if depth >= MAX_PAINT_DEPTH {
+            return true;
+        }
+        let id = paint_id(paint);
+        if let Some(bounded) = self.memo.get(&id) {
+            return *bounded;
+        }
+        // Provisionally, in case we come back here through a cycle
+        self.memo.insert(id, true);
+        let colr = self.colr;
+        let bounded = match paint {
+            Paint::Glyph(_) => true,
+            Paint::Solid(_)
+            | Paint::VarSolid(_)
+            | Paint::LinearGradient(_)
+            | Paint::VarLinearGradient(_)
+            | Paint::RadialGradient(_)
+            | Paint::VarRadialGradient(_)
+            | Paint::SweepGradient(_)
+            | Paint::VarSweepGradient(_) => false,
+            Paint::ColrLayers(p) => {
+                let first = p.first_layer_index() as usize;
+                (first..first + p.num_layers() as usize).all(|index| {
+                    self.is_child_bounded(colr.v1_layer(index).map(|(layer, _)| layer), depth)
+                })
+            }
+            Paint::ColrGlyph(p) => {
+                let gid = GlyphId::from(p.glyph_id());
+                if let Ok(Some(_)) = colr.v1_clip_box(gid) {
+                    true
+                } else {
+                    match colr.v1_base_glyph(gid) {
+                        Ok(Some((base_paint, _))) => self.is_bounded(&base_paint, depth + 1),
+                        _ => true,
+                    }
+                }
+            }
+            Paint::Composite(p) => {
+                let source = self.is_child_bounded(p.source_paint(), depth);
+                let backdrop = self.is_child_bounded(p.backdrop_paint(), depth);
+                match p.composite_mode() {
+                    CompositeMode::Clear => true,
+                    CompositeMode::Src | CompositeMode::SrcOut | CompositeMode::DestAtop => source,
+                    CompositeMode::Dest | CompositeMode::DestOut | CompositeMode::SrcAtop => {
+                        backdrop
+                    }
+                    CompositeMode::SrcIn | CompositeMode::DestIn => source || backdrop,
+                    _ => source && backdrop,
+                }
+            }
+            other => {
+                transformed_child(other).is_none_or(|child| self.is_child_bounded(child, depth))
+            }
+        };
+        self.memo.insert(id, bounded);
+        bounded
+    }
+}
+
+#[check(
+    id = "color/colrv1_clipboxes",
+    rationale = "
+        The ClipList of a COLRv1 table gives each color glyph a box outside of
+        which nothing is drawn. Without one, a renderer has to traverse the
+        whole paint graph to find out how big the glyph is before it can
+        allocate a surface to draw it on, which is slow; many renderers cache
+        color glyphs using the clip box, and some will refuse to draw glyphs
+        which do not have one.
+
+        It is worse when the glyph's paint graph is unbounded: a solid fill or
+        gradient which is not inside a PaintGlyph covers the whole (infinite)
+        canvas. Such glyphs must have a clip box, or they will paint over the
+        surrounding text. This check also reports clip boxes whose minimum
+        coordinates are greater than their maximum coordinates, as these
+        enclose nothing.
+    ",
+    proposal = "https://learn.microsoft.com/en-us/typography/opentype/spec/colr#cliplist",
+    title = "Do all COLRv1 glyphs have valid clip boxes?"
+)]
+fn colrv1_clipboxes(t: &Testable, context: &Context) -> CheckFnResult {
+    let f = testfont!(t);
+    skip!(!f.has_table(b"COLR"), "no-colr", "No COLR table.");
+    let colr = f.font().colr()?;
+    skip!(
+        !is_colrv1(&colr),
+        "no-colrv1",
+        "COLR table has no v1 paints."
+    );
+
+    let mut problems = vec![];
+    let mut bad_clips = vec![];
+    if let Some(clip_list) = colr.clip_list().transpose()? {
+        for clip in clip_list.clips() {
+            let range = format!(
+                "{}-{}",
+                f.glyph_name_for_id_synthesise(clip.start_glyph_id()),
+                f.glyph_name_for_id_synthesise(clip.end_glyph_id())
+            );
+            if clip.start_glyph_id() > clip.end_glyph_id() {
+                bad_clips.push(format!("{range}: the glyph range is reversed"));
+                continue;
+            }
+            let clip_box = clip.clip_box(clip_list.offset_data())?;
+            let (x_min, y_min, x_max, y_max) = (
+                clip_box.x_min().to_i16(),
+                clip_box.y_min().to_i16(),
+                clip_box.x_max().to_i16(),
+                clip_box.y_max().to_i16(),
+            );
+            if x_min > x_max || y_min > y_max {
+                bad_clips.push(format!(
+                    "{range}: ({x_min}, {y_min}, {x_max}, {y_max}) encloses nothing"
+                ));
+            }
+        }
+    }
+    if !bad_clips.is_empty() {
+        problems.push(Status::fail(
+            "bad-clipbox",
+            &format!(
+                "The following clip boxes are invalid:\n\n{}",
+                bullet_list(context, bad_clips)
+            ),
+        ));
+    }
+
+    let mut unbounded = vec![];
+    let mut unclipped = vec![];
+    let mut bounds = BoundsFinder {
+        colr: &colr,
+        memo: HashMap::new(),
+    };
+    if let Some(base_glyph_list) = colr.base_glyph_list().transpose()? {
+        for record in base_glyph_list.base_glyph_paint_records() {
+            context.check_deadline()?;
+            let gid = GlyphId::from(record.glyph_id());
+            if let Ok(Some(_)) = colr.v1_clip_box(gid) {
+                continue;
+            }
+            let name = f.glyph_name_for_id_synthesise(gid);
+            let bounded = record
+                .paint(base_glyph_list.offset_data())
+                .map(|paint| bounds.is_bounded(&paint, 0))
+                .unwrap_or(true);
+            if bounded {
+                unclipped.push(name);
+            } else {
+                unbounded.push(name);
+            }
+        }
+    }
+    if !unbounded.is_empty() {
+        problems.push(Status::fail(
+            "unbounded-paint",
+            &format!(
+                "The following glyphs paint outside of any glyph outline, but have no clip box:\n\n{}",
+                bullet_list(context, unbounded)
+            ),
+        ));
+    }
+    if !unclipped.is_empty() {
+        problems.push(Status::warn(
+            "missing-clipbox",
+            &format!(
+                "The following glyphs have no clip box:\n\n{}",
+                bullet_list(context, unclipped)
+            ),
+        ));
+    }
+    return_result(problems)
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use fontations::{skrifa::GlyphId16, write::tables::colr::Paint};
+    use fontspector_checkapi::{
+        codetesting::{assert_pass, assert_results_contain, assert_skip, run_check, test_able},
+        StatusCode,
+    };
+
+    use super::super::test_fonts::{clip, colrv1_font, glyph, solid};
+
+    #[test]
+    fn test_colrv1_clipboxes_skip() {
+        let testable = test_able("color_fonts/AmiriQuranColored.ttf");
+        let results = run_check(super::colrv1_clipboxes, testable);
+        assert_skip(&results);
+    }
+
+    #[test]
+    fn test_colrv1_clipboxes_pass() {
+        let testable = colrv1_font(
+            vec![(5, Paint::colr_layers(2, 0)), (6, solid())],
+            vec![glyph(10), glyph(11)],
+            vec![clip(5, 0, 0, 500, 500), clip(6, 0, 0, 500, 500)],
+        );
+        let results = run_check(super::colrv1_clipboxes, testable);
+        assert_pass(&results);
+    }
+
+    #[test]
+    fn test_colrv1_clipboxes_missing() {
+        let testable = colrv1_font(
+            vec![
+                (5, Paint::colr_layers(2, 0)),
+                (6, Paint::colr_glyph(GlyphId16::new(5))),
+                (7, solid()),
+            ],
+            vec![glyph(10), solid()],
+            vec![clip(6, 0, 0, -500, 500)],
+        );
+        let results = run_check(super::colrv1_clipboxes, testable);
+        assert_results_contain(&results, StatusCode::Fail, Some("bad-clipbox".to_string()));
+        // Glyph 5 has a solid layer which fills the whole canvas...
+        assert_results_contain(
+            &results,
+            StatusCode::Fail,
+            Some("unbounded-paint".to_string()),
+        );
+
+        let testable = colrv1_font(
+            vec![(5, Paint::colr_layers(2, 0))],
+            vec![glyph(10), glyph(11)],
+            vec![],
+        );
+        let results = run_check(super::colrv1_clipboxes, testable);
+        // ...but this one doesn't
+        assert_results_contain(
+            &results,
+            StatusCode::Warn,
+            Some("missing-clipbox".to_string()),
+        );
+    }
+}
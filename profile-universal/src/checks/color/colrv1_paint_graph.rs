@@ -0,0 +1,387 @@
+use std::collections::{BTreeMap, HashSet};
+
+use fontations::skrifa::{
+    raw::{
+        tables::colr::{ColorLine, Colr, Paint, VarColorLine},
+        ReadError, TableProvider,
+    },
+    GlyphId, GlyphId16,
+};
+use fontspector_checkapi::{prelude::*, skip, testfont, FileTypeConvert, TestFont};
+
+use super::{is_colrv1, paint_id, transformed_child, MAX_PAINT_DEPTH};
+
+/// A palette index meaning "use the text foreground colour"
+const FOREGROUND_PALETTE_INDEX: u16 = 0xFFFF;
+/// A variation index base meaning "this paint does not vary"
+const NO_VARIATION_INDEX: u32 = 0xFFFFFFFF;
+
+/// Walks the COLRv1 paint graph, collecting problems as it goes
+struct PaintGraphWalker<'a> {
+    font: &'a TestFont<'a>,
+    colr: &'a Colr<'a>,
+    palette_entries: u16,
+    /// Paints on the path from the current base glyph
+    path: Vec<usize>,
+    /// Paints whose whole subgraph has been visited
+    finished: HashSet<usize>,
+    /// Base glyphs which use variable paints
+    variable_glyphs: Vec<String>,
+    problems: BTreeMap<&'static str, Vec<String>>,
+}
+
+impl<'a> PaintGraphWalker<'a> {
+    fn report(&mut self, code: &'static str, glyph: &str, message: String) {
+        self.problems
+            .entry(code)
+            .or_default()
+            .push(format!("{glyph}: {message}"));
+    }
+
+    fn check_glyph_id(&mut self, gid: GlyphId16, glyph: &str) {
+        if gid.to_u32() >= self.font.glyph_count as u32 {
+            self.report(
+                "glyph-out-of-range",
+                glyph,
+                format!(
+                    "refers to glyph ID {}, but the font only has {} glyphs",
+                    gid.to_u32(),
+                    self.font.glyph_count
+                ),
+            );
+        }
+    }
+
+    fn check_palette_index(&mut self, index: u16, glyph: &str) {
+        if index != FOREGROUND_PALETTE_INDEX && index >= self.palette_entries {
+            self.report(
+                "palette-index-out-of-range",
+                glyph,
+                format!(
+                    "uses palette index {index}, but palettes only have {} entries",
+                    self.palette_entries
+                ),
+            );
+        }
+    }
+
+    fn check_variation_index(&mut self, var_index_base: u32, glyph: &str) {
+        if var_index_base != NO_VARIATION_INDEX && !self.variable_glyphs.iter().any(|g| g == glyph)
+        {
+            self.variable_glyphs.push(glyph.to_string());
+        }
+    }
+
+    fn check_color_line(&mut self, color_line: Result<ColorLine<'a>, ReadError>, glyph: &str) {
+        match color_line {
+            Ok(color_line) => {
+                for stop in color_line.color_stops() {
+                    self.check_palette_index(stop.palette_index(), glyph);
+                }
+            }
+            Err(e) => self.report("malformed-paint", glyph, e.to_string()),
+        }
+    }
+
+    fn check_var_color_line(
+        &mut self,
+        color_line: Result<VarColorLine<'a>, ReadError>,
+        glyph: &str,
+    ) {
+        match color_line {
+            Ok(color_line) => {
+                for stop in color_line.color_stops() {
+                    self.check_palette_index(stop.palette_index(), glyph);
+                    self.check_variation_index(stop.var_index_base(), glyph);
+                }
+            }
+            Err(e) => self.report("malformed-paint", glyph, e.to_string()),
+        }
+    }
+
+    fn visit_child(&mut self, paint: Result<Paint<'a>, ReadError>, glyph: &str) {
+        match paint {
+            Ok(paint) => self.visit(&paint, glyph),
+            Err(e) => self.report("malformed-paint", glyph, e.to_string()),
+        }
+    }
+
+    fn visit(&mut self, paint: &Paint<'a>, glyph: &str) {
+        let id = paint_id(paint);
+        if self.path.contains(&id) {
+            self.report(
+                "paint-cycle",
+                glyph,
+                "the paint graph refers back to itself".to_string(),
+            );
+            return;
+        }
+        if self.finished.contains(&id) {
+            return;
+        }
+        if self.path.len() >= MAX_PAINT_DEPTH {
+            self.report(
+                "paint-too-deep",
+                glyph,
+                format!("paints are nested more than {MAX_PAINT_DEPTH} levels deep"),
+            );
+            return;
+        }
+        self.path.push(id);
+        match paint {
+            Paint::ColrLayers(p) => {
+                let first = p.first_layer_index() as usize;
+                for index in first..first + p.num_layers() as usize {
+                    match self.colr.v1_layer(index) {
+                        Ok((layer, _)) => self.visit(&layer, glyph),
+                        Err(_) => self.report(
+                            "layer-out-of-range",
+                            glyph,
+                            format!("refers to layer {index}, which is not in the LayerList"),
+                        ),
+                    }
+                }
+            }
+            Paint::Solid(p) => self.check_palette_index(p.palette_index(), glyph),
+            Paint::VarSolid(p) => {
+                self.check_palette_index(p.palette_index(), glyph);
+                self.check_variation_index(p.var_index_base(), glyph);
+            }
+            Paint::LinearGradient(p) => self.check_color_line(p.color_line(), glyph),
+            Paint::RadialGradient(p) => self.check_color_line(p.color_line(), glyph),
+            Paint::SweepGradient(p) => self.check_color_line(p.color_line(), glyph),
+            Paint::VarLinearGradient(p) => {
+                self.check_variation_index(p.var_index_base(), glyph);
+                self.check_var_color_line(p.color_line(), glyph);
+            }
+            Paint::VarRadialGradient(p) => {
+                self.check_variation_index(p.var_index_base(), glyph);
+                self.check_var_color_line(p.color_line(), glyph);
+            }
+            Paint::VarSweepGradient(p) => {
+                self.check_variation_index(p.var_index_base(), glyph);
+                self.check_var_color_line(p.color_line(), glyph);
+            }
+            Paint::Glyph(p) => {
+                self.check_glyph_id(p.glyph_id(), glyph);
+                self.visit_child(p.paint(), glyph);
+            }
+            Paint::ColrGlyph(p) => match self.colr.v1_base_glyph(GlyphId::from(p.glyph_id())) {
+                Ok(Some((base_paint, _))) => self.visit(&base_paint, glyph),
+                Ok(None) => self.report(
+                    "missing-colr-glyph",
+                    glyph,
+                    format!(
+                        "refers to {}, which is not in the BaseGlyphList",
+                        self.font.glyph_name_for_id_synthesise(p.glyph_id())
+                    ),
+                ),
+                Err(e) => self.report("malformed-paint", glyph, e.to_string()),
+            },
+            Paint::Composite(p) => {
+                self.visit_child(p.source_paint(), glyph);
+                self.visit_child(p.backdrop_paint(), glyph);
+            }
+            other => {
+                let var_index_base = match other {
+                    Paint::VarTransform(p) => p.transform().map(|t| t.var_index_base()).ok(),
+                    Paint::VarTranslate(p) => Some(p.var_index_base()),
+                    Paint::VarScale(p) => Some(p.var_index_base()),
+                    Paint::VarScaleAroundCenter(p) => Some(p.var_index_base()),
+                    Paint::VarScaleUniform(p) => Some(p.var_index_base()),
+                    Paint::VarScaleUniformAroundCenter(p) => Some(p.var_index_base()),
+                    Paint::VarRotate(p) => Some(p.var_index_base()),
+                    Paint::VarRotateAroundCenter(p) => Some(p.var_index_base()),
+                    Paint::VarSkew(p) => Some(p.var_index_base()),
+                    Paint::VarSkewAroundCenter(p) => Some(p.var_index_base()),
+                    _ => None,
+                };
+                if let Some(var_index_base) = var_index_base {
+                    self.check_variation_index(var_index_base, glyph);
+                }
+                if let Some(child) = transformed_child(other) {
+                    self.visit_child(child, glyph);
+                }
+            }
+        }
+        self.path.pop();
+        self.finished.insert(id);
+    }
+}
+
+#[check(
+    id = "color/colrv1_paint_graph",
+    rationale = "
+        A COLRv1 glyph is drawn by walking a graph of paint tables, starting from
+        the glyph's entry in the BaseGlyphList. Renderers trust this graph to be
+        well formed, and mistakes in it have been known to crash them or send
+        them into infinite loops. This check walks the graph for every base
+        glyph and looks for:
+
+        - cycles, where a paint (through PaintColrLayers or PaintColrGlyph)
+          eventually refers back to itself;
+        - references to layers beyond the end of the LayerList, to glyphs
+          beyond maxp.numGlyphs, or to PaintColrGlyph glyphs which have no
+          entry in the BaseGlyphList;
+        - palette indices beyond the number of entries in each CPAL palette
+          (0xFFFF, the foreground colour, is allowed);
+        - variable paints or color stops without an ItemVariationStore to
+          resolve their variation indices.
+    ",
+    proposal = "https://learn.microsoft.com/en-us/typography/opentype/spec/colr",
+    title = "Check the COLRv1 paint graph for cycles and bad references"
+)]
+fn colrv1_paint_graph(t: &Testable, context: &Context) -> CheckFnResult {
+    let f = testfont!(t);
+    skip!(!f.has_table(b"COLR"), "no-colr", "No COLR table.");
+    let colr = f.font().colr()?;
+    skip!(
+        !is_colrv1(&colr),
+        "no-colrv1",
+        "COLR table has no v1 paints."
+    );
+    let palette_entries = f
+        .font()
+        .cpal()
+        .map(|cpal| cpal.num_palette_entries())
+        .unwrap_or(0);
+    let mut walker = PaintGraphWalker {
+        font: &f,
+        colr: &colr,
+        palette_entries,
+        path: vec![],
+        finished: HashSet::new(),
+        variable_glyphs: vec![],
+        problems: BTreeMap::new(),
+    };
+    if let Some(base_glyph_list) = colr.base_glyph_list().transpose()? {
+        for record in base_glyph_list.base_glyph_paint_records() {
+            context.check_deadline()?;
+            let glyph = f.glyph_name_for_id_synthesise(record.glyph_id());
+            walker.check_glyph_id(record.glyph_id(), &glyph);
+            walker.visit_child(record.paint(base_glyph_list.offset_data()), &glyph);
+        }
+    }
+    if !walker.variable_glyphs.is_empty() && colr.item_variation_store().is_none() {
+        let glyphs = std::mem::take(&mut walker.variable_glyphs);
+        for glyph in glyphs {
+            walker.report(
+                "missing-varstore",
+                &glyph,
+                "uses variable paints, but the COLR table has no ItemVariationStore".to_string(),
+            );
+        }
+    }
+
+    let problems = walker
+        .problems
+        .into_iter()
+        .map(|(code, glyphs)| {
+            Status::fail(
+                code,
+                &format!(
+                    "The following COLRv1 glyphs have problems in their paint graph:\n\n{}",
+                    bullet_list(context, glyphs)
+                ),
+            )
+        })
+        .collect::<Vec<_>>();
+    return_result(problems)
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use fontations::{
+        skrifa::{raw::types::F2Dot14, GlyphId16},
+        write::tables::colr::Paint,
+    };
+    use fontspector_checkapi::{
+        codetesting::{assert_pass, assert_results_contain, assert_skip, run_check, test_able},
+        StatusCode,
+    };
+
+    use super::super::test_fonts::{colrv1_font, glyph};
+
+    #[test]
+    fn test_colrv1_paint_graph_skip() {
+        let testable = test_able("color_fonts/AmiriQuranColored.ttf");
+        let results = run_check(super::colrv1_paint_graph, testable);
+        assert_skip(&results);
+    }
+
+    #[test]
+    fn test_colrv1_paint_graph_pass() {
+        let testable = colrv1_font(
+            vec![
+                (5, Paint::colr_layers(2, 0)),
+                (6, Paint::colr_glyph(GlyphId16::new(5))),
+            ],
+            vec![glyph(10), glyph(11)],
+            vec![],
+        );
+        let results = run_check(super::colrv1_paint_graph, testable);
+        assert_pass(&results);
+    }
+
+    #[test]
+    fn test_colrv1_paint_graph_cycle() {
+        let testable = colrv1_font(
+            vec![
+                (5, Paint::colr_glyph(GlyphId16::new(6))),
+                (6, Paint::colr_glyph(GlyphId16::new(5))),
+            ],
+            vec![],
+            vec![],
+        );
+        let results = run_check(super::colrv1_paint_graph, testable);
+        assert_results_contain(&results, StatusCode::Fail, Some("paint-cycle".to_string()));
+    }
+
+    #[test]
+    fn test_colrv1_paint_graph_bad_references() {
+        let testable = colrv1_font(
+            vec![
+                (5, Paint::colr_layers(3, 0)),
+                (6, Paint::colr_glyph(GlyphId16::new(7))),
+                (
+                    8,
+                    Paint::glyph(Paint::solid(99, F2Dot14::from_f32(1.0)), GlyphId16::new(10)),
+                ),
+                (9, glyph(5000)),
+            ],
+            vec![glyph(10), glyph(11)],
+            vec![],
+        );
+        let results = run_check(super::colrv1_paint_graph, testable);
+        for code in [
+            "layer-out-of-range",
+            "missing-colr-glyph",
+            "palette-index-out-of-range",
+            "glyph-out-of-range",
+        ] {
+            assert_results_contain(&results, StatusCode::Fail, Some(code.to_string()));
+        }
+    }
+
+    #[test]
+    fn test_colrv1_paint_graph_missing_varstore() {
+        let testable = colrv1_font(
+            vec![(
+                5,
+                Paint::glyph(
+                    Paint::var_solid(0, F2Dot14::from_f32(1.0), 0),
+                    GlyphId16::new(10),
+                ),
+            )],
+            vec![],
+            vec![],
+        );
+        let results = run_check(super::colrv1_paint_graph, testable);
+        assert_results_contain(
+            &results,
+            StatusCode::Fail,
+            Some("missing-varstore".to_string()),
+        );
+    }
+}
@@ -0,0 +1,120 @@
+mod bitmap_strikes;
+mod colrv1_clipboxes;
+mod colrv1_paint_graph;
+mod png;
+mod svg_documents;
+
+pub use bitmap_strikes::bitmap_strikes;
+pub use colrv1_clipboxes::colrv1_clipboxes;
+pub use colrv1_paint_graph::colrv1_paint_graph;
+pub use svg_documents::svg_documents;
+
+use fontations::skrifa::raw::{
+    tables::colr::{Colr, Paint},
+    ReadError,
+};
+
+/// Paints nested deeper than this are not followed; renderers impose a
+/// similar limit to protect against cycles.
+const MAX_PAINT_DEPTH: usize = 64;
+
+/// An identifier for a paint table, so that we can tell when we revisit it
+fn paint_id(paint: &Paint) -> usize {
+    paint.offset_data().as_bytes().as_ptr() as usize
+}
+
+/// Is this a COLR table with version 1 paint data?
+fn is_colrv1(colr: &Colr) -> bool {
+    colr.version() >= 1 && colr.base_glyph_list().is_some()
+}
+
+/// The child paint of any paint which simply transforms a single paint
+fn transformed_child<'a>(paint: &Paint<'a>) -> Option<Result<Paint<'a>, ReadError>> {
+    Some(match paint {
+        Paint::Transform(p) => p.paint(),
+        Paint::VarTransform(p) => p.paint(),
+        Paint::Translate(p) => p.paint(),
+        Paint::VarTranslate(p) => p.paint(),
+        Paint::Scale(p) => p.paint(),
+        Paint::VarScale(p) => p.paint(),
+        Paint::ScaleAroundCenter(p) => p.paint(),
+        Paint::VarScaleAroundCenter(p) => p.paint(),
+        Paint::ScaleUniform(p) => p.paint(),
+        Paint::VarScaleUniform(p) => p.paint(),
+        Paint::ScaleUniformAroundCenter(p) => p.paint(),
+        Paint::VarScaleUniformAroundCenter(p) => p.paint(),
+        Paint::Rotate(p) => p.paint(),
+        Paint::VarRotate(p) => p.paint(),
+        Paint::RotateAroundCenter(p) => p.paint(),
+        Paint::VarRotateAroundCenter(p) => p.paint(),
+        Paint::Skew(p) => p.paint(),
+        Paint::VarSkew(p) => p.paint(),
+        Paint::SkewAroundCenter(p) => p.paint(),
+        Paint::VarSkewAroundCenter(p) => p.paint(),
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod test_fonts {
+    #![allow(clippy::unwrap_used)]
+    use fontations::{
+        skrifa::{
+            raw::types::{F2Dot14, FWord},
+            GlyphId16,
+        },
+        write::tables::colr::{
+            BaseGlyphList, BaseGlyphPaint, Clip, ClipBox, ClipList, Colr, LayerList, Paint,
+        },
+    };
+    use fontspector_checkapi::{codetesting::test_able, FileTypeConvert, Testable, TTF};
+
+    /// A solid fill using the first palette entry
+    pub(crate) fn solid() -> Paint {
+        Paint::solid(0, F2Dot14::from_f32(1.0))
+    }
+
+    /// A glyph outline filled with the first palette entry
+    pub(crate) fn glyph(gid: u16) -> Paint {
+        Paint::glyph(solid(), GlyphId16::new(gid))
+    }
+
+    /// A clip box around the given glyph
+    pub(crate) fn clip(gid: u16, x_min: i16, y_min: i16, x_max: i16, y_max: i16) -> Clip {
+        Clip::new(
+            GlyphId16::new(gid),
+            GlyphId16::new(gid),
+            ClipBox::format_1(
+                FWord::new(x_min),
+                FWord::new(y_min),
+                FWord::new(x_max),
+                FWord::new(y_max),
+            ),
+        )
+    }
+
+    /// Replace the COLR table of a COLRv0 font with a COLRv1 table
+    pub(crate) fn colrv1_font(
+        base_glyphs: Vec<(u16, Paint)>,
+        layers: Vec<Paint>,
+        clips: Vec<Clip>,
+    ) -> Testable {
+        let mut testable = test_able("color_fonts/AmiriQuranColored.ttf");
+        let f = TTF.from_testable(&testable).unwrap();
+        let records = base_glyphs
+            .into_iter()
+            .map(|(gid, paint)| BaseGlyphPaint::new(GlyphId16::new(gid), paint))
+            .collect::<Vec<_>>();
+        let colr = Colr {
+            base_glyph_list: BaseGlyphList::new(records.len() as u32, records).into(),
+            layer_list: LayerList::new(layers.len() as u32, layers).into(),
+            clip_list: (!clips.is_empty())
+                .then(|| ClipList::new(1, clips.len() as u32, clips))
+                .into(),
+            ..Default::default()
+        };
+        let new_font = f.rebuild_with_new_table(&colr).unwrap();
+        testable.set(new_font);
+        testable
+    }
+}
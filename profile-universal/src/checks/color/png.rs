@@ -0,0 +1,243 @@
+//! Just enough PNG parsing to tell whether an embedded image is intact
+use std::io::Read;
+
+use flate2::read::ZlibDecoder;
+
+const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1A, b'\n'];
+
+const CRC_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut n = 0;
+    while n < 256 {
+        let mut c = n as u32;
+        let mut k = 0;
+        while k < 8 {
+            c = if c & 1 != 0 {
+                0xEDB88320 ^ (c >> 1)
+            } else {
+                c >> 1
+            };
+            k += 1;
+        }
+        if let Some(entry) = table.get_mut(n) {
+            *entry = c;
+        }
+        n += 1;
+    }
+    table
+};
+
+fn crc32(chunk_type: &[u8], data: &[u8]) -> u32 {
+    let mut crc = 0xFFFFFFFFu32;
+    for byte in chunk_type.iter().chain(data) {
+        let index = ((crc ^ *byte as u32) & 0xFF) as usize;
+        crc = CRC_TABLE.get(index).copied().unwrap_or(0) ^ (crc >> 8);
+    }
+    crc ^ 0xFFFFFFFF
+}
+
+fn be_u32(bytes: &[u8]) -> Option<u32> {
+    match bytes {
+        [a, b, c, d, ..] => Some(u32::from_be_bytes([*a, *b, *c, *d])),
+        _ => None,
+    }
+}
+
+/// The width and height of a decodable PNG image
+pub(crate) struct PngInfo {
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Check that `data` is a complete, uncorrupted PNG image
+///
+/// This walks the chunks, verifying their checksums, and decompresses the
+/// image data to make sure there is enough of it for the declared size.
+pub(crate) fn validate_png(data: &[u8]) -> Result<PngInfo, String> {
+    let mut rest = data
+        .strip_prefix(&SIGNATURE)
+        .ok_or("does not start with the PNG signature")?;
+    let mut header: Option<(u32, u32, u8, u8, u8)> = None;
+    let mut image_data = vec![];
+    let mut seen_end = false;
+    while !rest.is_empty() {
+        if seen_end {
+            return Err("has data after the IEND chunk".to_string());
+        }
+        let length = be_u32(rest).ok_or("has a truncated chunk header")? as usize;
+        let chunk_type = rest.get(4..8).ok_or("has a truncated chunk header")?;
+        let data_end = length.checked_add(8).ok_or("has an oversized chunk")?;
+        let chunk_data = rest.get(8..data_end).ok_or_else(|| {
+            format!(
+                "has a truncated {} chunk",
+                String::from_utf8_lossy(chunk_type)
+            )
+        })?;
+        let crc = rest
+            .get(data_end..)
+            .and_then(be_u32)
+            .ok_or("has a chunk with a missing checksum")?;
+        let name = String::from_utf8_lossy(chunk_type);
+        if crc != crc32(chunk_type, chunk_data) {
+            return Err(format!("has a bad checksum in its {name} chunk"));
+        }
+        match chunk_type {
+            b"IHDR" => match chunk_data {
+                [w0, w1, w2, w3, h0, h1, h2, h3, bit_depth, color_type, _, _, interlace] => {
+                    header = Some((
+                        u32::from_be_bytes([*w0, *w1, *w2, *w3]),
+                        u32::from_be_bytes([*h0, *h1, *h2, *h3]),
+                        *bit_depth,
+                        *color_type,
+                        *interlace,
+                    ))
+                }
+                _ => return Err("has a malformed IHDR chunk".to_string()),
+            },
+            b"IDAT" => image_data.extend_from_slice(chunk_data),
+            b"IEND" => seen_end = true,
+            _ => {}
+        }
+        if header.is_none() {
+            return Err("does not start with an IHDR chunk".to_string());
+        }
+        rest = rest.get(data_end + 4..).unwrap_or_default();
+    }
+    let (width, height, bit_depth, color_type, interlace) = header.ok_or("has no IHDR chunk")?;
+    if !seen_end {
+        return Err("has no IEND chunk".to_string());
+    }
+    if width == 0 || height == 0 {
+        return Err(format!("has an invalid size of {width}x{height}"));
+    }
+    if image_data.is_empty() {
+        return Err("has no image data".to_string());
+    }
+    let channels: u64 = match color_type {
+        0 | 3 => 1,
+        2 => 3,
+        4 => 2,
+        6 => 4,
+        _ => return Err(format!("has an unknown color type {color_type}")),
+    };
+    let expected = image_data_size(width, height, channels * bit_depth as u64, interlace);
+    // Don't let a small IDAT decompress into more than the image can use
+    let mut decompressed = vec![];
+    ZlibDecoder::new(image_data.as_slice())
+        .take(expected + 1)
+        .read_to_end(&mut decompressed)
+        .map_err(|e| format!("has corrupt image data ({e})"))?;
+    if (decompressed.len() as u64) < expected {
+        return Err(format!(
+            "has {} bytes of image data, but a {width}x{height} image needs {expected}",
+            decompressed.len()
+        ));
+    }
+    if (decompressed.len() as u64) > expected {
+        return Err(format!(
+            "has more image data than the {expected} bytes a {width}x{height} image needs"
+        ));
+    }
+    Ok(PngInfo { width, height })
+}
+
+/// The size of the decompressed image data, including each row's filter byte
+///
+/// Interlaced images are stored as seven smaller images, one per Adam7 pass.
+fn image_data_size(width: u32, height: u32, bits_per_pixel: u64, interlace: u8) -> u64 {
+    let size = |width: u64, height: u64| {
+        if width == 0 || height == 0 {
+            0
+        } else {
+            height * ((width * bits_per_pixel).div_ceil(8) + 1)
+        }
+    };
+    let (width, height) = (width as u64, height as u64);
+    if interlace == 0 {
+        return size(width, height);
+    }
+    // (x offset, y offset, x step, y step) of each pass
+    const ADAM7: [(u64, u64, u64, u64); 7] = [
+        (0, 0, 8, 8),
+        (4, 0, 8, 8),
+        (0, 4, 4, 8),
+        (2, 0, 4, 4),
+        (0, 2, 2, 4),
+        (1, 0, 2, 2),
+        (0, 1, 1, 2),
+    ];
+    ADAM7
+        .iter()
+        .map(|(x0, y0, dx, dy)| {
+            size(
+                width.saturating_sub(*x0).div_ceil(*dx),
+                height.saturating_sub(*y0).div_ceil(*dy),
+            )
+        })
+        .sum()
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    #![allow(clippy::unwrap_used)]
+    use std::io::Write;
+
+    use flate2::{write::ZlibEncoder, Compression};
+
+    use super::{crc32, validate_png, SIGNATURE};
+
+    fn chunk(chunk_type: &[u8; 4], data: &[u8]) -> Vec<u8> {
+        let mut out = (data.len() as u32).to_be_bytes().to_vec();
+        out.extend_from_slice(chunk_type);
+        out.extend_from_slice(data);
+        out.extend_from_slice(&crc32(chunk_type, data).to_be_bytes());
+        out
+    }
+
+    /// A 2x2 8-bit RGBA image with the given (decompressed) image data
+    fn rgba_2x2(interlace: u8, image_data: &[u8]) -> Vec<u8> {
+        let mut ihdr = vec![0, 0, 0, 2, 0, 0, 0, 2];
+        ihdr.extend_from_slice(&[8, 6, 0, 0, interlace]);
+        let mut encoder = ZlibEncoder::new(vec![], Compression::default());
+        encoder.write_all(image_data).unwrap();
+        let mut png = SIGNATURE.to_vec();
+        png.extend(chunk(b"IHDR", &ihdr));
+        png.extend(chunk(b"IDAT", &encoder.finish().unwrap()));
+        png.extend(chunk(b"IEND", &[]));
+        png
+    }
+
+    /// A 2x2 opaque red RGBA image
+    pub(crate) fn red_square() -> Vec<u8> {
+        rgba_2x2(0, &[0, 255, 0, 0, 255, 255, 0, 0, 255].repeat(2))
+    }
+
+    #[test]
+    fn test_validate_png() {
+        let png = red_square();
+        let info = validate_png(&png).unwrap();
+        assert_eq!((info.width, info.height), (2, 2));
+
+        // Without the IEND chunk
+        assert!(validate_png(png.get(..png.len() - 12).unwrap()).is_err());
+        assert!(validate_png(b"GIF89a").is_err());
+        let mut corrupt = png.clone();
+        if let Some(byte) = corrupt.get_mut(40) {
+            *byte ^= 0xFF;
+        }
+        assert!(validate_png(&corrupt).is_err());
+    }
+
+    #[test]
+    fn test_image_data_size() {
+        // Too little, and too much, image data for a 2x2 RGBA image
+        assert!(validate_png(&rgba_2x2(0, &[0; 17])).is_err());
+        assert!(validate_png(&rgba_2x2(0, &[0; 19])).is_err());
+        // A tiny IDAT which would decompress to 64MB is stopped early
+        assert!(validate_png(&rgba_2x2(0, &vec![0; 64 * 1024 * 1024])).is_err());
+
+        // Interlaced, the 2x2 image is stored as 1x1, 1x1 and 2x1 passes
+        assert!(validate_png(&rgba_2x2(1, &[0; 19])).is_ok());
+        assert!(validate_png(&rgba_2x2(1, &[0; 18])).is_err());
+    }
+}
@@ -0,0 +1,264 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    io::Read,
+};
+
+use flate2::read::GzDecoder;
+use fontations::skrifa::raw::TableProvider;
+use fontspector_checkapi::{prelude::*, skip, testfont, FileTypeConvert};
+use quick_xml::{events::Event, Reader};
+
+const GZIP_MAGIC: [u8; 2] = [0x1F, 0x8B];
+
+/// Parse an SVG document, returning the ids of all its elements
+fn svg_element_ids(document: &[u8]) -> Result<HashSet<String>, String> {
+    let mut reader = Reader::from_reader(document);
+    let mut ids = HashSet::new();
+    let mut depth = 0usize;
+    let mut seen_root = false;
+    loop {
+        let event = reader
+            .read_event()
+            .map_err(|e| format!("is not well-formed XML ({e})"))?;
+        let element = match &event {
+            Event::Start(e) | Event::Empty(e) => e,
+            Event::End(_) => {
+                depth = depth.saturating_sub(1);
+                continue;
+            }
+            Event::Eof => break,
+            _ => continue,
+        };
+        if depth == 0 {
+            if seen_root {
+                return Err("has more than one root element".to_string());
+            }
+            if element.local_name().as_ref() != b"svg" {
+                return Err(format!(
+                    "has a <{}> root element instead of <svg>",
+                    String::from_utf8_lossy(element.name().as_ref())
+                ));
+            }
+            seen_root = true;
+        }
+        for attribute in element.attributes() {
+            let attribute = attribute.map_err(|e| format!("is not well-formed XML ({e})"))?;
+            if attribute.key.as_ref() == b"id" {
+                ids.insert(String::from_utf8_lossy(&attribute.value).to_string());
+            }
+        }
+        if matches!(event, Event::Start(_)) {
+            depth += 1;
+        }
+    }
+    if !seen_root {
+        return Err("has no <svg> element".to_string());
+    }
+    if depth != 0 {
+        return Err("ends before all its elements are closed".to_string());
+    }
+    Ok(ids)
+}
+
+#[check(
+    id = "color/svg_documents",
+    rationale = "
+        The 'SVG ' table maps ranges of glyph IDs to SVG documents, each of which
+        must contain an element with the ID \"glyphN\" for every glyph N in its
+        range. The document records must be sorted by glyph ID, must not
+        overlap, and must only refer to glyphs which exist in the font.
+        Documents may be gzip-compressed, but must then decompress to a
+        well-formed XML document whose root element is <svg>.
+
+        Renderers look up a glyph's document with a binary search over the
+        document records, so unsorted or overlapping records cause glyphs to
+        silently disappear; malformed documents either render nothing or,
+        with less defensive SVG parsers, crash the application.
+    ",
+    proposal = "https://learn.microsoft.com/en-us/typography/opentype/spec/svg",
+    title = "Are the SVG table's documents well-formed and correctly indexed?"
+)]
+fn svg_documents(t: &Testable, context: &Context) -> CheckFnResult {
+    let f = testfont!(t);
+    skip!(!f.has_table(b"SVG "), "no-svg", "No SVG table.");
+    let document_list = f.font().svg()?.svg_document_list()?;
+    let data = document_list.offset_data().as_bytes();
+
+    let mut problems: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+    let mut report = |code, message| problems.entry(code).or_default().push(message);
+    let mut previous_end: Option<u16> = None;
+    for record in document_list.document_records() {
+        let (start, end) = (
+            record.start_glyph_id().to_u16(),
+            record.end_glyph_id().to_u16(),
+        );
+        let range = format!("Glyphs {start}-{end}");
+        if start > end {
+            report("bad-glyph-range", format!("{range}: the range is reversed"));
+            continue;
+        }
+        if end as usize >= f.glyph_count {
+            report(
+                "bad-glyph-range",
+                format!("{range}: the font only has {} glyphs", f.glyph_count),
+            );
+        }
+        if previous_end.is_some_and(|previous| start <= previous) {
+            report(
+                "unsorted-records",
+                format!("{range}: overlaps or comes before the previous record"),
+            );
+        }
+        previous_end = Some(end);
+
+        let offset = record.svg_doc_offset() as usize;
+        let Some(document) = offset
+            .checked_add(record.svg_doc_length() as usize)
+            .and_then(|doc_end| data.get(offset..doc_end))
+        else {
+            report(
+                "bad-document",
+                format!("{range}: the document runs past the end of the table"),
+            );
+            continue;
+        };
+        let mut decompressed = vec![];
+        let document = if document.starts_with(&GZIP_MAGIC) {
+            if let Err(e) = GzDecoder::new(document).read_to_end(&mut decompressed) {
+                report(
+                    "bad-document",
+                    format!("{range}: the document could not be decompressed ({e})"),
+                );
+                continue;
+            }
+            decompressed.as_slice()
+        } else {
+            document
+        };
+        match svg_element_ids(document) {
+            Ok(ids) => {
+                let missing = (start..=end)
+                    .filter(|gid| !ids.contains(&format!("glyph{gid}")))
+                    .map(|gid| format!("glyph{gid}"))
+                    .collect::<Vec<_>>();
+                if !missing.is_empty() {
+                    report(
+                        "missing-glyph-element",
+                        format!(
+                            "{range}: the document has no element with id {}",
+                            missing.join(", ")
+                        ),
+                    );
+                }
+            }
+            Err(problem) => report("bad-document", format!("{range}: the document {problem}")),
+        }
+    }
+
+    let problems = problems
+        .into_iter()
+        .map(|(code, messages)| {
+            Status::fail(
+                code,
+                &format!(
+                    "The SVG table has the following problems:\n\n{}",
+                    bullet_list(context, messages)
+                ),
+            )
+        })
+        .collect::<Vec<_>>();
+    return_result(problems)
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use std::io::Write;
+
+    use flate2::{write::GzEncoder, Compression};
+    use fontations::{
+        skrifa::{FontRef, Tag},
+        write::FontBuilder,
+    };
+    use fontspector_checkapi::{
+        codetesting::{assert_pass, assert_results_contain, assert_skip, run_check, test_able},
+        StatusCode, Testable,
+    };
+
+    /// Add an SVG table made from (start glyph, end glyph, document) records
+    fn with_svg(documents: &[(u16, u16, Vec<u8>)]) -> Testable {
+        let mut list = (documents.len() as u16).to_be_bytes().to_vec();
+        let mut data = vec![];
+        let records_length = 2 + 12 * documents.len();
+        for (start, end, document) in documents {
+            list.extend(start.to_be_bytes());
+            list.extend(end.to_be_bytes());
+            list.extend(((records_length + data.len()) as u32).to_be_bytes());
+            list.extend((document.len() as u32).to_be_bytes());
+            data.extend(document);
+        }
+        let mut table = vec![0, 0, 0, 0, 0, 10, 0, 0, 0, 0];
+        table.extend(list);
+        table.extend(data);
+
+        let mut testable = test_able("cabin/Cabin-Regular.ttf");
+        let font = FontRef::new(&testable.contents).unwrap();
+        let mut builder = FontBuilder::new();
+        for record in font.table_directory.table_records() {
+            let tag = record.tag.get();
+            if let Some(data) = font.table_data(tag) {
+                builder.add_raw(tag, data);
+            }
+        }
+        builder.add_raw(Tag::new(b"SVG "), table);
+        testable.contents = builder.build();
+        testable
+    }
+
+    fn document(ids: &[u16]) -> Vec<u8> {
+        let glyphs = ids
+            .iter()
+            .map(|gid| format!("<g id=\"glyph{gid}\"><path d=\"M0,0 L100,0 L100,-100 Z\"/></g>"))
+            .collect::<String>();
+        format!("<svg xmlns=\"http://www.w3.org/2000/svg\" version=\"1.1\">{glyphs}</svg>")
+            .into_bytes()
+    }
+
+    #[test]
+    fn test_svg_documents_skip() {
+        let testable = test_able("cabin/Cabin-Regular.ttf");
+        let results = run_check(super::svg_documents, testable);
+        assert_skip(&results);
+    }
+
+    #[test]
+    fn test_svg_documents_pass() {
+        let mut compressed = GzEncoder::new(vec![], Compression::default());
+        compressed.write_all(&document(&[5])).unwrap();
+        let testable = with_svg(&[
+            (1, 3, document(&[1, 2, 3])),
+            (5, 5, compressed.finish().unwrap()),
+        ]);
+        let results = run_check(super::svg_documents, testable);
+        assert_pass(&results);
+    }
+
+    #[test]
+    fn test_svg_documents_bad() {
+        let testable = with_svg(&[
+            (5, 6, document(&[5])),
+            (3, 3, document(&[3])),
+            (7, 7, b"<svg><g id=\"glyph7\"></svg>".to_vec()),
+            (8, 9000, document(&[8])),
+        ]);
+        let results = run_check(super::svg_documents, testable);
+        for code in [
+            "missing-glyph-element",
+            "unsorted-records",
+            "bad-document",
+            "bad-glyph-range",
+        ] {
+            assert_results_contain(&results, StatusCode::Fail, Some(code.to_string()));
+        }
+    }
+}
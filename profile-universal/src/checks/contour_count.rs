@@ -84,6 +84,7 @@ fn contour_count(t: &Testable, context: &Context) -> CheckFnResult {
         .map(|(k, v)| (v, k))
         .collect::<HashMap<_, _>>();
     for glyph in f.all_glyphs() {
+        context.check_deadline()?;
         let codepoint = reverse_map.get(&glyph).copied();
         let glyph_name = f.glyph_name_for_id_synthesise(glyph);
         let expected = match codepoint {
@@ -172,6 +172,7 @@ mod tests {
         let collection = TestableCollection {
             testables,
             directory: "".to_string(),
+            label: None,
         };
 
         let results = run_check_with_config(
@@ -196,6 +197,7 @@ mod tests {
         let collection = TestableCollection {
             testables,
             directory: "".to_string(),
+            label: None,
         };
         let results = run_check_with_config(
             family_uniqueness_first_31_characters,
@@ -0,0 +1,146 @@
+use std::collections::BTreeMap;
+
+use fontspector_checkapi::{prelude::*, skip, testfont, FileTypeConvert};
+
+use super::interpreter::{analyze_hinting, is_hinted, ProgramSource};
+
+#[check(
+    id = "hinting/bytecode",
+    rationale = "
+        TrueType hinting programs (the 'fpgm' and 'prep' tables, and the
+        instructions attached to each glyph) are executed by the rasterizer
+        every time a glyph is drawn. Mistakes in these programs, whether made
+        by hand or by a buggy autohinter or VTT compile, are not caught by
+        font compilers and typically show up as crashes, garbled glyphs or
+        hinting being silently switched off in some rasterizers but not others.
+
+        This check executes the hinting programs symbolically, without
+        knowing the ppem size or outline, and reports programs which:
+
+        - pop more values from the stack than were pushed (stack underflow);
+        - read or write CVT entries beyond the end of the 'cvt ' table;
+        - read or write storage locations beyond maxp.maxStorage;
+        - call functions which were never defined with FDEF;
+        - use opcodes which are not defined by the specification or an IDEF;
+        - cannot be decoded, or have unbalanced IF/EIF or FDEF/ENDF pairs.
+
+        Where the outcome depends on values only known at rasterization time
+        (for example, function numbers computed from measurements), that
+        program is not analysed further, so a pass does not guarantee that
+        the hinting is correct.
+    ",
+    proposal = "https://learn.microsoft.com/en-us/typography/opentype/spec/tt_instructions",
+    title = "Check TrueType hinting programs for runtime errors"
+)]
+fn bytecode(t: &Testable, context: &Context) -> CheckFnResult {
+    let f = testfont!(t);
+    skip!(
+        !f.has_table(b"glyf"),
+        "not-ttf",
+        "Font does not have TrueType outlines"
+    );
+    skip!(!is_hinted(&f), "not-hinted", "Font is not hinted");
+    let report = analyze_hinting(&f, context)?;
+
+    let mut by_code: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+    for (source, error) in report.errors.iter() {
+        let program = match source {
+            ProgramSource::Fpgm => "fpgm table".to_string(),
+            ProgramSource::Prep => "prep table".to_string(),
+            ProgramSource::Glyph(gid) => format!("glyph {}", f.glyph_name_for_id_synthesise(*gid)),
+        };
+        by_code
+            .entry(error.code())
+            .or_default()
+            .push(format!("{program}: {error}"));
+    }
+    let problems = by_code
+        .into_iter()
+        .map(|(code, programs)| {
+            Status::fail(
+                code,
+                &format!(
+                    "The following hinting programs would fail when executed:\n\n{}",
+                    bullet_list(context, programs)
+                ),
+            )
+        })
+        .collect::<Vec<_>>();
+    return_result(problems)
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use fontations::{
+        skrifa::{FontRef, Tag},
+        write::FontBuilder,
+    };
+    use fontspector_checkapi::{
+        codetesting::{assert_pass, assert_results_contain, assert_skip, run_check, test_able},
+        StatusCode, Testable,
+    };
+
+    fn replace_prep(testable: &mut Testable, prep: &[u8]) {
+        let font = FontRef::new(&testable.contents).unwrap();
+        let mut builder = FontBuilder::new();
+        for record in font.table_directory.table_records() {
+            let tag = record.tag.get();
+            if tag == Tag::new(b"prep") {
+                builder.add_raw(tag, prep);
+            } else if let Some(data) = font.table_data(tag) {
+                builder.add_raw(tag, data);
+            }
+        }
+        testable.contents = builder.build();
+    }
+
+    #[test]
+    fn test_bytecode_pass() {
+        let testable = test_able("cabin/Cabin-Regular.ttf");
+        let results = run_check(super::bytecode, testable);
+        assert_pass(&results);
+    }
+
+    #[test]
+    fn test_bytecode_unhinted() {
+        let testable = test_able("mada/Mada-Regular.ttf");
+        let results = run_check(super::bytecode, testable);
+        assert_skip(&results);
+    }
+
+    #[test]
+    fn test_bytecode_cvt_out_of_range() {
+        // An old GX font with CVT instructions but no 'cvt ' table
+        let testable = test_able("ancho/AnchoGX.ttf");
+        let results = run_check(super::bytecode, testable);
+        assert_results_contain(
+            &results,
+            StatusCode::Fail,
+            Some("cvt-out-of-range".to_string()),
+        );
+    }
+
+    #[test]
+    fn test_bytecode_broken_prep() {
+        // POP
+        let mut testable = test_able("cabin/Cabin-Regular.ttf");
+        replace_prep(&mut testable, &[0x21]);
+        let results = run_check(super::bytecode, testable);
+        assert_results_contain(
+            &results,
+            StatusCode::Fail,
+            Some("stack-underflow".to_string()),
+        );
+
+        // PUSHB[0] 250, CALL
+        let mut testable = test_able("cabin/Cabin-Regular.ttf");
+        replace_prep(&mut testable, &[0xB0, 250, 0x2B]);
+        let results = run_check(super::bytecode, testable);
+        assert_results_contain(
+            &results,
+            StatusCode::Fail,
+            Some("undefined-function".to_string()),
+        );
+    }
+}
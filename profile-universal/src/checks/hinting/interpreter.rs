@@ -0,0 +1,1005 @@
+//! A small abstract interpreter for TrueType bytecode.
+//!
+//! We don't know the outline, the ppem size or the results of the rasterizer's
+//! measurements, so every value on the stack is either a known constant (pushed
+//! by the program or computed from other constants) or unknown. Conditionals
+//! with unknown conditions are executed down both branches and the resulting
+//! states merged. Whenever the analysis can no longer say anything sensible
+//! (a computed function number, a jump with an unknown offset, branches which
+//! leave the stack at different depths...) we quietly give up on that program
+//! rather than risk reporting a problem which isn't there.
+use std::{collections::HashMap, sync::Arc};
+
+use fontations::skrifa::{
+    raw::{tables::glyf::Glyph, TableProvider},
+    GlyphId, Tag,
+};
+use fontspector_checkapi::{Context, FontspectorError, TestFont};
+
+/// Maximum number of instructions we will execute for a single program
+const BUDGET: usize = 100_000;
+/// Maximum nesting of function calls before we give up
+const MAX_CALL_DEPTH: usize = 64;
+
+type Value = Option<i32>;
+
+/// Where a piece of bytecode came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ProgramSource {
+    Fpgm,
+    Prep,
+    Glyph(GlyphId),
+}
+
+/// A problem found while executing a program
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum HintingError {
+    Malformed(&'static str),
+    StackUnderflow(&'static str),
+    CvtOutOfRange(i32),
+    StorageOutOfRange(i32),
+    UndefinedFunction(i32),
+    UndefinedInstruction(u8),
+}
+
+impl HintingError {
+    pub(crate) fn code(&self) -> &'static str {
+        match self {
+            HintingError::Malformed(_) => "malformed-program",
+            HintingError::StackUnderflow(_) => "stack-underflow",
+            HintingError::CvtOutOfRange(_) => "cvt-out-of-range",
+            HintingError::StorageOutOfRange(_) => "storage-out-of-range",
+            HintingError::UndefinedFunction(_) => "undefined-function",
+            HintingError::UndefinedInstruction(_) => "undefined-instruction",
+        }
+    }
+}
+
+impl std::fmt::Display for HintingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HintingError::Malformed(why) => write!(f, "{why}"),
+            HintingError::StackUnderflow(instruction) => {
+                write!(f, "stack underflow in {instruction}")
+            }
+            HintingError::CvtOutOfRange(index) => write!(f, "CVT index {index} is out of range"),
+            HintingError::StorageOutOfRange(index) => {
+                write!(f, "storage index {index} is out of range")
+            }
+            HintingError::UndefinedFunction(number) => {
+                write!(f, "call to undefined function {number}")
+            }
+            HintingError::UndefinedInstruction(opcode) => {
+                write!(f, "undefined instruction 0x{opcode:02X}")
+            }
+        }
+    }
+}
+
+/// The resources the hinting programs were seen to use
+#[derive(Debug, Default)]
+pub(crate) struct HintingUsage {
+    pub max_stack_depth: usize,
+    pub max_function_number: Option<i32>,
+    pub instruction_defs: usize,
+    pub max_twilight_point: Option<i32>,
+    pub max_instructions_size: usize,
+}
+
+/// The result of analysing all the hinting programs in a font
+#[derive(Debug, Default)]
+pub(crate) struct HintingReport {
+    pub errors: Vec<(ProgramSource, HintingError)>,
+    pub usage: HintingUsage,
+}
+
+#[derive(Debug)]
+struct Instruction {
+    pc: usize,
+    opcode: u8,
+    args: Vec<i32>,
+}
+
+/// A decoded program, with its control flow structure worked out up front
+#[derive(Debug, Default)]
+struct Program {
+    instructions: Vec<Instruction>,
+    by_pc: HashMap<usize, usize>,
+    /// IF or ELSE index => (matching ELSE, matching EIF)
+    branches: HashMap<usize, (Option<usize>, usize)>,
+    /// FDEF or IDEF index => matching ENDF
+    definitions: HashMap<usize, usize>,
+    malformed: Option<&'static str>,
+}
+
+impl Program {
+    fn new(code: &[u8]) -> Self {
+        let mut program = Program::default();
+        let mut i = 0;
+        while let Some(&opcode) = code.get(i) {
+            let pc = i;
+            i += 1;
+            let (count, width) = match opcode {
+                0x40 | 0x41 => {
+                    let Some(&n) = code.get(i) else {
+                        program.malformed = Some("truncated NPUSH instruction");
+                        break;
+                    };
+                    i += 1;
+                    (n as usize, if opcode == 0x41 { 2 } else { 1 })
+                }
+                0xB0..=0xB7 => ((opcode - 0xAF) as usize, 1),
+                0xB8..=0xBF => ((opcode - 0xB7) as usize, 2),
+                _ => (0, 0),
+            };
+            let Some(data) = code.get(i..i + count * width) else {
+                program.malformed = Some("truncated PUSH instruction");
+                break;
+            };
+            let args = if width == 1 {
+                data.iter().map(|&b| b as i32).collect()
+            } else {
+                data.chunks_exact(2)
+                    .map(|word| match word {
+                        [hi, lo] => i16::from_be_bytes([*hi, *lo]) as i32,
+                        _ => 0,
+                    })
+                    .collect()
+            };
+            i += count * width;
+            program.by_pc.insert(pc, program.instructions.len());
+            program.instructions.push(Instruction { pc, opcode, args });
+        }
+        program.find_structure();
+        program
+    }
+
+    fn find_structure(&mut self) {
+        enum Open {
+            If(usize, Option<usize>),
+            Def(usize),
+        }
+        let mut open = vec![];
+        for (index, instruction) in self.instructions.iter().enumerate() {
+            match (instruction.opcode, open.last_mut()) {
+                (0x58, _) => open.push(Open::If(index, None)),
+                (0x1B, Some(Open::If(_, else_index @ None))) => *else_index = Some(index),
+                (0x59, Some(Open::If(if_index, else_index))) => {
+                    let (if_index, else_index) = (*if_index, *else_index);
+                    self.branches.insert(if_index, (else_index, index));
+                    if let Some(else_index) = else_index {
+                        self.branches.insert(else_index, (None, index));
+                    }
+                    open.pop();
+                }
+                (0x2C | 0x89, _) => open.push(Open::Def(index)),
+                (0x2D, Some(Open::Def(def_index))) => {
+                    self.definitions.insert(*def_index, index);
+                    open.pop();
+                }
+                (0x1B | 0x59 | 0x2D, _) => {
+                    self.malformed
+                        .get_or_insert("unbalanced IF/ELSE/EIF or FDEF/ENDF instructions");
+                    return;
+                }
+                _ => {}
+            }
+        }
+        if !open.is_empty() {
+            self.malformed
+                .get_or_insert("unbalanced IF/ELSE/EIF or FDEF/ENDF instructions");
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct State {
+    stack: Vec<Value>,
+    storage: HashMap<i32, Value>,
+    /// Set when something wrote to an unknown storage location
+    storage_tainted: bool,
+    zone_pointers: [Value; 3],
+    loop_count: Value,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        State {
+            stack: vec![],
+            storage: HashMap::new(),
+            storage_tainted: false,
+            zone_pointers: [Some(1); 3],
+            loop_count: Some(1),
+        }
+    }
+}
+
+impl State {
+    fn read_storage(&self, index: i32) -> Value {
+        match self.storage.get(&index) {
+            Some(value) => *value,
+            None if self.storage_tainted => None,
+            None => Some(0),
+        }
+    }
+
+    fn merge(&mut self, other: State) -> Result<(), Stop> {
+        if self.stack.len() != other.stack.len() {
+            return Err(Stop::GiveUp);
+        }
+        for (mine, theirs) in self.stack.iter_mut().zip(other.stack.iter()) {
+            if mine != theirs {
+                *mine = None;
+            }
+        }
+        let keys: Vec<i32> = self
+            .storage
+            .keys()
+            .chain(other.storage.keys())
+            .copied()
+            .collect();
+        for key in keys {
+            if self.read_storage(key) != other.read_storage(key) {
+                self.storage.insert(key, None);
+            }
+        }
+        self.storage_tainted |= other.storage_tainted;
+        for (mine, theirs) in self.zone_pointers.iter_mut().zip(other.zone_pointers) {
+            if *mine != theirs {
+                *mine = None;
+            }
+        }
+        if self.loop_count != other.loop_count {
+            self.loop_count = None;
+        }
+        Ok(())
+    }
+}
+
+/// Why execution of a program stopped early
+enum Stop {
+    Error(HintingError),
+    GiveUp,
+}
+
+impl From<HintingError> for Stop {
+    fn from(error: HintingError) -> Self {
+        Stop::Error(error)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Definer {
+    Fpgm,
+    Prep,
+}
+
+struct Interpreter<'a> {
+    fpgm: &'a Program,
+    prep: &'a Program,
+    cvt_len: i32,
+    max_storage: i32,
+    axis_count: usize,
+    functions: HashMap<i32, (Definer, usize, usize)>,
+    instruction_defs: HashMap<i32, (Definer, usize, usize)>,
+    /// False if we gave up on fpgm or prep before seeing all their definitions
+    definitions_complete: bool,
+    budget: usize,
+    usage: HintingUsage,
+}
+
+impl<'a> Interpreter<'a> {
+    fn program(&self, definer: Definer) -> &'a Program {
+        match definer {
+            Definer::Fpgm => self.fpgm,
+            Definer::Prep => self.prep,
+        }
+    }
+
+    fn execute(
+        &mut self,
+        program: &Program,
+        definer: Option<Definer>,
+        state: &mut State,
+    ) -> Result<(), Stop> {
+        if let Some(why) = program.malformed {
+            return Err(HintingError::Malformed(why).into());
+        }
+        self.budget = BUDGET;
+        self.run(program, definer, 0, program.instructions.len(), state, 0)
+    }
+
+    /// Charge one step against the budget, giving up when it runs out
+    fn spend(&mut self) -> Result<(), Stop> {
+        self.budget = self.budget.checked_sub(1).ok_or(Stop::GiveUp)?;
+        Ok(())
+    }
+
+    fn pop(&self, state: &mut State, instruction: &'static str) -> Result<Value, Stop> {
+        state
+            .stack
+            .pop()
+            .ok_or(Stop::Error(HintingError::StackUnderflow(instruction)))
+    }
+
+    fn push(&mut self, state: &mut State, value: Value) {
+        state.stack.push(value);
+        self.usage.max_stack_depth = self.usage.max_stack_depth.max(state.stack.len());
+    }
+
+    fn touch_point(&mut self, state: &State, zone_pointer: usize, point: Value) {
+        if let (Some(point), Some(Some(0))) = (point, state.zone_pointers.get(zone_pointer)) {
+            self.usage.max_twilight_point = self.usage.max_twilight_point.max(Some(point));
+        }
+    }
+
+    fn check_cvt(&self, index: Value) -> Result<(), Stop> {
+        match index {
+            Some(index) if index < 0 || index >= self.cvt_len => {
+                Err(HintingError::CvtOutOfRange(index).into())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn check_storage(&self, index: Value) -> Result<(), Stop> {
+        match index {
+            Some(index) if index < 0 || index >= self.max_storage => {
+                Err(HintingError::StorageOutOfRange(index).into())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn call(
+        &mut self,
+        definition: (Definer, usize, usize),
+        state: &mut State,
+        depth: usize,
+    ) -> Result<(), Stop> {
+        if depth >= MAX_CALL_DEPTH {
+            return Err(Stop::GiveUp);
+        }
+        let (definer, start, end) = definition;
+        let program = self.program(definer);
+        self.run(program, Some(definer), start, end, state, depth + 1)
+    }
+
+    /// Run the instructions from `index` up to (but not including) `end`
+    fn run(
+        &mut self,
+        program: &Program,
+        definer: Option<Definer>,
+        mut index: usize,
+        end: usize,
+        state: &mut State,
+        depth: usize,
+    ) -> Result<(), Stop> {
+        let start = index;
+        while index < end {
+            self.spend()?;
+            let instruction = program.instructions.get(index).ok_or(Stop::GiveUp)?;
+            match instruction.opcode {
+                0x40 | 0x41 | 0xB0..=0xBF => {
+                    for arg in instruction.args.iter() {
+                        self.push(state, Some(*arg));
+                    }
+                }
+                // IF
+                0x58 => {
+                    let condition = self.pop(state, "IF")?;
+                    let &(else_index, eif_index) =
+                        program.branches.get(&index).ok_or(Stop::GiveUp)?;
+                    let then_end = else_index.unwrap_or(eif_index);
+                    match condition {
+                        Some(0) => index = then_end,
+                        Some(_) => {}
+                        None => {
+                            let mut other = state.clone();
+                            self.run(program, definer, index + 1, then_end, state, depth)?;
+                            if let Some(else_index) = else_index {
+                                self.run(
+                                    program,
+                                    definer,
+                                    else_index + 1,
+                                    eif_index,
+                                    &mut other,
+                                    depth,
+                                )?;
+                            }
+                            state.merge(other)?;
+                            index = eif_index;
+                        }
+                    }
+                }
+                // ELSE, reached at the end of a taken IF branch
+                0x1B => index = program.branches.get(&index).ok_or(Stop::GiveUp)?.1,
+                // EIF
+                0x59 => {}
+                // JMPR, JROT, JROF
+                0x1C | 0x78 | 0x79 => {
+                    let jump = match instruction.opcode {
+                        0x1C => Some(true),
+                        0x78 => self.pop(state, "JROT")?.map(|c| c != 0),
+                        _ => self.pop(state, "JROF")?.map(|c| c == 0),
+                    };
+                    let offset = self.pop(state, "jump")?;
+                    match (jump, offset) {
+                        (Some(false), _) => {}
+                        (Some(true), Some(offset)) if offset != 0 => {
+                            let target = (instruction.pc as i64 + offset as i64) as usize;
+                            match program.by_pc.get(&target) {
+                                Some(&target) if (start..end).contains(&target) => {
+                                    index = target;
+                                    continue;
+                                }
+                                _ => return Err(Stop::GiveUp),
+                            }
+                        }
+                        _ => return Err(Stop::GiveUp),
+                    }
+                }
+                // FDEF, IDEF
+                0x2C | 0x89 => {
+                    let number = self.pop(state, "FDEF")?.ok_or(Stop::GiveUp)?;
+                    let definer = definer.ok_or(Stop::GiveUp)?;
+                    let endf = *program.definitions.get(&index).ok_or(Stop::GiveUp)?;
+                    if instruction.opcode == 0x2C {
+                        self.functions.insert(number, (definer, index + 1, endf));
+                        self.usage.max_function_number =
+                            self.usage.max_function_number.max(Some(number));
+                    } else {
+                        self.instruction_defs
+                            .insert(number, (definer, index + 1, endf));
+                        self.usage.instruction_defs = self.instruction_defs.len();
+                    }
+                    index = endf;
+                }
+                // ENDF
+                0x2D => return Ok(()),
+                // CALL, LOOPCALL
+                0x2B | 0x2A => {
+                    let number = self.pop(state, "CALL")?;
+                    let count = if instruction.opcode == 0x2A {
+                        self.pop(state, "LOOPCALL")?
+                    } else {
+                        Some(1)
+                    };
+                    let (Some(number), Some(count)) = (number, count) else {
+                        return Err(Stop::GiveUp);
+                    };
+                    let Some(&definition) = self.functions.get(&number) else {
+                        if self.definitions_complete {
+                            return Err(HintingError::UndefinedFunction(number).into());
+                        }
+                        return Err(Stop::GiveUp);
+                    };
+                    // Each iteration is charged, as calls to empty functions cost nothing
+                    for _ in 0..count.max(0) {
+                        self.spend()?;
+                        self.call(definition, state, depth)?;
+                    }
+                }
+                opcode => self.simple_instruction(opcode, state, depth)?,
+            }
+            index += 1;
+        }
+        Ok(())
+    }
+
+    /// Everything which doesn't affect control flow
+    fn simple_instruction(
+        &mut self,
+        opcode: u8,
+        state: &mut State,
+        depth: usize,
+    ) -> Result<(), Stop> {
+        match opcode {
+            // DUP
+            0x20 => {
+                let value = self.pop(state, "DUP")?;
+                self.push(state, value);
+                self.push(state, value);
+            }
+            // POP
+            0x21 => {
+                self.pop(state, "POP")?;
+            }
+            // CLEAR
+            0x22 => state.stack.clear(),
+            // SWAP
+            0x23 => {
+                let a = self.pop(state, "SWAP")?;
+                let b = self.pop(state, "SWAP")?;
+                self.push(state, a);
+                self.push(state, b);
+            }
+            // DEPTH
+            0x24 => {
+                let depth = state.stack.len() as i32;
+                self.push(state, Some(depth));
+            }
+            // CINDEX, MINDEX
+            0x25 | 0x26 => {
+                let name = if opcode == 0x25 { "CINDEX" } else { "MINDEX" };
+                let n = self.pop(state, name)?.ok_or(Stop::GiveUp)?;
+                if n <= 0 || n as usize > state.stack.len() {
+                    return Err(HintingError::StackUnderflow(name).into());
+                }
+                let position = state.stack.len() - n as usize;
+                let value = if opcode == 0x26 {
+                    state.stack.remove(position)
+                } else {
+                    state.stack.get(position).copied().flatten()
+                };
+                self.push(state, value);
+            }
+            // ROLL
+            0x8A => {
+                let a = self.pop(state, "ROLL")?;
+                let b = self.pop(state, "ROLL")?;
+                let c = self.pop(state, "ROLL")?;
+                self.push(state, b);
+                self.push(state, a);
+                self.push(state, c);
+            }
+            // SZP0, SZP1, SZP2, SZPS
+            0x13..=0x16 => {
+                let zone = self.pop(state, "SZPx")?;
+                if opcode == 0x16 {
+                    state.zone_pointers = [zone; 3];
+                } else if let Some(pointer) = state.zone_pointers.get_mut((opcode - 0x13) as usize)
+                {
+                    *pointer = zone;
+                }
+            }
+            // SLOOP
+            0x17 => state.loop_count = self.pop(state, "SLOOP")?,
+            // SHP, SHPIX, IP, ALIGNRP, FLIPPT
+            0x32 | 0x33 | 0x38 | 0x39 | 0x3C | 0x80 => {
+                if opcode == 0x38 {
+                    self.pop(state, "SHPIX")?;
+                }
+                let count = state.loop_count.ok_or(Stop::GiveUp)?;
+                let zone_pointer = if opcode == 0x3C { 1 } else { 2 };
+                for _ in 0..count.max(0) {
+                    self.spend()?;
+                    let point = self.pop(state, "looped instruction")?;
+                    self.touch_point(state, zone_pointer, point);
+                }
+                state.loop_count = Some(1);
+            }
+            // MDAP
+            0x2E | 0x2F => {
+                let point = self.pop(state, "MDAP")?;
+                self.touch_point(state, 0, point);
+            }
+            // MDRP
+            0xC0..=0xDF => {
+                let point = self.pop(state, "MDRP")?;
+                self.touch_point(state, 1, point);
+            }
+            // MIRP
+            0xE0..=0xFF => {
+                let cvt = self.pop(state, "MIRP")?;
+                self.check_cvt(cvt)?;
+                let point = self.pop(state, "MIRP")?;
+                self.touch_point(state, 1, point);
+            }
+            // MIAP
+            0x3E | 0x3F => {
+                let cvt = self.pop(state, "MIAP")?;
+                self.check_cvt(cvt)?;
+                let point = self.pop(state, "MIAP")?;
+                self.touch_point(state, 0, point);
+            }
+            // MSIRP
+            0x3A | 0x3B => {
+                self.pop(state, "MSIRP")?;
+                let point = self.pop(state, "MSIRP")?;
+                self.touch_point(state, 1, point);
+            }
+            // SCFS
+            0x48 => {
+                self.pop(state, "SCFS")?;
+                let point = self.pop(state, "SCFS")?;
+                self.touch_point(state, 2, point);
+            }
+            // GC
+            0x46 | 0x47 => {
+                let point = self.pop(state, "GC")?;
+                self.touch_point(state, 2, point);
+                self.push(state, None);
+            }
+            // WS
+            0x42 => {
+                let value = self.pop(state, "WS")?;
+                let location = self.pop(state, "WS")?;
+                self.check_storage(location)?;
+                match location {
+                    Some(location) => {
+                        state.storage.insert(location, value);
+                    }
+                    None => {
+                        state.storage.clear();
+                        state.storage_tainted = true;
+                    }
+                }
+            }
+            // RS
+            0x43 => {
+                let location = self.pop(state, "RS")?;
+                self.check_storage(location)?;
+                let value = location.and_then(|location| state.read_storage(location));
+                self.push(state, value);
+            }
+            // WCVTP, WCVTF
+            0x44 | 0x70 => {
+                self.pop(state, "WCVT")?;
+                let cvt = self.pop(state, "WCVT")?;
+                self.check_cvt(cvt)?;
+            }
+            // RCVT
+            0x45 => {
+                let cvt = self.pop(state, "RCVT")?;
+                self.check_cvt(cvt)?;
+                self.push(state, None);
+            }
+            // DELTAP1-3, DELTAC1-3
+            0x5D | 0x71..=0x75 => {
+                let count = self.pop(state, "DELTA")?.ok_or(Stop::GiveUp)?;
+                for _ in 0..count.max(0) {
+                    self.spend()?;
+                    let target = self.pop(state, "DELTA")?;
+                    if opcode >= 0x73 {
+                        self.check_cvt(target)?;
+                    }
+                    self.pop(state, "DELTA")?;
+                }
+            }
+            // LT, LTEQ, GT, GTEQ, EQ, NEQ, AND, OR, ADD, SUB, DIV, MUL, MAX, MIN
+            0x50..=0x55 | 0x5A | 0x5B | 0x60..=0x63 | 0x8B | 0x8C => {
+                let b = self.pop(state, "binary operator")?;
+                let a = self.pop(state, "binary operator")?;
+                let result = binary_operator(opcode, a, b);
+                self.push(state, result);
+            }
+            // ODD, EVEN, NOT, ABS, NEG, FLOOR, CEILING, ROUND, NROUND
+            0x56 | 0x57 | 0x5C | 0x64..=0x6F => {
+                let a = self.pop(state, "unary operator")?;
+                let result = a.and_then(|a| match opcode {
+                    0x5C => Some((a == 0) as i32),
+                    0x64 => Some(a.wrapping_abs()),
+                    0x65 => Some(a.wrapping_neg()),
+                    0x66 => Some(a & !63),
+                    0x67 => Some(a.wrapping_add(63) & !63),
+                    // Depends on the rounding state, engine compensation...
+                    _ => None,
+                });
+                self.push(state, result);
+            }
+            // GETVARIATION
+            0x91 => {
+                for _ in 0..self.axis_count {
+                    self.push(state, None);
+                }
+            }
+            _ => {
+                if let Some((pops, pushes)) = arity(opcode) {
+                    for _ in 0..pops {
+                        self.pop(state, "instruction")?;
+                    }
+                    for _ in 0..pushes {
+                        self.push(state, None);
+                    }
+                } else if let Some(&definition) = self.instruction_defs.get(&(opcode as i32)) {
+                    self.call(definition, state, depth)?;
+                } else if self.definitions_complete {
+                    return Err(HintingError::UndefinedInstruction(opcode).into());
+                } else {
+                    return Err(Stop::GiveUp);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn binary_operator(opcode: u8, a: Value, b: Value) -> Value {
+    match (opcode, a, b) {
+        // AND and OR can be decided with only one side known
+        (0x5A, Some(0), _) | (0x5A, _, Some(0)) => Some(0),
+        (0x5B, Some(x), _) | (0x5B, _, Some(x)) if x != 0 => Some(1),
+        (_, Some(a), Some(b)) => match opcode {
+            0x50 => Some((a < b) as i32),
+            0x51 => Some((a <= b) as i32),
+            0x52 => Some((a > b) as i32),
+            0x53 => Some((a >= b) as i32),
+            0x54 => Some((a == b) as i32),
+            0x55 => Some((a != b) as i32),
+            0x5A => Some(1),
+            0x5B => Some(0),
+            0x60 => Some(a.wrapping_add(b)),
+            0x61 => Some(a.wrapping_sub(b)),
+            // DIV and MUL work on F26Dot6 values
+            0x62 if b != 0 => Some((a as i64 * 64 / b as i64) as i32),
+            0x63 => Some((a as i64 * b as i64 / 64) as i32),
+            0x8B => Some(a.max(b)),
+            0x8C => Some(a.min(b)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Stack effect (pops, pushes) of instructions which only move values around
+fn arity(opcode: u8) -> Option<(usize, usize)> {
+    Some(match opcode {
+        // SVTCA, SPVTCA, SFVTCA, SFVTPV, RTG, RTHG, IUP, RTDG, FLIPON, FLIPOFF, ROFF, RUTG, RDTG
+        0x00..=0x05
+        | 0x0E
+        | 0x18
+        | 0x19
+        | 0x30
+        | 0x31
+        | 0x3D
+        | 0x4D
+        | 0x4E
+        | 0x7A
+        | 0x7C
+        | 0x7D => (0, 0),
+        // SPVTL, SFVTL, SPVFS, SFVFS, ALIGNPTS, FLIPRGON, FLIPRGOFF, SDPVTL, INSTCTRL
+        0x06..=0x0B | 0x27 | 0x81 | 0x82 | 0x86 | 0x87 | 0x8E => (2, 0),
+        // GPV, GFV
+        0x0C | 0x0D => (0, 2),
+        // ISECT
+        0x0F => (5, 0),
+        // SRP0-2, SMD, SCVTCI, SSWCI, SSW, UTP, SHC, SHZ, DEBUG, SDB, SDS, SROUND, S45ROUND,
+        // SANGW, AA, SCANCTRL, SCANTYPE
+        0x10..=0x12
+        | 0x1A
+        | 0x1D..=0x1F
+        | 0x29
+        | 0x34..=0x37
+        | 0x4F
+        | 0x5E
+        | 0x5F
+        | 0x76
+        | 0x77
+        | 0x7E
+        | 0x7F
+        | 0x85
+        | 0x8D => (1, 0),
+        // MPPEM, MPS, GETDATA
+        0x4B | 0x4C | 0x92 => (0, 1),
+        // GETINFO
+        0x88 => (1, 1),
+        // MD
+        0x49 | 0x4A => (2, 1),
+        _ => return None,
+    })
+}
+
+fn glyph_instructions(glyph: &Glyph) -> &[u8] {
+    match glyph {
+        Glyph::Simple(simple) => simple.instructions(),
+        Glyph::Composite(composite) => composite.instructions().unwrap_or_default(),
+    }
+}
+
+/// Returns true if the font has any TrueType hinting programs
+pub(crate) fn is_hinted(f: &TestFont) -> bool {
+    f.has_table(b"fpgm") || f.has_table(b"prep")
+}
+
+/// Execute the font and glyph programs, collecting errors and resource usage
+///
+/// The report is shared between the checks which need it. Stops with a
+/// timeout error if the check runs out of time.
+pub(crate) fn analyze_hinting(
+    f: &TestFont,
+    context: &Context,
+) -> Result<Arc<HintingReport>, FontspectorError> {
+    f.memoize(Some(context), "hinting_report", || {
+        run_hinting_programs(f, context)
+    })
+}
+
+fn run_hinting_programs(
+    f: &TestFont,
+    context: &Context,
+) -> Result<HintingReport, FontspectorError> {
+    let font = f.font();
+    let table = |tag: &[u8; 4]| {
+        font.table_data(Tag::new(tag))
+            .map(|data| Program::new(data.as_bytes()))
+            .unwrap_or_default()
+    };
+    let fpgm = table(b"fpgm");
+    let prep = table(b"prep");
+    let maxp = font.maxp()?;
+    let mut interpreter = Interpreter {
+        fpgm: &fpgm,
+        prep: &prep,
+        cvt_len: font
+            .table_data(Tag::new(b"cvt "))
+            .map(|data| (data.len() / 2) as i32)
+            .unwrap_or(0),
+        max_storage: maxp.max_storage().unwrap_or(0) as i32,
+        axis_count: font
+            .fvar()
+            .map(|fvar| fvar.axis_count() as usize)
+            .unwrap_or(0),
+        functions: HashMap::new(),
+        instruction_defs: HashMap::new(),
+        definitions_complete: true,
+        budget: BUDGET,
+        usage: HintingUsage::default(),
+    };
+    let mut report = HintingReport::default();
+
+    let mut state = State::default();
+    if let Err(stop) = interpreter.execute(&fpgm, Some(Definer::Fpgm), &mut state) {
+        interpreter.definitions_complete = false;
+        if let Stop::Error(error) = stop {
+            report.errors.push((ProgramSource::Fpgm, error));
+        }
+    }
+
+    context.check_deadline()?;
+
+    // The state left behind by prep is the starting point for each glyph
+    let mut glyph_state = State::default();
+    if let Err(stop) = interpreter.execute(&prep, Some(Definer::Prep), &mut glyph_state) {
+        if prep
+            .instructions
+            .iter()
+            .any(|i| i.opcode == 0x2C || i.opcode == 0x89)
+        {
+            interpreter.definitions_complete = false;
+        }
+        glyph_state.storage.clear();
+        glyph_state.storage_tainted = true;
+        if let Stop::Error(error) = stop {
+            report.errors.push((ProgramSource::Prep, error));
+        }
+    }
+    // ...apart from the stack, and the zone pointers and loop counter, which
+    // are reset before every program is run
+    glyph_state.stack.clear();
+    glyph_state.zone_pointers = [Some(1); 3];
+    glyph_state.loop_count = Some(1);
+
+    if f.has_table(b"glyf") {
+        for gid in f.all_glyphs() {
+            context.check_deadline()?;
+            let Some(glyph) = f.get_glyf_glyph(gid)? else {
+                continue;
+            };
+            let code = glyph_instructions(&glyph);
+            if code.is_empty() {
+                continue;
+            }
+            interpreter.usage.max_instructions_size =
+                interpreter.usage.max_instructions_size.max(code.len());
+            let program = Program::new(code);
+            let mut state = glyph_state.clone();
+            if let Err(Stop::Error(error)) = interpreter.execute(&program, None, &mut state) {
+                report.errors.push((ProgramSource::Glyph(gid), error));
+            }
+        }
+    }
+    report.usage = interpreter.usage;
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use fontspector_checkapi::{codetesting::test_able, FileTypeConvert, TTF};
+
+    use super::*;
+
+    fn run(code: &[u8]) -> (Result<(), HintingError>, HintingUsage) {
+        let empty = Program::default();
+        let mut interpreter = Interpreter {
+            fpgm: &empty,
+            prep: &empty,
+            cvt_len: 4,
+            max_storage: 4,
+            axis_count: 0,
+            functions: HashMap::new(),
+            instruction_defs: HashMap::new(),
+            definitions_complete: true,
+            budget: BUDGET,
+            usage: HintingUsage::default(),
+        };
+        let result = match interpreter.execute(&Program::new(code), None, &mut State::default()) {
+            Err(Stop::Error(error)) => Err(error),
+            _ => Ok(()),
+        };
+        (result, interpreter.usage)
+    }
+
+    #[test]
+    fn test_stack_effects() {
+        // PUSHB[2] 1 2, ADD, DUP, POP
+        let (result, usage) = run(&[0xB1, 1, 2, 0x60, 0x20, 0x21]);
+        assert_eq!(result, Ok(()));
+        assert_eq!(usage.max_stack_depth, 2);
+        // POP on an empty stack
+        assert_eq!(run(&[0x21]).0, Err(HintingError::StackUnderflow("POP")));
+        // PUSHB[0] 5, RCVT
+        assert_eq!(run(&[0xB0, 5, 0x45]).0, Err(HintingError::CvtOutOfRange(5)));
+        // PUSHB[1] 4 0, WS
+        assert_eq!(
+            run(&[0xB1, 4, 0, 0x42]).0,
+            Err(HintingError::StorageOutOfRange(4))
+        );
+        // PUSHB[0] 7, CALL
+        assert_eq!(
+            run(&[0xB0, 7, 0x2B]).0,
+            Err(HintingError::UndefinedFunction(7))
+        );
+    }
+
+    #[test]
+    fn test_unknown_branches() {
+        // MPPEM, IF, PUSHB[0] 1, ELSE, PUSHB[0] 2, EIF, POP
+        assert_eq!(
+            run(&[0x4B, 0x58, 0xB0, 1, 0x1B, 0xB0, 2, 0x59, 0x21]).0,
+            Ok(())
+        );
+        // An underflow which only happens on one branch is still found
+        // MPPEM, IF, POP, EIF
+        assert_eq!(
+            run(&[0x4B, 0x58, 0x21, 0x59]).0,
+            Err(HintingError::StackUnderflow("POP"))
+        );
+        // Known conditions only take one branch: PUSHB[0] 0, IF, POP, EIF
+        assert_eq!(run(&[0xB0, 0, 0x58, 0x21, 0x59]).0, Ok(()));
+    }
+
+    #[test]
+    fn test_malformed() {
+        assert!(matches!(run(&[0xB1, 1]).0, Err(HintingError::Malformed(_))));
+        assert!(matches!(
+            run(&[0x4B, 0x58, 0x21]).0,
+            Err(HintingError::Malformed(_))
+        ));
+    }
+
+    #[test]
+    fn test_budget() {
+        // Define an empty function 0, then LOOPCALL it 30000 * 30000 / 64 times:
+        // PUSHB[0] 0, FDEF, ENDF, PUSHW[1] 30000 30000, MUL, PUSHB[0] 0, LOOPCALL
+        let code = [
+            0xB0, 0, 0x2C, 0x2D, 0xB9, 0x75, 0x30, 0x75, 0x30, 0x63, 0xB0, 0, 0x2A,
+        ];
+        let program = Program::new(&code);
+        let mut interpreter = Interpreter {
+            fpgm: &program,
+            prep: &program,
+            cvt_len: 0,
+            max_storage: 0,
+            axis_count: 0,
+            functions: HashMap::new(),
+            instruction_defs: HashMap::new(),
+            definitions_complete: true,
+            budget: BUDGET,
+            usage: HintingUsage::default(),
+        };
+        let result = interpreter.execute(&program, Some(Definer::Fpgm), &mut State::default());
+        assert!(matches!(result, Err(Stop::GiveUp)));
+    }
+
+    #[test]
+    fn test_report_is_shared() {
+        let testable = test_able("cabin/Cabin-Regular.ttf");
+        let f = TTF.from_testable(&testable).unwrap();
+        let context = Context::default();
+        let first = analyze_hinting(&f, &context).unwrap();
+        let second = analyze_hinting(&f, &context).unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+    }
+}
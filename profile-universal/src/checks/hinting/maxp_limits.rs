@@ -0,0 +1,217 @@
+use fontations::{
+    skrifa::raw::TableProvider,
+    write::{from_obj::ToOwnedTable, tables::maxp::Maxp},
+};
+use fontspector_checkapi::{prelude::*, skip, testfont, FileTypeConvert, TestFont};
+
+use super::interpreter::{analyze_hinting, is_hinted, HintingUsage};
+
+/// The maxp values the hinting programs need
+struct Required {
+    twilight_points: u16,
+    function_defs: u16,
+    instruction_defs: u16,
+    stack_elements: u16,
+    size_of_instructions: u16,
+}
+
+impl From<&HintingUsage> for Required {
+    fn from(usage: &HintingUsage) -> Self {
+        let clamp = |value: i64| value.clamp(0, u16::MAX as i64) as u16;
+        Required {
+            twilight_points: clamp(usage.max_twilight_point.map_or(0, |p| p as i64 + 1)),
+            function_defs: clamp(usage.max_function_number.map_or(0, |f| f as i64 + 1)),
+            instruction_defs: clamp(usage.instruction_defs as i64),
+            stack_elements: clamp(usage.max_stack_depth as i64),
+            size_of_instructions: clamp(usage.max_instructions_size as i64),
+        }
+    }
+}
+
+fn required_limits(f: &TestFont, context: &Context) -> Result<Required, FontspectorError> {
+    Ok(Required::from(&analyze_hinting(f, context)?.usage))
+}
+
+#[check(
+    id = "hinting/maxp_limits",
+    rationale = "
+        The version 1.0 'maxp' table tells the rasterizer how much memory to
+        set aside for executing the font's TrueType hinting programs: the size
+        of the interpreter stack, the number of function and instruction
+        definitions, the number of points in the twilight zone, and the size
+        of the largest glyph program.
+
+        Rasterizers allocate exactly what is declared. If the programs use
+        more than this, the rasterizer will either refuse to hint the font
+        at all, or read and write past the end of its buffers. Values which
+        are too low are a common symptom of post-processing a hinted font
+        with tools that do not recalculate these fields.
+
+        This check executes the hinting programs symbolically and compares
+        what they use against the declared limits. As some programs can't be
+        followed to the end without knowing the ppem size and outline, the
+        usage found is a lower bound.
+    ",
+    proposal = "https://learn.microsoft.com/en-us/typography/opentype/spec/maxp",
+    title = "Check the maxp hinting limits against what the programs use",
+    hotfix = fix_maxp_limits
+)]
+fn maxp_limits(t: &Testable, context: &Context) -> CheckFnResult {
+    let f = testfont!(t);
+    skip!(
+        !f.has_table(b"glyf"),
+        "not-ttf",
+        "Font does not have TrueType outlines"
+    );
+    skip!(!is_hinted(&f), "not-hinted", "Font is not hinted");
+    let maxp = f.font().maxp()?;
+    let required = required_limits(&f, context)?;
+
+    let mut problems = vec![];
+    let mut compare = |code: &str, field: &str, declared: Option<u16>, needed: u16, fail: bool| {
+        let declared = declared.unwrap_or(0);
+        if needed > declared {
+            let message = format!(
+                "maxp.{field} is {declared}, but the hinting programs need at least {needed}."
+            );
+            problems.push(if fail {
+                Status::fail(code, &message)
+            } else {
+                Status::warn(code, &message)
+            });
+        }
+    };
+    compare(
+        "max-twilight-points",
+        "maxTwilightPoints",
+        maxp.max_twilight_points(),
+        required.twilight_points,
+        true,
+    );
+    compare(
+        "max-function-defs",
+        "maxFunctionDefs",
+        maxp.max_function_defs(),
+        required.function_defs,
+        true,
+    );
+    compare(
+        "max-instruction-defs",
+        "maxInstructionDefs",
+        maxp.max_instruction_defs(),
+        required.instruction_defs,
+        true,
+    );
+    compare(
+        "max-stack-elements",
+        "maxStackElements",
+        maxp.max_stack_elements(),
+        required.stack_elements,
+        true,
+    );
+    // Most rasterizers don't rely on this one, so it's less serious
+    compare(
+        "max-size-of-instructions",
+        "maxSizeOfInstructions",
+        maxp.max_size_of_instructions(),
+        required.size_of_instructions,
+        false,
+    );
+    return_result(problems)
+}
+
+fn fix_maxp_limits(
+    t: &mut Testable,
+    _replies: Option<MoreInfoReplies>,
+) -> Result<FixResult, FontspectorError> {
+    let f = testfont!(t);
+    if !f.has_table(b"glyf") || !is_hinted(&f) {
+        return Ok(FixResult::Unfixable);
+    }
+    // Fixes are not run against the clock
+    let required = required_limits(&f, &Context::default())?;
+    let mut maxp: Maxp = f.font().maxp()?.to_owned_table();
+    let mut changed = false;
+    for (field, needed) in [
+        (&mut maxp.max_twilight_points, required.twilight_points),
+        (&mut maxp.max_function_defs, required.function_defs),
+        (&mut maxp.max_instruction_defs, required.instruction_defs),
+        (&mut maxp.max_stack_elements, required.stack_elements),
+        (
+            &mut maxp.max_size_of_instructions,
+            required.size_of_instructions,
+        ),
+    ] {
+        let Some(declared) = field else {
+            // A version 0.5 maxp table; we can't fix that here
+            return Ok(FixResult::Unfixable);
+        };
+        if needed > *declared {
+            *declared = needed;
+            changed = true;
+        }
+    }
+    if !changed {
+        return Ok(FixResult::Unfixable);
+    }
+    t.set(f.rebuild_with_new_table(&maxp)?);
+    Ok(FixResult::Fixed)
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use fontations::{
+        skrifa::raw::TableProvider,
+        write::{from_obj::ToOwnedTable, tables::maxp::Maxp},
+    };
+    use fontspector_checkapi::{
+        codetesting::{assert_pass, assert_results_contain, run_check, test_able},
+        FileTypeConvert, StatusCode, TTF,
+    };
+
+    #[test]
+    fn test_maxp_limits_pass() {
+        let testable = test_able("cabin/Cabin-Regular.ttf");
+        let results = run_check(super::maxp_limits, testable);
+        assert_pass(&results);
+    }
+
+    #[test]
+    fn test_maxp_limits_fail() {
+        // The prep table pushes far more values than maxStackElements allows,
+        // and the fpgm defines functions numbered beyond maxFunctionDefs.
+        let testable = test_able("rubik/Rubik-Italic.ttf");
+        let results = run_check(super::maxp_limits, testable);
+        assert_results_contain(
+            &results,
+            StatusCode::Fail,
+            Some("max-stack-elements".to_string()),
+        );
+        assert_results_contain(
+            &results,
+            StatusCode::Fail,
+            Some("max-function-defs".to_string()),
+        );
+    }
+
+    #[test]
+    fn test_maxp_limits_twilight() {
+        let mut testable = test_able("cabin/Cabin-Regular.ttf");
+        let f = TTF.from_testable(&testable).unwrap();
+        let mut maxp: Maxp = f.font().maxp().unwrap().to_owned_table();
+        maxp.max_twilight_points = Some(1);
+        testable.set(f.rebuild_with_new_table(&maxp).unwrap());
+        let results = run_check(super::maxp_limits, testable.clone());
+        assert_results_contain(
+            &results,
+            StatusCode::Fail,
+            Some("max-twilight-points".to_string()),
+        );
+
+        let fixed = super::fix_maxp_limits(&mut testable, None).unwrap();
+        assert!(matches!(fixed, fontspector_checkapi::FixResult::Fixed));
+        let results = run_check(super::maxp_limits, testable);
+        assert_pass(&results);
+    }
+}
@@ -0,0 +1,6 @@
+mod bytecode;
+mod interpreter;
+mod maxp_limits;
+
+pub use bytecode::bytecode;
+pub use maxp_limits::maxp_limits;
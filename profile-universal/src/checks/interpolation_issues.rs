@@ -155,7 +155,7 @@ fn problem_report(p: &Problem) -> String {
     proposal = "https://github.com/fonttools/fontbakery/issues/3930",
     title = "Detect any interpolation issues in the font."
 )]
-fn interpolation_issues(t: &Testable, _context: &Context) -> CheckFnResult {
+fn interpolation_issues(t: &Testable, context: &Context) -> CheckFnResult {
     let f = testfont!(t);
     let font = f.font();
     let upem = font.head()?.units_per_em();
@@ -168,6 +168,7 @@ fn interpolation_issues(t: &Testable, _context: &Context) -> CheckFnResult {
     let mut all_statuses: Vec<Status> = vec![];
     let mut locations: Vec<Vec<VariationSetting>> = vec![vec![]];
     for gid in f.all_glyphs() {
+        context.check_deadline()?;
         let glyphname = f.glyph_name_for_id_synthesise(gid);
         let mut default_glyph = interpolatable::Glyph::new_from_font(&font, gid, &[]).ok_or(
             FontspectorError::General(format!("Can't convert glyph {glyphname}")),
@@ -0,0 +1,329 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use fontations::skrifa::{raw::TableProvider, GlyphId};
+use fontspector_checkapi::{prelude::*, skip, testfont, FileTypeConvert, TestFont};
+use kurbo::{PathEl, Point};
+
+use super::kern_rules;
+
+/// Glyphs are measured in bands one percent of the em high, but a font with
+/// a tiny em and a huge bounding box gets fewer, taller bands than that
+const MAX_BANDS: usize = 1000;
+
+/// A division of the font's vertical extent into thin horizontal bands
+struct Bands {
+    y_min: f64,
+    height: f64,
+    count: usize,
+}
+
+/// The leftmost and rightmost ink of a glyph within each band
+type Profile = Vec<Option<(f64, f64)>>;
+
+impl Bands {
+    fn index(&self, y: f64) -> usize {
+        (((y - self.y_min) / self.height).floor().max(0.0) as usize).min(self.count - 1)
+    }
+
+    /// Widen the profile to take in the line from `a` to `b`
+    fn add_line(&self, profile: &mut Profile, a: Point, b: Point) {
+        let (low, high) = if a.y <= b.y { (a, b) } else { (b, a) };
+        for index in self.index(low.y)..=self.index(high.y) {
+            let band_bottom = self.y_min + index as f64 * self.height;
+            let band_top = band_bottom + self.height;
+            let x_at = |y: f64| {
+                if high.y == low.y {
+                    low.x
+                } else {
+                    let t = ((y - low.y) / (high.y - low.y)).clamp(0.0, 1.0);
+                    low.x + t * (high.x - low.x)
+                }
+            };
+            let (x0, x1) = if high.y == low.y {
+                (low.x, high.x)
+            } else {
+                (x_at(band_bottom), x_at(band_top))
+            };
+            if let Some(extent) = profile.get_mut(index) {
+                let (min, max) = extent.get_or_insert((f64::MAX, f64::MIN));
+                *min = min.min(x0).min(x1);
+                *max = max.max(x0).max(x1);
+            }
+        }
+    }
+
+    fn profile(&self, f: &TestFont, gid: GlyphId, context: &Context) -> Option<Profile> {
+        let pen = f.bez_glyph(gid, Some(context)).ok()?;
+        let mut profile = vec![None; self.count];
+        for path in pen.iter() {
+            let mut start = Point::ZERO;
+            let mut last = Point::ZERO;
+            path.flatten(self.height / 4.0, |el| match el {
+                PathEl::MoveTo(p) => {
+                    start = p;
+                    last = p;
+                }
+                PathEl::LineTo(p) => {
+                    self.add_line(&mut profile, last, p);
+                    last = p;
+                }
+                PathEl::ClosePath => {
+                    self.add_line(&mut profile, last, start);
+                    last = start;
+                }
+                _ => {}
+            });
+        }
+        Some(profile)
+    }
+}
+
+/// How far the ink of the right glyph reaches into the ink of the left one,
+/// given the right edges of the left glyph and the left edges of the right
+/// glyph, both measured from the right glyph's unkerned origin
+fn overlap(left_edges: &[Option<f64>], right_edges: &[Option<f64>], kern: f64) -> Option<f64> {
+    left_edges
+        .iter()
+        .zip(right_edges.iter())
+        .filter_map(|(l, r)| Some((*l)? - (*r)? - kern))
+        .reduce(f64::max)
+}
+
+#[check(
+    id = "kerning/collisions",
+    rationale = "
+        Negative kerning brings two glyphs closer together. Done well, it
+        evens out the space between glyphs such as 'T' and 'o'; overdone, it
+        makes glyphs which would otherwise sit apart run into each other. This
+        is easy to miss when kerning is applied to whole classes, because a
+        value which suits most of the class can be far too tight for an
+        accented or wider member of it, such as 'Y' followed by 'ư'.
+
+        This check draws both glyphs of every pair with a negative kern and
+        reports the pairs whose outlines do not touch when set without
+        kerning, but overlap by more than one percent of the em once the kern
+        is applied. Only kerning which adjusts the advance of the first glyph
+        is considered; right-to-left kerning, which also moves the glyph, is
+        not. Variable fonts are checked at the default location.
+    ",
+    proposal = "https://learn.microsoft.com/en-us/typography/opentype/spec/gpos#lookup-type-2-pair-adjustment-positioning-subtable",
+    title = "Does kerning cause glyphs to collide?"
+)]
+fn collisions(t: &Testable, context: &Context) -> CheckFnResult {
+    let f = testfont!(t);
+    skip!(!f.has_table(b"GPOS"), "no-gpos", "Font has no GPOS table.");
+    let head = f.font().head()?;
+    let hmtx = f.font().hmtx()?;
+    skip!(
+        head.units_per_em() == 0,
+        "zero-units-per-em",
+        "Font's unitsPerEm is 0, so there is no scale to measure collisions against."
+    );
+    let tolerance = head.units_per_em() as f64 / 100.0;
+    let extent = (head.y_max() as f64 - head.y_min() as f64).max(tolerance);
+    let count = ((extent / tolerance).ceil() as usize).clamp(1, MAX_BANDS);
+    let bands = Bands {
+        y_min: head.y_min() as f64,
+        height: extent / count as f64,
+        count,
+    };
+
+    let all_rules = kern_rules(&f, context)?;
+    let rules = all_rules
+        .iter()
+        .filter(|rule| rule.x_advance < 0 && !rule.has_placement)
+        .collect::<Vec<_>>();
+    let glyphs = rules
+        .iter()
+        .flat_map(|rule| rule.left.iter().chain(rule.right.iter()))
+        .copied()
+        .collect::<HashSet<_>>();
+    let profiles: HashMap<GlyphId, Profile> = glyphs
+        .into_iter()
+        .filter_map(|gid| Some((gid, bands.profile(&f, gid, context)?)))
+        .collect();
+    let edges_as_left = |gid: &GlyphId| {
+        let advance = hmtx.advance(*gid).unwrap_or_default() as f64;
+        profiles.get(gid).map(|profile| {
+            profile
+                .iter()
+                .map(|extent| extent.map(|(_, max)| max - advance))
+                .collect::<Vec<_>>()
+        })
+    };
+    let edges_as_right = |gid: &GlyphId| {
+        profiles.get(gid).map(|profile| {
+            profile
+                .iter()
+                .map(|extent| extent.map(|(min, _)| min))
+                .collect::<Vec<_>>()
+        })
+    };
+
+    let mut collisions = BTreeMap::new();
+    for rule in rules.iter() {
+        context.check_deadline()?;
+        let kern = rule.x_advance as f64;
+        let lefts: Vec<_> = rule
+            .left
+            .iter()
+            .filter_map(|gid| Some((*gid, edges_as_left(gid)?)))
+            .collect();
+        let rights: Vec<_> = rule
+            .right
+            .iter()
+            .filter_map(|gid| Some((*gid, edges_as_right(gid)?)))
+            .collect();
+        // The outermost edges of each class give an upper bound on the overlap
+        // of any pair, which lets us skip most rules without looking at every
+        // pair of glyphs in them.
+        let outermost = |edges: &[(GlyphId, Vec<Option<f64>>)], pick: fn(f64, f64) -> f64| {
+            (0..bands.count)
+                .map(|band| {
+                    edges
+                        .iter()
+                        .filter_map(|(_, e)| e.get(band).copied().flatten())
+                        .reduce(pick)
+                })
+                .collect::<Vec<_>>()
+        };
+        if overlap(
+            &outermost(&lefts, f64::max),
+            &outermost(&rights, f64::min),
+            kern,
+        )
+        .is_none_or(|depth| depth <= tolerance)
+        {
+            continue;
+        }
+        for (left, left_edges) in lefts.iter() {
+            for (right, right_edges) in rights.iter() {
+                let (Some(unkerned), Some(kerned)) = (
+                    overlap(left_edges, right_edges, 0.0),
+                    overlap(left_edges, right_edges, kern),
+                ) else {
+                    continue;
+                };
+                if unkerned <= 0.0 && kerned > tolerance {
+                    collisions.insert((*left, *right), (rule.x_advance, kerned));
+                }
+            }
+        }
+    }
+    // Glyphs we ran out of time to draw were left out above
+    context.check_deadline()?;
+
+    if collisions.is_empty() {
+        return Ok(Status::just_one_pass());
+    }
+    Ok(Status::just_one_warn(
+        "kern-collision",
+        &format!(
+            "The following pairs collide when kerned:\n\n{}",
+            bullet_list(
+                context,
+                collisions
+                    .into_iter()
+                    .map(|((left, right), (kern, depth))| format!(
+                        "{} {}: a kern of {kern} makes them overlap by {depth:.0} units",
+                        f.glyph_name_for_id_synthesise(left),
+                        f.glyph_name_for_id_synthesise(right)
+                    ))
+            )
+        ),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use fontations::{
+        skrifa::{GlyphId16, MetadataProvider},
+        write::tables::{
+            gpos::{
+                Gpos, PairPos, PairSet, PairValueRecord, PositionLookup, PositionLookupList,
+                ValueRecord,
+            },
+            layout::{CoverageTable, FeatureList, Lookup, LookupFlag, ScriptList},
+        },
+    };
+    use fontspector_checkapi::{
+        codetesting::{
+            assert_pass, assert_results_contain, edit_font, result_codes, run_check, test_able,
+        },
+        FileTypeConvert, StatusCode, TTF,
+    };
+
+    #[test]
+    fn test_collisions_pass() {
+        let testable = test_able("mada/Mada-Regular.ttf");
+        let results = run_check(super::collisions, testable);
+        assert_pass(&results);
+    }
+
+    #[test]
+    fn test_collisions_warn() {
+        // ư followed by Y
+        let testable = test_able("cabin/Cabin-Regular.ttf");
+        let results = run_check(super::collisions, testable);
+        assert_results_contain(
+            &results,
+            StatusCode::Warn,
+            Some("kern-collision".to_string()),
+        );
+    }
+
+    #[test]
+    fn test_collisions_zero_units_per_em() {
+        let mut testable = test_able("cabin/Cabin-Regular.ttf");
+        edit_font(&mut testable).head(|head| head.units_per_em = 0);
+        let results = run_check(super::collisions, testable);
+        assert_results_contain(
+            &results,
+            StatusCode::Skip,
+            Some("zero-units-per-em".to_string()),
+        );
+    }
+
+    #[test]
+    fn test_collisions_tiny_em() {
+        // A 16 unit em would measure Cabin in thousands of bands
+        let mut testable = test_able("cabin/Cabin-Regular.ttf");
+        edit_font(&mut testable).head(|head| head.units_per_em = 16);
+        let results = run_check(super::collisions, testable);
+        assert!(result_codes(&results)
+            .iter()
+            .all(|(code, _)| *code != StatusCode::Error));
+    }
+
+    #[test]
+    fn test_collisions_overkerned() {
+        let mut testable = test_able("nunito/Nunito-Regular.ttf");
+        let f = TTF.from_testable(&testable).unwrap();
+        let charmap = f.font().charmap();
+        let glyph = |c: char| GlyphId16::new(charmap.map(c).unwrap().to_u32() as u16);
+        let pair_pos = PairPos::format_1(
+            CoverageTable::format_1(vec![glyph('H')]),
+            vec![PairSet::new(vec![PairValueRecord::new(
+                glyph('H'),
+                ValueRecord::new().with_x_advance(-300),
+                ValueRecord::default(),
+            )])],
+        );
+        let gpos = Gpos::new(
+            ScriptList::default(),
+            FeatureList::default(),
+            PositionLookupList::new(vec![PositionLookup::Pair(Lookup::new(
+                LookupFlag::empty(),
+                vec![pair_pos],
+            ))]),
+        );
+        testable.set(f.rebuild_with_new_table(&gpos).unwrap());
+        let results = run_check(super::collisions, testable);
+        assert_results_contain(
+            &results,
+            StatusCode::Warn,
+            Some("kern-collision".to_string()),
+        );
+    }
+}
@@ -0,0 +1,76 @@
+use std::collections::BTreeMap;
+
+use fontations::skrifa::raw::TableProvider;
+use fontspector_checkapi::{prelude::*, skip, testfont, FileTypeConvert};
+
+use super::kern_rules;
+
+#[check(
+    id = "kerning/extreme_values",
+    rationale = "
+        Kerning adjusts the space between particular pairs of glyphs, and the
+        adjustments needed are small compared to the size of the glyphs: even
+        a tight pair such as 'T' and 'o' rarely needs more than a fifth of an
+        em. A kerning value of more than half an em is almost always an error,
+        such as a value with an extra digit, a sign the wrong way round, or
+        kerning scaled twice when the font's units-per-em was changed. Such
+        values make the glyphs crash into one another or fly apart.
+    ",
+    proposal = "https://learn.microsoft.com/en-us/typography/opentype/spec/gpos#lookup-type-2-pair-adjustment-positioning-subtable",
+    title = "Are all kerning values of a sensible size?"
+)]
+fn extreme_values(t: &Testable, context: &Context) -> CheckFnResult {
+    let f = testfont!(t);
+    skip!(!f.has_table(b"GPOS"), "no-gpos", "Font has no GPOS table.");
+    let upem = f.font().head()?.units_per_em() as i32;
+
+    let mut extreme = BTreeMap::new();
+    for rule in kern_rules(&f, context)?.iter() {
+        if (rule.x_advance as i32).abs() * 2 > upem {
+            for pair in rule.pairs() {
+                extreme.insert(pair, rule.x_advance);
+            }
+        }
+    }
+
+    if extreme.is_empty() {
+        return Ok(Status::just_one_pass());
+    }
+    Ok(Status::just_one_warn(
+        "extreme-kern",
+        &format!(
+            "The following pairs are kerned by more than half an em ({} units):\n\n{}",
+            upem / 2,
+            bullet_list(
+                context,
+                extreme.into_iter().map(|((left, right), value)| format!(
+                    "{} {}: {value}",
+                    f.glyph_name_for_id_synthesise(left),
+                    f.glyph_name_for_id_synthesise(right)
+                ))
+            )
+        ),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use fontspector_checkapi::{
+        codetesting::{assert_pass, assert_results_contain, run_check, test_able},
+        StatusCode,
+    };
+
+    #[test]
+    fn test_extreme_values_pass() {
+        let testable = test_able("nunito/Nunito-Regular.ttf");
+        let results = run_check(super::extreme_values, testable);
+        assert_pass(&results);
+    }
+
+    #[test]
+    fn test_extreme_values_warn() {
+        let testable = test_able("tirodevanagarihindi/TiroDevanagariHindi-Regular.ttf");
+        let results = run_check(super::extreme_values, testable);
+        assert_results_contain(&results, StatusCode::Warn, Some("extreme-kern".to_string()));
+    }
+}
@@ -0,0 +1,141 @@
+use fontations::skrifa::{
+    raw::{types::F2Dot14, TableProvider},
+    GlyphId,
+};
+use fontspector_checkapi::{prelude::*, skip, testfont, FileTypeConvert, TestFont};
+
+use super::kern_rules;
+
+/// Name one side of a kerning rule; classes are named after their first glyph
+fn describe_side(f: &TestFont, glyphs: &[GlyphId]) -> String {
+    match glyphs {
+        [] => String::new(),
+        [gid] => f.glyph_name_for_id_synthesise(*gid),
+        [gid, rest @ ..] => format!(
+            "[{} and {} others]",
+            f.glyph_name_for_id_synthesise(*gid),
+            rest.len()
+        ),
+    }
+}
+
+#[check(
+    id = "kerning/master_consistency",
+    rationale = "
+        In a variable font, each kerning value is stored once for the default
+        master, with deltas giving its value at the other masters. Between
+        masters the value is interpolated. A pair which is kerned tighter in
+        one master is normally kerned tighter, by more or less, in all of them.
+
+        When the same pair or class pair is kerned apart by a noticeable
+        amount at one master and together by a noticeable amount at another,
+        it is usually because the kerning groups differ between the masters'
+        sources: a glyph was put in a different class in one master, or an
+        exception was only added to some of them. Interpolating between the
+        two produces instances where the pair's spacing swings from one
+        extreme to the other. This check reports rules whose value changes
+        sign across the masters by more than three percent of the em.
+    ",
+    proposal = "https://learn.microsoft.com/en-us/typography/opentype/spec/gdef#item-variation-store-table",
+    title = "Is kerning consistent across the masters of a variable font?"
+)]
+fn master_consistency(t: &Testable, context: &Context) -> CheckFnResult {
+    let f = testfont!(t);
+    skip!(
+        !f.is_variable_font(),
+        "not-variable",
+        "Font is not a variable font."
+    );
+    skip!(!f.has_table(b"GPOS"), "no-gpos", "Font has no GPOS table.");
+    let Some(varstore) = f
+        .font()
+        .gdef()
+        .ok()
+        .and_then(|gdef| gdef.item_var_store())
+        .transpose()?
+    else {
+        return Ok(Status::just_one_skip(
+            "no-variable-kerning",
+            "Font has no variation data for its kerning.",
+        ));
+    };
+    let tolerance = f.font().head()?.units_per_em() as i32 * 3 / 100;
+
+    // Each master sits at the peak of one of the variation regions
+    let mut masters: Vec<Vec<F2Dot14>> = vec![];
+    for region in varstore.variation_region_list()?.variation_regions().iter() {
+        let peaks = region?
+            .region_axes()
+            .iter()
+            .map(|axis| axis.peak_coord())
+            .collect::<Vec<_>>();
+        if !masters.contains(&peaks) {
+            masters.push(peaks);
+        }
+    }
+
+    let mut inconsistent = vec![];
+    for rule in kern_rules(&f, context)?.iter() {
+        let Some(index) = rule.variation else {
+            continue;
+        };
+        let mut values = vec![rule.x_advance as i32];
+        for master in masters.iter() {
+            values.push(rule.x_advance as i32 + varstore.compute_delta(index, master)?);
+        }
+        let (min, max) = values
+            .iter()
+            .fold((i32::MAX, i32::MIN), |(lo, hi), v| (lo.min(*v), hi.max(*v)));
+        if min < -tolerance && max > tolerance {
+            inconsistent.push(format!(
+                "{} {}: from {min} to {max}",
+                describe_side(&f, &rule.left),
+                describe_side(&f, &rule.right)
+            ));
+        }
+    }
+
+    if inconsistent.is_empty() {
+        return Ok(Status::just_one_pass());
+    }
+    Ok(Status::just_one_warn(
+        "inconsistent-master-kerning",
+        &format!(
+            "The following kerning rules pull glyphs together at some masters and push them apart at others:\n\n{}",
+            bullet_list(context, inconsistent)
+        ),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use fontspector_checkapi::{
+        codetesting::{assert_pass, assert_results_contain, assert_skip, run_check, test_able},
+        StatusCode,
+    };
+
+    #[test]
+    fn test_master_consistency_pass() {
+        let testable = test_able("cabinvf/Cabin[wdth,wght].ttf");
+        let results = run_check(super::master_consistency, testable);
+        assert_pass(&results);
+    }
+
+    #[test]
+    fn test_master_consistency_static() {
+        let testable = test_able("nunito/Nunito-Regular.ttf");
+        let results = run_check(super::master_consistency, testable);
+        assert_skip(&results);
+    }
+
+    #[test]
+    fn test_master_consistency_warn() {
+        let testable = test_able("ubuntusans/UbuntuSans[wdth,wght].ttf");
+        let results = run_check(super::master_consistency, testable);
+        assert_results_contain(
+            &results,
+            StatusCode::Warn,
+            Some("inconsistent-master-kerning".to_string()),
+        );
+    }
+}
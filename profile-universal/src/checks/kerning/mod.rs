@@ -0,0 +1,145 @@
+mod collisions;
+mod extreme_values;
+mod master_consistency;
+mod unreachable_glyphs;
+
+pub use collisions::collisions;
+pub use extreme_values::extreme_values;
+pub use master_consistency::master_consistency;
+pub use unreachable_glyphs::unreachable_glyphs;
+
+use std::{collections::BTreeMap, sync::Arc};
+
+use fontations::skrifa::{
+    raw::{
+        tables::{
+            gpos::{DeviceOrVariationIndex, PairPosFormat1, PairPosFormat2, ValueRecord},
+            variations::DeltaSetIndex,
+        },
+        FontData, ReadError,
+    },
+    GlyphId,
+};
+use fontspector_checkapi::{Context, FontspectorError, TestFont};
+
+/// A single kerning rule, flattened out of a PairPos subtable. Format 1
+/// subtables produce one rule per pair; format 2 subtables produce one rule
+/// per pair of classes.
+struct KernRule {
+    left: Vec<GlyphId>,
+    right: Vec<GlyphId>,
+    /// The adjustment to the advance of the left glyph at the default location
+    x_advance: i16,
+    /// Whether the rule also moves the left glyph, as right-to-left kerning does
+    has_placement: bool,
+    /// Where in the GDEF item variation store the advance adjustment varies
+    variation: Option<DeltaSetIndex>,
+}
+
+impl KernRule {
+    fn new(
+        left: Vec<GlyphId>,
+        right: Vec<GlyphId>,
+        value_record: &ValueRecord,
+        offset_data: FontData,
+    ) -> Option<Self> {
+        let variation = match value_record.x_advance_device(offset_data) {
+            // An outer index of 0xFFFF means "no variation data"
+            Some(Ok(DeviceOrVariationIndex::VariationIndex(index)))
+                if index.delta_set_outer_index() != 0xFFFF =>
+            {
+                Some(DeltaSetIndex {
+                    outer: index.delta_set_outer_index(),
+                    inner: index.delta_set_inner_index(),
+                })
+            }
+            _ => None,
+        };
+        let x_advance = value_record.x_advance().unwrap_or_default();
+        if x_advance == 0 && variation.is_none() {
+            return None;
+        }
+        Some(Self {
+            left,
+            right,
+            x_advance,
+            has_placement: value_record.x_placement().is_some_and(|x| x != 0),
+            variation,
+        })
+    }
+
+    fn pairs(&self) -> impl Iterator<Item = (GlyphId, GlyphId)> + '_ {
+        self.left
+            .iter()
+            .flat_map(|l| self.right.iter().map(move |r| (*l, *r)))
+    }
+}
+
+fn rules_format1(pp1: PairPosFormat1) -> Result<Vec<KernRule>, ReadError> {
+    let mut rules = vec![];
+    for (left, pairset) in pp1.coverage()?.iter().zip(pp1.pair_sets().iter()) {
+        let pairset = pairset?;
+        for record in pairset.pair_value_records().iter() {
+            let record = record?;
+            rules.extend(KernRule::new(
+                vec![left.into()],
+                vec![record.second_glyph().into()],
+                record.value_record1(),
+                pairset.offset_data(),
+            ));
+        }
+    }
+    Ok(rules)
+}
+
+fn rules_format2(pp2: PairPosFormat2) -> Result<Vec<KernRule>, ReadError> {
+    // Only glyphs in the coverage table are kerned; any of them not in the
+    // first class definition are in class 0
+    let class_def1 = pp2.class_def1()?;
+    let mut class1_glyphs: BTreeMap<u16, Vec<GlyphId>> = BTreeMap::new();
+    for gid in pp2.coverage()?.iter() {
+        class1_glyphs
+            .entry(class_def1.get(gid))
+            .or_default()
+            .push(gid.into());
+    }
+    // Class 0 of the second class definition is "every other glyph"; nobody
+    // kerns against that on purpose, so we leave it out.
+    let mut class2_glyphs: BTreeMap<u16, Vec<GlyphId>> = BTreeMap::new();
+    for (gid, class) in pp2.class_def2()?.iter() {
+        if class != 0 {
+            class2_glyphs.entry(class).or_default().push(gid.into());
+        }
+    }
+
+    let mut rules = vec![];
+    for (class1, record) in pp2.class1_records().iter().enumerate() {
+        let record = record?;
+        let Some(left) = class1_glyphs.get(&(class1 as u16)) else {
+            continue;
+        };
+        for (class2, class2_record) in record.class2_records().iter().enumerate() {
+            let class2_record = class2_record?;
+            let Some(right) = class2_glyphs.get(&(class2 as u16)) else {
+                continue;
+            };
+            rules.extend(KernRule::new(
+                left.clone(),
+                right.clone(),
+                class2_record.value_record1(),
+                pp2.offset_data(),
+            ));
+        }
+    }
+    Ok(rules)
+}
+
+/// All the kerning rules in the font's GPOS table which adjust the advance
+/// of the first glyph of a pair
+///
+/// Flattening the kerning is slow, so the rules are shared between checks.
+fn kern_rules(f: &TestFont, context: &Context) -> Result<Arc<Vec<KernRule>>, FontspectorError> {
+    f.memoize(Some(context), "kern_rules", || {
+        Ok(f.process_kerning(&rules_format1, &rules_format2)?)
+    })
+}
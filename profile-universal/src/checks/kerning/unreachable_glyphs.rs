@@ -0,0 +1,85 @@
+use std::collections::BTreeSet;
+
+use fontations::skrifa::GlyphId;
+use fontspector_checkapi::{prelude::*, skip, testfont, ClosureOptions, FileTypeConvert};
+
+use super::kern_rules;
+
+#[check(
+    id = "kerning/unreachable_glyphs",
+    rationale = "
+        A glyph can only take part in a kerning pair if it can appear in the
+        glyph stream: that is, if it is mapped from a Unicode codepoint in the
+        cmap table, is a Unicode variation sequence's variant glyph, or can
+        be produced from those glyphs by the GSUB table's features. Glyphs
+        which are only produced by lookups that no feature uses can never
+        appear, so are counted as unreachable.
+
+        Kerning on glyphs which can never be reached is dead weight, and is
+        usually a sign that something has gone wrong elsewhere: a glyph which
+        was meant to be encoded but lost its codepoint, an alternate whose
+        substitution feature was never written, or kerning classes which
+        still refer to glyphs which have been renamed or replaced.
+    ",
+    proposal = "https://github.com/fonttools/fontbakery/issues/3160",
+    title = "Are all kerned glyphs reachable?"
+)]
+fn unreachable_glyphs(t: &Testable, context: &Context) -> CheckFnResult {
+    let f = testfont!(t);
+    skip!(!f.has_table(b"GPOS"), "no-gpos", "Font has no GPOS table.");
+
+    let reachable = f.gsub_closure(
+        f.codepoints(Some(context)),
+        &ClosureOptions::default(),
+        Some(context),
+    )?;
+
+    let unreachable = kern_rules(&f, context)?
+        .iter()
+        .flat_map(|rule| rule.left.iter().chain(rule.right.iter()))
+        .filter(|gid| **gid != GlyphId::from(0u32) && !reachable.contains(**gid))
+        .copied()
+        .collect::<BTreeSet<_>>();
+
+    if unreachable.is_empty() {
+        return Ok(Status::just_one_pass());
+    }
+    Ok(Status::just_one_warn(
+        "unreachable-kerned-glyph",
+        &format!(
+            "The following glyphs are kerned, but cannot be reached by codepoint or substitution rules:\n\n{}",
+            bullet_list(
+                context,
+                unreachable
+                    .into_iter()
+                    .map(|gid| f.glyph_name_for_id_synthesise(gid))
+            )
+        ),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use fontspector_checkapi::{
+        codetesting::{assert_pass, assert_results_contain, run_check, test_able},
+        StatusCode,
+    };
+
+    #[test]
+    fn test_unreachable_glyphs_pass() {
+        let testable = test_able("nunito/Nunito-Regular.ttf");
+        let results = run_check(super::unreachable_glyphs, testable);
+        assert_pass(&results);
+    }
+
+    #[test]
+    fn test_unreachable_glyphs_warn() {
+        let testable = test_able("cabin/Cabin-Regular.ttf");
+        let results = run_check(super::unreachable_glyphs, testable);
+        assert_results_contain(
+            &results,
+            StatusCode::Warn,
+            Some("unreachable-kerned-glyph".to_string()),
+        );
+    }
+}
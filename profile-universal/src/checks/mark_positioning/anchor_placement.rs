@@ -0,0 +1,152 @@
+use std::collections::{BTreeMap, HashMap};
+
+use fontations::skrifa::{raw::TableProvider, GlyphId};
+use fontspector_checkapi::{prelude::*, skip, testfont, FileTypeConvert, TestFont};
+use kurbo::{Rect, Shape};
+
+use super::{mark_attachment_subtables, Anchor};
+
+/// The bounding box of a glyph's outline, or None for empty glyphs
+fn glyph_bounds(f: &TestFont, gid: GlyphId, context: &Context) -> Option<Rect> {
+    let pen = f.bez_glyph(gid, Some(context)).ok()?;
+    pen.iter()
+        .filter(|path| !path.elements().is_empty())
+        .map(|path| path.bounding_box())
+        .reduce(|a, b| a.union(b))
+}
+
+/// How far outside the box an anchor is, in font units
+fn distance_outside(bounds: Rect, (x, y): Anchor) -> f64 {
+    let (x, y) = (x as f64, y as f64);
+    let dx = (bounds.x0 - x).max(x - bounds.x1).max(0.0);
+    let dy = (bounds.y0 - y).max(y - bounds.y1).max(0.0);
+    dx.max(dy)
+}
+
+#[check(
+    id = "mark_positioning/anchor_placement",
+    rationale = "
+        Anchors are normally placed on or close to the outline of their glyph:
+        above the top of a base letter for marks which sit above it, at the
+        bottom of the mark for the mark's own anchor, and so on. An anchor
+        which is a long way outside the glyph's bounding box is almost always
+        a mistake, such as a coordinate typed with an extra digit, an anchor
+        copied from a glyph at a different scale, or one left behind after
+        the outline was moved. Marks attached to such an anchor are drawn far
+        away from the letter they belong to.
+
+        This check reports anchors in mark attachment lookups which lie more
+        than one em outside the bounding box of the glyph they belong to.
+    ",
+    proposal = "https://learn.microsoft.com/en-us/typography/opentype/spec/gpos#anchor-tables",
+    title = "Are all mark attachment anchors close to their glyphs?"
+)]
+fn anchor_placement(t: &Testable, context: &Context) -> CheckFnResult {
+    let f = testfont!(t);
+    skip!(!f.has_table(b"GPOS"), "no-gpos", "Font has no GPOS table.");
+    let upem = f.font().head()?.units_per_em() as f64;
+
+    let mut bounds: HashMap<GlyphId, Option<Rect>> = HashMap::new();
+    let mut far_anchors: BTreeMap<(GlyphId, Anchor), f64> = BTreeMap::new();
+    let mut check_anchor = |gid: GlyphId, anchor: Anchor| {
+        let Some(rect) = *bounds
+            .entry(gid)
+            .or_insert_with(|| glyph_bounds(&f, gid, context))
+        else {
+            return;
+        };
+        let distance = distance_outside(rect, anchor);
+        if distance > upem {
+            far_anchors.insert((gid, anchor), distance);
+        }
+    };
+    for subtable in mark_attachment_subtables(&f)? {
+        for (gid, (_, anchor)) in subtable.marks {
+            check_anchor(gid, anchor);
+        }
+        for (gid, components) in subtable.bases {
+            for anchor in components.into_iter().flatten().flatten() {
+                check_anchor(gid, anchor);
+            }
+        }
+    }
+    // Glyphs we ran out of time to draw were left out above
+    context.check_deadline()?;
+
+    if far_anchors.is_empty() {
+        return Ok(Status::just_one_pass());
+    }
+    Ok(Status::just_one_warn(
+        "anchor-far-from-glyph",
+        &format!(
+            "The following anchors are more than one em outside their glyph's bounding box:\n\n{}",
+            bullet_list(
+                context,
+                far_anchors.into_iter().map(|((gid, (x, y)), distance)| {
+                    format!(
+                        "{}: anchor at ({x}, {y}) is {distance:.0} units away",
+                        f.glyph_name_for_id_synthesise(gid)
+                    )
+                })
+            )
+        ),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use fontations::{
+        skrifa::{GlyphId16, MetadataProvider},
+        write::tables::{
+            gpos::{
+                AnchorTable, BaseArray, BaseRecord, Gpos, MarkArray, MarkBasePosFormat1,
+                MarkRecord, PositionLookup, PositionLookupList,
+            },
+            layout::{CoverageTable, FeatureList, Lookup, LookupFlag, ScriptList},
+        },
+    };
+    use fontspector_checkapi::{
+        codetesting::{assert_pass, assert_results_contain, run_check, test_able},
+        FileTypeConvert, StatusCode, TTF,
+    };
+
+    #[test]
+    fn test_anchor_placement_pass() {
+        let testable = test_able("nunito/Nunito-Regular.ttf");
+        let results = run_check(super::anchor_placement, testable);
+        assert_pass(&results);
+    }
+
+    #[test]
+    fn test_anchor_placement_far() {
+        let mut testable = test_able("nunito/Nunito-Regular.ttf");
+        let f = TTF.from_testable(&testable).unwrap();
+        let charmap = f.font().charmap();
+        let glyph = |c: char| GlyphId16::new(charmap.map(c).unwrap().to_u32() as u16);
+        // The top anchor of "a" is a digit too long
+        let mark_to_base = MarkBasePosFormat1::new(
+            CoverageTable::format_1(vec![glyph('\u{0301}')]),
+            CoverageTable::format_1(vec![glyph('a')]),
+            MarkArray::new(vec![MarkRecord::new(0, AnchorTable::format_1(-70, 500))]),
+            BaseArray::new(vec![BaseRecord::new(vec![Some(AnchorTable::format_1(
+                250, 5000,
+            ))])]),
+        );
+        let gpos = Gpos::new(
+            ScriptList::default(),
+            FeatureList::default(),
+            PositionLookupList::new(vec![PositionLookup::MarkToBase(Lookup::new(
+                LookupFlag::empty(),
+                vec![mark_to_base],
+            ))]),
+        );
+        testable.set(f.rebuild_with_new_table(&gpos).unwrap());
+        let results = run_check(super::anchor_placement, testable);
+        assert_results_contain(
+            &results,
+            StatusCode::Warn,
+            Some("anchor-far-from-glyph".to_string()),
+        );
+    }
+}
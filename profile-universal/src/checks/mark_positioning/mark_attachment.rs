@@ -0,0 +1,255 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use fontations::skrifa::{raw::tables::gdef::GlyphClassDef, GlyphId, MetadataProvider};
+use fontspector_checkapi::{prelude::*, skip, testfont, FileTypeConvert};
+use unicode_normalization::char::{canonical_combining_class, decompose_canonical};
+use unicode_properties::{GeneralCategory, GeneralCategoryGroup, UnicodeGeneralCategory};
+
+use super::{mark_attachment_subtables, AttachmentKind};
+
+fn is_combining_mark(c: char) -> bool {
+    matches!(
+        c.general_category(),
+        GeneralCategory::NonspacingMark | GeneralCategory::EnclosingMark
+    )
+}
+
+/// Default-ignorable marks, which are never displayed and so need no anchors
+fn is_ignorable(c: char) -> bool {
+    matches!(
+        c as u32,
+        0x034F | 0x180B..=0x180F | 0xFE00..=0xFE0F | 0xE0100..=0xE01EF
+    )
+}
+
+#[check(
+    id = "mark_positioning/mark_attachment",
+    rationale = "
+        Combining marks are positioned over or under the glyph they follow by
+        mark-to-base, mark-to-ligature and mark-to-mark attachment lookups in
+        the GPOS table. A mark which is not covered by any of these lookups,
+        or which has no anchor on a particular base glyph, is drawn wherever
+        its outline happens to sit; usually that means on top of the previous
+        letter or floating over empty space.
+
+        This check reports encoded marks in the GDEF mark class which no
+        lookup attaches at all. It then uses the Unicode canonical
+        decompositions of the characters in the font to find which base
+        letters each mark is expected to combine with: if the font encodes
+        U+1EBF (ế), then U+0302 must attach to 'e', and U+0301, which stacks
+        on top of the circumflex, must attach to U+0302 through a
+        mark-to-mark lookup. Text arrives decomposed more often than one
+        might think, and not every shaping engine recomposes it, so these
+        combinations must work even when the precomposed glyph exists.
+
+        Missing anchors are the most common cause of broken Vietnamese and
+        African-language text.
+    ",
+    proposal = "https://learn.microsoft.com/en-us/typography/opentype/spec/gpos#lookup-type-4-mark-to-base-attachment-positioning-subtable",
+    title = "Are all combining marks attached to the glyphs they follow?"
+)]
+fn mark_attachment(t: &Testable, context: &Context) -> CheckFnResult {
+    let f = testfont!(t);
+    skip!(!f.has_table(b"GDEF"), "no-gdef", "GDEF table not present");
+    let charmap: BTreeMap<char, GlyphId> = f
+        .font()
+        .charmap()
+        .mappings()
+        .filter_map(|(u, gid)| char::from_u32(u).map(|c| (c, gid)))
+        .collect();
+    let is_mark_glyph = |gid: GlyphId| f.gdef_class(gid) == GlyphClassDef::Mark;
+    let marks = charmap
+        .iter()
+        .filter(|(c, gid)| is_combining_mark(**c) && !is_ignorable(**c) && is_mark_glyph(**gid))
+        .collect::<Vec<_>>();
+    skip!(
+        marks.is_empty(),
+        "no-marks",
+        "Font has no combining mark glyphs."
+    );
+    let subtables = if f.has_table(b"GPOS") {
+        mark_attachment_subtables(&f)?
+    } else {
+        vec![]
+    };
+    let attaches = |kinds: &[AttachmentKind], mark: GlyphId, base: GlyphId| {
+        subtables
+            .iter()
+            .any(|subtable| kinds.contains(&subtable.kind) && subtable.attaches(mark, base))
+    };
+    let describe = |c: char| {
+        let name = charmap
+            .get(&c)
+            .map(|gid| f.glyph_name_for_id_synthesise(*gid))
+            .unwrap_or_default();
+        format!("{name} (U+{:04X})", c as u32)
+    };
+
+    let unattached = marks
+        .iter()
+        .filter(|(_, gid)| {
+            !subtables
+                .iter()
+                .any(|subtable| subtable.marks.contains_key(*gid))
+        })
+        .map(|(c, _)| **c)
+        .collect::<BTreeSet<char>>();
+
+    let mut missing_base = BTreeSet::new();
+    let mut missing_mark = BTreeSet::new();
+    for c in charmap.keys() {
+        let mut decomposition = vec![];
+        decompose_canonical(*c, |d| decomposition.push(d));
+        let Some((base, combining)) = decomposition.split_first() else {
+            continue;
+        };
+        if combining.is_empty()
+            || !matches!(base.general_category_group(), GeneralCategoryGroup::Letter)
+            || !combining.iter().all(|m| is_combining_mark(*m))
+        {
+            continue;
+        }
+        let (Some(base_gid), Some(mark_gids)) = (
+            charmap.get(base),
+            combining
+                .iter()
+                .map(|m| charmap.get(m).copied())
+                .collect::<Option<Vec<_>>>(),
+        ) else {
+            continue;
+        };
+        let mut previous: Option<(char, GlyphId)> = None;
+        for (mark, mark_gid) in combining.iter().copied().zip(mark_gids) {
+            if !is_mark_glyph(mark_gid) || unattached.contains(&mark) {
+                previous = Some((mark, mark_gid));
+                continue;
+            }
+            // A mark with the same combining class as the one before it is
+            // stacked on that mark, rather than positioned on the base
+            match previous.filter(|(prev, prev_gid)| {
+                canonical_combining_class(*prev) == canonical_combining_class(mark)
+                    && is_mark_glyph(*prev_gid)
+            }) {
+                Some((prev, prev_gid)) => {
+                    if !attaches(&[AttachmentKind::MarkToMark], mark_gid, prev_gid) {
+                        missing_mark.insert((prev, mark));
+                    }
+                }
+                None => {
+                    if !attaches(
+                        &[AttachmentKind::MarkToBase, AttachmentKind::MarkToLigature],
+                        mark_gid,
+                        *base_gid,
+                    ) {
+                        missing_base.insert((*base, mark));
+                    }
+                }
+            }
+            previous = Some((mark, mark_gid));
+        }
+    }
+
+    let mut problems = vec![];
+    if !unattached.is_empty() {
+        problems.push(Status::warn(
+            "unattached-mark",
+            &format!(
+                "The following combining marks are not attached by any mark positioning lookup:\n\n{}",
+                bullet_list(context, unattached.into_iter().map(describe))
+            ),
+        ));
+    }
+    if !missing_base.is_empty() {
+        problems.push(Status::warn(
+            "missing-base-attachment",
+            &format!(
+                "The following marks have no anchor to attach to the base glyphs they may follow:\n\n{}",
+                bullet_list(
+                    context,
+                    missing_base
+                        .into_iter()
+                        .map(|(base, mark)| format!("{} on {}", describe(mark), describe(base)))
+                )
+            ),
+        ));
+    }
+    if !missing_mark.is_empty() {
+        problems.push(Status::warn(
+            "missing-mark-attachment",
+            &format!(
+                "The following marks have no mark-to-mark anchor to stack on the marks they may follow:\n\n{}",
+                bullet_list(
+                    context,
+                    missing_mark
+                        .into_iter()
+                        .map(|(prev, mark)| format!("{} on {}", describe(mark), describe(prev)))
+                )
+            ),
+        ));
+    }
+    return_result(problems)
+}
+
+#[cfg(test)]
+mod tests {
+    use fontspector_checkapi::{
+        codetesting::{
+            assert_pass, assert_results_contain, assert_skip, remove_table, run_check, test_able,
+        },
+        StatusCode,
+    };
+
+    #[test]
+    fn test_mark_attachment_pass() {
+        let testable = test_able("nunito/Nunito-Regular.ttf");
+        let results = run_check(super::mark_attachment, testable);
+        assert_pass(&results);
+    }
+
+    #[test]
+    fn test_mark_attachment_skip() {
+        let mut testable = test_able("nunito/Nunito-Regular.ttf");
+        remove_table(&mut testable, b"GDEF");
+        let results = run_check(super::mark_attachment, testable);
+        assert_skip(&results);
+    }
+
+    #[test]
+    fn test_mark_attachment_missing() {
+        // Greek capitals have no anchors for their accents, and the
+        // Vietnamese breve + grave stack has no mark-to-mark anchor
+        let testable = test_able("source-sans-pro/TTF/SourceSansPro-Regular.ttf");
+        let results = run_check(super::mark_attachment, testable);
+        assert_results_contain(
+            &results,
+            StatusCode::Warn,
+            Some("missing-base-attachment".to_string()),
+        );
+        assert_results_contain(
+            &results,
+            StatusCode::Warn,
+            Some("missing-mark-attachment".to_string()),
+        );
+
+        // U+0953 and U+0954 are not attached to anything
+        let testable = test_able("notosans/NotoSans-VariableFont_wdth,wght.ttf");
+        let results = run_check(super::mark_attachment, testable);
+        assert_results_contain(
+            &results,
+            StatusCode::Warn,
+            Some("unattached-mark".to_string()),
+        );
+    }
+
+    #[test]
+    fn test_mark_attachment_no_gpos() {
+        let mut testable = test_able("nunito/Nunito-Regular.ttf");
+        remove_table(&mut testable, b"GPOS");
+        let results = run_check(super::mark_attachment, testable);
+        assert_results_contain(
+            &results,
+            StatusCode::Warn,
+            Some("unattached-mark".to_string()),
+        );
+    }
+}
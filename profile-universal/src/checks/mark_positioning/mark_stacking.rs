@@ -0,0 +1,91 @@
+use std::collections::BTreeSet;
+
+use fontspector_checkapi::{prelude::*, skip, testfont, FileTypeConvert};
+
+use super::{mark_attachment_subtables, AttachmentKind};
+
+#[check(
+    id = "mark_positioning/mark_stacking",
+    rationale = "
+        When one mark follows another, a mark-to-mark lookup moves the second
+        mark so that its attachment anchor sits on an anchor of the first.
+        For the marks to stack, the first mark's anchor has to be somewhere
+        other than the point at which it was itself attached: on the top of
+        an acute accent for marks which stack above it, for example.
+
+        If the anchor the second mark attaches to is at the same position as
+        the second mark's own anchor, the second mark is not moved at all, and
+        it is drawn over the first. This typically happens when a mark's
+        '_top' anchor is copied to make its 'top' anchor and never moved, and
+        makes Vietnamese and other languages which stack diacritics
+        unreadable.
+    ",
+    proposal = "https://learn.microsoft.com/en-us/typography/opentype/spec/gpos#lookup-type-6-mark-to-mark-attachment-positioning-subtable",
+    title = "Do mark-to-mark anchors stack marks rather than overlapping them?"
+)]
+fn mark_stacking(t: &Testable, context: &Context) -> CheckFnResult {
+    let f = testfont!(t);
+    skip!(!f.has_table(b"GPOS"), "no-gpos", "Font has no GPOS table.");
+
+    let mut collapsed = BTreeSet::new();
+    for subtable in mark_attachment_subtables(&f)?
+        .into_iter()
+        .filter(|subtable| subtable.kind == AttachmentKind::MarkToMark)
+    {
+        for (mark, (class, mark_anchor)) in subtable.marks.iter() {
+            for (base, components) in subtable.bases.iter() {
+                let base_anchor = components
+                    .iter()
+                    .flat_map(|anchors| anchors.get(*class as usize).copied().flatten())
+                    .next();
+                if base_anchor == Some(*mark_anchor) {
+                    collapsed.insert((*base, *mark));
+                }
+            }
+        }
+    }
+
+    if collapsed.is_empty() {
+        return Ok(Status::just_one_pass());
+    }
+    Ok(Status::just_one_warn(
+        "collapsed-mark-stack",
+        &format!(
+            "The following marks would be drawn on top of the mark they are stacked on:\n\n{}",
+            bullet_list(
+                context,
+                collapsed.into_iter().map(|(base, mark)| format!(
+                    "{} on {}",
+                    f.glyph_name_for_id_synthesise(mark),
+                    f.glyph_name_for_id_synthesise(base)
+                ))
+            )
+        ),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use fontspector_checkapi::{
+        codetesting::{assert_pass, assert_results_contain, run_check, test_able},
+        StatusCode,
+    };
+
+    #[test]
+    fn test_mark_stacking_pass() {
+        let testable = test_able("nunito/Nunito-Regular.ttf");
+        let results = run_check(super::mark_stacking, testable);
+        assert_pass(&results);
+    }
+
+    #[test]
+    fn test_mark_stacking_collapsed() {
+        let testable = test_able("broken_markazitext/MarkaziText-VF.ttf");
+        let results = run_check(super::mark_stacking, testable);
+        assert_results_contain(
+            &results,
+            StatusCode::Warn,
+            Some("collapsed-mark-stack".to_string()),
+        );
+    }
+}
@@ -0,0 +1,176 @@
+mod anchor_placement;
+mod mark_attachment;
+mod mark_stacking;
+
+pub use anchor_placement::anchor_placement;
+pub use mark_attachment::mark_attachment;
+pub use mark_stacking::mark_stacking;
+
+use std::collections::HashMap;
+
+use fontations::skrifa::{
+    raw::{
+        tables::gpos::{AnchorTable, MarkArray, PositionSubtables},
+        ReadError, TableProvider,
+    },
+    GlyphId,
+};
+use fontspector_checkapi::{FontspectorError, TestFont};
+
+/// An anchor's (x, y) coordinates, in font units
+type Anchor = (i16, i16);
+
+/// The type of glyph a mark attachment subtable attaches marks to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum AttachmentKind {
+    MarkToBase,
+    MarkToLigature,
+    MarkToMark,
+}
+
+/// The anchors defined by a single MarkBase, MarkLig or MarkMark subtable
+struct MarkAttachmentSubtable {
+    kind: AttachmentKind,
+    /// The class and anchor of each mark glyph
+    marks: HashMap<GlyphId, (u16, Anchor)>,
+    /// The anchors of each base glyph, indexed by mark class. Ligatures
+    /// have one set of anchors per component; other bases have just one.
+    bases: HashMap<GlyphId, Vec<Vec<Option<Anchor>>>>,
+}
+
+impl MarkAttachmentSubtable {
+    /// Does this subtable give `base` an anchor for `mark` to attach to?
+    fn attaches(&self, mark: GlyphId, base: GlyphId) -> bool {
+        let (Some((class, _)), Some(components)) = (self.marks.get(&mark), self.bases.get(&base))
+        else {
+            return false;
+        };
+        components.iter().any(|anchors| {
+            anchors
+                .get(*class as usize)
+                .is_some_and(|anchor| anchor.is_some())
+        })
+    }
+}
+
+fn to_anchor(anchor: &AnchorTable) -> Anchor {
+    (anchor.x_coordinate(), anchor.y_coordinate())
+}
+
+fn read_anchors<'a>(
+    anchors: impl Iterator<Item = Option<Result<AnchorTable<'a>, ReadError>>>,
+) -> Result<Vec<Option<Anchor>>, ReadError> {
+    anchors
+        .map(|anchor| anchor.transpose().map(|a| a.as_ref().map(to_anchor)))
+        .collect()
+}
+
+fn read_marks(
+    coverage: impl Iterator<Item = impl Into<GlyphId>>,
+    mark_array: MarkArray,
+) -> Result<HashMap<GlyphId, (u16, Anchor)>, ReadError> {
+    let data = mark_array.offset_data();
+    coverage
+        .zip(mark_array.mark_records())
+        .map(|(gid, record)| {
+            let anchor = record.mark_anchor(data)?;
+            Ok((gid.into(), (record.mark_class(), to_anchor(&anchor))))
+        })
+        .collect()
+}
+
+/// Read all the mark attachment subtables in the font's GPOS table
+fn mark_attachment_subtables(
+    f: &TestFont,
+) -> Result<Vec<MarkAttachmentSubtable>, FontspectorError> {
+    let mut result = vec![];
+    let gpos = f.font().gpos()?;
+    for subtables in gpos
+        .lookup_list()?
+        .lookups()
+        .iter()
+        .flatten()
+        .flat_map(|l| l.subtables())
+    {
+        match subtables {
+            PositionSubtables::MarkToBase(subtables) => {
+                for subtable in subtables.iter() {
+                    let subtable = subtable?;
+                    let base_array = subtable.base_array()?;
+                    let data = base_array.offset_data();
+                    let bases = subtable
+                        .base_coverage()?
+                        .iter()
+                        .zip(base_array.base_records().iter())
+                        .map(|(gid, record)| {
+                            let anchors = read_anchors(record?.base_anchors(data).iter())?;
+                            Ok((gid.into(), vec![anchors]))
+                        })
+                        .collect::<Result<_, ReadError>>()?;
+                    result.push(MarkAttachmentSubtable {
+                        kind: AttachmentKind::MarkToBase,
+                        marks: read_marks(
+                            subtable.mark_coverage()?.iter(),
+                            subtable.mark_array()?,
+                        )?,
+                        bases,
+                    });
+                }
+            }
+            PositionSubtables::MarkToLig(subtables) => {
+                for subtable in subtables.iter() {
+                    let subtable = subtable?;
+                    let bases = subtable
+                        .ligature_coverage()?
+                        .iter()
+                        .zip(subtable.ligature_array()?.ligature_attaches().iter())
+                        .map(|(gid, attach)| {
+                            let attach = attach?;
+                            let data = attach.offset_data();
+                            let components = attach
+                                .component_records()
+                                .iter()
+                                .map(|record| read_anchors(record?.ligature_anchors(data).iter()))
+                                .collect::<Result<Vec<_>, ReadError>>()?;
+                            Ok((gid.into(), components))
+                        })
+                        .collect::<Result<_, ReadError>>()?;
+                    result.push(MarkAttachmentSubtable {
+                        kind: AttachmentKind::MarkToLigature,
+                        marks: read_marks(
+                            subtable.mark_coverage()?.iter(),
+                            subtable.mark_array()?,
+                        )?,
+                        bases,
+                    });
+                }
+            }
+            PositionSubtables::MarkToMark(subtables) => {
+                for subtable in subtables.iter() {
+                    let subtable = subtable?;
+                    let mark2_array = subtable.mark2_array()?;
+                    let data = mark2_array.offset_data();
+                    let bases = subtable
+                        .mark2_coverage()?
+                        .iter()
+                        .zip(mark2_array.mark2_records().iter())
+                        .map(|(gid, record)| {
+                            let anchors = read_anchors(record?.mark2_anchors(data).iter())?;
+                            Ok((gid.into(), vec![anchors]))
+                        })
+                        .collect::<Result<_, ReadError>>()?;
+                    result.push(MarkAttachmentSubtable {
+                        kind: AttachmentKind::MarkToMark,
+                        marks: read_marks(
+                            subtable.mark1_coverage()?.iter(),
+                            subtable.mark1_array()?,
+                        )?,
+                        bases,
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(result)
+}
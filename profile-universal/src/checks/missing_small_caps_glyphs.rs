@@ -1,12 +1,7 @@
-use std::collections::HashSet;
+use std::collections::BTreeSet;
 
-use fontations::skrifa::{
-    raw::{tables::gsub::SubstitutionLookupList, ReadError, TableProvider},
-    GlyphId16, MetadataProvider,
-};
-use fontspector_checkapi::{
-    prelude::*, skip, testfont, FileTypeConvert, GetSubstitutionMap, Metadata,
-};
+use fontations::skrifa::{GlyphId, MetadataProvider};
+use fontspector_checkapi::{prelude::*, skip, testfont, ClosureOptions, FileTypeConvert, Metadata};
 use serde_json::json;
 use unicode_properties::{GeneralCategory, UnicodeGeneralCategory};
 
@@ -40,41 +35,36 @@ fn missing_small_caps_glyphs(t: &Testable, context: &Context) -> CheckFnResult {
     );
     let mut problems = vec![];
 
-    for (lookups, category, feature, error_code) in &[
+    for (category, feature, error_code) in &[
         (
-            smcp_lookups,
             GeneralCategory::LowercaseLetter,
             "smcp",
             "missing-smcp-lowercase",
         ),
         (
-            c2sc_lookups,
             GeneralCategory::UppercaseLetter,
             "c2sc",
             "missing-c2sc-uppercase",
         ),
     ] {
-        let mut glyphset: HashSet<GlyphId16> = f
+        let options = ClosureOptions {
+            features: Some(vec![feature.to_string()]),
+            ..Default::default()
+        };
+        // A letter takes part in the feature if the feature's lookups,
+        // including those called from contextual lookups, can replace it.
+        let closure = f.gsub_closure(f.codepoints(Some(context)), &options, Some(context))?;
+        let glyphset: BTreeSet<GlyphId> = f
             .font()
             .charmap()
             .mappings()
-            .filter_map(|(c, g)| {
-                char::from_u32(c).and_then(|c| {
-                    if c.general_category() == *category {
-                        GlyphId16::try_from(g).ok()
-                    } else {
-                        None
-                    }
-                })
+            .filter(|(codepoint, _gid)| {
+                char::from_u32(*codepoint).is_some_and(|c| c.general_category() == *category)
             })
+            .filter(|(_codepoint, gid)| closure.substituted_by(*gid).next().is_none())
+            .map(|(_codepoint, gid)| gid)
             .collect();
 
-        remove_lhs_glyphs(
-            &mut glyphset,
-            &f.font().gsub()?.lookup_list()?,
-            lookups.iter().map(|i| i.get()),
-        )?;
-
         if !glyphset.is_empty() {
             let mut status = Status::fail(
                 error_code,
@@ -90,7 +80,7 @@ fn missing_small_caps_glyphs(t: &Testable, context: &Context) -> CheckFnResult {
             for gid in glyphset.iter() {
                 status.add_metadata(Metadata::GlyphProblem {
                     glyph_name: f.glyph_name_for_id_synthesise(*gid),
-                    glyph_id: gid.to_u16() as u32,
+                    glyph_id: gid.to_u32(),
                     userspace_location: None,
                     position: None,
                     actual: None,
@@ -104,19 +94,3 @@ fn missing_small_caps_glyphs(t: &Testable, context: &Context) -> CheckFnResult {
 
     return_result(problems)
 }
-
-fn remove_lhs_glyphs(
-    glyphset: &mut HashSet<GlyphId16>,
-    lookups: &SubstitutionLookupList,
-    lookups_indices: impl Iterator<Item = u16>,
-) -> Result<(), ReadError> {
-    for i in lookups_indices {
-        let lookup = lookups.lookups().get(i as usize)?;
-        for (lhs, _rhs) in lookup.subtables()?.substitutions()?.iter() {
-            for gid in lhs {
-                glyphset.remove(gid);
-            }
-        }
-    }
-    Ok(())
-}
@@ -74,6 +74,10 @@ mod whitespace_ink;
 mod whitespace_widths;
 mod ytlc_sanity;
 
+pub mod color;
+pub mod hinting;
+pub mod kerning;
+pub mod mark_positioning;
 pub mod name;
 
 pub use alt_caron::alt_caron;
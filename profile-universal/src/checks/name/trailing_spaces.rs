@@ -1,5 +1,8 @@
-use fontations::skrifa::raw::TableProvider;
-use fontspector_checkapi::{prelude::*, testfont, FileTypeConvert, Metadata};
+use fontations::{
+    skrifa::raw::TableProvider,
+    write::tables::name::{Name, NameRecord},
+};
+use fontspector_checkapi::{prelude::*, source, testfont, FileTypeConvert, Metadata};
 use serde_json::json;
 
 #[check(
@@ -11,7 +14,8 @@ use serde_json::json;
                 to users. In most cases this can be fixed by removing extraneous
                 spaces from the metadata fields in the font editor.",
     proposal = "https://github.com/googlefonts/fontbakery/issues/2417",
-    hotfix = fix_trailing_spaces
+    hotfix = fix_trailing_spaces,
+    fix_source = fix_trailing_spaces_source
 )]
 fn trailing_spaces(f: &Testable, _context: &Context) -> CheckFnResult {
     let mut problems: Vec<Status> = vec![];
@@ -102,11 +106,108 @@ fn trailing_spaces(f: &Testable, _context: &Context) -> CheckFnResult {
     return_result(problems)
 }
 
+/// UFO font info keys which end up in the name table
+const FONTINFO_NAME_KEYS: [&str; 20] = [
+    "familyName",
+    "styleName",
+    "styleMapFamilyName",
+    "copyright",
+    "trademark",
+    "openTypeNameDesigner",
+    "openTypeNameDesignerURL",
+    "openTypeNameManufacturer",
+    "openTypeNameManufacturerURL",
+    "openTypeNameLicense",
+    "openTypeNameLicenseURL",
+    "openTypeNameVersion",
+    "openTypeNameUniqueID",
+    "openTypeNameDescription",
+    "openTypeNamePreferredFamilyName",
+    "openTypeNamePreferredSubfamilyName",
+    "openTypeNameCompatibleFullName",
+    "openTypeNameSampleText",
+    "openTypeNameWWSFamilyName",
+    "openTypeNameWWSSubfamilyName",
+];
+
+/// Remove leading, trailing and doubled spaces
+fn tidy_spaces(s: &str) -> String {
+    let mut tidied = s.trim().to_string();
+    while tidied.contains("  ") {
+        tidied = tidied.replace("  ", " ");
+    }
+    tidied
+}
+
 fn fix_trailing_spaces(
-    _f: &mut Testable,
+    t: &mut Testable,
     _replies: Option<MoreInfoReplies>,
 ) -> Result<FixResult, FontspectorError> {
-    Ok(FixResult::Unfixable)
+    let f = testfont!(t);
+    let name_table = f.font().name()?;
+    let mut changed = false;
+    let new_records: Vec<NameRecord> = name_table
+        .name_record()
+        .iter()
+        .map(|r| {
+            let string = r
+                .string(name_table.string_data())
+                .map(|s| s.chars().collect::<String>())
+                .unwrap_or_default();
+            let tidied = tidy_spaces(&string);
+            changed |= tidied != string;
+            NameRecord::new(
+                r.platform_id(),
+                r.encoding_id(),
+                r.language_id(),
+                r.name_id(),
+                tidied.into(),
+            )
+        })
+        .collect();
+    if !changed {
+        return Ok(FixResult::NotBroken);
+    }
+    let new_name = Name::new(new_records);
+    t.set(f.rebuild_with_new_table(&new_name)?);
+    Ok(FixResult::Fixed)
+}
+
+fn tidy_fontinfo_value(value: &mut source::Value) -> bool {
+    match value {
+        source::Value::String(s) => {
+            let tidied = tidy_spaces(s);
+            let changed = tidied != *s;
+            *s = tidied;
+            changed
+        }
+        _ => false,
+    }
+}
+
+fn fix_trailing_spaces_source(
+    t: &Testable,
+    _replies: Option<MoreInfoReplies>,
+) -> Result<FixResult, FontspectorError> {
+    source::fix_fontinfo(t, |fontinfo| {
+        let mut changed = false;
+        for key in FONTINFO_NAME_KEYS {
+            if let Some(value) = fontinfo.get_mut(key) {
+                changed |= tidy_fontinfo_value(value);
+            }
+        }
+        if let Some(source::Value::Array(records)) = fontinfo.get_mut("openTypeNameRecords") {
+            for record in records.iter_mut() {
+                if let Some(value) = record
+                    .as_dictionary_mut()
+                    .and_then(|record| record.get_mut("string"))
+                {
+                    changed |= tidy_fontinfo_value(value);
+                }
+            }
+        }
+        changed
+    })
 }
 
 #[cfg(test)]
@@ -124,8 +225,10 @@ mod tests {
         },
     };
     use fontspector_checkapi::{
-        codetesting::{assert_messages_contain, assert_results_contain, run_check_with_config},
-        StatusCode, Testable, TestableType,
+        codetesting::{
+            assert_messages_contain, assert_pass, assert_results_contain, run_check_with_config,
+        },
+        FixResult, StatusCode, Testable, TestableType,
     };
     use std::collections::HashMap;
 
@@ -188,4 +291,34 @@ mod tests {
             Some("double-spaces".to_string()),
         );
     }
+
+    #[test]
+    fn test_fix_trailing_spaces() {
+        let mut builder = FontBuilder::new();
+        builder.add_table(&Maxp::default()).unwrap();
+        let name_table = Name::new(vec![NameRecord::new(
+            3,
+            1,
+            1033,
+            NameId::new(1),
+            "  Spacey  Family ".to_string().into(),
+        )]);
+        builder.add_table(&name_table).unwrap();
+        let mut testable = Testable::new_with_contents("demo.ttf", builder.build().clone());
+
+        assert!(matches!(
+            super::fix_trailing_spaces(&mut testable, None).unwrap(),
+            FixResult::Fixed
+        ));
+        assert!(matches!(
+            super::fix_trailing_spaces(&mut testable, None).unwrap(),
+            FixResult::NotBroken
+        ));
+        let results = run_check_with_config(
+            super::trailing_spaces,
+            TestableType::Single(&testable),
+            HashMap::new(),
+        );
+        assert_pass(&results);
+    }
 }
@@ -1,4 +1,5 @@
-use fontspector_checkapi::{prelude::*, skip, testfont, FileTypeConvert};
+use fontations::skrifa::MetadataProvider;
+use fontspector_checkapi::{prelude::*, skip, testfont, ClosureOptions, FileTypeConvert};
 
 #[check(
     id = "smallcaps_before_ligatures",
@@ -12,11 +13,13 @@ use fontspector_checkapi::{prelude::*, skip, testfont, FileTypeConvert};
         he provided of the word \"WAFFLES\" in small caps, but with an unfortunate
         lowercase ffl ligature substitution.
 	
-        This check attempts to detect this kind of mistake.",
+        This check attempts to detect this kind of mistake. The ordering only
+        matters if a ligature can be formed from letters which have small caps,
+        so fonts where this can't happen are not reported.",
     proposal = "https://github.com/fonttools/fontbakery/issues/3020",
     title = "Ensure 'smcp' (small caps) lookups are defined before ligature lookups in the 'GSUB' table."
 )]
-fn smallcaps_before_ligatures(t: &Testable, _context: &Context) -> CheckFnResult {
+fn smallcaps_before_ligatures(t: &Testable, context: &Context) -> CheckFnResult {
     let f = testfont!(t);
     // Skip if no smcp
     let smcp_lookups = f
@@ -40,6 +43,36 @@ fn smallcaps_before_ligatures(t: &Testable, _context: &Context) -> CheckFnResult
     if first_smcp_lookup < first_liga_lookup {
         return Ok(Status::just_one_pass());
     }
+
+    // Which letters would small caps change?
+    let smcp_options = ClosureOptions {
+        features: Some(vec!["smcp".to_string()]),
+        ..Default::default()
+    };
+    let codepoints = f.codepoints(Some(context));
+    let small_caps = f.gsub_closure(codepoints.iter().copied(), &smcp_options, Some(context))?;
+    let charmap = f.font().charmap();
+    let smcp_letters = codepoints
+        .iter()
+        .copied()
+        .filter(|codepoint| {
+            charmap
+                .map(*codepoint)
+                .is_some_and(|gid| small_caps.substituted_by(gid).next().is_some())
+        })
+        .collect::<Vec<_>>();
+    // Can any ligature be formed from them?
+    let liga_options = ClosureOptions {
+        features: Some(vec!["liga".to_string()]),
+        ..Default::default()
+    };
+    let ligatures = f.gsub_closure(smcp_letters, &liga_options, Some(context))?;
+    if !ligatures
+        .glyphs()
+        .any(|gid| ligatures.features(gid).next().is_some())
+    {
+        return Ok(Status::just_one_pass());
+    }
     return Ok(Status::just_one_fail(
         "feature-ordering",
         "'smcp' lookups are not defined before 'liga' lookups.",
@@ -224,7 +224,7 @@ fn right_side_bearing(glyph: GlyphId, metrics: &GlyphMetrics) -> f32 {
     proposal = "https://github.com/fonttools/fontspector/issues/557",
     title = "Ensure variable fonts have relatively consistent sidebearings."
 )]
-fn suspicious_sidebearings(t: &Testable, _context: &Context) -> CheckFnResult {
+fn suspicious_sidebearings(t: &Testable, context: &Context) -> CheckFnResult {
     let f = testfont!(t);
     skip!(
         !f.is_variable_font(),
@@ -276,6 +276,7 @@ fn suspicious_sidebearings(t: &Testable, _context: &Context) -> CheckFnResult {
     for loc in locs.iter() {
         let metrics = GlyphMetrics::new(&f.font(), Size::unscaled(), LocationRef::new(loc));
         for glyph in f.all_glyphs() {
+            context.check_deadline()?;
             let lsb = metrics.left_side_bearing(glyph).unwrap_or_default();
             let rsb = right_side_bearing(glyph, &metrics);
             let aw = metrics.advance_width(glyph).unwrap_or_default();
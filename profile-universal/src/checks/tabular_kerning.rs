@@ -7,7 +7,7 @@ use fontations::skrifa::{
     },
     GlyphId, MetadataProvider,
 };
-use fontspector_checkapi::{prelude::*, skip, testfont, FileTypeConvert, GetSubstitutionMap};
+use fontspector_checkapi::{prelude::*, skip, testfont, FileTypeConvert};
 use itertools::Itertools;
 use unicode_properties::{GeneralCategory, UnicodeGeneralCategory};
 
@@ -63,7 +63,7 @@ const NUMERALS: [char; 10] = ['0', '1', '2', '3', '4', '5', '6', '7', '8', '9'];
     proposal = "https://github.com/fonttools/fontbakery/issues/4440",
     title = "Check tabular widths don't have kerning."
 )]
-fn tabular_kerning(t: &Testable, _context: &Context) -> CheckFnResult {
+fn tabular_kerning(t: &Testable, context: &Context) -> CheckFnResult {
     let f = testfont!(t);
     let mut problems = vec![];
     let tnum_lookups = f
@@ -97,10 +97,11 @@ fn tabular_kerning(t: &Testable, _context: &Context) -> CheckFnResult {
         // tabular glyphs is anything on the RHS of a tnum
         let mut tabular_glyphs: HashSet<GlyphId> = HashSet::new();
         let mut tabular_numerals = numeral_glyphs.clone();
-        let lookups = f.font().gsub()?.lookup_list()?;
+        let substitutions_by_lookup = f.gsub_substitutions(Some(context))?;
         for i in tnum_lookups {
-            let lookup = lookups.lookups().get(i.get() as usize)?;
-            let substitutions = lookup.subtables()?.substitutions()?;
+            let substitutions = substitutions_by_lookup
+                .get(i.get() as usize)
+                .ok_or(ReadError::OutOfBounds)?;
             for (lhs, rhs) in substitutions.iter() {
                 for gid in rhs {
                     #[allow(clippy::indexing_slicing)] // We check the length before slicing
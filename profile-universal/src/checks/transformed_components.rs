@@ -71,6 +71,7 @@ fn transformed_components(f: &Testable, context: &Context) -> CheckFnResult {
     let is_hinted = font.has_table(b"fpgm");
     let mut failures = vec![];
     for glyphid in font.all_glyphs() {
+        context.check_deadline()?;
         if let Some(glyph) = loca.get_glyf(glyphid, &glyf)? {
             match glyph {
                 Glyph::Simple(_) => {}
@@ -1,7 +1,6 @@
 use std::collections::HashSet;
 
 use fontations::skrifa::{
-    charmap::MapVariant,
     raw::{
         tables::{
             colr::Paint,
@@ -9,9 +8,9 @@ use fontations::skrifa::{
         },
         TableProvider,
     },
-    GlyphId, MetadataProvider,
+    GlyphId,
 };
-use fontspector_checkapi::{prelude::*, testfont, FileTypeConvert, GetSubstitutionMap, Metadata};
+use fontspector_checkapi::{prelude::*, testfont, ClosureOptions, FileTypeConvert, Metadata};
 use itertools::Itertools;
 use serde_json::json;
 
@@ -19,13 +18,19 @@ use serde_json::json;
     id = "unreachable_glyphs",
     rationale = r#" 
         Glyphs are either accessible directly through Unicode codepoints or through
-        substitution rules.
+        substitution rules in the features of the GSUB table.
 
         In Color Fonts, glyphs are also referenced by the COLR table. And mathematical
         fonts also reference glyphs via the MATH table.
 
         Any glyphs not accessible by these means are redundant and serve only
         to increase the font's file size.
+
+        Note that a substitution rule only makes a glyph reachable if its lookup
+        is used by a feature, directly or through a contextual lookup, and the
+        glyphs it applies to are themselves reachable. Glyphs which are only
+        produced by lookups that no feature uses are reported as unreachable;
+        earlier versions of this check accepted any glyph produced by any lookup.
     "#,
     proposal = "https://github.com/fonttools/fontbakery/issues/3160",
     title = "Check font contains no unreachable glyphs"
@@ -33,19 +38,13 @@ use serde_json::json;
 fn unreachable_glyphs(t: &Testable, context: &Context) -> CheckFnResult {
     let f = testfont!(t);
     let mut glyphs = f.all_glyphs().collect::<HashSet<_>>();
-    // cmap
-    for (_, gid) in f.font().charmap().mappings() {
-        glyphs.remove(&gid);
-    }
-    // UVS
-    for (_, _, map) in f.font().charmap().variant_mappings() {
-        match map {
-            MapVariant::UseDefault => {}
-            MapVariant::Variant(glyph_id) => {
-                glyphs.remove(&glyph_id);
-            }
-        }
-    }
+    // cmap, UVS and GSUB
+    let closure = f.gsub_closure(
+        f.codepoints(Some(context)),
+        &ClosureOptions::default(),
+        Some(context),
+    )?;
+    glyphs.retain(|gid| !closure.contains(*gid));
 
     // No math table support yet, working on it...
     // if let Some(Ok(math)) = f.font().math() {}
@@ -93,17 +92,6 @@ fn unreachable_glyphs(t: &Testable, context: &Context) -> CheckFnResult {
         }
     }
 
-    // GSUB productions
-    if let Ok(gsub) = f.font().gsub() {
-        for lookup in gsub.lookup_list()?.lookups().iter().flatten() {
-            let substitutions = lookup.subtables()?.substitutions()?;
-            for (_lhs, rhs) in substitutions {
-                for gid in rhs.iter() {
-                    glyphs.remove(&GlyphId::from(*gid));
-                }
-            }
-        }
-    }
     // Remove components used in TrueType table
     for glyph in f
         .all_glyphs()
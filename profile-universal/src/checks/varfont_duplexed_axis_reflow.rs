@@ -55,6 +55,7 @@ fn varfont_duplexed_axis_reflow(t: &Testable, context: &Context) -> CheckFnResul
     let gvar = f.font().gvar()?;
     let mut bad_glyphs: HashMap<String, HashSet<(GlyphId, String)>> = HashMap::new();
     for glyph in f.all_glyphs() {
+        context.check_deadline()?;
         if let Ok(Some(variation)) = gvar.glyph_variation_data(glyph) {
             let tuples = variation.tuples();
             for tuple in tuples {
@@ -13,6 +13,7 @@ impl fontspector_checkapi::ProfileProvider for Universal {
 
         let builder = ProfileBuilder::new()
             .include_profile("opentype")
+            .include_profile("designspace")
             .add_section("Universal Profile Checks")
             .add_and_register_check(checks::alt_caron)
             .add_and_register_check(checks::arabic_high_hamza)
@@ -27,6 +28,10 @@ impl fontspector_checkapi::ProfileProvider for Universal {
             .add_and_register_check(checks::control_chars)
             .add_and_register_check(checks::empty_glyph_on_gid1_for_colrv0)
             .add_and_register_check(checks::colr_glyph_entry_with_no_layers)
+            .add_and_register_check(checks::color::colrv1_paint_graph)
+            .add_and_register_check(checks::color::colrv1_clipboxes)
+            .add_and_register_check(checks::color::svg_documents)
+            .add_and_register_check(checks::color::bitmap_strikes)
             .add_and_register_check(checks::empty_letters)
             .add_and_register_check(checks::family_vertical_metrics)
             .add_and_register_check(checks::family_win_ascent_and_descent)
@@ -50,14 +55,23 @@ impl fontspector_checkapi::ProfileProvider for Universal {
             .add_and_register_check(checks::fvar_name_entries)
             .add_and_register_check(checks::gpos7)
             .add_and_register_check(checks::gpos_kerning_info)
+            .add_and_register_check(checks::hinting::bytecode)
+            .add_and_register_check(checks::hinting::maxp_limits)
             .add_and_register_check(checks::hinting_impact)
             .add_and_register_check(checks::integer_ppem_if_hinted)
             .add_and_register_check(checks::interpolation_issues)
+            .add_and_register_check(checks::kerning::collisions)
+            .add_and_register_check(checks::kerning::extreme_values)
+            .add_and_register_check(checks::kerning::unreachable_glyphs)
+            .add_and_register_check(checks::kerning::master_consistency)
             .add_and_register_check(checks::legacy_accents)
             .add_and_register_check(checks::ligature_carets)
             .add_and_register_check(checks::linegaps)
             .add_and_register_check(checks::mandatory_avar_table)
             .add_and_register_check(checks::mandatory_glyphs)
+            .add_and_register_check(checks::mark_positioning::mark_attachment)
+            .add_and_register_check(checks::mark_positioning::anchor_placement)
+            .add_and_register_check(checks::mark_positioning::mark_stacking)
             .add_and_register_check(checks::math_signs_width)
             .add_and_register_check(checks::missing_small_caps_glyphs)
             .add_and_register_check(checks::name::char_restrictions)
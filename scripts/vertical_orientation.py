@@ -0,0 +1,65 @@
+"""Generate the table of characters which need vertical alternates.
+
+The opentype/vertical/alternates check looks for 'vert'/'vrt2' coverage of
+every character whose Unicode Vertical_Orientation (UAX #50) is Tu or Tr.
+This script reads VerticalOrientation.txt from the Unicode Character Database
+and writes those ranges out as Rust source.
+
+Usage: python3 scripts/vertical_orientation.py [--version 15.0.0] [--file VerticalOrientation.txt]
+"""
+
+import argparse
+import re
+
+import requests
+
+outfile = "profile-opentype/src/checks/opentype/vertical/orientation_data.rs"
+url = "https://www.unicode.org/Public/{version}/ucd/VerticalOrientation.txt"
+
+parser = argparse.ArgumentParser(description=__doc__.splitlines()[0])
+parser.add_argument("--version", default="15.0.0", help="Unicode version")
+parser.add_argument("--file", help="Read a local copy instead of downloading")
+args = parser.parse_args()
+
+if args.file:
+    with open(args.file, encoding="utf-8") as f:
+        content = f.read()
+else:
+    response = requests.get(url.format(version=args.version))
+    response.raise_for_status()
+    content = response.text
+
+ranges = []
+for line in content.splitlines():
+    line = line.split("#")[0].strip()
+    if not line:
+        continue
+    codepoints, value = [field.strip() for field in line.split(";")]
+    if value not in ("Tu", "Tr"):
+        continue
+    match = re.fullmatch(r"([0-9A-F]+)(?:\.\.([0-9A-F]+))?", codepoints)
+    if not match:
+        raise ValueError(f"Can't parse line: {line}")
+    start = int(match.group(1), 16)
+    end = int(match.group(2) or match.group(1), 16)
+    # Merge adjacent ranges with the same value
+    if ranges and ranges[-1][2] == value and ranges[-1][1] + 1 == start:
+        ranges[-1] = (ranges[-1][0], end, value)
+    else:
+        ranges.append((start, end, value))
+
+with open(outfile, "w", encoding="utf-8") as f:
+    f.write(
+        f"""// Generated by scripts/vertical_orientation.py from
+// VerticalOrientation-{args.version}.txt; do not edit by hand.
+
+use super::alternates::Orientation::{{self, TransformedRotated as Tr, TransformedUpright as Tu}};
+
+/// Characters whose Vertical_Orientation is Tu or Tr, from UAX #50
+pub(super) const VERTICAL_ALTERNATE_RANGES: [(u32, u32, Orientation); {len(ranges)}] = [
+"""
+    )
+    for start, end, value in ranges:
+        f.write(f"    (0x{start:04X}, 0x{end:04X}, {value}),\n")
+    f.write("];\n")
+print(f"Wrote {len(ranges)} ranges to {outfile}")